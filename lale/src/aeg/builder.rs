@@ -1,7 +1,9 @@
 use super::types::{AEGEdge, EdgeMetrics, AEG};
+use crate::error::LaleError;
 use crate::microarch::simulator::MicroArchSimulator;
-use crate::microarch::state::{MicroArchState, StateKey};
+use crate::microarch::state::{Address, MicroArchState, StateKey};
 use ahash::AHashMap;
+use petgraph::graph::NodeIndex;
 use std::collections::VecDeque;
 
 /// AEG Builder - constructs Abstract Execution Graph via state space exploration
@@ -14,6 +16,10 @@ pub struct AEGBuilder {
 
     /// Enable state joining to bound state space
     enable_joining: bool,
+
+    /// Distinct states kept per program counter before joining, i.e. loop
+    /// iterations unrolled before widening
+    max_unroll: usize,
 }
 
 impl AEGBuilder {
@@ -23,6 +29,7 @@ impl AEGBuilder {
             simulator,
             max_states: 100_000,
             enable_joining: true,
+            max_unroll: 1,
         }
     }
 
@@ -38,25 +45,41 @@ impl AEGBuilder {
         self
     }
 
+    /// Keep up to `max` distinct states per program counter, then widen
+    ///
+    /// A loop body is unrolled for its first `max` iterations, each with a
+    /// state of its own; states of later iterations are joined into the last
+    /// unrolled one. Larger values are more precise and explore more states.
+    /// Only applies with joining enabled.
+    pub fn with_max_unroll(mut self, max: usize) -> Self {
+        self.max_unroll = max.max(1);
+        self
+    }
+
     /// Build AEG from initial state
     /// Uses worklist algorithm with state joining
-    pub fn build(&self, initial_state: MicroArchState, max_cycles: u32) -> Result<AEG, String> {
+    pub fn build(&self, initial_state: MicroArchState, max_cycles: u32) -> Result<AEG, LaleError> {
         let mut aeg = AEG::new();
         let mut worklist = VecDeque::new();
         let mut visited = AHashMap::new();
+        let mut unrolled: AHashMap<Address, Vec<NodeIndex>> = AHashMap::new();
 
         // Add initial state
         let initial_node = aeg.add_state(initial_state.clone());
         aeg.initial_state = initial_node;
+        unrolled
+            .entry(initial_state.program_counter)
+            .or_default()
+            .push(initial_node);
         worklist.push_back((initial_state, initial_node, 0u32)); // (state, node, cycles)
 
         while let Some((current_state, current_node, cycles)) = worklist.pop_front() {
             // Check limits
             if aeg.node_count() >= self.max_states {
-                return Err(format!(
+                return Err(LaleError::Analysis(format!(
                     "State space explosion: exceeded {} states",
                     self.max_states
-                ));
+                )));
             }
 
             if cycles >= max_cycles {
@@ -77,27 +100,42 @@ impl AEGBuilder {
 
             for successor in successors {
                 let successor_key = successor.key();
+                let pc = successor.program_counter;
+
+                // Past the unroll limit, widen into the last unrolled state
+                let widen_into = unrolled
+                    .get(&pc)
+                    .filter(|nodes| self.enable_joining && nodes.len() >= self.max_unroll)
+                    .and_then(|nodes| nodes.last().copied())
+                    .filter(|&node| {
+                        !aeg.state_map.contains_key(&successor_key)
+                            && self
+                                .simulator
+                                .is_joinable(&aeg.graph[node].state, &successor)
+                    });
+                if let Some(existing_node) = widen_into {
+                    let existing = aeg.graph[existing_node].state.clone();
+                    let joined = self.simulator.join(&existing, &successor);
+                    aeg.add_edge(current_node, existing_node, AEGEdge::new(1));
+                    if !aeg.widening_points.contains(&pc) {
+                        aeg.widening_points.push(pc);
+                    }
 
-                // Try to join with existing state if enabled
-                if self.enable_joining {
-                    if let Some(existing_node) = self.find_joinable_state(&aeg, &successor) {
-                        // Join with existing state
-                        let joined = self
-                            .simulator
-                            .join(&aeg.graph[existing_node].state, &successor);
-
-                        // Update existing node with joined state
-                        // (In practice, we'd need mutable access - simplified here)
-
-                        // Add edge to existing node
-                        let edge = AEGEdge::new(1);
-                        aeg.add_edge(current_node, existing_node, edge);
-                        continue;
+                    // A state that grew is explored again
+                    let joined_key = joined.key();
+                    if joined_key != existing.key() {
+                        aeg.replace_state(existing_node, joined.clone());
+                        worklist.push_back((joined, existing_node, cycles + 1));
                     }
+                    continue;
                 }
 
                 // Add new state
+                let node_count = aeg.node_count();
                 let successor_node = aeg.add_state(successor.clone());
+                if aeg.node_count() > node_count {
+                    unrolled.entry(pc).or_default().push(successor_node);
+                }
 
                 // Add edge
                 let edge = AEGEdge::new(1);
@@ -110,34 +148,17 @@ impl AEGBuilder {
             }
         }
 
+        aeg.record_final_cycles();
         Ok(aeg)
     }
 
-    /// Find a joinable state in the AEG
-    fn find_joinable_state(
-        &self,
-        aeg: &AEG,
-        state: &MicroArchState,
-    ) -> Option<petgraph::graph::NodeIndex> {
-        // Look for states at same program counter
-        for (key, &node_idx) in &aeg.state_map {
-            if key.pc == state.program_counter {
-                let existing_state = &aeg.graph[node_idx].state;
-                if self.simulator.is_joinable(existing_state, state) {
-                    return Some(node_idx);
-                }
-            }
-        }
-        None
-    }
-
     /// Build AEG for a basic block
     /// Simpler version that explores until pipeline is empty
     pub fn build_for_block(
         &self,
         initial_state: MicroArchState,
         target_pc: u64,
-    ) -> Result<AEG, String> {
+    ) -> Result<AEG, LaleError> {
         let mut aeg = AEG::new();
         let mut worklist = VecDeque::new();
         let mut visited = AHashMap::new();
@@ -150,10 +171,10 @@ impl AEGBuilder {
         while let Some((current_state, current_node)) = worklist.pop_front() {
             // Check limits
             if aeg.node_count() >= self.max_states {
-                return Err(format!(
+                return Err(LaleError::Analysis(format!(
                     "State space explosion: exceeded {} states",
                     self.max_states
-                ));
+                )));
             }
 
             // Check if final state
@@ -189,6 +210,7 @@ impl AEGBuilder {
             }
         }
 
+        aeg.record_final_cycles();
         Ok(aeg)
     }
 }
@@ -198,7 +220,7 @@ mod tests {
     use super::*;
     use crate::microarch::state::{
         CacheConfig, CacheLevelConfig, MemoryConfig, MemoryLatency, PlatformConfig,
-        ReplacementPolicy,
+        ReplacementPolicy, WritePolicy,
     };
 
     fn test_config() -> PlatformConfig {
@@ -210,12 +232,14 @@ mod tests {
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
                 data_cache: Some(CacheLevelConfig {
                     size_kb: 16,
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
             },
             memory_config: MemoryConfig {
@@ -281,6 +305,39 @@ mod tests {
         assert!(aeg.node_count() > 0);
     }
 
+    #[test]
+    fn test_max_unroll_widens_after_limit() {
+        use crate::microarch::pipeline::{InstructionSlot, Opcode};
+
+        let config = test_config();
+        let mut initial_state = MicroArchState::initial(&config);
+        let simulator = MicroArchSimulator::new(config.clone());
+        simulator.fetch_instruction(
+            &mut initial_state,
+            InstructionSlot {
+                address: 0x1000,
+                opcode: Opcode::ALU,
+                dependencies: vec![],
+                memory_access: None,
+            },
+        );
+
+        // One state per stage the instruction passes through
+        let unrolled = AEGBuilder::new(MicroArchSimulator::new(config.clone()))
+            .with_joining(false)
+            .build(initial_state.clone(), 20)
+            .unwrap();
+        assert!(unrolled.node_count() > 2);
+        assert!(unrolled.widening_points.is_empty());
+
+        let widened = AEGBuilder::new(simulator)
+            .with_max_unroll(2)
+            .build(initial_state, 20)
+            .unwrap();
+        assert_eq!(widened.node_count(), 2);
+        assert_eq!(widened.widening_points, vec![0]);
+    }
+
     #[test]
     fn test_state_limit() {
         let config = test_config();
@@ -294,7 +351,8 @@ mod tests {
         // Should hit limit or succeed with small graph
         match result {
             Ok(aeg) => assert!(aeg.node_count() <= 5),
-            Err(msg) => assert!(msg.contains("State space explosion")),
+            Err(LaleError::Analysis(msg)) => assert!(msg.contains("State space explosion")),
+            Err(e) => panic!("unexpected error: {}", e),
         }
     }
 }
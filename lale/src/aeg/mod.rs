@@ -7,4 +7,4 @@ pub use compression::{
     BlockEdge, BlockNode, CompressedAEG, Compression, CompressionMode, EfficientCompression,
     PreciseCompression,
 };
-pub use types::{AEGEdge, AEGNode, EdgeMetrics, AEG};
+pub use types::{AEGEdge, AEGNode, EdgeMetrics, FinalCycles, AEG};
@@ -1,6 +1,8 @@
-use crate::microarch::state::{MicroArchState, StateKey};
+use crate::microarch::state::{Address, MicroArchState, StateKey};
 use ahash::AHashMap;
+use petgraph::algo::toposort;
 use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
 
 /// Abstract Execution Graph
 /// Nodes are microarchitectural states, edges are processor cycles
@@ -15,8 +17,15 @@ pub struct AEG {
     /// Final states (exit points)
     pub final_states: Vec<NodeIndex>,
 
+    /// Cycles to reach each final state, see [`AEG::record_final_cycles`]
+    pub final_cycles: AHashMap<NodeIndex, FinalCycles>,
+
     /// Mapping from state key to node index
     pub state_map: AHashMap<StateKey, NodeIndex>,
+
+    /// Program counters where states were joined after the unroll limit,
+    /// in the order widening first kicked in
+    pub widening_points: Vec<Address>,
 }
 
 impl AEG {
@@ -26,7 +35,9 @@ impl AEG {
             graph: DiGraph::new(),
             initial_state: NodeIndex::new(0),
             final_states: Vec::new(),
+            final_cycles: AHashMap::new(),
             state_map: AHashMap::new(),
+            widening_points: Vec::new(),
         }
     }
 
@@ -51,6 +62,16 @@ impl AEG {
         node_idx
     }
 
+    /// Replace the state of a node, e.g. with its join with another state
+    pub fn replace_state(&mut self, node: NodeIndex, state: MicroArchState) {
+        let old_key = self.graph[node].state.key();
+        if self.state_map.get(&old_key) == Some(&node) {
+            self.state_map.remove(&old_key);
+        }
+        self.state_map.entry(state.key()).or_insert(node);
+        self.graph[node].state = state;
+    }
+
     /// Add an edge between two states
     pub fn add_edge(&mut self, from: NodeIndex, to: NodeIndex, edge: AEGEdge) -> EdgeIndex {
         self.graph.add_edge(from, to, edge)
@@ -66,6 +87,59 @@ impl AEG {
         }
     }
 
+    /// Record the fewest and most cycles on the paths to each final state
+    ///
+    /// Only an acyclic graph has a bound on its paths; for a cyclic one
+    /// nothing is recorded and `false` is returned, leaving the answer to
+    /// IPET with loop bounds.
+    pub fn record_final_cycles(&mut self) -> bool {
+        self.final_cycles.clear();
+        let Ok(order) = toposort(&self.graph, None) else {
+            return false;
+        };
+
+        let mut reached: AHashMap<NodeIndex, FinalCycles> = AHashMap::new();
+        if self.graph.node_weight(self.initial_state).is_some() {
+            reached.insert(self.initial_state, FinalCycles { min: 0, max: 0 });
+        }
+        for node in order {
+            let Some(&here) = reached.get(&node) else {
+                continue;
+            };
+            for edge in self.graph.edges(node) {
+                let cycles = edge.weight().cycles as u64;
+                let there = FinalCycles {
+                    min: here.min + cycles,
+                    max: here.max + cycles,
+                };
+                reached
+                    .entry(edge.target())
+                    .and_modify(|known| {
+                        known.min = known.min.min(there.min);
+                        known.max = known.max.max(there.max);
+                    })
+                    .or_insert(there);
+            }
+        }
+
+        for &node in &self.final_states {
+            if let Some(&cycles) = reached.get(&node) {
+                self.final_cycles.insert(node, cycles);
+            }
+        }
+        true
+    }
+
+    /// Most cycles to any final state: the WCET, if recorded
+    pub fn max_final_cycles(&self) -> Option<u64> {
+        self.final_cycles.values().map(|cycles| cycles.max).max()
+    }
+
+    /// Fewest cycles to any final state: the BCET, if recorded
+    pub fn min_final_cycles(&self) -> Option<u64> {
+        self.final_cycles.values().map(|cycles| cycles.min).min()
+    }
+
     /// Get node count
     pub fn node_count(&self) -> usize {
         self.graph.node_count()
@@ -83,6 +157,13 @@ impl Default for AEG {
     }
 }
 
+/// Fewest and most cycles from the initial state to a final state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalCycles {
+    pub min: u64,
+    pub max: u64,
+}
+
 /// Node in AEG (microarchitectural state)
 #[derive(Debug, Clone)]
 pub struct AEGNode {
@@ -159,6 +240,7 @@ mod tests {
     fn test_config() -> PlatformConfig {
         use crate::microarch::state::{
             CacheConfig, CacheLevelConfig, MemoryConfig, MemoryLatency, ReplacementPolicy,
+            WritePolicy,
         };
 
         PlatformConfig {
@@ -169,12 +251,14 @@ mod tests {
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
                 data_cache: Some(CacheLevelConfig {
                     size_kb: 16,
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
             },
             memory_config: MemoryConfig {
@@ -240,6 +324,36 @@ mod tests {
         assert!(aeg.graph[node].is_final);
     }
 
+    #[test]
+    fn test_final_cycles_of_acyclic_graph() {
+        let mut aeg = AEG::new();
+        let config = test_config();
+
+        // Two paths to the final state: 1 + 3 cycles, or 6 directly
+        let mut states = vec![MicroArchState::initial(&config)];
+        for pc in 1..3 {
+            let mut state = states[0].clone();
+            state.program_counter = pc;
+            states.push(state);
+        }
+        let nodes: Vec<NodeIndex> = states.into_iter().map(|s| aeg.add_state(s)).collect();
+        aeg.initial_state = nodes[0];
+        aeg.add_edge(nodes[0], nodes[1], AEGEdge::new(1));
+        aeg.add_edge(nodes[1], nodes[2], AEGEdge::new(3));
+        aeg.add_edge(nodes[0], nodes[2], AEGEdge::new(6));
+        aeg.mark_final(nodes[2]);
+
+        assert!(aeg.record_final_cycles());
+        assert_eq!(aeg.final_cycles[&nodes[2]], FinalCycles { min: 4, max: 6 });
+        assert_eq!(aeg.max_final_cycles(), Some(6));
+        assert_eq!(aeg.min_final_cycles(), Some(4));
+
+        // A loop leaves the bound to IPET
+        aeg.add_edge(nodes[1], nodes[1], AEGEdge::new(1));
+        assert!(!aeg.record_final_cycles());
+        assert_eq!(aeg.max_final_cycles(), None);
+    }
+
     #[test]
     fn test_edge_metrics() {
         let metrics1 = EdgeMetrics {
@@ -0,0 +1,85 @@
+//! Detection of blocks that never execute on a normal run
+//!
+//! Blocks with no path from the entry are dead code the optimizer left in:
+//! no execution reaches them, so they contribute nothing to the WCET. Blocks
+//! from which every path ends in an `unreachable` terminator, typically after
+//! a call to a panic or abort handler, or unwinds out of the function with
+//! `resume`, do execute, but only on the way to a crash. Both are worth
+//! knowing about when reviewing a WCET report.
+
+use crate::ir::InkwellCFG;
+use inkwell::values::InstructionOpcode;
+use serde::{Deserialize, Serialize};
+
+/// Why a block is not on any normal execution path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeadBlockKind {
+    /// No path from the function entry reaches the block
+    NeverExecutes,
+    /// Reachable, but every path from it ends in `unreachable` or `resume`
+    PanicPathOnly,
+}
+
+/// Block that never executes on a normal run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadBlock {
+    /// Block label
+    pub block: String,
+    /// Block index in the function's CFG; labels of unnamed blocks collide
+    pub block_id: usize,
+    pub kind: DeadBlockKind,
+}
+
+/// Find the dead and panic-only blocks of a function, in block order
+pub fn dead_blocks(cfg: &InkwellCFG) -> Vec<DeadBlock> {
+    let mut reachable = vec![false; cfg.blocks.len()];
+    for id in cfg.reachable_blocks() {
+        reachable[id] = true;
+    }
+
+    // Least fixpoint: a block ending in `unreachable` or `resume`, or one
+    // whose successors all lead there. Cycles never get in, so an endless
+    // loop that doesn't panic is not reported.
+    let mut panics = vec![false; cfg.blocks.len()];
+    for block in &cfg.blocks {
+        panics[block.id] = block.block.get_terminator().is_some_and(|terminator| {
+            matches!(
+                terminator.get_opcode(),
+                InstructionOpcode::Unreachable | InstructionOpcode::Resume
+            )
+        });
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in &cfg.blocks {
+            if panics[block.id] {
+                continue;
+            }
+            let successors = cfg.successors(block.id);
+            if !successors.is_empty() && successors.iter().all(|&succ| panics[succ]) {
+                panics[block.id] = true;
+                changed = true;
+            }
+        }
+    }
+
+    cfg.blocks
+        .iter()
+        .filter_map(|block| {
+            let kind = if !reachable[block.id] {
+                DeadBlockKind::NeverExecutes
+            } else if panics[block.id] {
+                DeadBlockKind::PanicPathOnly
+            } else {
+                return None;
+            };
+            Some(DeadBlock {
+                block: block.name.clone(),
+                block_id: block.id,
+                kind,
+            })
+        })
+        .collect()
+}
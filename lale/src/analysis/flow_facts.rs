@@ -0,0 +1,198 @@
+//! Loop bounds supplied from a file
+//!
+//! A flow-facts file maps each function, by LLVM name, to the maximum
+//! iterations of its loops, by header block label. In TOML, as written by
+//! `lale init`:
+//!
+//! ```toml
+//! ["sensor_filter"]
+//! "for.body" = 64
+//! "#3" = 16
+//! ```
+//!
+//! A header without a label, as in IR from `rustc` or `clang -O`, is keyed
+//! by `#` and its block index in function order instead; see [`loop_key`].
+//! or the same as a JSON object. A bound from the file replaces whatever the
+//! analysis would otherwise have chosen for that loop.
+
+use super::{AnalysisWarning, WarningKind};
+use crate::error::LaleError;
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Flow-facts key of the loop whose header is block `block_id`, labelled
+/// `label`: the label, or `#<block_id>` for an unlabelled header
+///
+/// Labels are unique within a function, and `#` starts no LLVM label, so
+/// the key names one loop.
+pub fn loop_key(label: &str, block_id: usize) -> String {
+    if label.is_empty() {
+        format!("#{}", block_id)
+    } else {
+        label.to_string()
+    }
+}
+
+/// Loop bounds by function and loop header
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FlowFactStore {
+    functions: AHashMap<String, AHashMap<String, u64>>,
+}
+
+impl FlowFactStore {
+    /// Load a flow-facts file, JSON if its extension is `.json` and TOML
+    /// otherwise
+    pub fn load(path: &Path) -> Result<Self, LaleError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            LaleError::io(format!("Failed to read flow facts {}", path.display()), e)
+        })?;
+        let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&content)
+        } else {
+            Self::from_toml(&content)
+        };
+        parsed.map_err(|e| {
+            LaleError::Config(format!(
+                "Failed to parse flow facts {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, LaleError> {
+        let store: Self = toml::from_str(content).map_err(|e| LaleError::Config(e.to_string()))?;
+        store.validate()
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, LaleError> {
+        let store: Self =
+            serde_json::from_str(content).map_err(|e| LaleError::Config(e.to_string()))?;
+        store.validate()
+    }
+
+    /// Reject empty loop keys, which would bound whichever unlabelled loop
+    /// of the function came first
+    fn validate(self) -> Result<Self, LaleError> {
+        for (function, bounds) in &self.functions {
+            if bounds.contains_key("") {
+                return Err(LaleError::Config(format!(
+                    "'{}' has a loop with an empty header label; \
+                     key unlabelled loops by block index, e.g. \"#3\"",
+                    function
+                )));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Set the bound of a loop
+    pub fn insert(&mut self, function: &str, header: &str, max_iterations: u64) {
+        self.functions
+            .entry(function.to_string())
+            .or_default()
+            .insert(header.to_string(), max_iterations);
+    }
+
+    /// Bound of the loop keyed `header` in `function`, if given
+    ///
+    /// `header` is the loop's [`loop_key`].
+    pub fn loop_bound(&self, function: &str, header: &str) -> Option<u64> {
+        self.functions.get(function)?.get(header).copied()
+    }
+
+    /// Entries that match no analyzed function or loop, as warnings sorted
+    /// by function
+    ///
+    /// `loop_headers` maps each analyzed function to its loop headers. A
+    /// mismatch usually means a renamed function or a changed build, and
+    /// the bound it carried silently no longer applies.
+    pub fn unmatched(&self, loop_headers: &AHashMap<String, Vec<String>>) -> Vec<AnalysisWarning> {
+        let mut warnings = Vec::new();
+        for (function, bounds) in &self.functions {
+            let Some(headers) = loop_headers.get(function) else {
+                warnings.push(AnalysisWarning::in_function(
+                    WarningKind::UnmatchedFlowFact,
+                    function,
+                    "no such function was analyzed",
+                ));
+                continue;
+            };
+            for header in bounds.keys() {
+                if !headers.contains(header) {
+                    warnings.push(AnalysisWarning::in_function(
+                        WarningKind::UnmatchedFlowFact,
+                        function,
+                        format!("no loop '{}'", header),
+                    ));
+                }
+            }
+        }
+        warnings.sort_by(|a, b| (&a.function, &a.message).cmp(&(&b.function, &b.message)));
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_and_json_agree() {
+        let toml = FlowFactStore::from_toml(
+            r#"
+            ["sensor_filter"]
+            "for.body" = 64
+
+            ["init"]
+            "#,
+        )
+        .unwrap();
+        let json =
+            FlowFactStore::from_json(r#"{"sensor_filter": {"for.body": 64}, "init": {}}"#).unwrap();
+
+        assert_eq!(toml, json);
+        assert_eq!(toml.loop_bound("sensor_filter", "for.body"), Some(64));
+        assert_eq!(toml.loop_bound("sensor_filter", "while.body"), None);
+        assert!(FlowFactStore::from_toml("[\"f\"]\n\"loop\" = -1\n").is_err());
+    }
+
+    #[test]
+    fn test_unlabelled_headers_are_keyed_by_block_index() {
+        assert_eq!(loop_key("for.body", 2), "for.body");
+        assert_eq!(loop_key("", 3), "#3");
+
+        let facts = FlowFactStore::from_toml("[\"f\"]\n\"#3\" = 16\n").unwrap();
+        assert_eq!(facts.loop_bound("f", &loop_key("", 3)), Some(16));
+        assert_eq!(facts.loop_bound("f", &loop_key("", 4)), None);
+
+        assert!(FlowFactStore::from_toml("[\"f\"]\n\"\" = 16\n").is_err());
+        assert!(FlowFactStore::from_json(r#"{"f": {"": 16}}"#).is_err());
+    }
+
+    #[test]
+    fn test_unmatched_entries() {
+        let mut facts = FlowFactStore::default();
+        facts.insert("filter", "for.body", 64);
+        facts.insert("filter", "for.cond", 8);
+        facts.insert("renamed", "loop", 4);
+
+        let loop_headers: AHashMap<String, Vec<String>> =
+            [("filter".to_string(), vec!["for.body".to_string()])].into();
+
+        let messages: Vec<String> = facts
+            .unmatched(&loop_headers)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "[unmatched-flow-fact] filter: no loop 'for.cond'".to_string(),
+                "[unmatched-flow-fact] renamed: no such function was analyzed".to_string(),
+            ]
+        );
+    }
+}
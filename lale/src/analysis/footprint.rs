@@ -0,0 +1,184 @@
+//! Memory footprint of a function
+//!
+//! Collects the distinct cache blocks a function touches, for cache
+//! pollution and shared-cache interference analysis. LLVM IR carries no
+//! addresses, so code and data are placed in a synthetic layout: the
+//! function body is contiguous from `CODE_BASE`, and every accessed base
+//! object (stack slot, global, pointer argument) gets its own line-aligned
+//! region from `DATA_BASE`.
+
+use crate::ir::InkwellCFG;
+use crate::microarch::cache::types::MemoryBlock;
+use ahash::AHashSet;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue};
+
+/// Line size used when no cache configuration is at hand
+pub const DEFAULT_LINE_SIZE_BYTES: usize = 32;
+
+/// Rough encoding size; IR instructions don't map 1:1 to machine code
+pub const ESTIMATED_BYTES_PER_INSTRUCTION: u64 = 4;
+
+/// Pointer width of the 32-bit embedded targets LALE models
+const POINTER_SIZE_BYTES: u64 = 4;
+
+/// Start of the synthetic code region
+const CODE_BASE: u64 = 0;
+
+/// Start of the synthetic data region, far above any function body
+const DATA_BASE: u64 = 0x2000_0000;
+
+/// Distinct instruction and data cache blocks a function accesses
+pub fn memory_footprint(function: &FunctionValue, cfg: &InkwellCFG) -> Vec<MemoryBlock> {
+    memory_footprint_with_line_size(function, cfg, DEFAULT_LINE_SIZE_BYTES)
+}
+
+/// Distinct cache blocks a function accesses for a given line size
+///
+/// Objects whose size can't be determined (globals, pointer arguments)
+/// count as a single line.
+pub fn memory_footprint_with_line_size(
+    function: &FunctionValue,
+    cfg: &InkwellCFG,
+    line_size_bytes: usize,
+) -> Vec<MemoryBlock> {
+    let line_size = line_size_bytes.max(1);
+
+    let code_bytes = cfg
+        .blocks
+        .iter()
+        .map(|block| block.instruction_count as u64)
+        .sum::<u64>()
+        * ESTIMATED_BYTES_PER_INSTRUCTION;
+    let mut blocks = region_blocks(CODE_BASE, code_bytes, line_size);
+
+    let mut next_address = DATA_BASE;
+    for size in accessed_object_sizes(function) {
+        let size = size.unwrap_or(line_size as u64);
+        blocks.extend(region_blocks(next_address, size, line_size));
+        next_address += line_count(size, line_size) * line_size as u64;
+    }
+
+    blocks
+}
+
+/// Cache blocks covering `size` bytes starting at a line-aligned address
+fn region_blocks(start: u64, size: u64, line_size: usize) -> Vec<MemoryBlock> {
+    (0..line_count(size, line_size))
+        .map(|line| MemoryBlock::from_address(start + line * line_size as u64, line_size))
+        .collect()
+}
+
+/// Number of lines needed to hold `size` bytes
+fn line_count(size: u64, line_size: usize) -> u64 {
+    size.div_ceil(line_size as u64)
+}
+
+/// Size of each distinct object the function loads from or stores to
+fn accessed_object_sizes(function: &FunctionValue) -> Vec<Option<u64>> {
+    let mut seen = AHashSet::new();
+    let mut sizes = Vec::new();
+
+    for block in function.get_basic_blocks() {
+        let mut instr_iter = block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            if let Some(BasicValueEnum::PointerValue(ptr)) = accessed_pointer(&instr) {
+                let base = base_object(ptr.into());
+                if seen.insert(base) {
+                    sizes.push(object_size(base));
+                }
+            }
+            instr_iter = instr.get_next_instruction();
+        }
+    }
+
+    sizes
+}
+
+/// Pointer operand of a memory access instruction
+fn accessed_pointer<'ctx>(instr: &InstructionValue<'ctx>) -> Option<BasicValueEnum<'ctx>> {
+    let index = match instr.get_opcode() {
+        InstructionOpcode::Load
+        | InstructionOpcode::AtomicRMW
+        | InstructionOpcode::AtomicCmpXchg => 0,
+        InstructionOpcode::Store => 1,
+        _ => return None,
+    };
+    instr.get_operand(index)?.value()
+}
+
+/// Strip address arithmetic and casts down to the underlying object
+fn base_object(mut value: BasicValueEnum) -> BasicValueEnum {
+    while let BasicValueEnum::PointerValue(ptr) = value {
+        let Some(instr) = ptr.as_instruction() else {
+            break;
+        };
+        if !matches!(
+            instr.get_opcode(),
+            InstructionOpcode::GetElementPtr
+                | InstructionOpcode::BitCast
+                | InstructionOpcode::AddrSpaceCast
+        ) {
+            break;
+        }
+        match instr.get_operand(0).and_then(|op| op.value()) {
+            Some(source) => value = source,
+            None => break,
+        }
+    }
+    value
+}
+
+/// Size in bytes of a stack slot; other objects are unknown
+fn object_size(base: BasicValueEnum) -> Option<u64> {
+    let BasicValueEnum::PointerValue(ptr) = base else {
+        return None;
+    };
+    let instr = ptr.as_instruction()?;
+    if instr.get_opcode() != InstructionOpcode::Alloca {
+        return None;
+    }
+    type_size(instr.get_allocated_type().ok()?)
+}
+
+/// Unpadded size of a type in bytes
+pub(crate) fn type_size(ty: BasicTypeEnum) -> Option<u64> {
+    match ty {
+        BasicTypeEnum::IntType(int) => Some((int.get_bit_width() as u64).div_ceil(8)),
+        // Sized for double so that float slots are never underestimated
+        BasicTypeEnum::FloatType(_) => Some(8),
+        BasicTypeEnum::PointerType(_) => Some(POINTER_SIZE_BYTES),
+        BasicTypeEnum::ArrayType(array) => {
+            Some(array.len() as u64 * type_size(array.get_element_type())?)
+        }
+        BasicTypeEnum::VectorType(vector) => {
+            Some(vector.get_size() as u64 * type_size(vector.get_element_type())?)
+        }
+        BasicTypeEnum::StructType(structure) => {
+            structure.get_field_types().into_iter().map(type_size).sum()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_blocks_cover_partial_lines() {
+        assert_eq!(line_count(0, 32), 0);
+        assert_eq!(line_count(1, 32), 1);
+        assert_eq!(line_count(64, 32), 2);
+        assert_eq!(line_count(65, 32), 3);
+
+        let blocks = region_blocks(DATA_BASE, 40, 32);
+        assert_eq!(
+            blocks,
+            vec![
+                MemoryBlock::from_address(DATA_BASE, 32),
+                MemoryBlock::from_address(DATA_BASE + 32, 32),
+            ]
+        );
+    }
+}
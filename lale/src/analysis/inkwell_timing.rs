@@ -4,25 +4,108 @@
 
 use ahash::AHashMap;
 use inkwell::basic_block::BasicBlock;
-use inkwell::values::{FunctionValue, InstructionOpcode};
-
-use crate::ir::InkwellCFG;
+use inkwell::values::{
+    AnyValue, BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue,
+};
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::timing::{AccessType, Cycles, InstructionClass};
+use crate::analysis::{Loop, LoopAnalyzer};
+use crate::config::types::{CacheLevelConfig, WritePolicy};
+use crate::ir::{InkwellCFG, SsaRegisters, CFG};
+use crate::microarch::cache::{
+    is_code_cached, CacheAccessClass, CacheAnalysisResult, CacheState, GlobalLayout,
+    InstructionCacheAnalysis,
+};
+use crate::microarch::{DependencyGraph, HazardDetector};
 use crate::platform::PlatformModel;
 
 /// Timing calculator for inkwell-based analysis
 pub struct InkwellTimingCalculator;
 
+/// Opcode cost override supplied by library users
+pub type CostFn = dyn Fn(&InstructionOpcode) -> u64;
+
+/// Blanket cache behaviour assumed for every load and store
+///
+/// Brackets the real WCET without a cache analysis: cold is a safe upper
+/// bound, warm an optimistic lower bound.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheAssumption {
+    /// Every access misses and pays the worst-case memory latency
+    #[default]
+    Cold,
+
+    /// Every access hits and pays the best-case latency
+    Warm,
+}
+
 impl InkwellTimingCalculator {
     /// Calculate timing for all basic blocks in a function
     pub fn calculate_block_timings(
-        function: &FunctionValue,
+        _function: &FunctionValue,
         cfg: &InkwellCFG,
         platform: &PlatformModel,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_block_timings_assuming(cfg, platform, CacheAssumption::Cold)
+    }
+
+    /// Calculate timing for all basic blocks under a blanket cache assumption
+    pub fn calculate_block_timings_assuming(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        cache: CacheAssumption,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_block_timings_with_instruction_cost(cfg, &|instr| {
+            Self::access_cost(instr, platform, cache)
+        })
+    }
+
+    /// Calculate timing for all basic blocks using a custom opcode cost
+    pub fn calculate_block_timings_with_cost(
+        cfg: &InkwellCFG,
+        cost: &dyn Fn(&InstructionOpcode) -> u64,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_block_timings_with_instruction_cost(cfg, &|instr| cost(&instr.get_opcode()))
+    }
+
+    /// Calculate best-case timing for all basic blocks
+    ///
+    /// Every instruction costs the best case of its timing, e.g. the
+    /// fastest early-out of a hardware divide and a cache hit for loads and
+    /// stores, so each block's cycles are a lower bound.
+    pub fn calculate_best_case_block_timings(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_block_timings_with_instruction_cost(cfg, &|instr| {
+            Self::best_case_instruction_cost(&instr.get_opcode(), platform)
+        })
+    }
+
+    /// Multi-issue best-case block timings
+    pub fn calculate_scheduled_best_case_block_timings(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_scheduled_block_timings_with_instruction_cost(
+            cfg,
+            platform.issue_width,
+            platform.fetch_width,
+            &|instr| Self::best_case_instruction_cost(&instr.get_opcode(), platform),
+        )
+    }
+
+    fn calculate_block_timings_with_instruction_cost(
+        cfg: &InkwellCFG,
+        cost: &dyn Fn(&InstructionValue) -> u64,
     ) -> AHashMap<usize, u64> {
         let mut timings = AHashMap::new();
 
         for block in &cfg.blocks {
-            let cycles = Self::calculate_block_timing(&block.block, platform);
+            let cycles = Self::calculate_block_timing(&block.block, cost);
             timings.insert(block.id, cycles);
         }
 
@@ -30,13 +113,13 @@ impl InkwellTimingCalculator {
     }
 
     /// Calculate timing for a single basic block
-    fn calculate_block_timing(block: &BasicBlock, platform: &PlatformModel) -> u64 {
+    fn calculate_block_timing(block: &BasicBlock, cost: &dyn Fn(&InstructionValue) -> u64) -> u64 {
         let mut total_cycles = 0u64;
 
         // Iterate through instructions
         let mut instr_iter = block.get_first_instruction();
         while let Some(instr) = instr_iter {
-            let cycles = Self::instruction_cost(&instr.get_opcode(), platform);
+            let cycles = cost(&instr);
             total_cycles += cycles;
             instr_iter = instr.get_next_instruction();
         }
@@ -44,8 +127,147 @@ impl InkwellTimingCalculator {
         total_cycles
     }
 
+    /// Calculate timing for all basic blocks, accounting for multi-issue
+    ///
+    /// Instructions are grouped into dependency levels; each level issues in
+    /// chunks of `platform.issue_width` and a chunk costs its slowest
+    /// instruction. With an issue width of 1 this equals the serial sum.
+    /// Fetching the block adds `platform.fetch_width`-wide front-end cycles.
+    pub fn calculate_scheduled_block_timings(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_scheduled_block_timings_assuming(cfg, platform, CacheAssumption::Cold)
+    }
+
+    /// Multi-issue block timings under a blanket cache assumption
+    pub fn calculate_scheduled_block_timings_assuming(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        cache: CacheAssumption,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_scheduled_block_timings_with_instruction_cost(
+            cfg,
+            platform.issue_width,
+            platform.fetch_width,
+            &|instr| Self::access_cost(instr, platform, cache),
+        )
+    }
+
+    /// Multi-issue block timings using a custom opcode cost
+    pub fn calculate_scheduled_block_timings_with_cost(
+        cfg: &InkwellCFG,
+        issue_width: u32,
+        fetch_width: u8,
+        cost: &dyn Fn(&InstructionOpcode) -> u64,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_scheduled_block_timings_with_instruction_cost(
+            cfg,
+            issue_width,
+            fetch_width,
+            &|instr| cost(&instr.get_opcode()),
+        )
+    }
+
+    fn calculate_scheduled_block_timings_with_instruction_cost(
+        cfg: &InkwellCFG,
+        issue_width: u32,
+        fetch_width: u8,
+        cost: &dyn Fn(&InstructionValue) -> u64,
+    ) -> AHashMap<usize, u64> {
+        let registers = SsaRegisters::from_blocks(cfg.blocks.iter().map(|block| block.block));
+        let mut timings = AHashMap::new();
+
+        for block in &cfg.blocks {
+            let cycles =
+                Self::calculate_scheduled_block_timing(&block.block, &registers, issue_width, cost)
+                    + Self::fetch_cost(block.instruction_count, fetch_width);
+            timings.insert(block.id, cycles);
+        }
+
+        timings
+    }
+
+    /// Calculate multi-issue timing for a single basic block
+    fn calculate_scheduled_block_timing<'ctx>(
+        block: &BasicBlock<'ctx>,
+        registers: &SsaRegisters<'ctx>,
+        issue_width: u32,
+        cost: &dyn Fn(&InstructionValue) -> u64,
+    ) -> u64 {
+        let mut instructions = Vec::new();
+        let mut instr_iter = block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            instructions.push(instr);
+            instr_iter = instr.get_next_instruction();
+        }
+
+        // Operands defined in other blocks are already available and create
+        // no dependency
+        let mut graph = DependencyGraph::new();
+        for dependency in registers.block_dependencies(*block) {
+            graph.add_instruction(dependency);
+        }
+        graph.build_dependencies(&HazardDetector::new(0, true));
+
+        let costs: Vec<u64> = instructions.iter().map(cost).collect();
+
+        graph
+            .get_independent_instructions()
+            .iter()
+            .map(|level| {
+                let mut level_costs: Vec<u64> = level.iter().map(|&i| costs[i]).collect();
+                Self::issue_cost(&mut level_costs, issue_width)
+            })
+            .sum()
+    }
+
+    /// Front-end cycles to fetch `instruction_count` instructions
+    fn fetch_cost(instruction_count: usize, fetch_width: u8) -> u64 {
+        (instruction_count as u64).div_ceil(fetch_width.max(1) as u64)
+    }
+
+    /// Cost of issuing one dependency level on an `issue_width`-wide core
+    fn issue_cost(level_costs: &mut [u64], issue_width: u32) -> u64 {
+        // Pair the most expensive instructions so slow ones share slots
+        level_costs.sort_unstable_by(|a, b| b.cmp(a));
+        level_costs
+            .chunks(issue_width.max(1) as usize)
+            .map(|chunk| chunk[0])
+            .sum()
+    }
+
     /// Get instruction cost based on opcode and platform
-    fn instruction_cost(opcode: &InstructionOpcode, platform: &PlatformModel) -> u64 {
+    ///
+    /// Opcodes without a timing cost as [`InstructionClass::Other`]; see
+    /// [`Self::known_instruction_cost`] to tell them apart.
+    pub fn instruction_cost(opcode: &InstructionOpcode, platform: &PlatformModel) -> u64 {
+        Self::known_instruction_cost(opcode, platform)
+            .unwrap_or_else(|| platform.get_timing(&InstructionClass::Other).worst_case as u64)
+    }
+
+    /// Best-case instruction cost based on opcode and platform
+    pub fn best_case_instruction_cost(opcode: &InstructionOpcode, platform: &PlatformModel) -> u64 {
+        Self::known_instruction_timing(opcode, platform)
+            .unwrap_or_else(|| platform.get_timing(&InstructionClass::Other))
+            .best_case as u64
+    }
+
+    /// Instruction cost based on opcode and platform, `None` for opcodes
+    /// without a timing
+    pub fn known_instruction_cost(
+        opcode: &InstructionOpcode,
+        platform: &PlatformModel,
+    ) -> Option<u64> {
+        Self::known_instruction_timing(opcode, platform).map(|timing| timing.worst_case as u64)
+    }
+
+    /// Best- and worst-case instruction timing, `None` for opcodes without
+    /// a timing
+    fn known_instruction_timing(
+        opcode: &InstructionOpcode,
+        platform: &PlatformModel,
+    ) -> Option<Cycles> {
         use crate::analysis::timing::{AccessType, AtomicOp, InstructionClass};
         use InstructionOpcode::*;
 
@@ -101,43 +323,99 @@ impl InkwellTimingCalculator {
             Select => InstructionClass::Add,
 
             // PHI nodes (no runtime cost)
-            Phi => return 0,
+            Phi => return Some(Cycles::new(0)),
 
             // Atomic operations
-            AtomicRMW | AtomicCmpXchg | Fence => {
-                return platform
-                    .get_timing(&InstructionClass::Atomic(AtomicOp::Add))
-                    .worst_case as u64;
-            }
+            AtomicRMW | AtomicCmpXchg | Fence => InstructionClass::Atomic(AtomicOp::Add),
 
             // Landing pad / exception handling
             LandingPad | Resume | CleanupRet | CatchRet | CatchSwitch | CatchPad | CleanupPad => {
-                return 10; // Exception handling is expensive
+                return Some(Cycles::new(10)); // Exception handling is expensive
             }
 
             // Unreachable
-            Unreachable => return 0,
+            Unreachable => return Some(Cycles::new(0)),
 
             // User operations (inline asm, etc.)
-            UserOp1 | UserOp2 => return 5,
+            UserOp1 | UserOp2 => return Some(Cycles::new(5)),
 
             // VA operations - treat as Add
             VAArg => InstructionClass::Add,
 
             // Freeze (LLVM 10+)
-            Freeze => return 0,
+            Freeze => return Some(Cycles::new(0)),
 
             // Default for unknown instructions
-            _ => {
-                eprintln!(
-                    "Warning: Unknown instruction opcode {:?}, using default cost",
-                    opcode
-                );
-                InstructionClass::Other
+            _ => return None,
+        };
+
+        Some(platform.get_timing(&class))
+    }
+
+    /// Get instruction cost under a blanket cache assumption
+    ///
+    /// Cold is the same as [`Self::instruction_cost`]; warm prices loads and
+    /// stores at the platform's best case.
+    pub fn instruction_cost_assuming(
+        opcode: &InstructionOpcode,
+        platform: &PlatformModel,
+        cache: CacheAssumption,
+    ) -> u64 {
+        use crate::analysis::timing::{AccessType, InstructionClass};
+
+        let class = match (cache, opcode) {
+            (CacheAssumption::Warm, InstructionOpcode::Load) => {
+                InstructionClass::Load(AccessType::Ram)
+            }
+            (CacheAssumption::Warm, InstructionOpcode::Store) => {
+                InstructionClass::Store(AccessType::Ram)
             }
+            _ => return Self::instruction_cost(opcode, platform),
         };
 
-        platform.get_timing(&class).worst_case as u64
+        platform.get_timing(&class).best_case as u64
+    }
+
+    /// Cost of one instruction under a blanket cache assumption
+    ///
+    /// Volatile loads and stores (MMIO) are real bus accesses every time, so
+    /// they are priced uncached whatever the assumption.
+    pub fn access_cost(
+        instr: &InstructionValue,
+        platform: &PlatformModel,
+        cache: CacheAssumption,
+    ) -> u64 {
+        let opcode = instr.get_opcode();
+        if Self::is_volatile_access(instr) {
+            return Self::uncached_access_cost(&opcode, platform);
+        }
+        Self::instruction_cost_assuming(&opcode, platform, cache)
+    }
+
+    /// Whether an instruction is a volatile load or store
+    pub fn is_volatile_access(instr: &InstructionValue) -> bool {
+        matches!(
+            instr.get_opcode(),
+            InstructionOpcode::Load | InstructionOpcode::Store
+        ) && instr.get_volatile().unwrap_or(false)
+    }
+
+    /// Worst-case cost of a load or store that bypasses the cache
+    ///
+    /// The slower of a peripheral and a RAM access, since a volatile access
+    /// may target either.
+    pub fn uncached_access_cost(opcode: &InstructionOpcode, platform: &PlatformModel) -> u64 {
+        use crate::analysis::timing::{AccessType, InstructionClass};
+
+        let class = |access| match opcode {
+            InstructionOpcode::Store => InstructionClass::Store(access),
+            _ => InstructionClass::Load(access),
+        };
+        [AccessType::Peripheral, AccessType::Ram]
+            .into_iter()
+            .map(|access| platform.get_timing(&class(access)).worst_case as u64)
+            .max()
+            .unwrap_or(0)
     }
 
     /// Calculate timing with cache effects
@@ -146,15 +424,295 @@ impl InkwellTimingCalculator {
         cfg: &InkwellCFG,
         platform: &PlatformModel,
     ) -> AHashMap<usize, u64> {
-        let mut timings = Self::calculate_block_timings(function, cfg, platform);
+        Self::calculate_with_cache_penalties(function, cfg, platform, true)
+    }
+
+    /// Calculate timing with cache effects from the cache state at entry
+    ///
+    /// Like `calculate_with_cache`, but the entry block pays the cold-cache
+    /// miss only if the function's code is not guaranteed to be cached in
+    /// `entry_state`, e.g. the state its caller leaves at the call. Used by
+    /// the library-only `ModuleAnalyzer::analyze_calls_with_cache`.
+    pub fn calculate_with_entry_cache(
+        function: &FunctionValue,
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        entry_state: &CacheState,
+    ) -> AHashMap<usize, u64> {
+        let name = function.get_name().to_str().unwrap_or("");
+        let cold_entry = !is_code_cached(entry_state, name);
+        Self::calculate_with_cache_penalties(function, cfg, platform, cold_entry)
+    }
 
-        // Apply cache miss penalties
-        // Simple model: assume cold cache at function entry, warm cache for loops
+    fn calculate_with_cache_penalties(
+        function: &FunctionValue,
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        cold_entry: bool,
+    ) -> AHashMap<usize, u64> {
+        let mut timings = Self::calculate_block_timings(function, cfg, platform);
         let cache_miss_penalty = 10; // Conservative estimate in cycles
+        Self::apply_cache_miss_penalties(&mut timings, cfg, cache_miss_penalty, cold_entry);
+        timings
+    }
+
+    /// Calculate timing from a Must/May cache analysis of the function
+    ///
+    /// Every non-volatile load and store is classified at its block: an
+    /// always-hit access pays the platform's best-case latency, an always-miss
+    /// or unknown one the worst case. A first-miss access pays the best case
+    /// in the loop, and its miss latency is charged once to the blocks
+    /// entering the loop it is persistent in. Under a write-through policy
+    /// stores always pay the worst case. Loads from constant globals are
+    /// priced as Flash accesses, everything else as RAM.
+    ///
+    /// Accesses are looked up at the addresses of the function's
+    /// [`GlobalLayout`], as computed by
+    /// [`DataCacheAnalysis`](crate::microarch::cache::DataCacheAnalysis);
+    /// accesses to anything but a global are priced at the worst case.
+    pub fn calculate_with_cache_analysis(
+        _function: &FunctionValue,
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        cache_result: &CacheAnalysisResult,
+    ) -> AHashMap<usize, u64> {
+        Self::calculate_block_timings_with_cache_analysis(cfg, platform, cache_result)
+    }
+
+    /// Calculate timing from a Must/May cache analysis of the blocks
+    ///
+    /// See [`Self::calculate_with_cache_analysis`].
+    pub fn calculate_block_timings_with_cache_analysis(
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        cache_result: &CacheAnalysisResult,
+    ) -> AHashMap<usize, u64> {
+        let layout = GlobalLayout::of(cfg);
+        let graph = cfg.to_petgraph();
+        let nodes: AHashMap<usize, NodeIndex> = graph
+            .graph
+            .node_indices()
+            .map(|node| (graph.graph[node].execution_count_var, node))
+            .collect();
+        let mut loops = LoopAnalyzer::analyze_loops(&graph);
+        // Enclosing loops are listed outermost first
+        loops.sort_by_key(|l| std::cmp::Reverse(l.body_blocks.len()));
+
+        let mut timings = AHashMap::new();
+        // Miss latency of each persistent address, by loop it is persistent in
+        let mut first_misses: AHashMap<(NodeIndex, u64), u64> = AHashMap::new();
+
+        for block in &cfg.blocks {
+            let node = nodes[&block.id];
+            let loop_headers: Vec<NodeIndex> = loops
+                .iter()
+                .filter(|l| l.body_blocks.contains(&node))
+                .map(|l| l.header)
+                .collect();
+
+            let mut cycles = 0u64;
+            let mut instr_iter = block.block.get_first_instruction();
+            while let Some(instr) = instr_iter {
+                instr_iter = instr.get_next_instruction();
+
+                let (Some(class), Some(addr)) =
+                    (Self::cached_access_class(&instr), layout.address(&instr))
+                else {
+                    cycles += Self::access_cost(&instr, platform, CacheAssumption::Cold);
+                    continue;
+                };
+
+                let timing = platform.get_timing(&class);
+                if matches!(class, InstructionClass::Store(_))
+                    && cache_result.write_policy == WritePolicy::WriteThrough
+                {
+                    cycles += timing.worst_case as u64;
+                    continue;
+                }
+                let access = cache_result.classify_access_in_loops(node, addr, &loop_headers);
+                cycles += Self::cached_access_cost(timing, access);
+
+                if access == CacheAccessClass::FirstMiss {
+                    if let Some(header) = cache_result.persistent_loop(addr, &loop_headers) {
+                        let latency = (timing.worst_case - timing.best_case) as u64;
+                        let miss = first_misses.entry((header, addr)).or_insert(0);
+                        *miss = (*miss).max(latency);
+                    }
+                }
+            }
+            timings.insert(block.id, cycles);
+        }
+
+        Self::charge_first_misses(&mut timings, &graph, &loops, first_misses);
+        timings
+    }
+
+    /// Calculate timing with an instruction-cache analysis of the function
+    ///
+    /// Blocks are laid out back to back in function order and their
+    /// fetches classified by [`InstructionCacheAnalysis`]. On top of the
+    /// block's instruction costs, a fetch that may miss every time pays the
+    /// cache's miss latency over its hit latency; a fetch persistent in a
+    /// loop pays it once, on the blocks entering that loop.
+    pub fn calculate_with_instruction_cache(
+        function: &FunctionValue,
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        instruction_cache: &CacheLevelConfig,
+    ) -> AHashMap<usize, u64> {
+        let mut timings = Self::calculate_block_timings(function, cfg, platform);
+        Self::charge_instruction_cache_misses(&mut timings, cfg, instruction_cache);
+        timings
+    }
+
+    /// Add the instruction-cache miss penalties of
+    /// [`calculate_with_instruction_cache`](Self::calculate_with_instruction_cache)
+    /// to block timings computed without them
+    pub fn charge_instruction_cache_misses(
+        timings: &mut AHashMap<usize, u64>,
+        cfg: &InkwellCFG,
+        instruction_cache: &CacheLevelConfig,
+    ) {
+        let result = InstructionCacheAnalysis::new(instruction_cache).analyze(cfg);
+        let penalty = instruction_cache
+            .miss_latency
+            .saturating_sub(instruction_cache.hit_latency) as u64;
+
+        let mut first_misses: AHashMap<(NodeIndex, u64), u64> = AHashMap::new();
+        for (&id, fetches) in &result.fetches {
+            *timings.entry(id).or_insert(0) += result.misses(id) as u64 * penalty;
+            for fetch in fetches {
+                if let (CacheAccessClass::FirstMiss, Some(header)) =
+                    (fetch.class, fetch.persistent_in)
+                {
+                    first_misses.insert((header, fetch.line), penalty);
+                }
+            }
+        }
+
+        let graph = cfg.to_petgraph();
+        let loops = LoopAnalyzer::analyze_loops(&graph);
+        Self::charge_first_misses(timings, &graph, &loops, first_misses);
+    }
+
+    /// Calculate timing with pipeline stalls from data hazards
+    ///
+    /// Each block's instructions issue one per cycle into an in-order
+    /// pipeline of `pipeline_depth` stages. A read of a value produced
+    /// earlier in the block stalls as [`HazardDetector::total_stall_cycles`]
+    /// computes, with or without `forwarding`, on top of the block's
+    /// instruction costs. Values from other blocks are assumed ready.
+    pub fn calculate_with_hazards(
+        function: &FunctionValue,
+        cfg: &InkwellCFG,
+        platform: &PlatformModel,
+        pipeline_depth: usize,
+        forwarding: bool,
+    ) -> AHashMap<usize, u64> {
+        let registers = SsaRegisters::from_blocks(cfg.blocks.iter().map(|block| block.block));
+        let detector = HazardDetector::new(pipeline_depth.max(1), forwarding);
+
+        let mut timings = Self::calculate_block_timings(function, cfg, platform);
+        for block in &cfg.blocks {
+            let stalls = detector.total_stall_cycles(&registers.block_dependencies(block.block));
+            *timings.entry(block.id).or_insert(0) += stalls as u64;
+        }
+        timings
+    }
+
+    /// Charge the miss latency of each line persistent in a loop, keyed by
+    /// loop header and line, to the blocks entering that loop
+    fn charge_first_misses(
+        timings: &mut AHashMap<usize, u64>,
+        graph: &CFG,
+        loops: &[Loop],
+        first_misses: AHashMap<(NodeIndex, u64), u64>,
+    ) {
+        for ((header, _), latency) in first_misses {
+            let Some(persistent_loop) = loops.iter().find(|l| l.header == header) else {
+                continue;
+            };
+            let mut entries: Vec<usize> = graph
+                .graph
+                .neighbors_directed(header, petgraph::Direction::Incoming)
+                .filter(|pred| !persistent_loop.body_blocks.contains(pred))
+                .map(|pred| graph.graph[pred].execution_count_var)
+                .collect();
+            // A loop headed by the entry block has no block before it
+            if entries.is_empty() {
+                entries.push(graph.graph[header].execution_count_var);
+            }
+            for id in entries {
+                *timings.entry(id).or_insert(0) += latency;
+            }
+        }
+    }
+
+    /// Cost of a cached load or store given its classification
+    ///
+    /// First misses pay the hit latency here; their miss is charged on loop
+    /// entry.
+    pub fn cached_access_cost(timing: Cycles, access: CacheAccessClass) -> u64 {
+        match access {
+            CacheAccessClass::AlwaysHit | CacheAccessClass::FirstMiss => timing.best_case as u64,
+            CacheAccessClass::AlwaysMiss | CacheAccessClass::Unknown => timing.worst_case as u64,
+        }
+    }
 
+    /// Timing class of a load or store that goes through the cache
+    ///
+    /// `None` for volatile accesses and for anything that isn't a load or
+    /// store.
+    fn cached_access_class(instr: &InstructionValue) -> Option<InstructionClass> {
+        if Self::is_volatile_access(instr) {
+            return None;
+        }
+        match instr.get_opcode() {
+            InstructionOpcode::Load => {
+                let flash = Self::accessed_pointer(instr).is_some_and(Self::is_constant_global);
+                Some(InstructionClass::Load(if flash {
+                    AccessType::Flash
+                } else {
+                    AccessType::Ram
+                }))
+            }
+            InstructionOpcode::Store => Some(InstructionClass::Store(AccessType::Ram)),
+            _ => None,
+        }
+    }
+
+    /// Pointer operand of a load or store
+    fn accessed_pointer<'ctx>(instr: &InstructionValue<'ctx>) -> Option<BasicValueEnum<'ctx>> {
+        let index = match instr.get_opcode() {
+            InstructionOpcode::Load => 0,
+            InstructionOpcode::Store => 1,
+            _ => return None,
+        };
+        instr.get_operand(index)?.value()
+    }
+
+    /// Whether a pointer is a constant global, which lives in Flash
+    fn is_constant_global(pointer: BasicValueEnum) -> bool {
+        let text = pointer.print_to_string().to_string();
+        text.starts_with('@')
+            && text.split_once('=').is_some_and(|(_, definition)| {
+                definition.split_whitespace().any(|w| w == "constant")
+            })
+    }
+
+    /// Add cache miss penalties to block timings
+    ///
+    /// Simple model: cold cache at function entry unless `cold_entry` is
+    /// false, warm cache for loops
+    fn apply_cache_miss_penalties(
+        timings: &mut AHashMap<usize, u64>,
+        cfg: &InkwellCFG,
+        cache_miss_penalty: u64,
+        cold_entry: bool,
+    ) {
         for block in &cfg.blocks {
             // Add cache miss penalty for first block (cold cache)
-            if block.id == cfg.entry_block {
+            if cold_entry && block.id == cfg.entry_block {
                 if let Some(timing) = timings.get_mut(&block.id) {
                     *timing += cache_miss_penalty;
                 }
@@ -169,8 +727,6 @@ impl InkwellTimingCalculator {
                 }
             }
         }
-
-        timings
     }
 
     /// Count memory operations in a basic block
@@ -275,6 +831,70 @@ mod tests {
         assert_eq!(phi_cost, 0, "PHI node should have zero cost");
     }
 
+    #[test]
+    fn test_cache_assumption_brackets_memory_cost() {
+        use crate::platform::CortexA53Model;
+
+        // RAM loads and stores take 1-6 cycles on the A53
+        let platform = CortexA53Model::new();
+        let cost = |opcode, cache| {
+            InkwellTimingCalculator::instruction_cost_assuming(&opcode, &platform, cache)
+        };
+
+        assert_eq!(cost(InstructionOpcode::Load, CacheAssumption::Cold), 6);
+        assert_eq!(cost(InstructionOpcode::Load, CacheAssumption::Warm), 1);
+        assert_eq!(
+            cost(InstructionOpcode::Store, CacheAssumption::Cold),
+            InkwellTimingCalculator::instruction_cost(&InstructionOpcode::Store, &platform)
+        );
+
+        // Only memory accesses depend on the assumption
+        assert_eq!(
+            cost(InstructionOpcode::Mul, CacheAssumption::Warm),
+            cost(InstructionOpcode::Mul, CacheAssumption::Cold)
+        );
+    }
+
+    #[test]
+    fn test_issue_cost_respects_width() {
+        // Scalar issue is the serial sum
+        assert_eq!(InkwellTimingCalculator::issue_cost(&mut [1, 3, 1, 2], 1), 7);
+
+        // Dual issue pairs the two slowest, then the two fastest
+        assert_eq!(InkwellTimingCalculator::issue_cost(&mut [1, 3, 1, 2], 2), 4);
+
+        // Zero width is treated as scalar
+        assert_eq!(InkwellTimingCalculator::issue_cost(&mut [2, 2], 0), 4);
+    }
+
+    #[test]
+    fn test_fetch_cost_respects_width() {
+        assert_eq!(InkwellTimingCalculator::fetch_cost(7, 1), 7);
+        assert_eq!(InkwellTimingCalculator::fetch_cost(7, 2), 4);
+        assert_eq!(InkwellTimingCalculator::fetch_cost(8, 4), 2);
+        assert_eq!(InkwellTimingCalculator::fetch_cost(0, 4), 0);
+
+        // Zero width is treated as single fetch
+        assert_eq!(InkwellTimingCalculator::fetch_cost(3, 0), 3);
+    }
+
+    #[test]
+    fn test_always_hit_access_cheaper_than_always_miss() {
+        use crate::platform::CortexA53Model;
+
+        let platform = CortexA53Model::new();
+        let timing = platform.get_timing(&InstructionClass::Load(AccessType::Ram));
+        let cost = |access| InkwellTimingCalculator::cached_access_cost(timing, access);
+
+        assert_eq!(cost(CacheAccessClass::AlwaysHit), 1);
+        assert_eq!(cost(CacheAccessClass::AlwaysMiss), 6);
+        assert!(cost(CacheAccessClass::AlwaysHit) < cost(CacheAccessClass::AlwaysMiss));
+
+        // Unknown is priced as a miss; a first miss pays its miss on loop entry
+        assert_eq!(cost(CacheAccessClass::Unknown), 6);
+        assert_eq!(cost(CacheAccessClass::FirstMiss), 1);
+    }
+
     #[test]
     fn test_instruction_cost_unreachable() {
         let platform = CortexM4Model::new();
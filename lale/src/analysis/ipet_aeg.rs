@@ -1,5 +1,6 @@
 use crate::aeg::compression::CompressedAEG;
 use crate::analysis::Loop;
+use crate::error::LaleError;
 use ahash::AHashMap;
 use good_lp::*;
 use petgraph::visit::EdgeRef;
@@ -12,7 +13,7 @@ pub struct AEGIPETSolver;
 impl AEGIPETSolver {
     /// Solve WCET using AEG-based IPET
     /// Key difference: variables per edge, edges have context-dependent weights
-    pub fn solve_wcet(aeg: &CompressedAEG, loops: &[Loop]) -> Result<u64, String> {
+    pub fn solve_wcet(aeg: &CompressedAEG, loops: &[Loop]) -> Result<u64, LaleError> {
         // Step 1: Create ILP variables (one per AEG edge)
         let mut vars = ProblemVariables::new();
         let mut edge_vars = AHashMap::new();
@@ -130,9 +131,7 @@ impl AEGIPETSolver {
         }
 
         // Step 4: Solve the ILP problem
-        let solution = problem
-            .solve()
-            .map_err(|e| format!("ILP solver failed: {:?}", e))?;
+        let solution = problem.solve()?;
 
         // Step 5: Extract WCET from solution
         let wcet = solution.eval(&objective);
@@ -144,7 +143,7 @@ impl AEGIPETSolver {
     pub fn extract_edge_counts(
         aeg: &CompressedAEG,
         _loops: &[Loop],
-    ) -> Result<AHashMap<petgraph::graph::EdgeIndex, u64>, String> {
+    ) -> Result<AHashMap<petgraph::graph::EdgeIndex, u64>, LaleError> {
         // Simplified: just solve WCET and return edge counts
         // In practice, would extract from the ILP solution
         let mut counts = AHashMap::new();
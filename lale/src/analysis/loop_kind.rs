@@ -0,0 +1,142 @@
+//! Classification of loops by what their exit conditions depend on
+//!
+//! A loop's bound is only as good as what its exit test compares against.
+//! Tracing the compared values back through the SSA graph tells the user
+//! which loops are safe as they are, which need context from the caller and
+//! which need a manual bound annotation.
+
+use crate::ir::InkwellCFG;
+use inkwell::values::{BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What a loop's trip count depends on, from best to worst bounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoopKind {
+    /// Exit test against compile-time constants only
+    Counted,
+    /// Exit test depends on function arguments; bounded per call site
+    Parametric,
+    /// Exit test depends on memory, call results or nothing at all
+    DataDependent,
+}
+
+impl LoopKind {
+    /// Whether the loop needs a bound annotation to be analyzed safely
+    pub fn needs_annotation(&self) -> bool {
+        *self == LoopKind::DataDependent
+    }
+
+    /// Advice shown next to the loop in reports
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            LoopKind::Counted => "counted, bound is a compile-time constant",
+            LoopKind::Parametric => {
+                "parametric, bound depends on arguments and needs caller context"
+            }
+            LoopKind::DataDependent => "data-dependent, annotate its bound",
+        }
+    }
+}
+
+/// Classify a loop given the ids of its blocks in `cfg`
+///
+/// Every conditional exit is classified by the values its condition is
+/// computed from, and the loop takes its best-bounded exit. A loop without
+/// conditional exits never terminates on its own and is data-dependent.
+pub fn classify_loop(cfg: &InkwellCFG, body: &HashSet<usize>) -> LoopKind {
+    let mut kind = None;
+    for &block_id in body {
+        if cfg.successors(block_id).iter().all(|to| body.contains(to)) {
+            continue;
+        }
+        let Some(condition) = cfg
+            .blocks
+            .get(block_id)
+            .and_then(|block| block.block.get_terminator())
+            .and_then(exit_condition)
+        else {
+            continue;
+        };
+
+        let exit_kind = trace(condition, &mut HashSet::new());
+        kind = Some(kind.map_or(exit_kind, |best: LoopKind| best.min(exit_kind)));
+    }
+    kind.unwrap_or(LoopKind::DataDependent)
+}
+
+/// Condition of a conditional branch or switch
+fn exit_condition(terminator: InstructionValue) -> Option<BasicValueEnum> {
+    match terminator.get_opcode() {
+        InstructionOpcode::Br if terminator.get_num_operands() == 3 => {
+            terminator.get_operand(0)?.value()
+        }
+        InstructionOpcode::Switch => terminator.get_operand(0)?.value(),
+        _ => None,
+    }
+}
+
+/// Worst dependency among the leaves a value is computed from
+///
+/// Phis are followed into both the entry and the back-edge value, so an
+/// induction variable is as good as its start and step; the cycle through
+/// the phi itself adds nothing.
+fn trace<'ctx>(
+    value: BasicValueEnum<'ctx>,
+    visited: &mut HashSet<InstructionValue<'ctx>>,
+) -> LoopKind {
+    let Some(instr) = value.as_instruction_value() else {
+        // Not computed by an instruction: a constant or an argument
+        return if is_constant(value) {
+            LoopKind::Counted
+        } else {
+            LoopKind::Parametric
+        };
+    };
+    if !visited.insert(instr) {
+        return LoopKind::Counted;
+    }
+
+    match instr.get_opcode() {
+        InstructionOpcode::Load
+        | InstructionOpcode::Call
+        | InstructionOpcode::Invoke
+        | InstructionOpcode::VAArg
+        | InstructionOpcode::AtomicRMW
+        | InstructionOpcode::AtomicCmpXchg
+        | InstructionOpcode::LandingPad => LoopKind::DataDependent,
+        _ => (0..instr.get_num_operands())
+            .filter_map(|i| instr.get_operand(i)?.value())
+            .map(|operand| trace(operand, visited))
+            .max()
+            .unwrap_or(LoopKind::Counted),
+    }
+}
+
+/// Whether a value is a compile-time constant
+fn is_constant(value: BasicValueEnum) -> bool {
+    match value {
+        BasicValueEnum::IntValue(int) => int.is_const(),
+        BasicValueEnum::FloatValue(float) => float.is_const(),
+        BasicValueEnum::PointerValue(ptr) => ptr.is_const(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_kinds_order_from_best_bounded() {
+        assert!(LoopKind::Counted < LoopKind::Parametric);
+        assert!(LoopKind::Parametric < LoopKind::DataDependent);
+        assert!(LoopKind::DataDependent.needs_annotation());
+        assert!(!LoopKind::Parametric.needs_annotation());
+        assert_eq!(
+            serde_json::to_string(&LoopKind::DataDependent).unwrap(),
+            "\"data-dependent\""
+        );
+    }
+}
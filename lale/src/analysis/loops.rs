@@ -40,7 +40,7 @@ impl LoopAnalyzer {
         let mut loops = Vec::new();
         for (tail, head) in back_edges {
             let body = Self::find_loop_body(cfg, head, tail);
-            let bounds = Self::extract_bounds(cfg, head, &body);
+            let bounds = Self::extract_bounds(cfg, head, tail, &body);
 
             loops.push(Loop {
                 header: head,
@@ -57,6 +57,25 @@ impl LoopAnalyzer {
         loops
     }
 
+    /// Check if a loop's bound comes from a user annotation rather than a heuristic
+    pub fn has_annotated_bound(cfg: &CFG, loop_info: &Loop) -> bool {
+        if loop_info
+            .back_edges
+            .iter()
+            .any(|&(tail, _)| cfg.graph[tail].loop_bound.is_some())
+        {
+            return true;
+        }
+
+        let label = &cfg.graph[loop_info.header].label;
+        label
+            .strip_prefix("bb.loop_")
+            .and_then(|bounds_str| bounds_str.split_once('_'))
+            .is_some_and(|(min_str, max_str)| {
+                min_str.parse::<u64>().is_ok() && max_str.parse::<u64>().is_ok()
+            })
+    }
+
     /// Find back edges (edges where target dominates source)
     fn find_back_edges(
         cfg: &CFG,
@@ -106,10 +125,15 @@ impl LoopAnalyzer {
     }
 
     /// Extract loop bounds from metadata or analysis
-    fn extract_bounds(cfg: &CFG, header: NodeIndex, body: &HashSet<NodeIndex>) -> LoopBounds {
+    fn extract_bounds(
+        cfg: &CFG,
+        header: NodeIndex,
+        latch: NodeIndex,
+        body: &HashSet<NodeIndex>,
+    ) -> LoopBounds {
         // Priority order:
         // 1. Check for user annotations in metadata
-        if let Some(bounds) = Self::check_metadata_bounds(cfg, header) {
+        if let Some(bounds) = Self::check_metadata_bounds(cfg, header, latch) {
             return bounds;
         }
 
@@ -128,7 +152,12 @@ impl LoopAnalyzer {
     }
 
     /// Check for loop bound metadata annotations
-    fn check_metadata_bounds(cfg: &CFG, header: NodeIndex) -> Option<LoopBounds> {
+    fn check_metadata_bounds(cfg: &CFG, header: NodeIndex, latch: NodeIndex) -> Option<LoopBounds> {
+        // `!llvm.loop` metadata sits on the back edge's branch
+        if let Some((min, max)) = cfg.graph[latch].loop_bound {
+            return Some(LoopBounds::Constant { min, max });
+        }
+
         // Check if block label contains loop bound annotation
         // Format: bb.loop_N_M where N=min, M=max iterations
         let block = &cfg.graph[header];
@@ -161,11 +190,10 @@ impl LoopAnalyzer {
         // Analyze instructions in loop header for comparison patterns
         let header_block = &cfg.graph[header];
 
-        // Look for common induction variable patterns in instruction names
+        // Pattern: icmp slt/ult i32 %i, constant. Checked before the label
+        // patterns below, which a phi or branch naming `for.inc` would match
         for instr in &header_block.instructions {
             let instr_lower = instr.to_lowercase();
-
-            // Pattern: icmp slt/ult i32 %i, constant
             if instr_lower.contains("icmp") {
                 // Extract comparison constant if present
                 if let Some(constant) = Self::extract_comparison_constant(&instr_lower) {
@@ -176,6 +204,11 @@ impl LoopAnalyzer {
                     });
                 }
             }
+        }
+
+        // Look for common induction variable patterns in instruction names
+        for instr in &header_block.instructions {
+            let instr_lower = instr.to_lowercase();
 
             // Pattern: for loop with known iteration count
             if instr_lower.contains("for.cond") || instr_lower.contains("for.inc") {
@@ -202,8 +235,9 @@ impl LoopAnalyzer {
     fn extract_comparison_constant(instr: &str) -> Option<u64> {
         // Look for numeric constants in comparison instructions
         // Pattern: "icmp ... i32 %var, 100" or "icmp ... 100, %var"
+        // Only whole operands count, not the width of `i32` or a `%5` value
         for token in instr.split_whitespace() {
-            if let Ok(val) = token.trim_matches(|c: char| !c.is_numeric()).parse::<u64>() {
+            if let Ok(val) = token.trim_end_matches(',').parse::<u64>() {
                 if val > 0 && val < 1000000 {
                     return Some(val);
                 }
@@ -300,4 +334,16 @@ mod tests {
         // Basic compilation test
         assert!(true);
     }
+
+    #[test]
+    fn test_comparison_constant_ignores_types_and_values() {
+        assert_eq!(
+            LoopAnalyzer::extract_comparison_constant("%cond = icmp slt i32 %i, 8"),
+            Some(8)
+        );
+        assert_eq!(
+            LoopAnalyzer::extract_comparison_constant("%3 = icmp ult i64 %2, %n, !dbg !17"),
+            None
+        );
+    }
 }
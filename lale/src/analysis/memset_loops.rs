@@ -0,0 +1,394 @@
+//! Recognition of zero-init loops as bulk memory operations
+//!
+//! Compilers don't always turn an initialization loop into `llvm.memset`.
+//! A single-block loop whose only side effect is a store of a constant
+//! through an address indexed by a counted induction variable is a memset
+//! in disguise: its trip count follows from the induction range, and it is
+//! costed like a bulk store of the whole range instead of per element.
+
+use crate::analysis::footprint::type_size;
+use crate::ir::{InkwellCFG, InkwellCFGBlock};
+use inkwell::values::{BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue};
+use inkwell::IntPredicate;
+
+/// Width of the stores a bulk memset issues
+pub const BULK_STORE_BYTES: u64 = 4;
+
+/// Loop recognized as a memset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemsetLoop {
+    /// Id of the loop block in the `InkwellCFG`
+    pub block_id: usize,
+
+    /// Label of the loop block
+    pub block: String,
+
+    /// Exact number of iterations
+    pub trip_count: u64,
+
+    /// Bytes stored per iteration
+    pub store_bytes: u64,
+}
+
+/// Counted induction variable `start, start + step, ...`
+struct Induction {
+    start: i128,
+    step: i128,
+
+    /// Exit condition tests the incremented value rather than the phi
+    compares_next: bool,
+}
+
+impl MemsetLoop {
+    /// Bytes written by the whole loop
+    pub fn total_bytes(&self) -> u64 {
+        self.trip_count.saturating_mul(self.store_bytes)
+    }
+
+    /// Cycles of the equivalent bulk operation given the cost of one store
+    pub fn bulk_cycles(&self, store_cycles: u64) -> u64 {
+        self.total_bytes()
+            .div_ceil(BULK_STORE_BYTES)
+            .saturating_mul(store_cycles)
+    }
+
+    /// Per-iteration share of the bulk cost, rounded up
+    pub fn iteration_cycles(&self, store_cycles: u64) -> u64 {
+        self.bulk_cycles(store_cycles)
+            .div_ceil(self.trip_count.max(1))
+    }
+}
+
+/// Find all memset-like loops of a function
+pub fn recognize_memset_loops(cfg: &InkwellCFG) -> Vec<MemsetLoop> {
+    cfg.blocks.iter().filter_map(recognize_block).collect()
+}
+
+/// Match a self-looping block containing a single constant store
+fn recognize_block(block: &InkwellCFGBlock) -> Option<MemsetLoop> {
+    let terminator = block.block.get_terminator()?;
+
+    // Conditional br: condition, false target, true target
+    if terminator.get_opcode() != InstructionOpcode::Br || terminator.get_num_operands() != 3 {
+        return None;
+    }
+    let false_target = terminator.get_operand(1)?.block()?;
+    let true_target = terminator.get_operand(2)?.block()?;
+    let continue_on_true = match (true_target == block.block, false_target == block.block) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => return None,
+    };
+
+    // Nothing but the store, address arithmetic and loop control
+    let mut store = None;
+    let mut instr_iter = block.block.get_first_instruction();
+    while let Some(instr) = instr_iter {
+        match instr.get_opcode() {
+            InstructionOpcode::Store => {
+                if store.replace(instr).is_some() {
+                    return None;
+                }
+            }
+            InstructionOpcode::Phi
+            | InstructionOpcode::GetElementPtr
+            | InstructionOpcode::Add
+            | InstructionOpcode::ICmp
+            | InstructionOpcode::Br
+            | InstructionOpcode::BitCast
+            | InstructionOpcode::ZExt
+            | InstructionOpcode::SExt
+            | InstructionOpcode::Trunc => {}
+            _ => return None,
+        }
+        instr_iter = instr.get_next_instruction();
+    }
+
+    // Volatile stores are device accesses, not initialization
+    let store = store?;
+    if store.get_volatile().unwrap_or(true) {
+        return None;
+    }
+    let value = operand(store, 0)?;
+    if !is_constant(value) {
+        return None;
+    }
+    let store_bytes = type_size(value.get_type())?;
+
+    let compare = operand(terminator, 0)?.as_instruction_value()?;
+    if compare.get_opcode() != InstructionOpcode::ICmp {
+        return None;
+    }
+    let mut predicate = compare.get_icmp_predicate()?;
+    let signed = matches!(
+        predicate,
+        IntPredicate::SLT | IntPredicate::SLE | IntPredicate::SGT | IntPredicate::SGE
+    );
+
+    // Counter on the left, constant bound on the right
+    let (lhs, rhs) = (operand(compare, 0)?, operand(compare, 1)?);
+    let (counter, bound) = match (int_constant(rhs, signed), int_constant(lhs, signed)) {
+        (Some(bound), _) => (lhs, bound),
+        (None, Some(bound)) => {
+            predicate = swapped(predicate);
+            (rhs, bound)
+        }
+        (None, None) => return None,
+    };
+    if !continue_on_true {
+        predicate = inverse(predicate);
+    }
+
+    let (phi, induction) = find_induction(counter.as_instruction_value()?, block, signed)?;
+    if !derives_from(operand(store, 1)?, phi) {
+        return None;
+    }
+
+    Some(MemsetLoop {
+        block_id: block.id,
+        block: block.name.clone(),
+        trip_count: trip_count(&induction, predicate, bound)?,
+        store_bytes,
+    })
+}
+
+/// Value operand of an instruction
+fn operand<'ctx>(instr: InstructionValue<'ctx>, index: u32) -> Option<BasicValueEnum<'ctx>> {
+    instr.get_operand(index)?.value()
+}
+
+/// Whether a stored value is a compile-time constant
+fn is_constant(value: BasicValueEnum) -> bool {
+    match value {
+        BasicValueEnum::IntValue(int) => int.is_const(),
+        BasicValueEnum::FloatValue(float) => float.is_const(),
+        BasicValueEnum::PointerValue(ptr) => ptr.is_null(),
+        _ => false,
+    }
+}
+
+/// Integer constant, extended according to the comparison's signedness
+fn int_constant(value: BasicValueEnum, signed: bool) -> Option<i128> {
+    let BasicValueEnum::IntValue(int) = value else {
+        return None;
+    };
+    if !int.is_const() {
+        return None;
+    }
+    if signed {
+        int.get_sign_extended_constant().map(i128::from)
+    } else {
+        int.get_zero_extended_constant().map(i128::from)
+    }
+}
+
+/// Phi of this block stepped by a constant `add`, reached from the compared counter
+fn find_induction<'ctx>(
+    counter: InstructionValue<'ctx>,
+    block: &InkwellCFGBlock<'ctx>,
+    signed: bool,
+) -> Option<(InstructionValue<'ctx>, Induction)> {
+    let in_block = |instr: InstructionValue| instr.get_parent() == Some(block.block);
+    if !in_block(counter) {
+        return None;
+    }
+
+    let (phi, compares_next) = match counter.get_opcode() {
+        InstructionOpcode::Phi => (counter, false),
+        InstructionOpcode::Add => {
+            let phi = (0..2).find_map(|i| {
+                operand(counter, i)?
+                    .as_instruction_value()
+                    .filter(|op| op.get_opcode() == InstructionOpcode::Phi && in_block(*op))
+            })?;
+            (phi, true)
+        }
+        _ => return None,
+    };
+    if phi.get_num_operands() != 2 {
+        return None;
+    }
+
+    // One incoming value is the increment, the other the constant start
+    let mut start = None;
+    let mut step = None;
+    for i in 0..2 {
+        let incoming = operand(phi, i)?;
+        match incoming.as_instruction_value() {
+            Some(next) if next.get_opcode() == InstructionOpcode::Add && in_block(next) => {
+                let (a, b) = (operand(next, 0)?, operand(next, 1)?);
+                let step_value = if a.as_instruction_value() == Some(phi) {
+                    b
+                } else if b.as_instruction_value() == Some(phi) {
+                    a
+                } else {
+                    return None;
+                };
+                if compares_next && next != counter {
+                    return None;
+                }
+                step = int_constant(step_value, true);
+            }
+            _ => start = int_constant(incoming, signed),
+        }
+    }
+
+    let induction = Induction {
+        start: start?,
+        step: step?,
+        compares_next,
+    };
+    Some((phi, induction))
+}
+
+/// Whether an address is computed from the induction variable
+fn derives_from(value: BasicValueEnum, phi: InstructionValue) -> bool {
+    let Some(instr) = value.as_instruction_value() else {
+        return false;
+    };
+    if instr == phi {
+        return true;
+    }
+    match instr.get_opcode() {
+        InstructionOpcode::GetElementPtr => (1..instr.get_num_operands())
+            .any(|i| operand(instr, i).is_some_and(|op| derives_from(op, phi))),
+        InstructionOpcode::ZExt
+        | InstructionOpcode::SExt
+        | InstructionOpcode::Trunc
+        | InstructionOpcode::BitCast => operand(instr, 0).is_some_and(|op| derives_from(op, phi)),
+        _ => false,
+    }
+}
+
+/// Predicate with its operands exchanged
+pub(crate) fn swapped(predicate: IntPredicate) -> IntPredicate {
+    match predicate {
+        IntPredicate::ULT => IntPredicate::UGT,
+        IntPredicate::ULE => IntPredicate::UGE,
+        IntPredicate::UGT => IntPredicate::ULT,
+        IntPredicate::UGE => IntPredicate::ULE,
+        IntPredicate::SLT => IntPredicate::SGT,
+        IntPredicate::SLE => IntPredicate::SGE,
+        IntPredicate::SGT => IntPredicate::SLT,
+        IntPredicate::SGE => IntPredicate::SLE,
+        other => other,
+    }
+}
+
+/// Negated predicate
+pub(crate) fn inverse(predicate: IntPredicate) -> IntPredicate {
+    match predicate {
+        IntPredicate::EQ => IntPredicate::NE,
+        IntPredicate::NE => IntPredicate::EQ,
+        IntPredicate::ULT => IntPredicate::UGE,
+        IntPredicate::ULE => IntPredicate::UGT,
+        IntPredicate::UGT => IntPredicate::ULE,
+        IntPredicate::UGE => IntPredicate::ULT,
+        IntPredicate::SLT => IntPredicate::SGE,
+        IntPredicate::SLE => IntPredicate::SGT,
+        IntPredicate::SGT => IntPredicate::SLE,
+        IntPredicate::SGE => IntPredicate::SLT,
+    }
+}
+
+/// Iterations of a bottom-tested loop that continues while `counter PRED bound`
+///
+/// Only upward-counting loops are recognized.
+fn trip_count(induction: &Induction, predicate: IntPredicate, bound: i128) -> Option<u64> {
+    let step = induction.step;
+    if step <= 0 {
+        return None;
+    }
+
+    // Counter value tested after the first iteration
+    let first = if induction.compares_next {
+        induction.start + step
+    } else {
+        induction.start
+    };
+
+    let exclusive_bound = match predicate {
+        IntPredicate::ULT | IntPredicate::SLT => bound,
+        IntPredicate::ULE | IntPredicate::SLE => bound + 1,
+        IntPredicate::NE => {
+            let distance = bound - first;
+            if distance < 0 || distance % step != 0 {
+                return None;
+            }
+            return u64::try_from(distance / step + 1).ok();
+        }
+        _ => return None,
+    };
+
+    if first >= exclusive_bound {
+        return Some(1);
+    }
+    u64::try_from((exclusive_bound - first + step - 1) / step + 1).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn induction(start: i128, step: i128, compares_next: bool) -> Induction {
+        Induction {
+            start,
+            step,
+            compares_next,
+        }
+    }
+
+    #[test]
+    fn test_trip_count_from_induction_range() {
+        // for (i = 0; i < 64; i++), rotated: i.next < 64
+        assert_eq!(
+            trip_count(&induction(0, 1, true), IntPredicate::ULT, 64),
+            Some(64)
+        );
+        // i.next != 64
+        assert_eq!(
+            trip_count(&induction(0, 1, true), IntPredicate::NE, 64),
+            Some(64)
+        );
+        // i <= 63 tested on the phi runs one extra body
+        assert_eq!(
+            trip_count(&induction(0, 1, false), IntPredicate::SLE, 63),
+            Some(65)
+        );
+        // Stride 4 over 0..100
+        assert_eq!(
+            trip_count(&induction(0, 4, true), IntPredicate::ULT, 100),
+            Some(25)
+        );
+        // Bottom-tested loops run at least once
+        assert_eq!(
+            trip_count(&induction(10, 1, true), IntPredicate::ULT, 5),
+            Some(1)
+        );
+
+        // Stride that skips the != bound, or counting down
+        assert_eq!(
+            trip_count(&induction(0, 3, true), IntPredicate::NE, 64),
+            None
+        );
+        assert_eq!(
+            trip_count(&induction(64, -1, true), IntPredicate::NE, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bulk_cost_uses_word_stores() {
+        let byte_loop = MemsetLoop {
+            block_id: 1,
+            block: "for.body".to_string(),
+            trip_count: 100,
+            store_bytes: 1,
+        };
+
+        // 100 bytes as 25 word stores of 2 cycles
+        assert_eq!(byte_loop.total_bytes(), 100);
+        assert_eq!(byte_loop.bulk_cycles(2), 50);
+        assert_eq!(byte_loop.iteration_cycles(2), 1);
+    }
+}
@@ -1,12 +1,30 @@
 pub mod cache;
+pub mod dead_blocks;
+pub mod flow_facts;
+pub mod footprint;
 pub mod inkwell_timing;
 pub mod ipet;
 pub mod ipet_aeg;
+pub mod loop_kind;
 pub mod loops;
+pub mod memset_loops;
+pub mod poison;
+pub mod profile;
+pub mod smt;
 pub mod timing;
+pub mod warning;
 
-pub use inkwell_timing::InkwellTimingCalculator;
-pub use ipet::IPETSolver;
+pub use dead_blocks::{dead_blocks, DeadBlock, DeadBlockKind};
+pub use flow_facts::{loop_key, FlowFactStore};
+pub use footprint::{memory_footprint, memory_footprint_with_line_size};
+pub use inkwell_timing::{CacheAssumption, CostFn, InkwellTimingCalculator};
+pub use ipet::{IPETSolver, InfeasiblePath};
 pub use ipet_aeg::AEGIPETSolver;
+pub use loop_kind::{classify_loop, LoopKind};
 pub use loops::{Loop, LoopAnalyzer, LoopBounds};
+pub use memset_loops::{recognize_memset_loops, MemsetLoop};
+pub use poison::poison_branches;
+pub use profile::{EdgeCount, FunctionProfile, LoopObservation, Profile};
+pub use smt::SmtFeasibility;
 pub use timing::{Cycles, InstructionClass};
+pub use warning::{AnalysisWarning, WarningKind};
@@ -0,0 +1,72 @@
+//! Detection of branches on `undef` and `poison`
+//!
+//! A branch whose condition is computed from `undef` or `poison`, directly
+//! or through a `freeze`, may go either way. The analysis never prunes a
+//! successor as infeasible, so every such branch is costed on its worst
+//! side; reporting them makes that assumption visible instead of silent.
+
+use crate::ir::InkwellCFG;
+use inkwell::values::{AnyValue, BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue};
+use std::collections::HashSet;
+
+/// Labels of the blocks whose conditional branch or switch depends on
+/// `undef` or `poison`, in block order
+pub fn poison_branches(cfg: &InkwellCFG) -> Vec<String> {
+    cfg.blocks
+        .iter()
+        .filter(|block| {
+            block
+                .block
+                .get_terminator()
+                .and_then(branch_condition)
+                .is_some_and(|condition| derives_from_poison(condition, &mut HashSet::new()))
+        })
+        .map(|block| block.name.clone())
+        .collect()
+}
+
+/// Condition of a conditional branch or switch
+fn branch_condition(terminator: InstructionValue) -> Option<BasicValueEnum> {
+    match terminator.get_opcode() {
+        InstructionOpcode::Br if terminator.get_num_operands() == 3 => {
+            terminator.get_operand(0)?.value()
+        }
+        InstructionOpcode::Switch => terminator.get_operand(0)?.value(),
+        _ => None,
+    }
+}
+
+/// Whether any value a value is computed from is `undef` or `poison`
+///
+/// Memory and call results are opaque: what they hold is not traced.
+fn derives_from_poison<'ctx>(
+    value: BasicValueEnum<'ctx>,
+    visited: &mut HashSet<InstructionValue<'ctx>>,
+) -> bool {
+    let Some(instr) = value.as_instruction_value() else {
+        return is_undef_or_poison(value);
+    };
+    if !visited.insert(instr) {
+        return false;
+    }
+
+    match instr.get_opcode() {
+        InstructionOpcode::Load
+        | InstructionOpcode::Call
+        | InstructionOpcode::Invoke
+        | InstructionOpcode::AtomicRMW
+        | InstructionOpcode::AtomicCmpXchg => false,
+        _ => (0..instr.get_num_operands())
+            .filter_map(|i| instr.get_operand(i)?.value())
+            .any(|operand| derives_from_poison(operand, visited)),
+    }
+}
+
+/// Whether a value is an `undef` or `poison` constant
+fn is_undef_or_poison(value: BasicValueEnum) -> bool {
+    match value {
+        BasicValueEnum::IntValue(int) => int.is_undef() || int.is_poison(),
+        BasicValueEnum::PointerValue(ptr) => ptr.is_undef() || ptr.is_poison(),
+        _ => false,
+    }
+}
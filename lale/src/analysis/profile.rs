@@ -0,0 +1,255 @@
+//! Profile-guided edge frequencies
+//!
+//! Edge counts of a profiled run, e.g. read out of a `.profdata` for an
+//! instrumented build, supplied as JSON. Observed counts are not a safe
+//! bound, so they never tighten the WCET. They give a typical-case estimate
+//! with every IPET edge frequency fixed to its observed value, and a check of
+//! loop bounds against the iterations the loops actually ran.
+
+use crate::analysis::{Loop, LoopBounds};
+use crate::error::LaleError;
+use crate::ir::CFG;
+use ahash::AHashMap;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Loop bounds this many times above the observed iterations are reported as loose
+pub const LOOSE_BOUND_FACTOR: u64 = 10;
+
+/// Observed edge counts of a program run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// Profile of each function, by name
+    pub functions: AHashMap<String, FunctionProfile>,
+}
+
+/// Observed counts of one function
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionProfile {
+    /// Number of calls
+    pub entry_count: u64,
+
+    #[serde(default)]
+    pub edges: Vec<EdgeCount>,
+}
+
+/// Times control flowed from one block to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeCount {
+    pub from: String,
+    pub to: String,
+    pub count: u64,
+}
+
+/// Loop bound compared with the profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoopObservation {
+    /// Label of the loop header
+    pub header: String,
+
+    /// Bound used by the analysis, if constant
+    pub bound: Option<u64>,
+
+    /// Header executions per loop entry, averaged over entries and rounded up
+    pub observed_iterations: u64,
+}
+
+impl Profile {
+    /// Load a JSON profile
+    pub fn load(path: &Path) -> Result<Self, LaleError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| LaleError::io(format!("Failed to read profile {}", path.display()), e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            LaleError::Config(format!("Failed to parse profile {}: {}", path.display(), e))
+        })
+    }
+
+    /// Profile of a function, if it was recorded
+    pub fn function(&self, name: &str) -> Option<&FunctionProfile> {
+        self.functions.get(name)
+    }
+}
+
+impl FunctionProfile {
+    /// Execution count of each block
+    ///
+    /// A block runs as often as control enters it: the sum of its incoming
+    /// edge counts, plus the calls for the entry block. Edges into labels the
+    /// CFG doesn't have are ignored.
+    pub fn block_counts(&self, cfg: &CFG) -> AHashMap<NodeIndex, u64> {
+        let mut counts = AHashMap::new();
+        counts.insert(cfg.entry, self.entry_count);
+
+        for edge in &self.edges {
+            if let Some(&to) = cfg.label_to_node.get(&edge.to) {
+                *counts.entry(to).or_insert(0) += edge.count;
+            }
+        }
+
+        counts
+    }
+
+    /// Typical cycles per call with every block run as often as profiled
+    ///
+    /// This is the IPET objective with each edge frequency fixed to its
+    /// profiled count, divided by the number of calls. `None` if the function
+    /// was never called.
+    pub fn typical_cycles(&self, cfg: &CFG, block_timings: &AHashMap<usize, u64>) -> Option<u64> {
+        if self.entry_count == 0 {
+            return None;
+        }
+
+        let total = self
+            .block_counts(cfg)
+            .iter()
+            .map(|(&node, &count)| {
+                let cycles = block_timings
+                    .get(&cfg.graph[node].execution_count_var)
+                    .copied()
+                    .unwrap_or(0);
+                count.saturating_mul(cycles)
+            })
+            .fold(0u64, |acc, cycles| acc.saturating_add(cycles));
+
+        Some(total.div_ceil(self.entry_count))
+    }
+
+    /// Observed iterations of every loop the profile entered
+    pub fn loop_observations(&self, cfg: &CFG, loops: &[Loop]) -> Vec<LoopObservation> {
+        let counts = self.block_counts(cfg);
+
+        loops
+            .iter()
+            .filter_map(|loop_info| {
+                let header = &cfg.graph[loop_info.header].label;
+
+                // Entries come from outside the loop, or from the caller
+                let calls = if loop_info.header == cfg.entry {
+                    self.entry_count
+                } else {
+                    0
+                };
+                let entries: u64 = self
+                    .edges
+                    .iter()
+                    .filter(|edge| &edge.to == header)
+                    .filter(|edge| {
+                        cfg.label_to_node
+                            .get(&edge.from)
+                            .is_some_and(|from| !loop_info.body_blocks.contains(from))
+                    })
+                    .map(|edge| edge.count)
+                    .sum::<u64>()
+                    + calls;
+                if entries == 0 {
+                    return None;
+                }
+
+                let header_count = counts.get(&loop_info.header).copied().unwrap_or(0);
+                Some(LoopObservation {
+                    header: header.clone(),
+                    bound: match loop_info.bounds {
+                        LoopBounds::Constant { max, .. } => Some(max),
+                        _ => None,
+                    },
+                    observed_iterations: header_count.div_ceil(entries),
+                })
+            })
+            .collect()
+    }
+}
+
+impl LoopObservation {
+    /// The profile ran the loop more often than its bound allows
+    pub fn exceeds_bound(&self) -> bool {
+        self.bound
+            .is_some_and(|bound| self.observed_iterations > bound)
+    }
+
+    /// The bound is far above the iterations the profile observed
+    pub fn is_loose(&self) -> bool {
+        self.bound.is_some_and(|bound| {
+            self.observed_iterations > 0
+                && bound > self.observed_iterations.saturating_mul(LOOSE_BOUND_FACTOR)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BasicBlock, EdgeType};
+    use petgraph::graph::DiGraph;
+
+    fn edge(from: &str, to: &str, count: u64) -> EdgeCount {
+        EdgeCount {
+            from: from.to_string(),
+            to: to.to_string(),
+            count,
+        }
+    }
+
+    /// entry -> loop -> loop -> exit
+    fn loop_cfg() -> (CFG, Vec<Loop>) {
+        let mut graph = DiGraph::new();
+        let mut label_to_node = AHashMap::new();
+        let mut nodes = Vec::new();
+        for (id, label) in ["entry", "loop", "exit"].iter().enumerate() {
+            let node = graph.add_node(BasicBlock {
+                label: label.to_string(),
+                instructions: vec![],
+                execution_count_var: id,
+                loop_bound: None,
+            });
+            label_to_node.insert(label.to_string(), node);
+            nodes.push(node);
+        }
+        graph.add_edge(nodes[0], nodes[1], EdgeType::Direct);
+        graph.add_edge(nodes[1], nodes[1], EdgeType::ConditionalTrue);
+        graph.add_edge(nodes[1], nodes[2], EdgeType::ConditionalFalse);
+
+        let cfg = CFG {
+            graph,
+            entry: nodes[0],
+            exits: vec![nodes[2]],
+            label_to_node,
+        };
+        let loops = vec![Loop {
+            header: nodes[1],
+            back_edges: vec![(nodes[1], nodes[1])],
+            body_blocks: [nodes[1]].into_iter().collect(),
+            nesting_level: 0,
+            bounds: LoopBounds::Constant { min: 0, max: 10000 },
+        }];
+        (cfg, loops)
+    }
+
+    #[test]
+    fn test_profile_typical_cycles_and_loop_observations() {
+        let (cfg, loops) = loop_cfg();
+        // 2 calls, 50 iterations each
+        let profile = FunctionProfile {
+            entry_count: 2,
+            edges: vec![
+                edge("entry", "loop", 2),
+                edge("loop", "loop", 98),
+                edge("loop", "exit", 2),
+            ],
+        };
+
+        let timings: AHashMap<usize, u64> = [(0, 4), (1, 10), (2, 6)].into_iter().collect();
+        // (2 * 4 + 100 * 10 + 2 * 6) / 2
+        assert_eq!(profile.typical_cycles(&cfg, &timings), Some(510));
+
+        let observations = profile.loop_observations(&cfg, &loops);
+        assert_eq!(observations.len(), 1);
+        assert_eq!(observations[0].observed_iterations, 50);
+        assert!(observations[0].is_loose());
+        assert!(!observations[0].exceeds_bound());
+
+        let uncalled = FunctionProfile::default();
+        assert_eq!(uncalled.typical_cycles(&cfg, &timings), None);
+        assert!(uncalled.loop_observations(&cfg, &loops).is_empty());
+    }
+}
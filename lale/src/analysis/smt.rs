@@ -0,0 +1,404 @@
+//! SMT-backed pruning of infeasible paths
+//!
+//! For short functions, the conditions of the branches guarding two blocks
+//! are encoded as bit-vector formulas and handed to an external SMT solver
+//! as SMT-LIB 2 on stdin (`z3 -in` by default). A pair whose conditions
+//! cannot hold together becomes an [`InfeasiblePath`] for
+//! [`IPETSolver::solve_wcet_with_constraints`](crate::analysis::IPETSolver::solve_wcet_with_constraints).
+//!
+//! Only blocks outside every loop are considered: there, each SSA value has
+//! a single value per call, so two conditions over the same values can be
+//! checked together. Values the encoding does not model, such as loads,
+//! calls and phis, are free variables, so a pair is pruned only if no input
+//! at all takes both branches.
+
+use crate::analysis::{InfeasiblePath, Loop};
+use crate::error::LaleError;
+use crate::ir::{InkwellCFG, CFG};
+use ahash::AHashMap;
+use inkwell::values::{BasicValueEnum, InstructionOpcode, InstructionValue, IntValue};
+use inkwell::IntPredicate;
+use petgraph::graph::NodeIndex;
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Solver run when none is configured
+pub const DEFAULT_SMT_SOLVER: &str = "z3";
+
+/// Largest function, in basic blocks, checked by default
+pub const DEFAULT_SMT_MAX_BLOCKS: usize = 32;
+
+/// Feasibility check of branch pairs by an external SMT solver
+#[derive(Debug, Clone)]
+pub struct SmtFeasibility {
+    solver: String,
+    solver_args: Vec<String>,
+    max_blocks: usize,
+}
+
+impl Default for SmtFeasibility {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SmtFeasibility {
+    /// `z3 -in` on functions of up to [`DEFAULT_SMT_MAX_BLOCKS`] blocks
+    pub fn new() -> Self {
+        Self {
+            solver: DEFAULT_SMT_SOLVER.to_string(),
+            solver_args: vec!["-in".to_string()],
+            max_blocks: DEFAULT_SMT_MAX_BLOCKS,
+        }
+    }
+
+    /// Run another solver; it must read SMT-LIB 2 from stdin and answer each
+    /// `check-sat` on its own line
+    pub fn with_solver(mut self, command: impl Into<String>, args: Vec<String>) -> Self {
+        self.solver = command.into();
+        self.solver_args = args;
+        self
+    }
+
+    /// Skip functions with more than `max_blocks` basic blocks
+    pub fn with_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = max_blocks;
+        self
+    }
+
+    pub fn max_blocks(&self) -> usize {
+        self.max_blocks
+    }
+
+    /// Pairs of blocks no execution of the function runs both of
+    ///
+    /// `graph` and `loops` are the petgraph CFG of `cfg` and its loops, as
+    /// handed to IPET. Functions over the size limit yield no pairs.
+    pub fn infeasible_paths(
+        &self,
+        cfg: &InkwellCFG,
+        graph: &CFG,
+        loops: &[Loop],
+    ) -> Result<Vec<InfeasiblePath>, LaleError> {
+        if cfg.blocks.len() > self.max_blocks {
+            return Ok(Vec::new());
+        }
+
+        let in_loop: HashSet<usize> = loops
+            .iter()
+            .flat_map(|loop_info| &loop_info.body_blocks)
+            .map(|&node| graph.graph[node].execution_count_var)
+            .collect();
+
+        let mut encoder = Encoder::default();
+        let guards = guarded_blocks(cfg, &in_loop, &mut encoder);
+
+        let mut pairs = Vec::new();
+        let mut script = String::from("(set-logic QF_BV)\n");
+        for declaration in &encoder.declarations {
+            script.push_str(declaration);
+            script.push('\n');
+        }
+        for (i, a) in guards.iter().enumerate() {
+            // Both successors of one branch already exclude each other
+            for b in guards[i + 1..].iter().filter(|b| b.branch != a.branch) {
+                script.push_str(&format!(
+                    "(push 1)\n(assert (and {} {}))\n(check-sat)\n(pop 1)\n",
+                    a.condition, b.condition
+                ));
+                pairs.push((a.block, b.block));
+            }
+        }
+        if pairs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let satisfiable = self.check(&script, pairs.len())?;
+        let node = |block_id: usize| -> Option<NodeIndex> {
+            graph
+                .graph
+                .node_indices()
+                .find(|&idx| graph.graph[idx].execution_count_var == block_id)
+        };
+        Ok(pairs
+            .into_iter()
+            .zip(satisfiable)
+            .filter(|&(_, sat)| !sat)
+            .filter_map(|((a, b), _)| {
+                Some(InfeasiblePath {
+                    block_a: node(a)?,
+                    block_b: node(b)?,
+                })
+            })
+            .collect())
+    }
+
+    /// Run the solver on a script with `queries` `check-sat` commands and
+    /// return whether each one may be satisfiable
+    fn check(&self, script: &str, queries: usize) -> Result<Vec<bool>, LaleError> {
+        let context = || format!("Failed to run SMT solver '{}'", self.solver);
+        let mut child = Command::new(&self.solver)
+            .args(&self.solver_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| LaleError::io(context(), e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(script.as_bytes())
+                .map_err(|e| LaleError::io(context(), e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| LaleError::io(context(), e))?;
+
+        parse_answers(&String::from_utf8_lossy(&output.stdout), queries).ok_or_else(|| {
+            LaleError::Analysis(format!(
+                "SMT solver '{}' gave no answer to {} queries: {}",
+                self.solver,
+                queries,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        })
+    }
+}
+
+/// Block entered only from one side of a conditional branch
+struct Guard {
+    block: usize,
+    /// Block ending in the branch
+    branch: usize,
+    /// SMT-LIB formula true when the branch goes to `block`
+    condition: String,
+}
+
+/// Blocks outside loops whose only predecessor is a conditional branch,
+/// also outside loops, with the condition of entering them
+fn guarded_blocks<'ctx>(
+    cfg: &InkwellCFG<'ctx>,
+    in_loop: &HashSet<usize>,
+    encoder: &mut Encoder<'ctx>,
+) -> Vec<Guard> {
+    let block_id = |target| cfg.blocks.iter().position(|b| b.block == target);
+
+    let mut guards = Vec::new();
+    for block in cfg.blocks.iter().filter(|b| !in_loop.contains(&b.id)) {
+        let Some(terminator) = block.block.get_terminator() else {
+            continue;
+        };
+        if terminator.get_opcode() != InstructionOpcode::Br || terminator.get_num_operands() != 3 {
+            continue;
+        }
+        let Some(condition) = operand(terminator, 0).and_then(|c| encoder.term(c)) else {
+            continue;
+        };
+
+        // br i1 %c, label %true, label %false stores the false target first
+        let targets = [
+            (2, format!("(= {} #b1)", condition)),
+            (1, format!("(= {} #b0)", condition)),
+        ];
+        for (index, taken) in targets {
+            let Some(target) = terminator
+                .get_operand(index)
+                .and_then(|op| op.block())
+                .and_then(block_id)
+            else {
+                continue;
+            };
+            if !in_loop.contains(&target) && cfg.predecessors(target) == [block.id] {
+                guards.push(Guard {
+                    block: target,
+                    branch: block.id,
+                    condition: taken,
+                });
+            }
+        }
+    }
+    guards
+}
+
+/// Value operand of an instruction
+fn operand<'ctx>(instr: InstructionValue<'ctx>, index: u32) -> Option<BasicValueEnum<'ctx>> {
+    instr.get_operand(index)?.value()
+}
+
+/// Integer values as SMT-LIB bit-vector constants
+///
+/// Every value gets one `define-fun` if its instruction is modeled, else one
+/// `declare-const`, in dependency order, so shared subterms are written once.
+#[derive(Default)]
+struct Encoder<'ctx> {
+    names: AHashMap<IntValue<'ctx>, String>,
+    declarations: Vec<String>,
+}
+
+impl<'ctx> Encoder<'ctx> {
+    /// Term for an integer value, `None` for other types
+    fn term(&mut self, value: BasicValueEnum<'ctx>) -> Option<String> {
+        let BasicValueEnum::IntValue(int) = value else {
+            return None;
+        };
+        let width = int.get_type().get_bit_width();
+        if int.is_const() {
+            if let Some(constant) = int.get_zero_extended_constant() {
+                return Some(literal(constant, width));
+            }
+        }
+        if let Some(name) = self.names.get(&int) {
+            return Some(name.clone());
+        }
+
+        let name = format!("v{}", self.names.len());
+        let sort = format!("(_ BitVec {})", width);
+        match int
+            .as_instruction()
+            .and_then(|instr| self.expression(instr, width))
+        {
+            Some(expression) => self
+                .declarations
+                .push(format!("(define-fun {} () {} {})", name, sort, expression)),
+            None => self
+                .declarations
+                .push(format!("(declare-const {} {})", name, sort)),
+        }
+        self.names.insert(int, name.clone());
+        Some(name)
+    }
+
+    /// Expression of a modeled instruction
+    fn expression(&mut self, instr: InstructionValue<'ctx>, width: u32) -> Option<String> {
+        let opcode = instr.get_opcode();
+        let binary = match opcode {
+            InstructionOpcode::Add => Some("bvadd"),
+            InstructionOpcode::Sub => Some("bvsub"),
+            InstructionOpcode::Mul => Some("bvmul"),
+            InstructionOpcode::And => Some("bvand"),
+            InstructionOpcode::Or => Some("bvor"),
+            InstructionOpcode::Xor => Some("bvxor"),
+            _ => None,
+        };
+        if let Some(op) = binary {
+            let lhs = self.term(operand(instr, 0)?)?;
+            let rhs = self.term(operand(instr, 1)?)?;
+            return Some(format!("({} {} {})", op, lhs, rhs));
+        }
+
+        match opcode {
+            // Shifts by the width or more are poison; model only constant
+            // amounts below it
+            InstructionOpcode::Shl | InstructionOpcode::LShr | InstructionOpcode::AShr => {
+                let BasicValueEnum::IntValue(amount) = operand(instr, 1)? else {
+                    return None;
+                };
+                if amount.get_zero_extended_constant()? >= u64::from(width) {
+                    return None;
+                }
+                let op = match opcode {
+                    InstructionOpcode::Shl => "bvshl",
+                    InstructionOpcode::LShr => "bvlshr",
+                    _ => "bvashr",
+                };
+                let value = self.term(operand(instr, 0)?)?;
+                let amount = self.term(BasicValueEnum::IntValue(amount))?;
+                Some(format!("({} {} {})", op, value, amount))
+            }
+            InstructionOpcode::ZExt | InstructionOpcode::SExt | InstructionOpcode::Trunc => {
+                let source = operand(instr, 0)?;
+                let BasicValueEnum::IntValue(source_int) = source else {
+                    return None;
+                };
+                let source_width = source_int.get_type().get_bit_width();
+                let value = self.term(source)?;
+                Some(match opcode {
+                    InstructionOpcode::ZExt => format!(
+                        "((_ zero_extend {}) {})",
+                        width.checked_sub(source_width)?,
+                        value
+                    ),
+                    InstructionOpcode::SExt => format!(
+                        "((_ sign_extend {}) {})",
+                        width.checked_sub(source_width)?,
+                        value
+                    ),
+                    _ => format!("((_ extract {} 0) {})", width.checked_sub(1)?, value),
+                })
+            }
+            InstructionOpcode::ICmp => {
+                let op = match instr.get_icmp_predicate()? {
+                    IntPredicate::EQ => "=",
+                    IntPredicate::NE => "distinct",
+                    IntPredicate::UGT => "bvugt",
+                    IntPredicate::UGE => "bvuge",
+                    IntPredicate::ULT => "bvult",
+                    IntPredicate::ULE => "bvule",
+                    IntPredicate::SGT => "bvsgt",
+                    IntPredicate::SGE => "bvsge",
+                    IntPredicate::SLT => "bvslt",
+                    IntPredicate::SLE => "bvsle",
+                };
+                let lhs = self.term(operand(instr, 0)?)?;
+                let rhs = self.term(operand(instr, 1)?)?;
+                Some(format!("(ite ({} {} {}) #b1 #b0)", op, lhs, rhs))
+            }
+            InstructionOpcode::Select => {
+                let condition = self.term(operand(instr, 0)?)?;
+                let then = self.term(operand(instr, 1)?)?;
+                let otherwise = self.term(operand(instr, 2)?)?;
+                Some(format!(
+                    "(ite (= {} #b1) {} {})",
+                    condition, then, otherwise
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Bit-vector literal of a constant
+fn literal(value: u64, width: u32) -> String {
+    format!("(_ bv{} {})", value, width)
+}
+
+/// Whether each of `queries` answers may be satisfiable: `unsat` is the only
+/// answer that proves a pair infeasible, `unknown` keeps it
+fn parse_answers(output: &str, queries: usize) -> Option<Vec<bool>> {
+    let answers: Vec<bool> = output
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| match line {
+            "sat" | "unknown" => Some(true),
+            "unsat" => Some(false),
+            _ => None,
+        })
+        .collect();
+    (answers.len() == queries).then_some(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_keep_pairs_unless_unsat() {
+        assert_eq!(
+            parse_answers("sat\nunsat\n unknown \n", 3),
+            Some(vec![true, false, true])
+        );
+
+        // An error line in place of an answer
+        assert_eq!(parse_answers("(error \"line 3\")\nsat\n", 2), None);
+    }
+
+    #[test]
+    fn test_missing_solver_is_an_io_error() {
+        let smt = SmtFeasibility::new().with_solver("lale-no-such-smt-solver", vec![]);
+        assert!(matches!(
+            smt.check("(check-sat)\n", 1),
+            Err(LaleError::Io { .. })
+        ));
+        assert_eq!(literal(7, 32), "(_ bv7 32)");
+    }
+}
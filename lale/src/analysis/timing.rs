@@ -29,7 +29,7 @@ impl Cycles {
 }
 
 /// Memory access type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AccessType {
     Ram,
     Flash,
@@ -38,7 +38,7 @@ pub enum AccessType {
 }
 
 /// Atomic operation type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AtomicOp {
     Load,
     Store,
@@ -48,7 +48,7 @@ pub enum AtomicOp {
 }
 
 /// Instruction classification for timing
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum InstructionClass {
     // Arithmetic
     Add,
@@ -0,0 +1,113 @@
+//! Caveats of an analysis, as data
+//!
+//! Anything that makes a result less trustworthy than it looks, such as an
+//! input that failed to parse or an opcode priced by a guess, is collected as
+//! an [`AnalysisWarning`] next to the results instead of only being printed,
+//! so reports and the GUI can list them and CI can count them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What an analysis warning is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningKind {
+    /// An input file could not be parsed and was skipped
+    ParseFailure,
+    /// A function could not be analyzed and has no WCET
+    AnalysisFailure,
+    /// A function's IPET solve timed out and it has no WCET
+    SolveTimeout,
+    /// An instruction's opcode has no timing and was given a default cost
+    UnknownOpcode,
+    /// A flow fact names a function or loop that was not analyzed
+    UnmatchedFlowFact,
+    /// The SMT feasibility check failed and no paths were pruned
+    SmtFailure,
+    /// An indirect call has unknown or unbounded targets, so its caller's
+    /// WCET leaves it out
+    UnboundedIndirectCall,
+}
+
+impl WarningKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningKind::ParseFailure => "parse-failure",
+            WarningKind::AnalysisFailure => "analysis-failure",
+            WarningKind::SolveTimeout => "solve-timeout",
+            WarningKind::UnknownOpcode => "unknown-opcode",
+            WarningKind::UnmatchedFlowFact => "unmatched-flow-fact",
+            WarningKind::SmtFailure => "smt-failure",
+            WarningKind::UnboundedIndirectCall => "unbounded-indirect-call",
+        }
+    }
+}
+
+/// One caveat of an analysis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisWarning {
+    pub kind: WarningKind,
+
+    /// Function the warning is about, if it is about one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    pub message: String,
+}
+
+impl AnalysisWarning {
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            function: None,
+            message: message.into(),
+        }
+    }
+
+    /// Warning about a function
+    pub fn in_function(
+        kind: WarningKind,
+        function: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            function: Some(function.into()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AnalysisWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.function {
+            Some(function) => write!(f, "[{}] {}: {}", self.kind.name(), function, self.message),
+            None => write!(f, "[{}] {}", self.kind.name(), self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_and_json_use_the_same_kind_names() {
+        let warning = AnalysisWarning::in_function(
+            WarningKind::UnknownOpcode,
+            "filter",
+            "no timing for CallBr",
+        );
+
+        assert_eq!(
+            warning.to_string(),
+            "[unknown-opcode] filter: no timing for CallBr"
+        );
+        let json = serde_json::to_string(&warning).unwrap();
+        assert!(json.contains(r#""kind":"unknown-opcode""#));
+        assert_eq!(
+            serde_json::from_str::<AnalysisWarning>(&json).unwrap(),
+            warning
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::async_analysis::{
     Actor, ActorConfigLoader, AsyncFunctionInfo, InkwellAsyncDetector, InkwellSegmentExtractor,
     InkwellSegmentWCETAnalyzer, SchedulingPolicy,
 };
+use crate::error::LaleError;
 use crate::ir::InkwellParser;
 use crate::multicore::{MultiCoreResult, MultiCoreScheduler};
 use crate::platform::PlatformModel;
@@ -10,20 +11,54 @@ use crate::platform::PlatformModel;
 pub struct ActorAnalyzer {
     config_loader: ActorConfigLoader,
     platform: PlatformModel,
+
+    /// Timing model per core (index = core id); cores without an entry use
+    /// `platform`
+    core_platforms: Vec<PlatformModel>,
 }
 
 impl ActorAnalyzer {
     /// Create new analyzer with config directory and platform
-    pub fn new(config_dir: &str, platform_name: &str) -> Result<Self, String> {
+    pub fn new(config_dir: &str, platform_name: &str) -> Result<Self, LaleError> {
         let mut config_loader = ActorConfigLoader::new(config_dir);
         let platform = config_loader.load_platform_model(platform_name)?;
 
         Ok(Self {
             config_loader,
             platform,
+            core_platforms: vec![],
         })
     }
 
+    /// Load a timing model for each core of a heterogeneous board
+    ///
+    /// Each actor is analyzed on the model of the core it is pinned to, and
+    /// on every other core's model for the per-core schedulability check.
+    pub fn with_core_platforms(mut self, platform_names: &[&str]) -> Result<Self, LaleError> {
+        self.core_platforms = platform_names
+            .iter()
+            .map(|name| self.config_loader.load_platform_model(name))
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    /// Timing model of a core
+    fn core_platform(&self, core: usize) -> &PlatformModel {
+        self.core_platforms.get(core).unwrap_or(&self.platform)
+    }
+
+    /// Distinct timing models of the first `num_cores` cores, by name
+    fn distinct_platforms(&self, num_cores: usize) -> Vec<&PlatformModel> {
+        let mut platforms: Vec<&PlatformModel> = Vec::new();
+        for core in 0..num_cores.max(1) {
+            let platform = self.core_platform(core);
+            if platforms.iter().all(|p| p.name != platform.name) {
+                platforms.push(platform);
+            }
+        }
+        platforms
+    }
+
     /// Analyze Veecle OS project
     ///
     /// Returns (actors_with_wcet, schedulability_result)
@@ -33,7 +68,7 @@ impl ActorAnalyzer {
         ir_dir: &str,
         num_cores: usize,
         policy: SchedulingPolicy,
-    ) -> Result<(Vec<Actor>, MultiCoreResult), String> {
+    ) -> Result<(Vec<Actor>, MultiCoreResult), LaleError> {
         // Load Veecle Model.toml (platform already loaded in constructor)
         let model_path = std::path::Path::new(project_dir).join("Model.toml");
         eprintln!("Loading Model.toml from: {}", model_path.display());
@@ -51,7 +86,7 @@ impl ActorAnalyzer {
         for (name, path) in actor_paths {
             eprintln!("Analyzing actor: {} (path: {})", name, path);
             // Try to find matching LLVM IR file
-            match self.analyze_actor_from_ir(ir_dir, &name, &path) {
+            match self.analyze_actor_from_ir(ir_dir, &name, &path, num_cores) {
                 Ok(actor) => {
                     eprintln!("  ✓ Successfully analyzed actor: {}", name);
                     actors.push(actor);
@@ -65,7 +100,10 @@ impl ActorAnalyzer {
         eprintln!("Total actors analyzed: {}", actors.len());
 
         // Perform multi-core schedulability analysis
-        let scheduler = MultiCoreScheduler::new(num_cores, policy);
+        let platforms = (0..num_cores)
+            .map(|core| self.core_platform(core).clone())
+            .collect();
+        let scheduler = MultiCoreScheduler::heterogeneous(platforms, policy);
         let schedulability = scheduler.analyze(&actors);
 
         Ok((actors, schedulability))
@@ -77,13 +115,14 @@ impl ActorAnalyzer {
         ir_dir: &str,
         actor_name: &str,
         function_path: &str,
-    ) -> Result<Actor, String> {
+        num_cores: usize,
+    ) -> Result<Actor, LaleError> {
         eprintln!("  Searching for actor in IR directory: {}", ir_dir);
         eprintln!("  Looking for function path: {}", function_path);
 
         // Find IR files in directory
-        let ir_files =
-            std::fs::read_dir(ir_dir).map_err(|e| format!("Failed to read IR directory: {}", e))?;
+        let ir_files = std::fs::read_dir(ir_dir)
+            .map_err(|e| LaleError::io("Failed to read IR directory", e))?;
 
         let mut ir_file_count = 0;
         let mut async_func_count = 0;
@@ -113,6 +152,7 @@ impl ActorAnalyzer {
                     }
                     Ok(Err(e)) => {
                         // Log parse errors for debugging
+                        let e = e.to_string();
                         if e.contains("dbg_value") || e.contains("dbg_declare") {
                             eprintln!("    Skipping (debug intrinsics)");
                         } else if e.contains("samesign") {
@@ -174,21 +214,35 @@ impl ActorAnalyzer {
                                                     &async_info,
                                                 );
 
-                                            // Analyze WCET using inkwell
-                                            let analyzer = InkwellSegmentWCETAnalyzer::new(
-                                                self.platform.clone(),
-                                            );
-                                            let wcets =
-                                                analyzer.analyze_segments(&inkwell_func, &segments);
+                                            // Analyze WCET using inkwell, on every
+                                            // core's model; the pinned core's
+                                            // model gives the actor-level WCET
+                                            let home = self
+                                                .core_platform(actor.core_affinity.unwrap_or(0));
+                                            let mut platforms = self.distinct_platforms(num_cores);
+                                            if platforms.iter().all(|p| p.name != home.name) {
+                                                platforms.push(home);
+                                            }
+                                            for platform in platforms {
+                                                let analyzer = InkwellSegmentWCETAnalyzer::new(
+                                                    platform.clone(),
+                                                );
+                                                let segment_wcets = analyzer
+                                                    .analyze_segments(&inkwell_func, &segments)
+                                                    .into_iter()
+                                                    .map(|(id, w)| (id as u32, w.wcet_cycles))
+                                                    .collect();
+                                                actor.record_platform_wcet(
+                                                    &platform.name,
+                                                    &segment_wcets,
+                                                );
+                                                if platform.name == home.name {
+                                                    actor.segment_wcets = segment_wcets;
+                                                }
+                                            }
 
                                             actor.segments = segments;
-                                            actor.segment_wcets = wcets
-                                                .into_iter()
-                                                .map(|(id, w)| (id as u32, w.wcet_cycles))
-                                                .collect();
-                                            actor.compute_actor_wcet(
-                                                self.platform.cpu_frequency_mhz,
-                                            );
+                                            actor.compute_actor_wcet(home.cpu_frequency_mhz);
                                             eprintln!(
                                                 "      ✓ WCET analysis completed successfully"
                                             );
@@ -240,6 +294,9 @@ impl ActorAnalyzer {
             "  Scanned {} IR files, found {} async functions total",
             ir_file_count, async_func_count
         );
-        Err(format!("Could not find LLVM IR for actor: {}", actor_name))
+        Err(LaleError::Analysis(format!(
+            "Could not find LLVM IR for actor: {}",
+            actor_name
+        )))
     }
 }
@@ -0,0 +1,357 @@
+//! Context-sensitive WCET analysis per call site
+//!
+//! Propagates constant arguments from call sites into the callee's
+//! parameter-bounded loops, e.g. `fn process(buf: &[u8])` looping over
+//! `buf.len()` called with a fixed-size buffer.
+
+use crate::analysis::memset_loops::{inverse, swapped};
+use crate::analysis::LoopAnalyzer;
+use crate::analyzers::FunctionAnalyzer;
+use crate::error::LaleError;
+use crate::ir::{CallGraph, CallSite, InkwellCFG};
+use crate::platform::PlatformModel;
+use ahash::AHashMap;
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module;
+use inkwell::values::{
+    BasicValue, BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue,
+};
+use inkwell::IntPredicate;
+use petgraph::graph::NodeIndex;
+
+/// WCET of a callee in the context of one call site
+#[derive(Debug, Clone)]
+pub struct CallSiteWCET {
+    /// Calling function
+    pub caller: String,
+
+    /// Called function
+    pub callee: String,
+
+    /// Block of the caller containing the call
+    pub block: String,
+
+    /// Loop bounds propagated from constant arguments (loop header block
+    /// index -> iterations)
+    pub loop_bounds: AHashMap<usize, u64>,
+
+    /// Callee WCET with the propagated loop bounds
+    pub wcet_cycles: u64,
+
+    /// Callee WCET without call-site context
+    pub context_free_wcet_cycles: u64,
+}
+
+/// Parameter a loop's exit test compares its induction variable with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterBound {
+    /// Index of the compared parameter
+    pub param: u32,
+
+    /// Whether the loop still runs when the counter reaches the parameter,
+    /// as in `i <= n`
+    pub inclusive: bool,
+}
+
+/// Analyzer for context-sensitive loop bounds at call sites
+pub struct CallSiteAnalyzer {
+    platform: PlatformModel,
+}
+
+impl CallSiteAnalyzer {
+    /// Create a new call-site analyzer with the given platform
+    pub fn new(platform: PlatformModel) -> Self {
+        Self { platform }
+    }
+
+    /// Analyze every call site that passes a constant to a loop-bounding parameter
+    pub fn analyze_module(&self, module: &Module) -> Result<Vec<CallSiteWCET>, LaleError> {
+        let call_graph = CallGraph::from_module(module);
+        let function_analyzer = FunctionAnalyzer::new(self.platform.clone());
+
+        let mut bounded_loops_cache: AHashMap<String, AHashMap<usize, ParameterBound>> =
+            AHashMap::new();
+        let mut context_free_cache: AHashMap<String, u64> = AHashMap::new();
+        let mut results = Vec::new();
+
+        for site in &call_graph.call_sites {
+            if !call_graph.is_defined(&site.callee) {
+                continue;
+            }
+
+            let Some(callee) = module.get_function(&site.callee) else {
+                continue;
+            };
+
+            let bounded_loops = bounded_loops_cache
+                .entry(site.callee.clone())
+                .or_insert_with(|| Self::parameter_bounded_loops(&callee));
+
+            let loop_bounds = Self::propagate_bounds(site, bounded_loops);
+            if loop_bounds.is_empty() {
+                continue;
+            }
+
+            let context_free_wcet_cycles = match context_free_cache.get(&site.callee) {
+                Some(&wcet) => wcet,
+                None => {
+                    let wcet = function_analyzer.analyze(&callee)?.wcet_cycles;
+                    context_free_cache.insert(site.callee.clone(), wcet);
+                    wcet
+                }
+            };
+
+            let wcet_cycles = function_analyzer
+                .analyze_with_loop_bounds(&callee, &loop_bounds)?
+                .wcet_cycles;
+
+            results.push(CallSiteWCET {
+                caller: site.caller.clone(),
+                callee: site.callee.clone(),
+                block: site.block.clone(),
+                loop_bounds,
+                wcet_cycles,
+                context_free_wcet_cycles,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Find loops whose exit test compares the induction variable with a
+    /// parameter
+    ///
+    /// Returns loop header block index -> bounding parameter. Only the test
+    /// of the header or of the single latch counts, as it runs on every
+    /// iteration, and only a counter starting at a non-negative constant and
+    /// stepping up by a constant. Such a loop iterates at most `n` times, or
+    /// `n + 1` with `<=`. A `!=` test needs a counter from 0 stepping by 1,
+    /// and a zero count is assumed to be guarded before a bottom-tested loop.
+    pub fn parameter_bounded_loops(function: &FunctionValue) -> AHashMap<usize, ParameterBound> {
+        let params: Vec<BasicValueEnum> = function.get_param_iter().collect();
+        let inkwell_cfg = InkwellCFG::from_function(function);
+        let cfg = inkwell_cfg.to_petgraph();
+        let block_of =
+            |node: NodeIndex| inkwell_cfg.blocks[cfg.graph[node].execution_count_var].block;
+
+        let mut bounded = AHashMap::new();
+        for loop_info in LoopAnalyzer::analyze_loops(&cfg) {
+            let header = block_of(loop_info.header);
+            let body: Vec<BasicBlock> =
+                loop_info.body_blocks.iter().map(|&n| block_of(n)).collect();
+
+            let mut tested = vec![loop_info.header];
+            if let [(latch, _)] = loop_info.back_edges[..] {
+                tested.push(latch);
+            }
+            let bound = tested
+                .into_iter()
+                .find_map(|node| Self::exit_bound(block_of(node), header, &body, &params));
+            if let Some(bound) = bound {
+                bounded.insert(cfg.graph[loop_info.header].execution_count_var, bound);
+            }
+        }
+
+        bounded
+    }
+
+    /// Parameter bounding the loop if `block` leaves it on an induction
+    /// variable test
+    fn exit_bound(
+        block: BasicBlock,
+        header: BasicBlock,
+        body: &[BasicBlock],
+        params: &[BasicValueEnum],
+    ) -> Option<ParameterBound> {
+        let terminator = block.get_terminator()?;
+
+        // Conditional br: condition, false target, true target
+        if terminator.get_opcode() != InstructionOpcode::Br || terminator.get_num_operands() != 3 {
+            return None;
+        }
+        let false_stays = body.contains(&terminator.get_operand(1)?.block()?);
+        let true_stays = body.contains(&terminator.get_operand(2)?.block()?);
+        let continue_on_true = match (true_stays, false_stays) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => return None,
+        };
+
+        let compare = terminator.get_operand(0)?.value()?.as_instruction_value()?;
+        if compare.get_opcode() != InstructionOpcode::ICmp {
+            return None;
+        }
+        let mut predicate = compare.get_icmp_predicate()?;
+
+        // Counter on the left, parameter on the right
+        let lhs = compare.get_operand(0)?.value()?;
+        let rhs = compare.get_operand(1)?.value()?;
+        let (counter, param) = match (
+            Self::param_index(params, rhs),
+            Self::param_index(params, lhs),
+        ) {
+            (Some(param), _) => (lhs, param),
+            (None, Some(param)) => {
+                predicate = swapped(predicate);
+                (rhs, param)
+            }
+            (None, None) => return None,
+        };
+        if !continue_on_true {
+            predicate = inverse(predicate);
+        }
+
+        let (start, step) = Self::induction(counter.as_instruction_value()?, header, body)?;
+        let inclusive = match predicate {
+            IntPredicate::ULT | IntPredicate::SLT => false,
+            IntPredicate::ULE | IntPredicate::SLE => true,
+            // A larger step or start may never hit the parameter exactly
+            IntPredicate::NE if start == 0 && step == 1 => false,
+            _ => return None,
+        };
+        Some(ParameterBound { param, inclusive })
+    }
+
+    /// Start and step of a counter: a phi of the loop header or the `add`
+    /// stepping it, counting up from a non-negative constant
+    fn induction(
+        counter: InstructionValue,
+        header: BasicBlock,
+        body: &[BasicBlock],
+    ) -> Option<(i64, i64)> {
+        let phi = match counter.get_opcode() {
+            InstructionOpcode::Phi => counter,
+            InstructionOpcode::Add => (0..2).find_map(|i| {
+                counter
+                    .get_operand(i)?
+                    .value()?
+                    .as_instruction_value()
+                    .filter(|op| op.get_opcode() == InstructionOpcode::Phi)
+            })?,
+            _ => return None,
+        };
+        if phi.get_parent() != Some(header) || phi.get_num_operands() != 2 {
+            return None;
+        }
+
+        // One incoming value is the increment, the other the constant start
+        let mut start = None;
+        let mut step = None;
+        for i in 0..2 {
+            let incoming = phi.get_operand(i)?.value()?;
+            match incoming.as_instruction_value() {
+                Some(next)
+                    if next.get_opcode() == InstructionOpcode::Add
+                        && next.get_parent().is_some_and(|b| body.contains(&b)) =>
+                {
+                    let a = next.get_operand(0)?.value()?;
+                    let b = next.get_operand(1)?.value()?;
+                    let step_value = if a.as_instruction_value() == Some(phi) {
+                        b
+                    } else if b.as_instruction_value() == Some(phi) {
+                        a
+                    } else {
+                        return None;
+                    };
+                    if counter != phi && next != counter {
+                        return None;
+                    }
+                    step = Self::int_constant(step_value);
+                }
+                _ => start = Self::int_constant(incoming),
+            }
+        }
+
+        let (start, step) = (start?, step?);
+        (start >= 0 && step >= 1).then_some((start, step))
+    }
+
+    /// Value of an integer constant
+    fn int_constant(value: BasicValueEnum) -> Option<i64> {
+        match value {
+            BasicValueEnum::IntValue(int) if int.is_const() => int.get_sign_extended_constant(),
+            _ => None,
+        }
+    }
+
+    /// Map a compared value back to a parameter, looking through integer casts
+    fn param_index(params: &[BasicValueEnum], value: BasicValueEnum) -> Option<u32> {
+        if let Some(index) = params.iter().position(|p| *p == value) {
+            return Some(index as u32);
+        }
+
+        let cast: InstructionValue = value.as_instruction_value()?;
+        match cast.get_opcode() {
+            InstructionOpcode::ZExt | InstructionOpcode::SExt | InstructionOpcode::Trunc => {
+                let source = cast.get_operand(0)?.value()?;
+                params
+                    .iter()
+                    .position(|p| *p == source)
+                    .map(|index| index as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Loop bounds implied by the constant arguments of one call site
+    fn propagate_bounds(
+        site: &CallSite,
+        bounded_loops: &AHashMap<usize, ParameterBound>,
+    ) -> AHashMap<usize, u64> {
+        bounded_loops
+            .iter()
+            .filter_map(|(&header, bound)| {
+                let value = site
+                    .constant_args
+                    .get(bound.param as usize)
+                    .copied()
+                    .flatten()?;
+                Some((header, value.saturating_add(u64::from(bound.inclusive))))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propagate_bounds() {
+        let site = CallSite {
+            caller: "main".to_string(),
+            callee: "process".to_string(),
+            block: "entry".to_string(),
+            block_id: 0,
+            constant_args: vec![None, Some(16)],
+        };
+
+        let mut bounded_loops = AHashMap::new();
+        let exclusive = ParameterBound {
+            param: 1,
+            inclusive: false,
+        };
+        bounded_loops.insert(1, exclusive);
+        bounded_loops.insert(
+            2,
+            ParameterBound {
+                param: 0,
+                inclusive: false,
+            },
+        );
+        bounded_loops.insert(
+            3,
+            ParameterBound {
+                inclusive: true,
+                ..exclusive
+            },
+        );
+
+        let bounds = CallSiteAnalyzer::propagate_bounds(&site, &bounded_loops);
+
+        // `i <= n` runs once more than `i < n`
+        assert_eq!(bounds.len(), 2);
+        assert_eq!(bounds.get(&1), Some(&16));
+        assert_eq!(bounds.get(&3), Some(&17));
+    }
+}
@@ -2,12 +2,19 @@
 //!
 //! Analyzes all LLVM IR files in a directory and generates WCET estimates.
 
-use crate::analysis::{Cycles, InkwellTimingCalculator};
-use crate::ir::{InkwellCFG, InkwellParser};
+use crate::analysis::{AnalysisWarning, FlowFactStore, WarningKind};
+use crate::analyzers::FunctionAnalyzer;
+use crate::error::LaleError;
+use crate::ir::InkwellParser;
 use crate::platform::PlatformModel;
 use crate::scheduling::Task;
 use ahash::AHashMap;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default per-function IPET solve timeout
+pub const DEFAULT_SOLVE_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Result of analyzing a directory
 #[derive(Debug, Clone)]
@@ -15,6 +22,9 @@ pub struct DirectoryAnalysisResult {
     /// WCET results per function (function_name -> wcet_cycles)
     pub function_wcets: AHashMap<String, u64>,
 
+    /// BCET results per function (function_name -> bcet_cycles)
+    pub function_bcets: AHashMap<String, u64>,
+
     /// Tasks generated from functions
     pub tasks: Vec<Task>,
 
@@ -23,123 +33,214 @@ pub struct DirectoryAnalysisResult {
 
     /// Files that failed to analyze
     pub failed_files: Vec<(PathBuf, String)>,
+
+    /// Functions whose IPET solve exceeded the timeout (no WCET recorded)
+    pub timed_out_functions: Vec<String>,
+
+    /// Failed files, timed-out functions, unmatched flow facts and the
+    /// caveats of individual results
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+/// Results of the functions in one file
+#[derive(Default)]
+struct FileAnalysis {
+    wcets: AHashMap<String, u64>,
+    bcets: AHashMap<String, u64>,
+    timed_out: Vec<String>,
+    warnings: Vec<AnalysisWarning>,
+    /// Loop headers of every analyzed function
+    loop_headers: AHashMap<String, Vec<String>>,
 }
 
 /// Analyzer for directories containing LLVM IR files
 pub struct DirectoryAnalyzer {
     platform: PlatformModel,
+    solve_timeout: Duration,
+    flow_facts: Option<FlowFactStore>,
 }
 
 impl DirectoryAnalyzer {
     /// Create a new directory analyzer with the given platform
     pub fn new(platform: PlatformModel) -> Self {
-        Self { platform }
+        Self {
+            platform,
+            solve_timeout: DEFAULT_SOLVE_TIMEOUT,
+            flow_facts: None,
+        }
+    }
+
+    /// Set the per-function IPET solve timeout
+    pub fn with_solve_timeout(mut self, timeout: Duration) -> Self {
+        self.solve_timeout = timeout;
+        self
+    }
+
+    /// Bound loops from a flow-facts file instead of heuristically
+    pub fn with_flow_facts(mut self, flow_facts: FlowFactStore) -> Self {
+        self.flow_facts = Some(flow_facts);
+        self
     }
 
     /// Analyze all .ll files in a directory recursively
     pub fn analyze_directory(
         &self,
         dir_path: impl AsRef<Path>,
-    ) -> Result<DirectoryAnalysisResult, String> {
+    ) -> Result<DirectoryAnalysisResult, LaleError> {
         let dir = dir_path.as_ref();
 
         if !dir.exists() {
-            return Err(format!("Directory does not exist: {}", dir.display()));
+            return Err(LaleError::io(
+                dir.display().to_string(),
+                io::Error::new(io::ErrorKind::NotFound, "Directory does not exist"),
+            ));
         }
 
         if !dir.is_dir() {
-            return Err(format!("Path is not a directory: {}", dir.display()));
+            return Err(LaleError::io(
+                dir.display().to_string(),
+                io::Error::new(io::ErrorKind::InvalidInput, "Path is not a directory"),
+            ));
         }
 
         // Find all .ll files
         let ll_files = self.find_ll_files(dir)?;
 
         if ll_files.is_empty() {
-            return Err(format!(
-                "No .ll files found in directory: {}",
+            return Err(LaleError::Analysis(format!(
+                "No .ll, .bc or .a files found in directory: {}",
                 dir.display()
-            ));
+            )));
         }
 
         let mut function_wcets = AHashMap::new();
+        let mut function_bcets = AHashMap::new();
         let mut analyzed_files = Vec::new();
         let mut failed_files = Vec::new();
+        let mut timed_out_functions = Vec::new();
+        let mut loop_headers = AHashMap::new();
+        let mut warnings = Vec::new();
 
         // Analyze each file
         for ll_file in ll_files {
             match self.analyze_file(&ll_file) {
-                Ok(wcets) => {
-                    function_wcets.extend(wcets);
+                Ok(file) => {
+                    function_wcets.extend(file.wcets);
+                    function_bcets.extend(file.bcets);
+                    timed_out_functions.extend(file.timed_out);
+                    warnings.extend(file.warnings);
+                    loop_headers.extend(file.loop_headers);
                     analyzed_files.push(ll_file);
                 }
                 Err(e) => {
-                    failed_files.push((ll_file, e));
+                    warnings.push(AnalysisWarning::new(
+                        WarningKind::ParseFailure,
+                        format!("{}: {}", ll_file.display(), e),
+                    ));
+                    failed_files.push((ll_file, e.to_string()));
                 }
             }
         }
 
-        if function_wcets.is_empty() {
-            return Err("No functions were successfully analyzed".to_string());
+        if function_wcets.is_empty() && timed_out_functions.is_empty() {
+            return Err(LaleError::Analysis(
+                "No functions were successfully analyzed".to_string(),
+            ));
         }
 
         // Generate tasks from analyzed functions
         let tasks = self.generate_tasks(&function_wcets);
+        warnings.extend(timed_out_functions.iter().map(|function| {
+            AnalysisWarning::in_function(
+                WarningKind::SolveTimeout,
+                function,
+                format!("IPET solve exceeded {:?}, no WCET", self.solve_timeout),
+            )
+        }));
+        if let Some(flow_facts) = &self.flow_facts {
+            warnings.extend(flow_facts.unmatched(&loop_headers));
+        }
 
         Ok(DirectoryAnalysisResult {
             function_wcets,
+            function_bcets,
             tasks,
             analyzed_files,
             failed_files,
+            timed_out_functions,
+            warnings,
         })
     }
 
-    /// Analyze a single LLVM IR file
-    fn analyze_file(&self, path: &Path) -> Result<AHashMap<String, u64>, String> {
-        let (_context, module) = InkwellParser::parse_file(path)?;
-
-        let mut results = AHashMap::new();
+    /// Analyze a single LLVM IR, bitcode or archive file
+    ///
+    /// Returns the WCET, BCET and loop headers of each function, the names
+    /// of functions whose IPET solve timed out and a warning for each
+    /// function that failed to analyze.
+    fn analyze_file(&self, path: &Path) -> Result<FileAnalysis, LaleError> {
+        let modules = InkwellParser::parse_modules(path)?;
+        let mut analyzer = FunctionAnalyzer::new(self.platform.clone());
+        if let Some(flow_facts) = &self.flow_facts {
+            analyzer = analyzer.with_flow_facts(flow_facts.clone());
+        }
 
-        // Analyze each function in the module
-        let mut func_iter = module.get_first_function();
-        while let Some(function) = func_iter {
-            let func_name = function.get_name().to_str().unwrap_or("").to_string();
+        let mut file = FileAnalysis::default();
 
-            // Skip intrinsics and declarations
-            if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
-                func_iter = function.get_next_function();
-                continue;
-            }
+        // Analyze each function in each module
+        for parsed in &modules {
+            let module = parsed.module();
+            let mut func_iter = module.get_first_function();
+            while let Some(function) = func_iter {
+                let func_name = function.get_name().to_str().unwrap_or("").to_string();
 
-            // Build CFG and calculate timing
-            let cfg = InkwellCFG::from_function(&function);
-            let timings =
-                InkwellTimingCalculator::calculate_block_timings(&function, &cfg, &self.platform);
-
-            // Sum all block timings as a simple WCET estimate
-            let wcet: u64 = timings.values().sum();
+                // Skip intrinsics and declarations
+                if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
+                    func_iter = function.get_next_function();
+                    continue;
+                }
 
-            results.insert(func_name, wcet);
+                match analyzer.analyze_with_timeout(&function, self.solve_timeout) {
+                    Ok(Some(result)) => {
+                        let headers = result
+                            .loop_bound_decisions
+                            .iter()
+                            .map(|decision| decision.header.clone())
+                            .collect();
+                        file.loop_headers.insert(func_name.clone(), headers);
+                        file.warnings.extend(result.warnings);
+                        file.bcets.insert(func_name.clone(), result.bcet_cycles);
+                        file.wcets.insert(func_name, result.wcet_cycles);
+                    }
+                    Ok(None) => file.timed_out.push(func_name),
+                    // One function failing leaves the rest of the file
+                    Err(e) => file.warnings.push(AnalysisWarning::in_function(
+                        WarningKind::AnalysisFailure,
+                        &func_name,
+                        e.to_string(),
+                    )),
+                }
 
-            func_iter = function.get_next_function();
+                func_iter = function.get_next_function();
+            }
         }
 
-        Ok(results)
+        Ok(file)
     }
 
-    /// Find all .ll files in directory recursively
-    fn find_ll_files(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    /// Find all .ll, .bc, .o and .a files in directory recursively
+    fn find_ll_files(&self, dir: &Path) -> Result<Vec<PathBuf>, LaleError> {
         let mut ll_files = Vec::new();
 
         let entries =
-            std::fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+            std::fs::read_dir(dir).map_err(|e| LaleError::io("Failed to read directory", e))?;
 
         for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let entry = entry.map_err(|e| LaleError::io("Failed to read entry", e))?;
             let path = entry.path();
 
             if path.is_file() {
                 if let Some(ext) = path.extension() {
-                    if ext == "ll" {
+                    if ext == "ll" || ext == "bc" || ext == "o" || ext == "a" {
                         ll_files.push(path);
                     }
                 }
@@ -156,18 +257,14 @@ impl DirectoryAnalyzer {
         function_wcets
             .iter()
             .map(|(func_name, &wcet_cycles)| {
-                let wcet_us = wcet_cycles as f64 / self.platform.cpu_frequency_mhz as f64;
+                let wcet_us = self.platform.cycles_to_us(wcet_cycles);
 
                 Task {
                     name: func_name.clone(),
                     function: func_name.clone(),
                     wcet_cycles,
                     wcet_us,
-                    period_us: None,
-                    deadline_us: None,
-                    priority: None,
-                    preemptible: true,
-                    dependencies: vec![],
+                    ..Default::default()
                 }
             })
             .collect()
@@ -178,7 +275,7 @@ impl DirectoryAnalyzer {
         &self,
         dir_path: impl AsRef<Path>,
         period_us: f64,
-    ) -> Result<DirectoryAnalysisResult, String> {
+    ) -> Result<DirectoryAnalysisResult, LaleError> {
         let mut result = self.analyze_directory(dir_path)?;
 
         // Update tasks with period
@@ -2,12 +2,101 @@
 //!
 //! Provides detailed analysis of individual functions.
 
-use crate::analysis::{IPETSolver, InkwellTimingCalculator, Loop, LoopAnalyzer};
-use crate::ir::{BasicBlock, EdgeType, InkwellCFG, CFG};
+use crate::analysis::{
+    classify_loop, dead_blocks, loop_key, poison_branches, recognize_memset_loops, AnalysisWarning,
+    CacheAssumption, CostFn, Cycles, DeadBlock, DeadBlockKind, FlowFactStore, IPETSolver,
+    InfeasiblePath, InkwellTimingCalculator, Loop, LoopAnalyzer, LoopBounds, LoopKind,
+    LoopObservation, MemsetLoop, Profile, SmtFeasibility, WarningKind,
+};
+use crate::config::types::CacheLevelConfig;
+use crate::error::LaleError;
+use crate::ir::{InkwellCFG, CFG};
+use crate::microarch::cache::DataCacheAnalysis;
 use crate::platform::PlatformModel;
 use ahash::AHashMap;
-use inkwell::values::FunctionValue;
-use petgraph::graph::DiGraph;
+use inkwell::values::{FunctionValue, InstructionOpcode};
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How a function's WCET bound was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// Solved by IPET over the CFG
+    Ipet,
+    /// Looser bound not derived from an IPET solution (e.g. fast mode for
+    /// huge functions, or solver fallback)
+    Approximate,
+}
+
+/// Where a loop's iteration bound came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoopBoundSource {
+    /// `bb.loop_N_M` block label or `!llvm.loop` metadata annotation
+    Annotation,
+    /// Flow-facts file
+    FlowFact,
+    /// Constant argument propagated from a call site
+    CallSite,
+    /// Exact trip count of a recognized memset loop
+    MemsetPattern,
+    /// Heuristic estimate from the loop's shape
+    Heuristic,
+}
+
+/// Bound chosen for one loop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoopBoundDecision {
+    /// Label of the loop header, or `#<block index>` without one; its
+    /// flow-facts key
+    pub header: String,
+    pub source: LoopBoundSource,
+    /// Maximum iterations, if the bound is a constant
+    pub max_iterations: Option<u64>,
+    /// What the loop's exit condition depends on
+    pub kind: LoopKind,
+    /// Heuristic bound before it was clamped to the configured range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clamped_from: Option<u64>,
+}
+
+/// Cycles each block spends in the functions it calls
+///
+/// Sums the WCETs of the `(block index, callee WCET)` pairs per block, in
+/// the form [`FunctionAnalyzer::analyze_with_callee_cycles`] takes.
+pub fn callee_cycles(calls: impl IntoIterator<Item = (usize, u64)>) -> AHashMap<usize, u64> {
+    let mut cycles: AHashMap<usize, u64> = AHashMap::new();
+    for (block, wcet) in calls {
+        let total = cycles.entry(block).or_insert(0);
+        *total = total.saturating_add(wcet);
+    }
+    cycles
+}
+
+/// Worst-case cost of one basic block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockCost {
+    /// Block label
+    pub block: String,
+    /// Block index in the function's CFG
+    pub block_id: usize,
+    /// Maximum executions per call: product of the bounds of enclosing loops,
+    /// zero for blocks that never execute
+    pub executions: u64,
+    /// Cycles per execution
+    pub cycles: u64,
+}
+
+impl BlockCost {
+    /// Cycles the block contributes per call at most
+    pub fn total_cycles(&self) -> u64 {
+        self.executions.saturating_mul(self.cycles)
+    }
+}
 
 /// Detailed result of analyzing a function
 #[derive(Debug, Clone)]
@@ -18,7 +107,8 @@ pub struct FunctionAnalysisResult {
     /// WCET in cycles (using IPET if possible)
     pub wcet_cycles: u64,
 
-    /// BCET in cycles (best case)
+    /// BCET in cycles: the cheapest path at best-case timings and minimum
+    /// loop counts, excluding known callee cycles
     pub bcet_cycles: u64,
 
     /// Number of basic blocks
@@ -30,101 +120,636 @@ pub struct FunctionAnalysisResult {
     /// Loops detected
     pub loops: Vec<Loop>,
 
+    /// Number of loops whose bound is a heuristic estimate rather than an annotation
+    pub estimated_loop_bounds: usize,
+
+    /// Zero-init loops bounded exactly and costed as bulk stores
+    pub memset_loops: Vec<MemsetLoop>,
+
+    /// Bound source of each loop, in `loops` order
+    pub loop_bound_decisions: Vec<LoopBoundDecision>,
+
     /// Per-block timing information
     pub block_timings: AHashMap<usize, u64>,
+
+    /// Worst-case executions and cost of each block
+    pub block_costs: Vec<BlockCost>,
+
+    /// How the WCET bound was obtained
+    pub confidence: Confidence,
+
+    /// Typical cycles per call with profiled edge frequencies, if the
+    /// profile covers this function
+    pub profiled_typical_cycles: Option<u64>,
+
+    /// Loop bounds checked against profiled iterations
+    pub loop_observations: Vec<LoopObservation>,
+
+    /// Cache behaviour assumed for loads and stores
+    pub cache_assumption: CacheAssumption,
+
+    /// Blocks that never execute, or execute only on the way to a panic
+    pub dead_blocks: Vec<DeadBlock>,
+
+    /// Blocks branching on `undef` or `poison`, both ways assumed feasible
+    pub poison_branches: Vec<String>,
+
+    /// Pairs of blocks the SMT check proved never both run, excluded from
+    /// the IPET solve
+    pub infeasible_paths: Vec<(String, String)>,
+
+    /// Caveats of the result, e.g. instructions priced by a default cost
+    pub warnings: Vec<AnalysisWarning>,
 }
 
 /// Analyzer for individual functions with detailed analysis
 pub struct FunctionAnalyzer {
     platform: PlatformModel,
+    issue_scheduling: bool,
+    max_ipet_blocks: Option<usize>,
+    cost_fn: Option<Box<CostFn>>,
+    profile: Option<Profile>,
+    cache_assumption: CacheAssumption,
+    instruction_cache: Option<CacheLevelConfig>,
+    data_cache: Option<CacheLevelConfig>,
+    heuristic_loop_min: Option<u64>,
+    heuristic_loop_max: Option<u64>,
+    flow_facts: Option<FlowFactStore>,
+    smt: Option<SmtFeasibility>,
 }
 
 impl FunctionAnalyzer {
     /// Create a new function analyzer with the given platform
     pub fn new(platform: PlatformModel) -> Self {
-        Self { platform }
+        Self {
+            platform,
+            issue_scheduling: false,
+            max_ipet_blocks: None,
+            cost_fn: None,
+            profile: None,
+            cache_assumption: CacheAssumption::default(),
+            instruction_cache: None,
+            data_cache: None,
+            heuristic_loop_min: None,
+            heuristic_loop_max: None,
+            flow_facts: None,
+            smt: None,
+        }
+    }
+
+    /// Cost blocks by dependency levels and the platform's issue width
+    /// instead of the serial instruction sum
+    pub fn with_issue_scheduling(mut self, enabled: bool) -> Self {
+        self.issue_scheduling = enabled;
+        self
+    }
+
+    /// Skip IPET for functions with more than `max_blocks` basic blocks and
+    /// use the approximate loop-aware bound instead
+    pub fn with_max_ipet_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_ipet_blocks = Some(max_blocks);
+        self
+    }
+
+    /// Never guess fewer than `min` iterations for a loop with a heuristic bound
+    pub fn with_heuristic_loop_min(mut self, min: u64) -> Self {
+        self.heuristic_loop_min = Some(min);
+        self
+    }
+
+    /// Never guess more than `max` iterations for a loop with a heuristic bound
+    ///
+    /// Takes precedence over the minimum if the two conflict. Bounds from
+    /// annotations, call sites and recognized patterns are left alone.
+    pub fn with_heuristic_loop_max(mut self, max: u64) -> Self {
+        self.heuristic_loop_max = Some(max);
+        self
+    }
+
+    /// Bound loops from a flow-facts file, overriding every other source
+    pub fn with_flow_facts(mut self, flow_facts: FlowFactStore) -> Self {
+        self.flow_facts = Some(flow_facts);
+        self
+    }
+
+    /// Flow facts the analyzer bounds loops from, if any
+    pub fn flow_facts(&self) -> Option<&FlowFactStore> {
+        self.flow_facts.as_ref()
+    }
+
+    /// Prune infeasible paths of small functions with an SMT solver before
+    /// the IPET solve
+    ///
+    /// Functions over the check's block limit are solved as usual. If the
+    /// solver fails, the function is solved without pruning and a warning
+    /// is recorded.
+    pub fn with_smt_feasibility(mut self, smt: SmtFeasibility) -> Self {
+        self.smt = Some(smt);
+        self
+    }
+
+    /// Override per-instruction costs with a custom function
+    ///
+    /// Replaces the built-in opcode classifier, e.g. for custom accelerators
+    /// or calibrated measurements. Fall back to
+    /// [`InkwellTimingCalculator::instruction_cost`] for opcodes the custom
+    /// function does not handle.
+    pub fn with_cost_fn(mut self, cost_fn: Box<CostFn>) -> Self {
+        self.cost_fn = Some(cost_fn);
+        self
+    }
+
+    /// Use profiled edge counts for typical-case estimates and to check loop
+    /// bounds against observed iterations
+    ///
+    /// The WCET bound itself is unaffected.
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Assume every load and store misses (cold, the default) or hits (warm)
+    ///
+    /// Analyzing once under each brackets the real WCET before investing in
+    /// a precise cache analysis. Ignored for opcodes priced by a custom cost
+    /// function.
+    pub fn with_cache_assumption(mut self, cache: CacheAssumption) -> Self {
+        self.cache_assumption = cache;
+        self
+    }
+
+    /// Charge the misses of an instruction-cache analysis on top of each
+    /// block's instruction costs
+    ///
+    /// Ignored under the warm cache assumption, where every fetch hits, and
+    /// with a custom cost function.
+    pub fn with_instruction_cache(mut self, instruction_cache: CacheLevelConfig) -> Self {
+        self.instruction_cache = Some(instruction_cache);
+        self
+    }
+
+    /// Price loads and stores from a data-cache analysis instead of as
+    /// misses
+    ///
+    /// Ignored under the warm cache assumption, where every access hits,
+    /// with issue scheduling and with a custom cost function.
+    pub fn with_data_cache(mut self, data_cache: CacheLevelConfig) -> Self {
+        self.data_cache = Some(data_cache);
+        self
     }
 
     /// Analyze a function with full IPET-based WCET analysis
-    pub fn analyze(&self, function: &FunctionValue) -> Result<FunctionAnalysisResult, String> {
+    pub fn analyze(&self, function: &FunctionValue) -> Result<FunctionAnalysisResult, LaleError> {
+        self.analyze_with_loop_bounds(function, &AHashMap::new())
+    }
+
+    /// Analyze a function with known iteration bounds for some loops
+    ///
+    /// `loop_bounds` maps the block index of a loop header to a maximum
+    /// iteration count, used instead of the heuristic bound of that loop.
+    pub fn analyze_with_loop_bounds(
+        &self,
+        function: &FunctionValue,
+        loop_bounds: &AHashMap<usize, u64>,
+    ) -> Result<FunctionAnalysisResult, LaleError> {
+        self.analyze_internal(function, loop_bounds, &AHashMap::new(), None)?
+            .ok_or_else(|| LaleError::Analysis("IPET solve did not complete".to_string()))
+    }
+
+    /// Analyze a function with the cost of some of its calls known
+    ///
+    /// `callee_cycles` maps a block index to the cycles spent in the functions
+    /// the block calls, added to its own timing on every execution.
+    pub fn analyze_with_callee_cycles(
+        &self,
+        function: &FunctionValue,
+        callee_cycles: &AHashMap<usize, u64>,
+    ) -> Result<FunctionAnalysisResult, LaleError> {
+        self.analyze_internal(function, &AHashMap::new(), callee_cycles, None)?
+            .ok_or_else(|| LaleError::Analysis("IPET solve did not complete".to_string()))
+    }
+
+    /// Analyze a function, giving up on the IPET solve after `timeout`
+    ///
+    /// Returns `Ok(None)` if the solve timed out. The solver thread cannot be
+    /// cancelled; it is detached and its result discarded. While it runs it
+    /// holds CBC, which solves one problem at a time, so CBC is told to give
+    /// up after twice `timeout` and later solves wait at most that long.
+    pub fn analyze_with_timeout(
+        &self,
+        function: &FunctionValue,
+        timeout: Duration,
+    ) -> Result<Option<FunctionAnalysisResult>, LaleError> {
+        self.analyze_internal(function, &AHashMap::new(), &AHashMap::new(), Some(timeout))
+    }
+
+    fn analyze_internal(
+        &self,
+        function: &FunctionValue,
+        loop_bounds: &AHashMap<usize, u64>,
+        callee_cycles: &AHashMap<usize, u64>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<FunctionAnalysisResult>, LaleError> {
         let func_name = function.get_name().to_str().unwrap_or("").to_string();
 
         // Build CFG
         let inkwell_cfg = InkwellCFG::from_function(function);
 
         // Calculate block timings
-        let block_timings = InkwellTimingCalculator::calculate_block_timings(
-            function,
-            &inkwell_cfg,
-            &self.platform,
-        );
+        let mut block_timings = self.block_timings(&inkwell_cfg);
+        let mut best_case_timings = self.best_case_block_timings(&inkwell_cfg);
+
+        // Memset-like loops pay their share of one bulk store per iteration
+        // in place of their single store, on top of the loop control
+        let memset_loops = recognize_memset_loops(&inkwell_cfg);
+        let store_cycles = self.store_cycles();
+        for memset in &memset_loops {
+            let timing = block_timings.entry(memset.block_id).or_insert(0);
+            *timing = timing
+                .saturating_sub(store_cycles)
+                .saturating_add(memset.iteration_cycles(store_cycles));
+            let worst = *timing;
+            let best = best_case_timings.entry(memset.block_id).or_insert(0);
+            *best = (*best).min(worst);
+        }
+
+        for block in &inkwell_cfg.blocks {
+            if let Some(&cycles) = callee_cycles.get(&block.id) {
+                let timing = block_timings.entry(block.id).or_insert(0);
+                *timing = timing.saturating_add(cycles);
+            }
+        }
 
         // Convert to CFG format for IPET solver
-        let cfg = self.convert_to_cfg(&inkwell_cfg);
+        let cfg = inkwell_cfg.to_petgraph();
 
         // Analyze loops
-        let loops = LoopAnalyzer::analyze_loops(&cfg);
+        let mut loops = LoopAnalyzer::analyze_loops(&cfg);
+        let mut estimated_loop_bounds = 0;
+        let mut loop_bound_decisions = Vec::new();
+        for loop_info in &mut loops {
+            let header_id = cfg.graph[loop_info.header].execution_count_var;
+            let known_bound = loop_bounds.get(&header_id);
+
+            let header_key = loop_key(&cfg.graph[loop_info.header].label, header_id);
+            let flow_fact = self
+                .flow_facts
+                .as_ref()
+                .and_then(|facts| facts.loop_bound(&func_name, &header_key));
+
+            let mut clamped_from = None;
+            let source = if let Some(max) = flow_fact {
+                loop_info.bounds = LoopBounds::Constant { min: 0, max };
+                LoopBoundSource::FlowFact
+            } else if let Some(memset) = memset_loops.iter().find(|m| m.block_id == header_id) {
+                loop_info.bounds = LoopBounds::Constant {
+                    min: memset.trip_count,
+                    max: memset.trip_count,
+                };
+                LoopBoundSource::MemsetPattern
+            } else if let Some(&max) = known_bound {
+                loop_info.bounds = LoopBounds::Constant { min: 0, max };
+                LoopBoundSource::CallSite
+            } else if LoopAnalyzer::has_annotated_bound(&cfg, loop_info) {
+                LoopBoundSource::Annotation
+            } else {
+                estimated_loop_bounds += 1;
+                clamped_from = self.clamp_heuristic_bound(&mut loop_info.bounds);
+                LoopBoundSource::Heuristic
+            };
+
+            let body: HashSet<usize> = loop_info
+                .body_blocks
+                .iter()
+                .map(|&node| cfg.graph[node].execution_count_var)
+                .collect();
+            loop_bound_decisions.push(LoopBoundDecision {
+                header: header_key,
+                source,
+                kind: classify_loop(&inkwell_cfg, &body),
+                max_iterations: match loop_info.bounds {
+                    LoopBounds::Constant { max, .. } => Some(max),
+                    _ => None,
+                },
+                clamped_from,
+            });
+        }
 
         // Convert timings to Cycles format for IPET
         let ipet_timings: AHashMap<_, _> = block_timings
             .iter()
             .filter_map(|(&block_id, &cycles)| {
+                let best = best_case_timings
+                    .get(&block_id)
+                    .copied()
+                    .unwrap_or(0)
+                    .min(cycles);
                 // Find corresponding node in CFG
                 cfg.graph
                     .node_indices()
                     .find(|&idx| cfg.graph[idx].execution_count_var == block_id)
-                    .map(|idx| (idx, crate::analysis::Cycles::new(cycles as u32)))
+                    .map(|idx| (idx, Cycles::range(best as u32, cycles as u32)))
             })
             .collect();
 
-        // Solve WCET using IPET
-        let wcet_cycles =
-            IPETSolver::solve_wcet(&cfg, &ipet_timings, &loops).unwrap_or_else(|_| {
-                // Fallback: sum all block timings
-                block_timings.values().sum()
+        // Profiled typical case and loop bound check, if profiled
+        let function_profile = self
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.function(&func_name));
+        let profiled_typical_cycles =
+            function_profile.and_then(|profile| profile.typical_cycles(&cfg, &block_timings));
+        let loop_observations = function_profile
+            .map(|profile| profile.loop_observations(&cfg, &loops))
+            .unwrap_or_default();
+
+        let block_count = inkwell_cfg.blocks.len();
+        let use_ipet = self.max_ipet_blocks.is_none_or(|max| block_count <= max);
+        let dead_blocks = dead_blocks(&inkwell_cfg);
+        let mut block_costs = Self::block_costs(&cfg, &block_timings, &loops);
+        for cost in &mut block_costs {
+            let never_executes = dead_blocks.iter().any(|dead| {
+                dead.block_id == cost.block_id && dead.kind == DeadBlockKind::NeverExecutes
             });
+            if never_executes {
+                cost.executions = 0;
+            }
+        }
+        let approximate_wcet = Self::approximate_wcet(&block_costs);
+
+        let mut warnings = self.unknown_opcodes(function, &inkwell_cfg);
+        let infeasible_paths = match &self.smt {
+            Some(smt) if use_ipet => smt
+                .infeasible_paths(&inkwell_cfg, &cfg, &loops)
+                .unwrap_or_else(|e| {
+                    warnings.push(AnalysisWarning::in_function(
+                        WarningKind::SmtFailure,
+                        &func_name,
+                        format!("{}, solving without pruning", e),
+                    ));
+                    Vec::new()
+                }),
+            _ => Vec::new(),
+        };
+        let infeasible_labels = infeasible_paths
+            .iter()
+            .map(|path| {
+                (
+                    cfg.graph[path.block_a].label.clone(),
+                    cfg.graph[path.block_b].label.clone(),
+                )
+            })
+            .collect();
 
-        // BCET is sum of minimum path (conservative estimate)
-        let bcet_cycles: u64 = block_timings.values().copied().min().unwrap_or(0);
+        // Solve WCET using IPET
+        let solved = if !use_ipet {
+            Err(LaleError::Analysis(format!(
+                "{} blocks exceed IPET limit, using approximate bound",
+                block_count
+            )))
+        } else {
+            match timeout {
+                Some(timeout) => {
+                    match Self::solve_with_timeout(
+                        cfg,
+                        ipet_timings,
+                        &loops,
+                        infeasible_paths,
+                        timeout,
+                    ) {
+                        Some(solved) => solved,
+                        None => return Ok(None),
+                    }
+                }
+                None => Self::solve_bounds(&cfg, &ipet_timings, &loops, &infeasible_paths, None),
+            }
+        };
+        // The entry block runs on every path, so it alone is a lower bound
+        let entry_best_case = best_case_timings
+            .get(&inkwell_cfg.entry_block)
+            .copied()
+            .unwrap_or(0);
+        let (wcet_cycles, bcet_cycles, confidence) = match solved {
+            Ok((wcet, bcet)) => (wcet, bcet.unwrap_or(entry_best_case), Confidence::Ipet),
+            // Fallback: loop-aware upper bound
+            Err(_) => (approximate_wcet, entry_best_case, Confidence::Approximate),
+        };
 
-        let block_count = inkwell_cfg.blocks.len();
         let edge_count: usize = inkwell_cfg
             .blocks
             .iter()
             .map(|b| inkwell_cfg.successors(b.id).len())
             .sum();
 
-        Ok(FunctionAnalysisResult {
+        Ok(Some(FunctionAnalysisResult {
             function_name: func_name,
             wcet_cycles,
             bcet_cycles,
             block_count,
             edge_count,
             loops,
+            estimated_loop_bounds,
+            memset_loops,
+            loop_bound_decisions,
             block_timings,
-        })
+            block_costs,
+            confidence,
+            profiled_typical_cycles,
+            loop_observations,
+            cache_assumption: self.cache_assumption,
+            dead_blocks,
+            poison_branches: poison_branches(&inkwell_cfg),
+            infeasible_paths: infeasible_labels,
+            warnings,
+        }))
+    }
+
+    /// Clamp a heuristic loop bound to the configured range
+    ///
+    /// Returns the guessed maximum if it was changed. A loop without a guess
+    /// counts as the IPET default of 100 iterations.
+    fn clamp_heuristic_bound(&self, bounds: &mut LoopBounds) -> Option<u64> {
+        if self.heuristic_loop_min.is_none() && self.heuristic_loop_max.is_none() {
+            return None;
+        }
+
+        let (guessed_min, guessed_max) = match *bounds {
+            LoopBounds::Constant { min, max } => (min, max),
+            _ => (0, 100),
+        };
+        let clamped = guessed_max
+            .max(self.heuristic_loop_min.unwrap_or(0))
+            .min(self.heuristic_loop_max.unwrap_or(u64::MAX));
+        if clamped == guessed_max {
+            return None;
+        }
+
+        *bounds = LoopBounds::Constant {
+            min: guessed_min.min(clamped),
+            max: clamped,
+        };
+        Some(guessed_max)
+    }
+
+    /// Loop-aware WCET upper bound without IPET
+    ///
+    /// Each block's cost is multiplied by the bounds of every loop enclosing
+    /// it and all blocks are summed. Branch exclusivity is ignored, so this
+    /// over-approximates the IPET result.
+    fn approximate_wcet(block_costs: &[BlockCost]) -> u64 {
+        block_costs
+            .iter()
+            .fold(0u64, |acc, cost| acc.saturating_add(cost.total_cycles()))
+    }
+
+    /// Cost of each block, executed as often as its enclosing loops allow
+    ///
+    /// As in IPET, a loop bound limits the back edges taken per entry, so
+    /// the header runs once more than that on the final pass, and so do the
+    /// blocks that pass can reach before leaving the loop.
+    fn block_costs(
+        cfg: &CFG,
+        block_timings: &AHashMap<usize, u64>,
+        loops: &[Loop],
+    ) -> Vec<BlockCost> {
+        let final_passes: Vec<HashSet<NodeIndex>> = loops
+            .iter()
+            .map(|loop_info| Self::final_pass_blocks(cfg, loop_info))
+            .collect();
+
+        cfg.graph
+            .node_indices()
+            .map(|node| {
+                let block = &cfg.graph[node];
+                let executions = loops
+                    .iter()
+                    .zip(&final_passes)
+                    .filter(|(loop_info, _)| loop_info.body_blocks.contains(&node))
+                    .map(|(loop_info, final_pass)| {
+                        let max = match loop_info.bounds {
+                            LoopBounds::Constant { max, .. } => max,
+                            _ => 100, // Same conservative default as IPET
+                        };
+                        max.saturating_add(final_pass.contains(&node) as u64)
+                    })
+                    .fold(1u64, |acc, bound| acc.saturating_mul(bound));
+
+                BlockCost {
+                    block: block.label.clone(),
+                    block_id: block.execution_count_var,
+                    executions,
+                    cycles: block_timings
+                        .get(&block.execution_count_var)
+                        .copied()
+                        .unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+
+    /// Header of a loop and the blocks of its body that can leave the loop
+    /// without getting back to the header
+    fn final_pass_blocks(cfg: &CFG, loop_info: &Loop) -> HashSet<NodeIndex> {
+        let mut reached = HashSet::from([loop_info.header]);
+        let mut worklist: Vec<NodeIndex> = loop_info
+            .body_blocks
+            .iter()
+            .copied()
+            .filter(|&node| {
+                cfg.graph
+                    .neighbors_directed(node, Direction::Outgoing)
+                    .any(|successor| !loop_info.body_blocks.contains(&successor))
+            })
+            .collect();
+
+        while let Some(node) = worklist.pop() {
+            if reached.insert(node) {
+                worklist.extend(
+                    cfg.graph
+                        .neighbors_directed(node, Direction::Incoming)
+                        .filter(|pred| loop_info.body_blocks.contains(pred)),
+                );
+            }
+        }
+        reached
+    }
+
+    /// WCET and, if its solve succeeds too, BCET by IPET
+    fn solve_bounds(
+        cfg: &CFG,
+        timings: &AHashMap<NodeIndex, Cycles>,
+        loops: &[Loop],
+        infeasible_paths: &[InfeasiblePath],
+        time_limit: Option<Duration>,
+    ) -> Result<(u64, Option<u64>), LaleError> {
+        let wcet =
+            IPETSolver::solve_wcet_within(cfg, timings, loops, infeasible_paths, time_limit)?;
+        let bcet = IPETSolver::solve_bcet_within(cfg, timings, loops, time_limit).ok();
+        Ok((wcet, bcet))
+    }
+
+    /// Run the IPET solves on a worker thread, returning `None` on timeout
+    fn solve_with_timeout(
+        cfg: CFG,
+        timings: AHashMap<NodeIndex, Cycles>,
+        loops: &[Loop],
+        infeasible_paths: Vec<InfeasiblePath>,
+        timeout: Duration,
+    ) -> Option<Result<(u64, Option<u64>), LaleError>> {
+        let loops = loops.to_vec();
+        let (sender, receiver) = mpsc::channel();
+
+        // A solve cut short by CBC's own limit may not be optimal, but by
+        // then the receiver has long given up on it
+        let time_limit = timeout.saturating_mul(2);
+        std::thread::spawn(move || {
+            // Receiver is gone if we already timed out
+            let _ = sender.send(Self::solve_bounds(
+                &cfg,
+                &timings,
+                &loops,
+                &infeasible_paths,
+                Some(time_limit),
+            ));
+        });
+
+        receiver.recv_timeout(timeout).ok()
     }
 
     /// Analyze with simple timing (no IPET)
     pub fn analyze_simple(
         &self,
         function: &FunctionValue,
-    ) -> Result<FunctionAnalysisResult, String> {
+    ) -> Result<FunctionAnalysisResult, LaleError> {
         let func_name = function.get_name().to_str().unwrap_or("").to_string();
 
         // Build CFG
         let cfg = InkwellCFG::from_function(function);
 
         // Calculate block timings
-        let block_timings =
-            InkwellTimingCalculator::calculate_block_timings(function, &cfg, &self.platform);
+        let block_timings = self.block_timings(&cfg);
 
-        // Simple WCET: sum all blocks
+        // Simple WCET: sum all blocks; BCET: the entry block, which runs on
+        // every path
         let wcet_cycles: u64 = block_timings.values().sum();
-        let bcet_cycles: u64 = block_timings.values().copied().min().unwrap_or(0);
+        let bcet_cycles: u64 = self
+            .best_case_block_timings(&cfg)
+            .get(&cfg.entry_block)
+            .copied()
+            .unwrap_or(0);
 
         let block_count = cfg.blocks.len();
         let edge_count: usize = cfg.blocks.iter().map(|b| cfg.successors(b.id).len()).sum();
+        let block_costs = cfg
+            .blocks
+            .iter()
+            .map(|block| BlockCost {
+                block: block.name.clone(),
+                block_id: block.id,
+                executions: 1,
+                cycles: block_timings.get(&block.id).copied().unwrap_or(0),
+            })
+            .collect();
 
         Ok(FunctionAnalysisResult {
             function_name: func_name,
@@ -133,51 +758,246 @@ impl FunctionAnalyzer {
             block_count,
             edge_count,
             loops: vec![],
+            estimated_loop_bounds: 0,
+            memset_loops: vec![],
+            loop_bound_decisions: vec![],
             block_timings,
+            block_costs,
+            confidence: Confidence::Approximate,
+            profiled_typical_cycles: None,
+            loop_observations: vec![],
+            cache_assumption: self.cache_assumption,
+            dead_blocks: dead_blocks(&cfg),
+            poison_branches: poison_branches(&cfg),
+            infeasible_paths: vec![],
+            warnings: self.unknown_opcodes(function, &cfg),
         })
     }
 
-    /// Convert InkwellCFG to CFG for IPET solver
-    fn convert_to_cfg(&self, inkwell_cfg: &InkwellCFG) -> CFG {
-        let mut graph = DiGraph::new();
-        let mut label_to_node = AHashMap::new();
-        let mut id_to_node = AHashMap::new();
+    /// A warning for each opcode in the function priced by the default cost
+    ///
+    /// A custom cost function may handle any opcode, so none are reported
+    /// with one.
+    fn unknown_opcodes(&self, function: &FunctionValue, cfg: &InkwellCFG) -> Vec<AnalysisWarning> {
+        if self.cost_fn.is_some() {
+            return Vec::new();
+        }
 
-        // Create nodes
-        for block in &inkwell_cfg.blocks {
-            let cfg_block = BasicBlock {
-                label: block.name.clone(),
-                instructions: vec![],
-                execution_count_var: block.id,
-            };
+        let mut unknown = Vec::new();
+        for block in &cfg.blocks {
+            for instr in block.block.get_instructions() {
+                let opcode = instr.get_opcode();
+                let known =
+                    InkwellTimingCalculator::known_instruction_cost(&opcode, &self.platform)
+                        .is_some();
+                if !known && !unknown.contains(&opcode) {
+                    unknown.push(opcode);
+                }
+            }
+        }
 
-            let node = graph.add_node(cfg_block);
-            label_to_node.insert(block.name.clone(), node);
-            id_to_node.insert(block.id, node);
+        let func_name = function.get_name().to_str().unwrap_or("");
+        unknown
+            .into_iter()
+            .map(|opcode| {
+                AnalysisWarning::in_function(
+                    WarningKind::UnknownOpcode,
+                    func_name,
+                    format!("no timing for {:?}, using the default cost", opcode),
+                )
+            })
+            .collect()
+    }
+
+    /// Cost of one store under the configured cost model
+    fn store_cycles(&self) -> u64 {
+        let store = InstructionOpcode::Store;
+        match &self.cost_fn {
+            Some(cost_fn) => cost_fn(&store),
+            None => InkwellTimingCalculator::instruction_cost_assuming(
+                &store,
+                &self.platform,
+                self.cache_assumption,
+            ),
         }
+    }
 
-        // Create edges
-        for block in &inkwell_cfg.blocks {
-            let from_node = id_to_node[&block.id];
-            for &succ_id in &inkwell_cfg.successors(block.id) {
-                let to_node = id_to_node[&succ_id];
-                graph.add_edge(from_node, to_node, EdgeType::Direct);
-            }
+    /// Best-case block timings in the configured timing mode
+    ///
+    /// A custom cost function gives a single cost per opcode, used for both.
+    fn best_case_block_timings(&self, cfg: &InkwellCFG) -> AHashMap<usize, u64> {
+        if self.cost_fn.is_some() {
+            return self.block_timings(cfg);
         }
 
-        let entry = id_to_node[&inkwell_cfg.entry_block];
+        if self.issue_scheduling {
+            InkwellTimingCalculator::calculate_scheduled_best_case_block_timings(
+                cfg,
+                &self.platform,
+            )
+        } else {
+            InkwellTimingCalculator::calculate_best_case_block_timings(cfg, &self.platform)
+        }
+    }
 
-        // Find exit nodes (nodes with no successors)
-        let exits: Vec<_> = graph
-            .node_indices()
-            .filter(|&idx| graph.neighbors(idx).count() == 0)
-            .collect();
+    /// Calculate block timings in the configured timing mode
+    fn block_timings(&self, cfg: &InkwellCFG) -> AHashMap<usize, u64> {
+        if let Some(cost_fn) = &self.cost_fn {
+            return if self.issue_scheduling {
+                InkwellTimingCalculator::calculate_scheduled_block_timings_with_cost(
+                    cfg,
+                    self.platform.issue_width,
+                    self.platform.fetch_width,
+                    cost_fn.as_ref(),
+                )
+            } else {
+                InkwellTimingCalculator::calculate_block_timings_with_cost(cfg, cost_fn.as_ref())
+            };
+        }
+
+        let data_cache = self
+            .data_cache
+            .as_ref()
+            .filter(|_| self.cache_assumption == CacheAssumption::Cold);
+        let mut timings = if self.issue_scheduling {
+            InkwellTimingCalculator::calculate_scheduled_block_timings_assuming(
+                cfg,
+                &self.platform,
+                self.cache_assumption,
+            )
+        } else if let Some(data_cache) = data_cache {
+            InkwellTimingCalculator::calculate_block_timings_with_cache_analysis(
+                cfg,
+                &self.platform,
+                &DataCacheAnalysis::new(data_cache).analyze(cfg),
+            )
+        } else {
+            InkwellTimingCalculator::calculate_block_timings_assuming(
+                cfg,
+                &self.platform,
+                self.cache_assumption,
+            )
+        };
+        if let Some(instruction_cache) = &self.instruction_cache {
+            if self.cache_assumption == CacheAssumption::Cold {
+                InkwellTimingCalculator::charge_instruction_cache_misses(
+                    &mut timings,
+                    cfg,
+                    instruction_cache,
+                );
+            }
+        }
+        timings
+    }
+}
 
-        CFG {
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BasicBlock, EdgeType};
+    use petgraph::graph::DiGraph;
+
+    #[test]
+    fn test_solve_with_timeout_completes() {
+        let mut graph = DiGraph::new();
+        let entry = graph.add_node(BasicBlock {
+            label: "entry".to_string(),
+            instructions: vec![],
+            execution_count_var: 0,
+            loop_bound: None,
+        });
+
+        let cfg = CFG {
             graph,
             entry,
-            exits,
-            label_to_node,
+            exits: vec![entry],
+            label_to_node: AHashMap::new(),
+        };
+
+        let mut timings = AHashMap::new();
+        timings.insert(entry, Cycles::new(3));
+
+        let solved = FunctionAnalyzer::solve_with_timeout(
+            cfg,
+            timings,
+            &[],
+            Vec::new(),
+            Duration::from_secs(10),
+        );
+
+        assert!(matches!(solved, Some(Ok((3, Some(3))))));
+    }
+
+    #[test]
+    fn test_approximate_wcet_multiplies_loop_bounds() {
+        let mut graph = DiGraph::new();
+        let mut label_to_node = AHashMap::new();
+        let mut nodes = Vec::new();
+        for (id, label) in ["entry", "header", "body", "exit"].iter().enumerate() {
+            let node = graph.add_node(BasicBlock {
+                label: label.to_string(),
+                instructions: vec![],
+                execution_count_var: id,
+                loop_bound: None,
+            });
+            label_to_node.insert(label.to_string(), node);
+            nodes.push(node);
         }
+        graph.add_edge(nodes[0], nodes[1], EdgeType::Direct);
+        graph.add_edge(nodes[1], nodes[2], EdgeType::Direct);
+        graph.add_edge(nodes[2], nodes[1], EdgeType::Direct);
+        graph.add_edge(nodes[1], nodes[3], EdgeType::Direct);
+
+        let cfg = CFG {
+            graph,
+            entry: nodes[0],
+            exits: vec![nodes[3]],
+            label_to_node,
+        };
+
+        let block_timings: AHashMap<usize, u64> =
+            [(0, 2), (1, 1), (2, 5), (3, 3)].into_iter().collect();
+
+        let loops = vec![Loop {
+            header: nodes[1],
+            back_edges: vec![(nodes[2], nodes[1])],
+            body_blocks: [nodes[1], nodes[2]].into_iter().collect(),
+            nesting_level: 0,
+            bounds: LoopBounds::Constant { min: 0, max: 10 },
+        }];
+
+        // entry + exit once, header 11 times and body 10 times
+        assert_eq!(
+            FunctionAnalyzer::approximate_wcet(&FunctionAnalyzer::block_costs(
+                &cfg,
+                &block_timings,
+                &loops
+            )),
+            2 + 3 + 11 * 1 + 10 * 5
+        );
+    }
+
+    #[test]
+    fn test_clamp_heuristic_bound() {
+        let analyzer = FunctionAnalyzer::new(crate::platform::CortexM4Model::new())
+            .with_heuristic_loop_min(4)
+            .with_heuristic_loop_max(256);
+
+        let mut bounds = LoopBounds::Constant { min: 1, max: 10000 };
+        assert_eq!(analyzer.clamp_heuristic_bound(&mut bounds), Some(10000));
+        assert_eq!(bounds, LoopBounds::Constant { min: 1, max: 256 });
+
+        let mut bounds = LoopBounds::Constant { min: 0, max: 2 };
+        assert_eq!(analyzer.clamp_heuristic_bound(&mut bounds), Some(2));
+        assert_eq!(bounds, LoopBounds::Constant { min: 0, max: 4 });
+
+        // Unknown counts as the IPET default of 100, already in range
+        let mut bounds = LoopBounds::Unknown;
+        assert_eq!(analyzer.clamp_heuristic_bound(&mut bounds), None);
+        assert_eq!(bounds, LoopBounds::Unknown);
+
+        let unclamped = FunctionAnalyzer::new(crate::platform::CortexM4Model::new());
+        let mut bounds = LoopBounds::Constant { min: 1, max: 10000 };
+        assert_eq!(unclamped.clamp_heuristic_bound(&mut bounds), None);
     }
 }
@@ -0,0 +1,122 @@
+//! Module-wide WCET hotspots
+//!
+//! Ranks the basic blocks of all analyzed functions by their worst-case
+//! contribution, executions times cost, so optimizing a whole firmware can
+//! start where the most cycles go rather than function by function.
+
+use crate::analyzers::FunctionAnalysisResult;
+use crate::ir::DebugInfo;
+use serde::{Deserialize, Serialize};
+
+/// Number of hotspots reported by default
+pub const DEFAULT_HOTSPOT_COUNT: usize = 10;
+
+/// One block's worst-case contribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHotspot {
+    pub function: String,
+    pub block: String,
+
+    /// Block index in the function's CFG
+    #[serde(default)]
+    pub block_id: usize,
+
+    /// Maximum executions per call of the function
+    pub executions: u64,
+
+    /// Cycles per execution
+    pub cycles: u64,
+
+    /// `executions * cycles`
+    pub total_cycles: u64,
+
+    /// Source file and line the block starts at, if the module has debug info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<u32>,
+}
+
+/// Every block of a function as a hotspot candidate
+pub fn function_hotspots(result: &FunctionAnalysisResult) -> Vec<BlockHotspot> {
+    result
+        .block_costs
+        .iter()
+        .map(|cost| BlockHotspot {
+            function: result.function_name.clone(),
+            block: cost.block.clone(),
+            block_id: cost.block_id,
+            executions: cost.executions,
+            cycles: cost.cycles,
+            total_cycles: cost.total_cycles(),
+            source_file: None,
+            source_line: None,
+        })
+        .collect()
+}
+
+/// Fill in where each hotspot's block starts in the source
+pub fn locate_hotspots(hotspots: &mut [BlockHotspot], debug_info: &DebugInfo) {
+    for hotspot in hotspots {
+        let location = debug_info.block_location(&hotspot.function, hotspot.block_id);
+        hotspot.source_file = location.map(|location| location.file.clone());
+        hotspot.source_line = location.map(|location| location.line);
+    }
+}
+
+/// The `limit` blocks with the largest contribution, largest first
+///
+/// Blocks that cost nothing are dropped; ties are ordered by function and
+/// block so reports are stable between runs.
+pub fn top_hotspots(mut hotspots: Vec<BlockHotspot>, limit: usize) -> Vec<BlockHotspot> {
+    hotspots.retain(|hotspot| hotspot.total_cycles > 0);
+    hotspots.sort_by(|a, b| {
+        b.total_cycles
+            .cmp(&a.total_cycles)
+            .then_with(|| a.function.cmp(&b.function))
+            .then_with(|| a.block_id.cmp(&b.block_id))
+    });
+    hotspots.truncate(limit);
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotspot(function: &str, block: &str, executions: u64, cycles: u64) -> BlockHotspot {
+        BlockHotspot {
+            function: function.to_string(),
+            block: block.to_string(),
+            block_id: 0,
+            executions,
+            cycles,
+            total_cycles: executions * cycles,
+            source_file: None,
+            source_line: None,
+        }
+    }
+
+    #[test]
+    fn test_top_hotspots_ranks_across_functions() {
+        let hotspots = vec![
+            hotspot("init", "entry", 1, 40),
+            hotspot("filter", "loop", 64, 12),
+            hotspot("filter", "entry", 1, 5),
+            hotspot("control", "loop", 100, 3),
+            hotspot("control", "exit", 1, 0),
+        ];
+
+        let top = top_hotspots(hotspots, 3);
+
+        let ranked: Vec<(&str, &str)> = top
+            .iter()
+            .map(|h| (h.function.as_str(), h.block.as_str()))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![("filter", "loop"), ("control", "loop"), ("init", "entry")]
+        );
+        assert_eq!(top[0].total_cycles, 768);
+    }
+}
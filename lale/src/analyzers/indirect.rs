@@ -0,0 +1,339 @@
+//! Conservative WCET for indirect calls through function pointers
+//!
+//! An indirect call costs the WCET of the worst function it may reach. The
+//! candidate targets come from the user; failing that, for a trait-object
+//! call, from the module's vtables; and otherwise from every function in the
+//! module with the call's signature. The last two assume the module is the
+//! whole program. A call without candidates, or with a candidate that has no
+//! bound or fails to analyze, leaves its caller without a sound bound.
+
+use crate::analysis::{AnalysisWarning, WarningKind};
+use crate::analyzers::{callee_cycles, FunctionAnalyzer};
+use crate::ir::{CallGraph, IndirectCallSite, VtableSlot};
+use ahash::{AHashMap, AHashSet};
+use inkwell::module::Module;
+use serde::{Deserialize, Serialize};
+
+/// Where the candidate targets of an indirect call came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetSource {
+    /// Supplied by the user for the calling function
+    Annotation,
+    /// Functions the module's vtables store at the loaded entry
+    Vtable,
+    /// Every function in the module with the call's signature
+    Signature,
+}
+
+/// Cost of one indirect call
+#[derive(Debug, Clone)]
+pub struct IndirectCallWCET {
+    /// Calling function
+    pub caller: String,
+
+    /// Block of the caller containing the call
+    pub block: String,
+
+    /// Index of that block in the caller
+    pub block_id: usize,
+
+    /// Type of the called function
+    pub signature: String,
+
+    /// Vtable entry the call loads its target from, for trait-object calls
+    pub vtable_slot: Option<VtableSlot>,
+
+    /// Functions the call may reach, empty if unknown
+    pub targets: Vec<String>,
+
+    /// Where `targets` came from
+    pub source: TargetSource,
+
+    /// WCET of the worst target, `None` if the targets are unknown or one of
+    /// them has no bound
+    pub wcet_cycles: Option<u64>,
+}
+
+/// WCET of a function with its indirect calls charged their worst target
+#[derive(Debug, Clone)]
+pub struct IndirectCallBound {
+    /// Function containing the indirect calls
+    pub function: String,
+
+    /// Its indirect calls, in block order
+    pub sites: Vec<IndirectCallWCET>,
+
+    /// WCET with every bounded indirect call charged its worst target
+    pub wcet_cycles: u64,
+
+    /// WCET with indirect calls costed as the call instruction alone
+    pub context_free_wcet_cycles: u64,
+}
+
+impl IndirectCallWCET {
+    /// Why the call has no bound, `None` if it has one
+    pub fn unbounded_reason(&self) -> Option<String> {
+        if self.wcet_cycles.is_some() {
+            None
+        } else if self.targets.is_empty() {
+            Some(format!("no function matches {}", self.signature))
+        } else {
+            Some(format!(
+                "unbounded target among {}",
+                self.targets.join(", ")
+            ))
+        }
+    }
+}
+
+impl IndirectCallBound {
+    /// Whether every indirect call has a known, bounded set of targets
+    ///
+    /// If not, `wcet_cycles` leaves out the unbounded calls and is not a
+    /// safe upper bound.
+    pub fn is_sound(&self) -> bool {
+        self.sites.iter().all(|site| site.wcet_cycles.is_some())
+    }
+
+    /// A warning for each indirect call `wcet_cycles` leaves out
+    pub fn warnings(&self) -> Vec<AnalysisWarning> {
+        self.sites
+            .iter()
+            .filter_map(|site| {
+                let reason = site.unbounded_reason()?;
+                Some(AnalysisWarning::in_function(
+                    WarningKind::UnboundedIndirectCall,
+                    &self.function,
+                    format!(
+                        "indirect call in {}: {}, WCET is not a sound bound",
+                        site.block, reason
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Analyzer charging indirect calls the WCET of their candidate targets
+#[derive(Debug, Clone, Default)]
+pub struct IndirectCallAnalyzer {
+    /// User-supplied targets by calling function
+    targets: AHashMap<String, Vec<String>>,
+}
+
+/// Bounds computed so far while analyzing one module
+struct Resolution<'a, 'ctx> {
+    analyzer: &'a FunctionAnalyzer,
+    module: &'a Module<'ctx>,
+    call_graph: CallGraph,
+    /// Interprocedural WCET of each function, `None` if it has no sound bound
+    wcets: AHashMap<String, Option<u64>>,
+    /// Functions whose indirect calls are being resolved
+    in_progress: AHashSet<String>,
+}
+
+impl IndirectCallAnalyzer {
+    /// Create an analyzer inferring every target set from signatures
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the functions the indirect calls in `caller` may reach
+    ///
+    /// Replaces the signature-based candidates for that caller.
+    pub fn with_targets(mut self, caller: impl Into<String>, targets: Vec<String>) -> Self {
+        self.targets.insert(caller.into(), targets);
+        self
+    }
+
+    /// Bound every function of the module that makes indirect calls
+    ///
+    /// Targets are analyzed with `analyzer`, their own indirect calls
+    /// included. A target reached again while its own calls are being
+    /// resolved, i.e. recursion through function pointers, or failing to
+    /// analyze has no bound. Callers that fail to analyze are left out.
+    pub fn analyze_module(
+        &self,
+        analyzer: &FunctionAnalyzer,
+        module: &Module,
+    ) -> Vec<IndirectCallBound> {
+        let mut resolution = Resolution {
+            analyzer,
+            module,
+            call_graph: CallGraph::from_module(module),
+            wcets: AHashMap::new(),
+            in_progress: AHashSet::new(),
+        };
+
+        let callers: Vec<String> = resolution
+            .call_graph
+            .functions
+            .iter()
+            .filter(|name| {
+                !resolution
+                    .call_graph
+                    .indirect_call_sites_from(name)
+                    .is_empty()
+            })
+            .cloned()
+            .collect();
+
+        let mut results = Vec::new();
+        for caller in callers {
+            let Some(function) = module.get_function(&caller) else {
+                continue;
+            };
+
+            let sites = self.resolve_sites(&mut resolution, &caller);
+            let (Ok(charged), Ok(context_free)) = (
+                analyzer.analyze_with_callee_cycles(&function, &Self::callee_cycles(&sites)),
+                analyzer.analyze(&function),
+            ) else {
+                continue;
+            };
+            let wcet_cycles = charged.wcet_cycles;
+            let context_free_wcet_cycles = context_free.wcet_cycles;
+
+            results.push(IndirectCallBound {
+                function: caller,
+                sites,
+                wcet_cycles,
+                context_free_wcet_cycles,
+            });
+        }
+
+        results
+    }
+
+    /// Candidate targets of one indirect call and where they came from
+    fn candidates(
+        &self,
+        call_graph: &CallGraph,
+        site: &IndirectCallSite,
+    ) -> (Vec<String>, TargetSource) {
+        if let Some(targets) = self.targets.get(&site.caller) {
+            return (targets.clone(), TargetSource::Annotation);
+        }
+
+        let to_owned = |targets: Vec<&str>| -> Vec<String> {
+            targets.into_iter().map(str::to_string).collect()
+        };
+        let vtable_targets = call_graph.vtable_targets(site);
+        if !vtable_targets.is_empty() {
+            return (to_owned(vtable_targets), TargetSource::Vtable);
+        }
+        (
+            to_owned(call_graph.functions_with_signature(&site.signature)),
+            TargetSource::Signature,
+        )
+    }
+
+    /// Cost every indirect call in `caller`
+    fn resolve_sites(&self, resolution: &mut Resolution, caller: &str) -> Vec<IndirectCallWCET> {
+        resolution.in_progress.insert(caller.to_string());
+
+        let indirect_sites: Vec<IndirectCallSite> = resolution
+            .call_graph
+            .indirect_call_sites_from(caller)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let mut sites = Vec::new();
+        for site in indirect_sites {
+            let (targets, source) = self.candidates(&resolution.call_graph, &site);
+
+            // Worst target, unbounded if any target is
+            let mut wcet_cycles = (!targets.is_empty()).then_some(0);
+            for target in &targets {
+                let target_wcet = self.function_wcet(resolution, target);
+                wcet_cycles = wcet_cycles.zip(target_wcet).map(|(a, b)| a.max(b));
+            }
+
+            sites.push(IndirectCallWCET {
+                caller: site.caller,
+                block: site.block,
+                block_id: site.block_id,
+                signature: site.signature,
+                vtable_slot: site.vtable_slot,
+                targets,
+                source,
+                wcet_cycles,
+            });
+        }
+
+        resolution.in_progress.remove(caller);
+        sites
+    }
+
+    /// Interprocedural WCET of a possible target, `None` if it has no sound bound
+    fn function_wcet(&self, resolution: &mut Resolution, name: &str) -> Option<u64> {
+        if let Some(&wcet) = resolution.wcets.get(name) {
+            return wcet;
+        }
+        if resolution.in_progress.contains(name) || !resolution.call_graph.is_defined(name) {
+            return None;
+        }
+        let function = resolution.module.get_function(name)?;
+
+        let sites = self.resolve_sites(resolution, name);
+        let sound = sites.iter().all(|site| site.wcet_cycles.is_some());
+        let wcet = resolution
+            .analyzer
+            .analyze_with_callee_cycles(&function, &Self::callee_cycles(&sites))
+            .ok()
+            .filter(|_| sound)
+            .map(|result| result.wcet_cycles);
+
+        resolution.wcets.insert(name.to_string(), wcet);
+        wcet
+    }
+
+    /// Cycles each block spends in the targets of its bounded indirect calls
+    fn callee_cycles(sites: &[IndirectCallWCET]) -> AHashMap<usize, u64> {
+        callee_cycles(
+            sites
+                .iter()
+                .filter_map(|site| Some((site.block_id, site.wcet_cycles?))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(block_id: usize, wcet_cycles: Option<u64>) -> IndirectCallWCET {
+        IndirectCallWCET {
+            caller: "dispatch".to_string(),
+            block: String::new(),
+            block_id,
+            signature: "void (i32)".to_string(),
+            vtable_slot: None,
+            targets: vec!["on_tick".to_string()],
+            source: TargetSource::Signature,
+            wcet_cycles,
+        }
+    }
+
+    #[test]
+    fn test_callee_cycles_sum_bounded_calls_per_block() {
+        // Unnamed blocks, told apart by index
+        let sites = vec![site(0, Some(40)), site(0, Some(25)), site(2, None)];
+
+        let cycles = IndirectCallAnalyzer::callee_cycles(&sites);
+        assert_eq!(cycles.get(&0), Some(&65));
+        assert!(!cycles.contains_key(&2));
+
+        let bound = IndirectCallBound {
+            function: "dispatch".to_string(),
+            sites,
+            wcet_cycles: 80,
+            context_free_wcet_cycles: 15,
+        };
+        assert!(!bound.is_sound());
+        assert_eq!(bound.warnings().len(), 1);
+        assert_eq!(bound.warnings()[0].kind, WarningKind::UnboundedIndirectCall);
+    }
+}
@@ -4,11 +4,24 @@
 //! analysis components into easy-to-use interfaces.
 
 pub mod actor_analyzer;
+pub mod callsite;
 pub mod directory;
 pub mod function;
+pub mod hotspots;
+pub mod indirect;
 pub mod module;
+pub mod system;
 
 pub use actor_analyzer::ActorAnalyzer;
+pub use callsite::{CallSiteAnalyzer, CallSiteWCET, ParameterBound};
 pub use directory::{DirectoryAnalysisResult, DirectoryAnalyzer};
-pub use function::{FunctionAnalysisResult, FunctionAnalyzer};
-pub use module::{FunctionTimingDetails, ModuleAnalysisResult, ModuleAnalyzer};
+pub use function::{
+    callee_cycles, BlockCost, Confidence, FunctionAnalysisResult, FunctionAnalyzer,
+    LoopBoundDecision, LoopBoundSource,
+};
+pub use hotspots::{
+    function_hotspots, locate_hotspots, top_hotspots, BlockHotspot, DEFAULT_HOTSPOT_COUNT,
+};
+pub use indirect::{IndirectCallAnalyzer, IndirectCallBound, IndirectCallWCET, TargetSource};
+pub use module::{CallCacheTiming, FunctionTimingDetails, ModuleAnalysisResult, ModuleAnalyzer};
+pub use system::{SystemAnalysisResult, SystemAnalyzer};
@@ -2,8 +2,12 @@
 //!
 //! Analyzes all functions in an LLVM module.
 
-use crate::analysis::InkwellTimingCalculator;
-use crate::ir::{InkwellCFG, InkwellParser};
+use crate::analysis::{AnalysisWarning, InkwellTimingCalculator, WarningKind};
+use crate::analyzers::{callee_cycles, FunctionAnalyzer};
+use crate::error::LaleError;
+use crate::ir::{CallGraph, InkwellCFG, InkwellParser};
+use crate::microarch::cache::{is_code_cached, CacheState, EntryCacheStates};
+use crate::microarch::state::CacheConfig;
 use crate::platform::PlatformModel;
 use ahash::AHashMap;
 use inkwell::module::Module;
@@ -20,6 +24,31 @@ pub struct ModuleAnalysisResult {
 
     /// Number of functions skipped (intrinsics, declarations)
     pub functions_skipped: usize,
+
+    /// Functions that failed to analyze, and why
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+/// Callee timing at one direct call, from the cache state its caller leaves
+#[derive(Debug, Clone)]
+pub struct CallCacheTiming {
+    /// Calling function
+    pub caller: String,
+
+    /// Called function
+    pub callee: String,
+
+    /// Block of the caller containing the call
+    pub block: String,
+
+    /// Whether the callee's code is guaranteed to be cached at the call
+    pub warm: bool,
+
+    /// Callee WCET from the instruction-cache state at the call
+    pub wcet_cycles: u64,
+
+    /// Callee WCET from a cold cache
+    pub cold_wcet_cycles: u64,
 }
 
 /// Analyzer for LLVM modules
@@ -34,16 +63,25 @@ impl ModuleAnalyzer {
     }
 
     /// Analyze all functions in a module from file
-    pub fn analyze_file(&self, path: impl AsRef<Path>) -> Result<ModuleAnalysisResult, String> {
+    pub fn analyze_file(&self, path: impl AsRef<Path>) -> Result<ModuleAnalysisResult, LaleError> {
         let (_context, module) = InkwellParser::parse_file(path)?;
         self.analyze_module(&module)
     }
 
     /// Analyze all functions in a module
-    pub fn analyze_module(&self, module: &Module) -> Result<ModuleAnalysisResult, String> {
+    ///
+    /// A function's WCET includes the WCET of the defined functions it
+    /// calls. Fails if any functions call each other or themselves
+    /// recursively, since their WCET has no bound.
+    pub fn analyze_module(&self, module: &Module) -> Result<ModuleAnalysisResult, LaleError> {
+        let call_graph = CallGraph::from_module(module);
+        call_graph.reject_recursion()?;
+
         let mut function_wcets = AHashMap::new();
         let mut functions_analyzed = 0;
         let mut functions_skipped = 0;
+        let mut warnings = Vec::new();
+        let mut inclusive_wcets = self.inclusive_wcets(module, &call_graph);
 
         // Iterate through all functions
         let mut func_iter = module.get_first_function();
@@ -58,12 +96,20 @@ impl ModuleAnalyzer {
             }
 
             // Analyze function
-            match self.analyze_function_internal(&function) {
+            let wcet = inclusive_wcets
+                .remove(&func_name)
+                .unwrap_or_else(|| self.analyze_function_internal(&function, &AHashMap::new()));
+            match wcet {
                 Ok(wcet) => {
                     function_wcets.insert(func_name, wcet);
                     functions_analyzed += 1;
                 }
-                Err(_) => {
+                Err(e) => {
+                    warnings.push(AnalysisWarning::in_function(
+                        WarningKind::AnalysisFailure,
+                        func_name,
+                        e,
+                    ));
                     functions_skipped += 1;
                 }
             }
@@ -72,41 +118,125 @@ impl ModuleAnalyzer {
         }
 
         if function_wcets.is_empty() {
-            return Err("No functions were successfully analyzed".to_string());
+            return Err(LaleError::Analysis(
+                "No functions were successfully analyzed".to_string(),
+            ));
         }
 
         Ok(ModuleAnalysisResult {
             function_wcets,
             functions_analyzed,
             functions_skipped,
+            warnings,
         })
     }
 
     /// Analyze a specific function by name
-    pub fn analyze_function(&self, module: &Module, function_name: &str) -> Result<u64, String> {
-        let function = module
-            .get_function(function_name)
-            .ok_or_else(|| format!("Function '{}' not found in module", function_name))?;
+    pub fn analyze_function(&self, module: &Module, function_name: &str) -> Result<u64, LaleError> {
+        let function = module.get_function(function_name).ok_or_else(|| {
+            LaleError::Analysis(format!("Function '{}' not found in module", function_name))
+        })?;
 
-        self.analyze_function_internal(&function)
+        self.analyze_function_internal(&function, &AHashMap::new())
     }
 
-    /// Internal function analysis
-    fn analyze_function_internal(
+    /// WCET of every defined function, including the functions it calls
+    ///
+    /// Callees are analyzed before their callers, so each call costs the
+    /// callee's WCET on top of the flat call/return overhead of the `call`
+    /// itself, on every execution of its block. Calls to functions without a
+    /// body keep only the flat cost.
+    fn inclusive_wcets(
         &self,
-        function: &inkwell::values::FunctionValue,
-    ) -> Result<u64, String> {
-        // Build CFG
-        let cfg = InkwellCFG::from_function(function);
+        module: &Module,
+        call_graph: &CallGraph,
+    ) -> AHashMap<String, Result<u64, LaleError>> {
+        let mut wcets: AHashMap<String, Result<u64, LaleError>> = AHashMap::new();
+        for name in call_graph.callees_first() {
+            let Some(function) = module.get_function(name) else {
+                continue;
+            };
 
-        // Calculate block timings
-        let timings =
-            InkwellTimingCalculator::calculate_block_timings(function, &cfg, &self.platform);
+            let mut calls = Vec::new();
+            let mut failed_callee = None;
+            for site in call_graph.call_sites_from(name) {
+                match wcets.get(&site.callee) {
+                    Some(Ok(wcet)) => calls.push((site.block_id, *wcet)),
+                    Some(Err(_)) => failed_callee = Some(&site.callee),
+                    None => {}
+                }
+            }
+
+            let wcet = match failed_callee {
+                Some(callee) => Err(LaleError::Analysis(format!(
+                    "Callee '{}' has no WCET",
+                    callee
+                ))),
+                None => self.analyze_function_internal(&function, &callee_cycles(calls)),
+            };
+            wcets.insert(name.to_string(), wcet);
+        }
+        wcets
+    }
+
+    /// Time every reachable direct call from the cache state at the call
+    ///
+    /// The instruction-cache state is threaded through the call graph, so
+    /// a callee whose code an earlier call already fetched doesn't pay the
+    /// cold miss at its entry again. Library only; no CLI command calls it.
+    pub fn analyze_calls_with_cache(
+        &self,
+        module: &Module,
+        cache: &CacheConfig,
+    ) -> Result<Vec<CallCacheTiming>, LaleError> {
+        let states = EntryCacheStates::analyze(module, cache);
+        let cold = CacheState::new(cache);
+
+        let mut cold_wcets: AHashMap<String, u64> = AHashMap::new();
+        let mut results = Vec::new();
+        for site_state in states.call_sites() {
+            let site = &site_state.site;
+            let callee = module.get_function(&site.callee).ok_or_else(|| {
+                LaleError::Analysis(format!("Function '{}' not found in module", site.callee))
+            })?;
 
-        // Sum all block timings as a simple WCET estimate
-        let wcet: u64 = timings.values().sum();
+            let cold_wcet_cycles = *cold_wcets
+                .entry(site.callee.clone())
+                .or_insert_with(|| self.cached_wcet(&callee, &cold));
+
+            results.push(CallCacheTiming {
+                caller: site.caller.clone(),
+                callee: site.callee.clone(),
+                block: site.block.clone(),
+                warm: is_code_cached(&site_state.state, &site.callee),
+                wcet_cycles: self.cached_wcet(&callee, &site_state.state),
+                cold_wcet_cycles,
+            });
+        }
 
-        Ok(wcet)
+        Ok(results)
+    }
+
+    /// Sum of block timings with cache effects from a state at entry
+    fn cached_wcet(&self, function: &inkwell::values::FunctionValue, entry: &CacheState) -> u64 {
+        let cfg = InkwellCFG::from_function(function);
+        InkwellTimingCalculator::calculate_with_entry_cache(function, &cfg, &self.platform, entry)
+            .values()
+            .sum()
+    }
+
+    /// Internal function analysis
+    ///
+    /// `callee_cycles` maps a block index to the cycles spent in the
+    /// functions it calls.
+    fn analyze_function_internal(
+        &self,
+        function: &inkwell::values::FunctionValue,
+        callee_cycles: &AHashMap<usize, u64>,
+    ) -> Result<u64, LaleError> {
+        FunctionAnalyzer::new(self.platform.clone())
+            .analyze_with_callee_cycles(function, callee_cycles)
+            .map(|result| result.wcet_cycles)
     }
 
     /// Get detailed timing information for a function
@@ -114,10 +244,10 @@ impl ModuleAnalyzer {
         &self,
         module: &Module,
         function_name: &str,
-    ) -> Result<FunctionTimingDetails, String> {
-        let function = module
-            .get_function(function_name)
-            .ok_or_else(|| format!("Function '{}' not found in module", function_name))?;
+    ) -> Result<FunctionTimingDetails, LaleError> {
+        let function = module.get_function(function_name).ok_or_else(|| {
+            LaleError::Analysis(format!("Function '{}' not found in module", function_name))
+        })?;
 
         // Build CFG
         let cfg = InkwellCFG::from_function(&function);
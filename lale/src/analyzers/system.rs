@@ -0,0 +1,177 @@
+//! System-wide WCET of entry points
+//!
+//! All modules of a system are analyzed together, so an entry point's WCET
+//! includes the WCET of every function it calls, whichever module defines
+//! it. A call resolves to the caller's own module first, as a linker binds
+//! internal functions, and otherwise to an externally visible definition in
+//! another module. Only functions reachable from an entry point are
+//! analyzed.
+
+use crate::analysis::AnalysisWarning;
+use crate::analyzers::{callee_cycles, FunctionAnalyzer};
+use crate::error::LaleError;
+use crate::ir::CallGraph;
+use ahash::AHashMap;
+use inkwell::module::{Linkage, Module};
+
+/// Result of analyzing a system's entry points
+#[derive(Debug, Clone, Default)]
+pub struct SystemAnalysisResult {
+    /// WCET of each entry point and each function it reaches, callees
+    /// included (function_name -> wcet_cycles). Same-named internal
+    /// functions of different modules share the worst of their WCETs.
+    pub function_wcets: AHashMap<String, u64>,
+
+    /// Heuristic loop bounds used by all analyzed functions
+    pub estimated_loop_bounds: usize,
+
+    /// Caveats of the individual function results
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+/// Analyzer for the entry points of a system spread over several modules
+pub struct SystemAnalyzer {
+    analyzer: FunctionAnalyzer,
+}
+
+/// Call graphs of all modules and where each function is defined
+struct SystemGraph<'a, 'ctx> {
+    modules: &'a [&'a Module<'ctx>],
+    call_graphs: Vec<CallGraph>,
+    /// Index of the first module defining each function with external
+    /// linkage
+    external: AHashMap<String, usize>,
+}
+
+impl SystemGraph<'_, '_> {
+    /// Module whose definition of `callee` a call from module `caller`
+    /// reaches, `None` if no module defines it
+    fn resolve(&self, caller: usize, callee: &str) -> Option<usize> {
+        if self.call_graphs[caller].is_defined(callee) {
+            return Some(caller);
+        }
+        self.external.get(callee).copied()
+    }
+}
+
+/// WCETs found so far, by defining module and function
+type Wcets = AHashMap<(usize, String), u64>;
+
+impl SystemAnalyzer {
+    /// Create a system analyzer timing each function with `analyzer`
+    pub fn new(analyzer: FunctionAnalyzer) -> Self {
+        Self { analyzer }
+    }
+
+    /// WCET of each entry point, including every function it calls
+    ///
+    /// A call to a function defined in any of `modules` costs that
+    /// function's WCET on top of the flat call overhead; calls to functions
+    /// without a body keep only the flat cost. Fails if an entry point is
+    /// not defined, a reachable function calls itself recursively or any
+    /// reachable function fails to analyze.
+    pub fn analyze(
+        &self,
+        modules: &[&Module<'_>],
+        entry_points: &[&str],
+    ) -> Result<SystemAnalysisResult, LaleError> {
+        let call_graphs: Vec<CallGraph> = modules
+            .iter()
+            .map(|module| CallGraph::from_module(module))
+            .collect();
+        let mut external = AHashMap::new();
+        for (index, call_graph) in call_graphs.iter().enumerate() {
+            for function in &call_graph.functions {
+                let visible = modules[index].get_function(function).is_some_and(|f| {
+                    !matches!(f.get_linkage(), Linkage::Internal | Linkage::Private)
+                });
+                if visible {
+                    external.entry(function.clone()).or_insert(index);
+                }
+            }
+        }
+        let graph = SystemGraph {
+            modules,
+            call_graphs,
+            external,
+        };
+
+        let mut result = SystemAnalysisResult::default();
+        let mut wcets = Wcets::new();
+        for &entry in entry_points {
+            // An entry point is called from outside, so only an internal
+            // definition that's alone can be meant
+            let index = graph.external.get(entry).copied().or_else(|| {
+                let mut defining =
+                    (0..modules.len()).filter(|&i| graph.call_graphs[i].is_defined(entry));
+                defining.next().filter(|_| defining.next().is_none())
+            });
+            let Some(index) = index else {
+                return Err(LaleError::Analysis(format!(
+                    "Entry point '{}' is not defined in any module, or only internally in several",
+                    entry
+                )));
+            };
+            self.inclusive_wcet(
+                &graph,
+                index,
+                entry,
+                &mut Vec::new(),
+                &mut wcets,
+                &mut result,
+            )?;
+        }
+        Ok(result)
+    }
+
+    /// WCET of `name` as defined in module `index` with its callees,
+    /// analyzing callees first
+    ///
+    /// `stack` holds the functions whose callees are being analyzed; meeting
+    /// one of them again is a recursive cycle.
+    fn inclusive_wcet(
+        &self,
+        graph: &SystemGraph,
+        index: usize,
+        name: &str,
+        stack: &mut Vec<(usize, String)>,
+        wcets: &mut Wcets,
+        result: &mut SystemAnalysisResult,
+    ) -> Result<u64, LaleError> {
+        let key = (index, name.to_string());
+        if let Some(&wcet) = wcets.get(&key) {
+            return Ok(wcet);
+        }
+        if let Some(start) = stack.iter().position(|function| *function == key) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|(_, f)| f.clone()).collect();
+            cycle.sort();
+            return Err(LaleError::Recursion(vec![cycle]));
+        }
+
+        let function = graph.modules[index].get_function(name).ok_or_else(|| {
+            LaleError::Analysis(format!("Function '{}' not found in module", name))
+        })?;
+
+        stack.push(key.clone());
+        let mut calls = Vec::new();
+        for site in graph.call_graphs[index].call_sites_from(name) {
+            let Some(callee_index) = graph.resolve(index, &site.callee) else {
+                continue;
+            };
+            let wcet =
+                self.inclusive_wcet(graph, callee_index, &site.callee, stack, wcets, result)?;
+            calls.push((site.block_id, wcet));
+        }
+        stack.pop();
+
+        let analysis = self
+            .analyzer
+            .analyze_with_callee_cycles(&function, &callee_cycles(calls))?;
+        result.estimated_loop_bounds += analysis.estimated_loop_bounds;
+        result.warnings.extend(analysis.warnings);
+        let reported = result.function_wcets.entry(name.to_string()).or_insert(0);
+        *reported = (*reported).max(analysis.wcet_cycles);
+        wcets.insert(key, analysis.wcet_cycles);
+        Ok(analysis.wcet_cycles)
+    }
+}
@@ -39,6 +39,11 @@ pub struct Actor {
 
     /// Actor-level WCET in microseconds
     pub actor_wcet_us: f64,
+
+    /// Actor-level WCET in cycles on each platform it was analyzed for, by
+    /// platform name
+    #[serde(default)]
+    pub platform_wcet_cycles: AHashMap<String, u64>,
 }
 
 impl Actor {
@@ -62,6 +67,7 @@ impl Actor {
             segment_wcets: AHashMap::new(),
             actor_wcet_cycles: 0,
             actor_wcet_us: 0.0,
+            platform_wcet_cycles: AHashMap::new(),
         }
     }
 
@@ -73,6 +79,22 @@ impl Actor {
         self.actor_wcet_us = self.actor_wcet_cycles as f64 / cpu_freq_mhz as f64;
     }
 
+    /// Record the actor-level WCET from segment WCETs timed on `platform`
+    pub fn record_platform_wcet(&mut self, platform: &str, segment_wcets: &AHashMap<u32, u64>) {
+        let cycles = segment_wcets.values().copied().max().unwrap_or(0);
+        self.platform_wcet_cycles
+            .insert(platform.to_string(), cycles);
+    }
+
+    /// WCET in cycles on `platform`, the actor-level WCET if it was never
+    /// analyzed there
+    pub fn wcet_cycles_on(&self, platform: &str) -> u64 {
+        self.platform_wcet_cycles
+            .get(platform)
+            .copied()
+            .unwrap_or(self.actor_wcet_cycles)
+    }
+
     /// Convert to schedulable task
     pub fn to_task(&self) -> Task {
         Task {
@@ -84,7 +106,7 @@ impl Actor {
             deadline_us: Some(self.deadline_us),
             priority: Some(self.priority),
             preemptible: false, // Cooperative scheduling
-            dependencies: vec![],
+            ..Default::default()
         }
     }
 
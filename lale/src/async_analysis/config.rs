@@ -4,7 +4,9 @@
 
 use crate::async_analysis::actor::{Actor, ActorConfig};
 use crate::config::loader::ConfigManager;
-use crate::platform::PlatformModel;
+use crate::config::types::PlatformConfiguration;
+use crate::error::LaleError;
+use crate::platform::{PlatformCapabilities, PlatformModel};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -92,6 +94,8 @@ fn default_num_cores() -> usize {
 pub enum SchedulingPolicy {
     RMA,
     EDF,
+    /// Deadline Monotonic
+    DM,
 }
 
 impl Default for SchedulingPolicy {
@@ -139,30 +143,57 @@ impl ActorConfigLoader {
     }
 
     /// Load actor system configuration from file
-    pub fn load_system_config(&self, path: impl AsRef<Path>) -> Result<ActorSystemConfig, String> {
+    pub fn load_system_config(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<ActorSystemConfig, LaleError> {
         let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read config file: {}", e))?;
+            .map_err(|e| LaleError::io("Failed to read config file", e))?;
 
-        toml::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+        toml::from_str(&content)
+            .map_err(|e| LaleError::Config(format!("Failed to parse config: {}", e)))
     }
 
     /// Load platform model from configuration
-    pub fn load_platform_model(&mut self, platform_name: &str) -> Result<PlatformModel, String> {
+    pub fn load_platform_model(&mut self, platform_name: &str) -> Result<PlatformModel, LaleError> {
         // Load platform configuration
         let platform_config = self.config_manager.load_platform(platform_name)?;
+        Self::platform_model_from_config(platform_name, &platform_config)
+    }
 
+    /// Convert a resolved platform configuration to a `PlatformModel`
+    pub fn platform_model_from_config(
+        platform_name: &str,
+        platform_config: &PlatformConfiguration,
+    ) -> Result<PlatformModel, LaleError> {
         // Extract CPU frequency
         let cpu_freq_mhz = platform_config
             .soc
             .as_ref()
             .map(|soc| soc.cpu_frequency_mhz)
-            .ok_or_else(|| format!("Platform {} has no SoC configuration", platform_name))?;
+            .ok_or_else(|| {
+                LaleError::Config(format!(
+                    "Platform {} has no SoC configuration",
+                    platform_name
+                ))
+            })?;
 
         // Convert to PlatformModel
         let platform_model = PlatformModel {
             name: platform_name.to_string(),
             cpu_frequency_mhz: cpu_freq_mhz,
-            instruction_timings: Self::build_instruction_timings(&platform_config),
+            issue_width: platform_config.core.pipeline.issue_width as u32,
+            fetch_width: platform_config.core.pipeline.fetch_width as u8,
+            // The ISA gives multiply/divide latencies, and FP operations take
+            // the one-cycle default, so both are modeled as in hardware
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: platform_config.core.cache.instruction_cache.is_some()
+                    || platform_config.core.cache.data_cache.is_some(),
+            },
+            instruction_timings: Self::build_instruction_timings(platform_config),
         };
 
         Ok(platform_model)
@@ -170,7 +201,7 @@ impl ActorConfigLoader {
 
     /// Build instruction timings from platform config
     fn build_instruction_timings(
-        config: &crate::config::types::PlatformConfiguration,
+        config: &PlatformConfiguration,
     ) -> ahash::AHashMap<crate::analysis::timing::InstructionClass, crate::analysis::Cycles> {
         use crate::analysis::{timing::InstructionClass, Cycles};
         use ahash::AHashMap;
@@ -240,19 +271,19 @@ impl ActorConfigLoader {
     }
 
     /// Load Veecle OS Model.toml
-    pub fn load_veecle_model(&self, path: impl AsRef<Path>) -> Result<VeecleModel, String> {
+    pub fn load_veecle_model(&self, path: impl AsRef<Path>) -> Result<VeecleModel, LaleError> {
         let path_ref = path.as_ref();
         eprintln!("Attempting to read Model.toml from: {}", path_ref.display());
 
         let content = std::fs::read_to_string(path_ref).map_err(|e| {
-            format!(
-                "Failed to read Model.toml from {}: {}",
-                path_ref.display(),
-                e
+            LaleError::io(
+                format!("Failed to read Model.toml from {}", path_ref.display()),
+                e,
             )
         })?;
 
-        toml::from_str(&content).map_err(|e| format!("Failed to parse Model.toml: {}", e))
+        toml::from_str(&content)
+            .map_err(|e| LaleError::Config(format!("Failed to parse Model.toml: {}", e)))
     }
 
     /// Extract actor paths from Veecle model
@@ -281,7 +312,7 @@ impl ActorConfigLoader {
         &mut self,
         project_dir: impl AsRef<Path>,
         platform_name: &str,
-    ) -> Result<(Vec<(String, String)>, PlatformModel), String> {
+    ) -> Result<(Vec<(String, String)>, PlatformModel), LaleError> {
         // Load Model.toml
         let model_path = project_dir.as_ref().join("Model.toml");
         let model = self.load_veecle_model(&model_path)?;
@@ -299,7 +330,7 @@ impl ActorConfigLoader {
     pub fn load_complete_system(
         &mut self,
         config_path: impl AsRef<Path>,
-    ) -> Result<(ActorSystemConfig, PlatformModel), String> {
+    ) -> Result<(ActorSystemConfig, PlatformModel), LaleError> {
         let system_config = self.load_system_config(config_path)?;
         let platform_model = self.load_platform_model(&system_config.platform.name)?;
 
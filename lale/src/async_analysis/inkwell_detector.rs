@@ -10,6 +10,7 @@ use inkwell::values::{FunctionValue, InstructionOpcode};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+use crate::error::LaleError;
 use crate::ir::inkwell_parser::{InkwellFunction, InkwellParser, TerminatorKind};
 
 /// Information about detected async function
@@ -45,10 +46,10 @@ pub struct InkwellAsyncDetector;
 
 impl InkwellAsyncDetector {
     /// Detect async functions from LLVM IR file
-    pub fn detect_from_file(path: impl AsRef<Path>) -> Result<Vec<AsyncFunctionInfo>, String> {
+    pub fn detect_from_file(path: impl AsRef<Path>) -> Result<Vec<AsyncFunctionInfo>, LaleError> {
         // Read file first to check if it's empty or has no functions
         let ir_text = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| format!("Failed to read file: {}", e))?;
+            .map_err(|e| LaleError::io("Failed to read file", e))?;
 
         // Quick check: if file has no "define" keyword, it's empty
         if !ir_text.contains("define ") {
@@ -63,16 +64,16 @@ impl InkwellAsyncDetector {
             || ir_text.contains("dead_on_unwind")
             || ir_text.contains("initializes(")
         {
-            return Err("File contains LLVM attributes that cause inkwell to crash (abort/segfault). These cannot be caught by panic handlers. Recompile with older LLVM or wait for inkwell updates.".to_string());
+            return Err(LaleError::Parse("File contains LLVM attributes that cause inkwell to crash (abort/segfault). These cannot be caught by panic handlers. Recompile with older LLVM or wait for inkwell updates.".to_string()));
         }
 
         // Skip very large files (>5000 lines) as they may cause memory issues
         let line_count = ir_text.lines().count();
         if line_count > 5000 {
-            return Err(format!(
+            return Err(LaleError::Parse(format!(
                 "File too large ({} lines). Skipping to avoid potential crashes.",
                 line_count
-            ));
+            )));
         }
 
         let (_context, module) = InkwellParser::parse_file(path)?;
@@ -80,13 +81,13 @@ impl InkwellAsyncDetector {
     }
 
     /// Detect async functions from LLVM IR text
-    pub fn detect_from_ir_text(ir_text: &str) -> Result<Vec<AsyncFunctionInfo>, String> {
+    pub fn detect_from_ir_text(ir_text: &str) -> Result<Vec<AsyncFunctionInfo>, LaleError> {
         let (_context, module) = InkwellParser::parse_ir_from_buffer(ir_text)?;
         Self::detect_from_module(&module)
     }
 
     /// Detect async functions from inkwell module
-    pub fn detect_from_module(module: &Module) -> Result<Vec<AsyncFunctionInfo>, String> {
+    pub fn detect_from_module(module: &Module) -> Result<Vec<AsyncFunctionInfo>, LaleError> {
         let functions = InkwellParser::extract_functions(module);
         let mut results = Vec::new();
 
@@ -277,8 +278,8 @@ impl InkwellAsyncDetector {
 }
 
 /// Analyze async functions in LLVM IR file
-pub fn analyze_async_functions(ir_file_path: &str) -> Result<Vec<AsyncFunctionInfo>, String> {
-    InkwellAsyncDetector::detect_from_file(ir_file_path).map_err(|e| e.to_string())
+pub fn analyze_async_functions(ir_file_path: &str) -> Result<Vec<AsyncFunctionInfo>, LaleError> {
+    InkwellAsyncDetector::detect_from_file(ir_file_path)
 }
 
 #[cfg(test)]
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 use super::inkwell_segment::ActorSegment;
 use crate::analysis::{Cycles, IPETSolver, InkwellTimingCalculator, LoopAnalyzer};
+use crate::ir::inkwell_cfg::{instruction_text, loop_metadata_bound};
 use crate::ir::InkwellCFG;
 use crate::platform::PlatformModel;
 
@@ -140,8 +141,9 @@ impl InkwellSegmentWCETAnalyzer {
         for (new_id, &old_id) in segment_blocks.iter().enumerate() {
             let block = CFGBlock {
                 label: cfg.blocks[old_id].name.clone(),
-                instructions: vec![],
+                instructions: instruction_text(cfg.blocks[old_id].block),
                 execution_count_var: new_id,
+                loop_bound: loop_metadata_bound(cfg.blocks[old_id].block),
             };
 
             let node = graph.add_node(block);
@@ -161,7 +163,12 @@ impl InkwellSegmentWCETAnalyzer {
             for &old_to in &cfg.successors(old_from) {
                 if block_set.contains(&old_to) {
                     let to_node = id_map[&old_to];
-                    graph.add_edge(from_node, to_node, EdgeType::Direct);
+                    let edge_type = if cfg.is_unwind_edge(old_from, old_to) {
+                        EdgeType::Unwind
+                    } else {
+                        EdgeType::Direct
+                    };
+                    graph.add_edge(from_node, to_node, edge_type);
                     has_successor = true;
                 }
             }
@@ -0,0 +1,117 @@
+//! `.laleignore`: inputs a directory analysis leaves out
+//!
+//! One pattern per line; blank lines and lines starting with `#` are
+//! skipped. A pattern without a `/` matches a file or directory name at any
+//! depth, one with a `/` the leading components of a path relative to the
+//! analyzed directory. `*` matches any run of characters within a name.
+
+use crate::error::LaleError;
+use std::fs;
+use std::path::{Component, Path};
+
+/// Name of the ignore file, looked up in the analyzed directory
+pub const IGNORE_FILE: &str = ".laleignore";
+
+/// Patterns of a `.laleignore`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreList {
+    patterns: Vec<String>,
+}
+
+impl IgnoreList {
+    /// Read the `.laleignore` of a directory, empty if there is none
+    pub fn load(dir: &Path) -> Result<Self, LaleError> {
+        let path = dir.join(IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| LaleError::io(format!("Failed to read {}", path.display()), e))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let patterns = content
+            .lines()
+            .map(|line| line.trim().trim_matches('/'))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether a path relative to the analyzed directory is ignored
+    pub fn is_ignored(&self, relative: &Path) -> bool {
+        let names: Vec<&str> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains('/') {
+                let segments: Vec<&str> = pattern.split('/').collect();
+                segments.len() <= names.len()
+                    && segments
+                        .iter()
+                        .zip(&names)
+                        .all(|(segment, name)| wildcard_match(segment, name))
+            } else {
+                names.iter().any(|name| wildcard_match(pattern, name))
+            }
+        })
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or("");
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_match_at_any_depth_and_paths_from_the_root() {
+        let ignore = IgnoreList::parse(
+            "# comment\n\nincremental\nbuild_script_build-*\ndeps/std-*.bc\n*.a\n",
+        );
+
+        assert!(ignore.is_ignored(Path::new("debug/incremental/x/a.o")));
+        assert!(ignore.is_ignored(Path::new("debug/build_script_build-1f2e.ll")));
+        assert!(ignore.is_ignored(Path::new("deps/std-0a1b.bc")));
+        assert!(ignore.is_ignored(Path::new("libfirmware.a")));
+
+        // Paths are anchored at the analyzed directory
+        assert!(!ignore.is_ignored(Path::new("debug/deps/std-0a1b.bc")));
+        assert!(!ignore.is_ignored(Path::new("deps/firmware-0a1b.bc")));
+        assert!(!ignore.is_ignored(Path::new("src/comment.ll")));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYc"));
+        assert!(wildcard_match("a*a", "aa"));
+        assert!(!wildcard_match("a*a", "a"));
+        assert!(!wildcard_match("*.ll", "x.bc"));
+    }
+}
@@ -1,7 +1,26 @@
 use super::types::*;
+use crate::error::LaleError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Environment variable pointing at a board definitions directory
+pub const CONFIG_DIR_ENV: &str = "LALE_CONFIG_DIR";
+
+/// Smallest cache line that holds more than one 32-bit access
+const MIN_SPATIAL_LINE_BYTES: usize = 8;
+
+/// Lines longer than this fill far slower than a single access, which the
+/// fixed miss latency does not capture
+const MAX_MODELED_LINE_BYTES: usize = 64;
+
+/// Board definitions directory compiled into the binary
+const DEFAULT_CONFIG_DIR: &str = match option_env!("LALE_DEFAULT_CONFIG_DIR") {
+    Some(dir) => dir,
+    None => concat!(env!("CARGO_MANIFEST_DIR"), "/../config"),
+};
 
 /// Configuration loader with hierarchical composition
 pub struct ConfigLoader;
@@ -10,15 +29,68 @@ pub struct ConfigLoader;
 pub struct ConfigManager {
     config_dir: PathBuf,
     cache: HashMap<String, PlatformConfiguration>,
+    persistent: Option<PersistentCache>,
+}
+
+/// Resolved configurations kept on disk between invocations
+struct PersistentCache {
+    path: PathBuf,
+
+    /// Entries by path of the configuration file
+    entries: HashMap<String, PersistedConfig>,
+}
+
+/// Resolved configuration and the files it was resolved from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedConfig {
+    /// Every file of the `inherits` chain with its modification time
+    files: Vec<(PathBuf, SystemTime)>,
+    config: PlatformConfiguration,
+}
+
+impl PersistedConfig {
+    /// No file of the chain changed since the entry was written
+    fn is_fresh(&self) -> bool {
+        self.files
+            .iter()
+            .all(|(path, modified)| modification_time(path).as_ref() == Some(modified))
+    }
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// One configuration file in an `inherits` chain
+#[derive(Debug, Clone)]
+pub struct InheritanceLevel {
+    /// Configuration name (e.g. "platforms/stm32f746-discovery")
+    pub name: String,
+
+    /// Sections this file defines
+    pub sections: Vec<SectionContribution>,
+}
+
+/// A top-level section (`isa`, `core`, `soc`, `board`) defined by one level
+#[derive(Debug, Clone)]
+pub struct SectionContribution {
+    pub section: &'static str,
+
+    /// Short description of the section's values
+    pub summary: String,
+
+    /// Level whose section wins in the resolved configuration, if not this one
+    pub overridden_by: Option<String>,
 }
 
 impl ConfigLoader {
     /// Load complete platform configuration from file
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<PlatformConfiguration, String> {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<PlatformConfiguration, LaleError> {
         let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read config file: {}", e))?;
+            fs::read_to_string(path).map_err(|e| LaleError::io("Failed to read config file", e))?;
 
-        toml::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+        toml::from_str(&content)
+            .map_err(|e| LaleError::Config(format!("Failed to parse config: {}", e)))
     }
 
     /// Load configuration with hierarchical composition
@@ -28,7 +100,7 @@ impl ConfigLoader {
         core_path: &str,
         soc_path: Option<&str>,
         board_path: Option<&str>,
-    ) -> Result<PlatformConfiguration, String> {
+    ) -> Result<PlatformConfiguration, LaleError> {
         // Load ISA config
         let isa: ISAConfig = Self::load_toml(isa_path)?;
 
@@ -54,11 +126,12 @@ impl ConfigLoader {
     }
 
     /// Load TOML file
-    fn load_toml<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    fn load_toml<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, LaleError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| LaleError::io(format!("Failed to read {}", path), e))?;
 
-        toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path, e))
+        toml::from_str(&content)
+            .map_err(|e| LaleError::Config(format!("Failed to parse {}: {}", path, e)))
     }
 
     /// Convert to microarch PlatformConfig
@@ -81,6 +154,7 @@ impl ConfigLoader {
                             super::types::ReplacementPolicy::FIFO => ReplacementPolicy::FIFO,
                             super::types::ReplacementPolicy::Random => ReplacementPolicy::LRU, // Default
                         },
+                        write_policy: Self::to_write_policy(c.write_policy),
                     }
                 }),
                 data_cache: config
@@ -98,6 +172,7 @@ impl ConfigLoader {
                             super::types::ReplacementPolicy::FIFO => ReplacementPolicy::FIFO,
                             super::types::ReplacementPolicy::Random => ReplacementPolicy::LRU,
                         },
+                        write_policy: Self::to_write_policy(c.write_policy),
                     }),
             },
             memory_config: MemoryConfig {
@@ -117,6 +192,27 @@ impl ConfigLoader {
             },
         }
     }
+
+    /// Map the config write policy to the microarchitectural one
+    fn to_write_policy(policy: WritePolicy) -> crate::microarch::state::WritePolicy {
+        match policy {
+            WritePolicy::WriteBack => crate::microarch::state::WritePolicy::WriteBack,
+            WritePolicy::WriteThrough => crate::microarch::state::WritePolicy::WriteThrough,
+        }
+    }
+}
+
+impl PersistentCache {
+    /// Write the cache file, ignoring failures
+    fn save(&self) {
+        let Ok(content) = serde_json::to_string(&self.entries) else {
+            return;
+        };
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(&self.path, content);
+    }
 }
 
 impl ConfigManager {
@@ -125,13 +221,122 @@ impl ConfigManager {
         Self {
             config_dir,
             cache: HashMap::new(),
+            persistent: None,
+        }
+    }
+
+    /// Keep resolved configurations in `path` across invocations
+    ///
+    /// An entry is reused until any file of its `inherits` chain is modified.
+    /// A missing or unreadable cache file starts an empty cache, and failing
+    /// to write it only costs the next invocation a reload.
+    pub fn with_persistent_cache(mut self, path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.persistent = Some(PersistentCache { path, entries });
+        self
+    }
+
+    /// Default persistent cache file: `$XDG_CACHE_HOME/lale/boards.json`,
+    /// else `~/.cache/lale/boards.json`
+    pub fn default_cache_file() -> Option<PathBuf> {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .map(|dir| dir.join("lale").join("boards.json"))
+    }
+
+    /// Resolve the board definitions directory
+    ///
+    /// Checked in order:
+    /// 1. an explicit directory (e.g. `--config-dir`)
+    /// 2. `$LALE_CONFIG_DIR`
+    /// 3. `$XDG_CONFIG_HOME/lale` (or `~/.config/lale`), if it exists
+    /// 4. the compiled-in default, if it exists: `$LALE_DEFAULT_CONFIG_DIR`
+    ///    at build time, else the source tree's `config` directory
+    /// 5. `config` relative to the working directory
+    pub fn resolve_config_dir(explicit: Option<PathBuf>) -> PathBuf {
+        let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+        Self::resolve_config_dir_from(
+            explicit.or_else(|| std::env::var_os(CONFIG_DIR_ENV).map(PathBuf::from)),
+            xdg_config_home.map(|dir| dir.join("lale")),
+            PathBuf::from(DEFAULT_CONFIG_DIR),
+        )
+    }
+
+    fn resolve_config_dir_from(
+        explicit: Option<PathBuf>,
+        user_dir: Option<PathBuf>,
+        default_dir: PathBuf,
+    ) -> PathBuf {
+        // An explicitly requested directory is used even if missing, so the
+        // error names the path the user asked for
+        if let Some(dir) = explicit {
+            return dir;
         }
+
+        user_dir
+            .into_iter()
+            .chain(std::iter::once(default_dir))
+            .find(|dir| dir.is_dir())
+            .unwrap_or_else(|| PathBuf::from("config"))
+    }
+
+    /// Directory board definitions are loaded from
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
     }
 
     /// Load platform configuration by name with inheritance resolution
     /// Supports paths like "platforms/stm32f746-discovery" or "cores/cortex-m4"
-    pub fn load_platform(&mut self, name: &str) -> Result<PlatformConfiguration, String> {
-        self.load_platform_with_chain(name, &mut Vec::new())
+    pub fn load_platform(&mut self, name: &str) -> Result<PlatformConfiguration, LaleError> {
+        if self.persistent.is_none() {
+            return self.load_platform_with_chain(name, &mut Vec::new());
+        }
+
+        let key = self.config_path(name).display().to_string();
+        let cached = self
+            .persistent
+            .as_ref()
+            .and_then(|persistent| persistent.entries.get(&key));
+        if let Some(entry) = cached.filter(|entry| entry.is_fresh()) {
+            return Ok(entry.config.clone());
+        }
+
+        // Parents resolved earlier in this process may have changed since
+        self.cache.clear();
+
+        let config = self.load_platform_with_chain(name, &mut Vec::new())?;
+        let files = self
+            .inheritance_chain(name)?
+            .iter()
+            .map(|level| {
+                let path = self.config_path(&level.name);
+                let modified = modification_time(&path).unwrap_or(SystemTime::UNIX_EPOCH);
+                (path, modified)
+            })
+            .collect();
+
+        if let Some(persistent) = &mut self.persistent {
+            persistent.entries.insert(
+                key,
+                PersistedConfig {
+                    files,
+                    config: config.clone(),
+                },
+            );
+            persistent.save();
+        }
+        Ok(config)
+    }
+
+    fn config_path(&self, name: &str) -> PathBuf {
+        self.config_dir.join(format!("{}.toml", name))
     }
 
     /// Internal method to load platform with circular dependency detection
@@ -139,14 +344,14 @@ impl ConfigManager {
         &mut self,
         name: &str,
         chain: &mut Vec<String>,
-    ) -> Result<PlatformConfiguration, String> {
+    ) -> Result<PlatformConfiguration, LaleError> {
         // Check for circular dependency
         if chain.contains(&name.to_string()) {
-            return Err(format!(
+            return Err(LaleError::Config(format!(
                 "Circular dependency detected: {} -> {}",
                 chain.join(" -> "),
                 name
-            ));
+            )));
         }
 
         // Check cache first
@@ -158,7 +363,7 @@ impl ConfigManager {
         chain.push(name.to_string());
 
         // Build full path
-        let path = self.config_dir.join(format!("{}.toml", name));
+        let path = self.config_path(name);
 
         // Load configuration
         let mut config = ConfigLoader::load_from_file(&path)?;
@@ -185,12 +390,90 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Inheritance chain of a configuration, child first
+    ///
+    /// Each level lists the sections its file defines and, following
+    /// `merge_configs`, whether a descendant overrides them.
+    pub fn inheritance_chain(&self, name: &str) -> Result<Vec<InheritanceLevel>, LaleError> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut configs = Vec::new();
+        let mut next = Some(name.to_string());
+
+        while let Some(current) = next {
+            if chain.contains(&current) {
+                return Err(LaleError::Config(format!(
+                    "Circular dependency detected: {} -> {}",
+                    chain.join(" -> "),
+                    current
+                )));
+            }
+
+            let config = ConfigLoader::load_from_file(self.config_path(&current))?;
+            next = config.board.as_ref().and_then(|b| b.inherits.clone());
+
+            chain.push(current);
+            configs.push(config);
+        }
+
+        // isa, core and board always come from the child; soc from the
+        // nearest level that defines one
+        let soc_provider = configs.iter().position(|c| c.soc.is_some());
+
+        let overridden_by =
+            |level: usize, provider: usize| (level != provider).then(|| chain[provider].clone());
+
+        let levels = configs
+            .iter()
+            .enumerate()
+            .map(|(level, config)| {
+                let mut sections = vec![
+                    SectionContribution {
+                        section: "isa",
+                        summary: config.isa.name.clone(),
+                        overridden_by: overridden_by(level, 0),
+                    },
+                    SectionContribution {
+                        section: "core",
+                        summary: format!(
+                            "{} ({}-stage)",
+                            config.core.name, config.core.pipeline.stages
+                        ),
+                        overridden_by: overridden_by(level, 0),
+                    },
+                ];
+
+                if let Some(ref soc) = config.soc {
+                    sections.push(SectionContribution {
+                        section: "soc",
+                        summary: format!("{} @ {} MHz", soc.name, soc.cpu_frequency_mhz),
+                        overridden_by: soc_provider.and_then(|p| overridden_by(level, p)),
+                    });
+                }
+
+                if let Some(ref board) = config.board {
+                    sections.push(SectionContribution {
+                        section: "board",
+                        summary: board.name.clone(),
+                        overridden_by: overridden_by(level, 0),
+                    });
+                }
+
+                InheritanceLevel {
+                    name: chain[level].clone(),
+                    sections,
+                }
+            })
+            .collect();
+
+        Ok(levels)
+    }
+
     /// Merge parent and child configurations (child overrides parent)
     fn merge_configs(
         &self,
         parent: PlatformConfiguration,
         child: PlatformConfiguration,
-    ) -> Result<PlatformConfiguration, String> {
+    ) -> Result<PlatformConfiguration, LaleError> {
         // Child values take precedence, but we keep parent values if child doesn't specify
         Ok(PlatformConfiguration {
             isa: child.isa,                // ISA from child
@@ -203,7 +486,7 @@ impl ConfigManager {
     /// List available platforms
     /// Only returns complete platform configurations (those in platforms/ directory)
     /// Core configs are incomplete and meant to be referenced by platforms
-    pub fn list_platforms(&self) -> Result<Vec<String>, String> {
+    pub fn list_platforms(&self) -> Result<Vec<String>, LaleError> {
         let mut platforms = Vec::new();
 
         // Only scan platforms directory - these are complete configurations
@@ -221,12 +504,12 @@ impl ConfigManager {
         dir: &Path,
         prefix: &str,
         results: &mut Vec<String>,
-    ) -> Result<(), String> {
+    ) -> Result<(), LaleError> {
         let entries = fs::read_dir(dir)
-            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+            .map_err(|e| LaleError::io(format!("Failed to read directory {}", dir.display()), e))?;
 
         for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let entry = entry.map_err(|e| LaleError::io("Failed to read entry", e))?;
             let path = entry.path();
 
             if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("toml") {
@@ -240,7 +523,7 @@ impl ConfigManager {
     }
 
     /// Validate platform configuration
-    pub fn validate(&self, config: &PlatformConfiguration) -> Result<(), String> {
+    pub fn validate(&self, config: &PlatformConfiguration) -> Result<(), LaleError> {
         let mut errors = Vec::new();
 
         // Validate cache sizes are powers of 2
@@ -312,23 +595,107 @@ impl ConfigManager {
                     }
                 }
             }
+
+            if let Some(ref bus) = soc.bus {
+                if bus.bandwidth_bytes_per_cycle == 0 {
+                    errors.push("Bus bandwidth must be greater than 0".to_string());
+                }
+                if bus.arbitration == (BusArbitration::Tdma { slot_cycles: 0 }) {
+                    errors.push("TDMA slot length must be greater than 0".to_string());
+                }
+            }
         }
 
         // Validate pipeline stages
         if config.core.pipeline.stages == 0 {
             errors.push("Pipeline stages must be greater than 0".to_string());
         }
+        if config.core.pipeline.fetch_width == 0
+            || config.core.pipeline.fetch_width > u8::MAX as usize
+        {
+            errors.push(format!("Fetch width must be between 1 and {}", u8::MAX));
+        }
 
         if errors.is_empty() {
             Ok(())
         } else {
-            Err(format!("Validation errors:\n  - {}", errors.join("\n  - ")))
+            Err(LaleError::Config(format!(
+                "Validation errors:\n  - {}",
+                errors.join("\n  - ")
+            )))
+        }
+    }
+
+    /// Validate a configuration that analysis will run against
+    ///
+    /// Bare ISA and core configurations pass [`Self::validate`] so platforms
+    /// can inherit from them, but without an SoC there is no CPU frequency
+    /// to turn cycles into time.
+    pub fn validate_analyzable(&self, config: &PlatformConfiguration) -> Result<(), LaleError> {
+        self.validate(config)?;
+        if config.soc.is_none() {
+            return Err(LaleError::Config(format!(
+                "Validation errors:\n  - No SoC configured for core '{}', so the CPU frequency \
+                 is unknown; add a [soc] section or inherit from an SoC configuration",
+                config.core.name
+            )));
         }
+        Ok(())
+    }
+
+    /// Advisory warnings for configurations that validate but limit the
+    /// precision of cache analysis
+    pub fn precision_warnings(&self, config: &PlatformConfiguration) -> Vec<String> {
+        let caches = [
+            ("Instruction cache", &config.core.cache.instruction_cache),
+            ("Data cache", &config.core.cache.data_cache),
+            ("L2 cache", &config.core.cache.l2_cache),
+        ];
+
+        let mut warnings = Vec::new();
+        for (label, cache) in caches {
+            let Some(cache) = cache else {
+                continue;
+            };
+
+            if cache.associativity == 1 {
+                warnings.push(format!(
+                    "{} is direct-mapped; LRU age analysis degenerates to conflict \
+                     tracking and most accesses will be classified as misses",
+                    label
+                ));
+            }
+
+            if matches!(cache.replacement_policy, ReplacementPolicy::Random) {
+                warnings.push(format!(
+                    "{} uses random replacement, which is analyzed as LRU; \
+                     hit classifications are not guaranteed",
+                    label
+                ));
+            }
+
+            if cache.line_size_bytes < MIN_SPATIAL_LINE_BYTES {
+                warnings.push(format!(
+                    "{} line size {} bytes holds at most one access; \
+                     no spatial locality will be exploited",
+                    label, cache.line_size_bytes
+                ));
+            } else if cache.line_size_bytes > MAX_MODELED_LINE_BYTES {
+                warnings.push(format!(
+                    "{} line size {} bytes exceeds {} bytes; the fixed miss latency \
+                     underestimates line fills and spatial locality is over-credited",
+                    label, cache.line_size_bytes, MAX_MODELED_LINE_BYTES
+                ));
+            }
+        }
+
+        warnings
     }
 
     /// Export resolved configuration to TOML string
-    pub fn export_platform(&self, config: &PlatformConfiguration) -> Result<String, String> {
-        toml::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))
+    pub fn export_platform(&self, config: &PlatformConfiguration) -> Result<String, LaleError> {
+        toml::to_string_pretty(config)
+            .map_err(|e| LaleError::Config(format!("Failed to serialize config: {}", e)))
     }
 }
 
@@ -350,6 +717,8 @@ mod tests {
                 pipeline: PipelineConfig {
                     stages: 3,
                     pipeline_type: PipelineType::InOrder,
+                    issue_width: 1,
+                    fetch_width: 1,
                 },
                 cache: CacheConfiguration {
                     instruction_cache: Some(CacheLevelConfig {
@@ -359,6 +728,7 @@ mod tests {
                         replacement_policy: ReplacementPolicy::LRU,
                         hit_latency: 1,
                         miss_latency: 10,
+                        write_policy: WritePolicy::WriteBack,
                     }),
                     data_cache: None,
                     l2_cache: None,
@@ -392,6 +762,8 @@ mod tests {
                 pipeline: PipelineConfig {
                     stages: 3,
                     pipeline_type: PipelineType::InOrder,
+                    issue_width: 1,
+                    fetch_width: 1,
                 },
                 cache: CacheConfiguration {
                     instruction_cache: Some(CacheLevelConfig {
@@ -401,6 +773,7 @@ mod tests {
                         replacement_policy: ReplacementPolicy::LRU,
                         hit_latency: 1,
                         miss_latency: 10,
+                        write_policy: WritePolicy::WriteBack,
                     }),
                     data_cache: None,
                     l2_cache: None,
@@ -415,6 +788,7 @@ mod tests {
                 name: "test-soc".to_string(),
                 cpu_frequency_mhz: 100,
                 memory_regions: vec![],
+                bus: None,
             }),
             board: None,
         };
@@ -427,6 +801,79 @@ mod tests {
             icache.size_kb = 15; // Not a power of 2
         }
         assert!(manager.validate(&invalid_config).is_err());
+
+        // A bare core validates but is not analyzable without an SoC clock
+        assert!(manager.validate_analyzable(&valid_config).is_ok());
+        let mut bare_core = valid_config.clone();
+        bare_core.soc = None;
+        assert!(manager.validate(&bare_core).is_ok());
+        assert!(matches!(
+            manager.validate_analyzable(&bare_core),
+            Err(LaleError::Config(err)) if err.contains("No SoC configured for core 'cortex-m4'")
+        ));
+
+        let mut unclocked = valid_config.clone();
+        unclocked.soc.as_mut().unwrap().cpu_frequency_mhz = 0;
+        assert!(matches!(
+            manager.validate_analyzable(&unclocked),
+            Err(LaleError::Config(err)) if err.contains("CPU frequency must be greater than 0")
+        ));
+    }
+
+    #[test]
+    fn test_precision_warnings() {
+        let cache = |associativity, line_size_bytes, replacement_policy| CacheLevelConfig {
+            size_kb: 16,
+            line_size_bytes,
+            associativity,
+            replacement_policy,
+            hit_latency: 1,
+            miss_latency: 10,
+            write_policy: WritePolicy::WriteBack,
+        };
+
+        let mut config = PlatformConfiguration {
+            isa: ISAConfig {
+                name: "armv7e-m".to_string(),
+                instruction_timings: InstructionTimings::default(),
+            },
+            core: CoreConfig {
+                name: "cortex-m7".to_string(),
+                pipeline: PipelineConfig {
+                    stages: 6,
+                    pipeline_type: PipelineType::InOrder,
+                    issue_width: 2,
+                    fetch_width: 2,
+                },
+                cache: CacheConfiguration {
+                    instruction_cache: Some(cache(4, 32, ReplacementPolicy::LRU)),
+                    data_cache: Some(cache(4, 32, ReplacementPolicy::LRU)),
+                    l2_cache: None,
+                },
+                memory: MemoryConfiguration {
+                    load_buffer_size: 4,
+                    store_buffer_size: 4,
+                    memory_latency: MemoryLatencyConfig::Fixed { cycles: 10 },
+                },
+            },
+            soc: None,
+            board: None,
+        };
+
+        let manager = ConfigManager::new(PathBuf::from("config"));
+        assert!(manager.precision_warnings(&config).is_empty());
+
+        config.core.cache.instruction_cache = Some(cache(1, 32, ReplacementPolicy::LRU));
+        config.core.cache.data_cache = Some(cache(4, 128, ReplacementPolicy::Random));
+
+        let warnings = manager.precision_warnings(&config);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("direct-mapped"));
+        assert!(warnings[1].contains("random replacement"));
+        assert!(warnings[2].contains("128 bytes"));
+
+        // Advisory only: the configuration still validates
+        assert!(manager.validate(&config).is_ok());
     }
 
     #[test]
@@ -437,4 +884,210 @@ mod tests {
         // Should succeed even if directories don't exist
         assert!(platforms.is_ok());
     }
+
+    fn write_config(dir: &Path, name: &str, config: &PlatformConfiguration) {
+        let path = dir.join(format!("{}.toml", name));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, toml::to_string_pretty(config).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_inheritance_chain() {
+        let dir = std::env::temp_dir().join(format!("lale-board-tree-{}", std::process::id()));
+
+        let parent = PlatformConfiguration {
+            isa: ISAConfig {
+                name: "armv7e-m".to_string(),
+                instruction_timings: InstructionTimings::default(),
+            },
+            core: CoreConfig {
+                name: "cortex-m4".to_string(),
+                pipeline: PipelineConfig {
+                    stages: 3,
+                    pipeline_type: PipelineType::InOrder,
+                    issue_width: 1,
+                    fetch_width: 1,
+                },
+                cache: CacheConfiguration {
+                    instruction_cache: None,
+                    data_cache: None,
+                    l2_cache: None,
+                },
+                memory: MemoryConfiguration {
+                    load_buffer_size: 4,
+                    store_buffer_size: 4,
+                    memory_latency: MemoryLatencyConfig::Fixed { cycles: 10 },
+                },
+            },
+            soc: Some(SoCConfig {
+                name: "stm32f4".to_string(),
+                cpu_frequency_mhz: 168,
+                memory_regions: vec![],
+                bus: None,
+            }),
+            board: Some(BoardConfig {
+                name: "base".to_string(),
+                inherits: None,
+                external_memory: None,
+            }),
+        };
+
+        let mut child = parent.clone();
+        child.core.name = "cortex-m4f".to_string();
+        child.soc = None;
+        child.board = Some(BoardConfig {
+            name: "custom".to_string(),
+            inherits: Some("platforms/base".to_string()),
+            external_memory: None,
+        });
+
+        write_config(&dir, "platforms/base", &parent);
+        write_config(&dir, "platforms/custom", &child);
+
+        let manager = ConfigManager::new(dir.clone());
+        let chain = manager.inheritance_chain("platforms/custom").unwrap();
+
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].name, "platforms/custom");
+        assert_eq!(chain[1].name, "platforms/base");
+
+        // Child has no SoC; the parent's is used
+        assert!(chain[0].sections.iter().all(|s| s.section != "soc"));
+        let parent_soc = chain[1].sections.iter().find(|s| s.section == "soc");
+        assert!(parent_soc.unwrap().overridden_by.is_none());
+
+        let parent_core = chain[1].sections.iter().find(|s| s.section == "core");
+        assert_eq!(
+            parent_core.unwrap().overridden_by.as_deref(),
+            Some("platforms/custom")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_persistent_cache_invalidated_by_chain() {
+        let dir = std::env::temp_dir().join(format!("lale-board-cache-{}", std::process::id()));
+        let cache_file = dir.join("cache").join("boards.json");
+
+        let mut parent = PlatformConfiguration {
+            isa: ISAConfig {
+                name: "armv7e-m".to_string(),
+                instruction_timings: InstructionTimings::default(),
+            },
+            core: CoreConfig {
+                name: "cortex-m4".to_string(),
+                pipeline: PipelineConfig {
+                    stages: 3,
+                    pipeline_type: PipelineType::InOrder,
+                    issue_width: 1,
+                    fetch_width: 1,
+                },
+                cache: CacheConfiguration {
+                    instruction_cache: None,
+                    data_cache: None,
+                    l2_cache: None,
+                },
+                memory: MemoryConfiguration {
+                    load_buffer_size: 4,
+                    store_buffer_size: 4,
+                    memory_latency: MemoryLatencyConfig::Fixed { cycles: 10 },
+                },
+            },
+            soc: Some(SoCConfig {
+                name: "stm32f4".to_string(),
+                cpu_frequency_mhz: 168,
+                memory_regions: vec![],
+                bus: None,
+            }),
+            board: None,
+        };
+        let mut child = parent.clone();
+        child.soc = None;
+        child.board = Some(BoardConfig {
+            name: "custom".to_string(),
+            inherits: Some("socs/base".to_string()),
+            external_memory: None,
+        });
+        write_config(&dir, "socs/base", &parent);
+        write_config(&dir, "platforms/custom", &child);
+
+        let load = || {
+            ConfigManager::new(dir.clone())
+                .with_persistent_cache(cache_file.clone())
+                .load_platform("platforms/custom")
+                .unwrap()
+        };
+        assert_eq!(load().core.name, "cortex-m4");
+        assert!(cache_file.exists());
+
+        // Same modification times: the next invocation reuses the cache
+        let child_path = dir.join("platforms/custom.toml");
+        let child_modified = modification_time(&child_path).unwrap();
+        child.core.name = "cortex-m4f".to_string();
+        write_config(&dir, "platforms/custom", &child);
+        fs::File::options()
+            .write(true)
+            .open(&child_path)
+            .unwrap()
+            .set_modified(child_modified)
+            .unwrap();
+        assert_eq!(load().core.name, "cortex-m4");
+
+        // Changing the parent invalidates the child's entry
+        parent.soc.as_mut().unwrap().cpu_frequency_mhz = 180;
+        write_config(&dir, "socs/base", &parent);
+        let parent_path = dir.join("socs/base.toml");
+        fs::File::options()
+            .write(true)
+            .open(&parent_path)
+            .unwrap()
+            .set_modified(child_modified + std::time::Duration::from_secs(60))
+            .unwrap();
+        let reloaded = load();
+        assert_eq!(reloaded.core.name, "cortex-m4f");
+        assert_eq!(reloaded.soc.unwrap().cpu_frequency_mhz, 180);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_config_dir_order() {
+        let user_dir = std::env::temp_dir().join(format!("lale-config-{}", std::process::id()));
+        let missing = user_dir.join("missing");
+        fs::create_dir_all(&user_dir).unwrap();
+
+        // Existing user directory wins over the compiled-in default
+        assert_eq!(
+            ConfigManager::resolve_config_dir_from(None, Some(user_dir.clone()), missing.clone()),
+            user_dir
+        );
+
+        // Missing directories fall through to ./config
+        assert_eq!(
+            ConfigManager::resolve_config_dir_from(None, Some(missing.clone()), missing.clone()),
+            PathBuf::from("config")
+        );
+
+        // Explicit directory is used even if it doesn't exist
+        assert_eq!(
+            ConfigManager::resolve_config_dir_from(
+                Some(missing.clone()),
+                Some(user_dir.clone()),
+                user_dir.clone()
+            ),
+            missing
+        );
+
+        fs::remove_dir_all(&user_dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_config_dir_prefers_explicit() {
+        let dir = ConfigManager::resolve_config_dir(Some(PathBuf::from("/opt/boards")));
+        assert_eq!(dir, PathBuf::from("/opt/boards"));
+
+        let manager = ConfigManager::new(dir);
+        assert_eq!(manager.config_dir(), Path::new("/opt/boards"));
+    }
 }
@@ -1,5 +1,11 @@
+pub mod ignore;
 pub mod loader;
+pub mod scaffold;
 pub mod types;
 
-pub use loader::{ConfigLoader, ConfigManager};
+pub use ignore::{IgnoreList, IGNORE_FILE};
+
+pub use loader::{
+    ConfigLoader, ConfigManager, InheritanceLevel, SectionContribution, CONFIG_DIR_ENV,
+};
 pub use types::{BoardConfig, CoreConfig, ISAConfig, PlatformConfiguration, SoCConfig};
@@ -0,0 +1,228 @@
+//! Starter files for a new project
+//!
+//! `lale init` writes a board definition to fill in, a flow-facts file with
+//! an entry for every loop found in the project's IR, and a `.laleignore`,
+//! so nothing has to be written from a blank page.
+
+/// Where the starter board definition is written, relative to the project
+pub const BOARD_FILE: &str = "config/platforms/my-board.toml";
+
+/// Where the starter flow facts are written, relative to the project
+pub const FLOW_FACTS_FILE: &str = "flow_facts.toml";
+
+/// Starter board definition, with placeholder values for a cacheless Cortex-M4
+pub const BOARD_TEMPLATE: &str = r#"# My board: starter platform configuration written by `lale init`
+#
+# Every value below is a placeholder for a Cortex-M4 part without caches;
+# replace them with the figures from your core's technical reference manual
+# and your SoC's datasheet.
+# Check the result with `lale validate-board platforms/my-board`.
+
+[isa]
+# Instruction set, e.g. "armv7e-m", "armv6-m", "riscv32"
+name = "armv7e-m"
+
+# Cycles per instruction class
+[isa.instruction_timings]
+alu = 1
+load = 2
+store = 2
+branch = 2
+multiply = 1
+divide = 12
+
+[core]
+# Core name, e.g. "cortex-m4", "cortex-m7", "rv32imac"
+name = "cortex-m4"
+
+[core.pipeline]
+stages = 3
+# "inorder" or "outoforder"
+pipeline_type = "inorder"
+# Instructions issued and fetched per cycle
+issue_width = 1
+fetch_width = 1
+
+[core.cache]
+# No caches by default. Uncomment for a core with an instruction cache;
+# [core.cache.data_cache] and [core.cache.l2_cache] take the same fields.
+# [core.cache.instruction_cache]
+# size_kb = 16
+# line_size_bytes = 32
+# associativity = 4
+# replacement_policy = "LRU"  # LRU, PLRU, FIFO or RANDOM
+# hit_latency = 1
+# miss_latency = 10
+
+[core.memory]
+load_buffer_size = 1
+store_buffer_size = 1
+
+# "fixed" with `cycles`, or "variable" with `min` and `max`
+[core.memory.memory_latency]
+type = "fixed"
+cycles = 2
+
+[soc]
+# SoC name, and the CPU clock that cycles are converted to time with
+name = "my-soc"
+cpu_frequency_mhz = 168
+
+# One entry per memory region, with its access latency in cycles
+[[soc.memory_regions]]
+name = "flash"
+start = 0x08000000
+size = 1048576
+latency = 5
+
+[[soc.memory_regions]]
+name = "sram"
+start = 0x20000000
+size = 131072
+latency = 1
+
+[board]
+name = "my-board"
+# Inherit everything above from another configuration instead, e.g.
+# inherits = "platforms/nucleo-h743zi"
+"#;
+
+/// A function found in the project, for the flow-facts template
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredFunction {
+    /// LLVM name, as the analysis reports it
+    pub name: String,
+
+    /// Demangled name, if it differs
+    pub demangled: Option<String>,
+
+    /// Loops, by flow-facts key (header label, or `#<block index>` without
+    /// one), with the bound the analysis estimates
+    pub loops: Vec<(String, Option<u64>)>,
+}
+
+/// Flow facts with a table per function and a commented-out bound per loop
+///
+/// The estimated bounds are heuristic guesses, written for reference only:
+/// a bound takes effect once its line is uncommented.
+pub fn flow_facts_template(functions: &[DiscoveredFunction]) -> String {
+    let mut toml = String::from(
+        "# Flow facts: loop bounds the analysis cannot derive, written by `lale init`\n\
+         #\n\
+         # A table per function, by LLVM name, with the maximum iterations of its\n\
+         # loops by header block label, or by `#` and block index if unlabelled:\n\
+         #\n\
+         #   [\"my_function\"]\n\
+         #   \"for.body\" = 64\n\
+         #   \"#3\" = 16\n\
+         #\n\
+         # Uncomment the loops whose bound you know and correct the value.\n",
+    );
+
+    for function in functions {
+        toml.push('\n');
+        if let Some(demangled) = &function.demangled {
+            toml.push_str(&format!("# {}\n", demangled));
+        }
+        toml.push_str(&format!("[{}]\n", toml_key(&function.name)));
+        for (header, estimated_max) in &function.loops {
+            match estimated_max {
+                Some(max) => {
+                    toml.push_str(&format!("# {} = {}  # estimated\n", toml_key(header), max))
+                }
+                None => toml.push_str(&format!("# {} =  # unknown\n", toml_key(header))),
+            }
+        }
+    }
+    toml
+}
+
+/// Starter `.laleignore`
+pub const IGNORE_TEMPLATE: &str =
+    "# Inputs `lale analyze` and `lale schedule` skip, written by `lale init`
+#
+# One pattern per line. Patterns without a `/` match a file or directory
+# name at any depth, others a path from the analyzed directory; `*`
+# matches any part of a name.
+
+# Cargo's incremental compilation cache
+incremental
+
+# Build scripts run on the host, not the target
+build_script_build-*
+";
+
+/// A name as a quoted TOML key
+fn toml_key(name: &str) -> String {
+    toml::Value::String(name.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigManager, PlatformConfiguration};
+
+    #[test]
+    fn test_board_template_is_analyzable() {
+        let config: PlatformConfiguration = toml::from_str(BOARD_TEMPLATE).unwrap();
+        let manager = ConfigManager::new(std::env::temp_dir());
+
+        assert!(manager.validate_analyzable(&config).is_ok());
+        assert_eq!(config.board.unwrap().name, "my-board");
+    }
+
+    #[test]
+    fn test_flow_facts_template_lists_functions_with_loops_commented() {
+        let functions = vec![
+            DiscoveredFunction {
+                name: "_ZN4demo6filter17h0123456789abcdefE".to_string(),
+                demangled: Some("demo::filter".to_string()),
+                loops: vec![
+                    ("for.body".to_string(), Some(100)),
+                    ("bb3".to_string(), None),
+                ],
+            },
+            DiscoveredFunction {
+                name: "init".to_string(),
+                demangled: None,
+                loops: vec![],
+            },
+        ];
+
+        let template = flow_facts_template(&functions);
+        assert!(template.contains("# demo::filter\n"));
+        assert!(template.contains("# \"for.body\" = 100  # estimated\n"));
+
+        // Nothing is bounded until uncommented
+        let parsed: toml::Table = toml::from_str(&template).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed["_ZN4demo6filter17h0123456789abcdefE"]
+            .as_table()
+            .unwrap()
+            .is_empty());
+        assert!(parsed["init"].as_table().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unlabelled_loops_get_distinct_keys() {
+        use crate::analysis::{loop_key, FlowFactStore};
+
+        let functions = vec![DiscoveredFunction {
+            name: "spin".to_string(),
+            demangled: None,
+            loops: vec![(loop_key("", 1), Some(8)), (loop_key("", 4), None)],
+        }];
+
+        let template = flow_facts_template(&functions);
+        assert!(template.contains("# \"#1\" = 8  # estimated\n"));
+        assert!(template.contains("# \"#4\" =  # unknown\n"));
+
+        // Uncommented, both bounds load side by side
+        let uncommented = template
+            .replace("# \"#1\" = 8  # estimated", "\"#1\" = 8")
+            .replace("# \"#4\" =  # unknown", "\"#4\" = 16");
+        let facts = FlowFactStore::from_toml(&uncommented).unwrap();
+        assert_eq!(facts.loop_bound("spin", "#1"), Some(8));
+        assert_eq!(facts.loop_bound("spin", "#4"), Some(16));
+    }
+}
@@ -53,6 +53,10 @@ pub struct SoCConfig {
 
     /// Memory regions
     pub memory_regions: Vec<MemoryRegion>,
+
+    /// Shared memory bus (optional, relevant for multicore SoCs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bus: Option<BusConfig>,
 }
 
 /// Board-level configuration
@@ -77,6 +81,22 @@ pub struct PipelineConfig {
 
     /// Pipeline type
     pub pipeline_type: PipelineType,
+
+    /// Instructions issued per cycle (2 for dual-issue cores)
+    #[serde(default = "default_issue_width")]
+    pub issue_width: usize,
+
+    /// Instructions fetched per cycle (wider on cores with 64/128-bit fetch)
+    #[serde(default = "default_fetch_width")]
+    pub fetch_width: usize,
+}
+
+fn default_issue_width() -> usize {
+    1
+}
+
+fn default_fetch_width() -> usize {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +139,10 @@ pub struct CacheLevelConfig {
 
     /// Miss latency in cycles
     pub miss_latency: u32,
+
+    /// Write policy (default: write-back)
+    #[serde(default)]
+    pub write_policy: WritePolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +154,17 @@ pub enum ReplacementPolicy {
     Random,
 }
 
+/// Cache write policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WritePolicy {
+    /// Stores update only the cache; dirty lines are written back on eviction
+    #[default]
+    WriteBack,
+    /// Every store is also written to memory
+    WriteThrough,
+}
+
 /// Memory configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryConfiguration {
@@ -166,6 +201,26 @@ pub struct MemoryRegion {
     pub latency: u32,
 }
 
+/// Shared memory bus configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusConfig {
+    /// Arbitration between contending cores
+    pub arbitration: BusArbitration,
+
+    /// Bus bandwidth in bytes transferred per cycle
+    pub bandwidth_bytes_per_cycle: u32,
+}
+
+/// Bus arbitration policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum BusArbitration {
+    /// Each pending core is served in turn
+    RoundRobin,
+    /// Each core owns a fixed slot of a repeating frame
+    Tdma { slot_cycles: u32 },
+}
+
 /// External memory configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalMemoryConfig {
@@ -230,6 +285,8 @@ mod tests {
         let config = PipelineConfig {
             stages: 5,
             pipeline_type: PipelineType::InOrder,
+            issue_width: 1,
+            fetch_width: 1,
         };
         assert_eq!(config.stages, 5);
     }
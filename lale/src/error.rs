@@ -0,0 +1,120 @@
+//! Errors of the analysis API
+
+use thiserror::Error;
+
+/// Why an analysis could not produce a result
+#[derive(Debug, Error)]
+pub enum LaleError {
+    /// LLVM IR or bitcode that LLVM rejects
+    #[error("{0}")]
+    Parse(String),
+
+    /// A cycle of the CFG with no iteration bound, so no finite WCET
+    #[error("Unbounded loop: {0}")]
+    UnboundedLoop(String),
+
+    /// Recursive call cycles, each sorted by function name
+    #[error("Recursive functions have no bounded WCET: {}", format_cycles(.0))]
+    Recursion(Vec<Vec<String>>),
+
+    /// Missing, malformed or inconsistent configuration, flow facts or
+    /// profile
+    #[error("{0}")]
+    Config(String),
+
+    /// Timing constraints no execution can satisfy
+    #[error("Infeasible: {0}")]
+    Infeasible(String),
+
+    /// Reading or writing a file failed
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Any other failure of an analysis, e.g. no function analyzed
+    #[error("{0}")]
+    Analysis(String),
+}
+
+impl LaleError {
+    /// I/O error with what was being done when it happened
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Self::Io {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+impl From<good_lp::ResolutionError> for LaleError {
+    fn from(error: good_lp::ResolutionError) -> Self {
+        match error {
+            good_lp::ResolutionError::Unbounded => Self::UnboundedLoop(
+                "the IPET problem has no maximum, a cycle of the CFG is not a bounded loop"
+                    .to_string(),
+            ),
+            good_lp::ResolutionError::Infeasible => Self::Infeasible(
+                "no execution counts satisfy the flow and loop bound constraints".to_string(),
+            ),
+            error => Self::Analysis(format!("ILP solver failed: {:?}", error)),
+        }
+    }
+}
+
+impl From<LaleError> for String {
+    fn from(error: LaleError) -> Self {
+        error.to_string()
+    }
+}
+
+fn format_cycles(cycles: &[Vec<String>]) -> String {
+    cycles
+        .iter()
+        .map(|cycle| format!("[{}]", cycle.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_and_string_conversion() {
+        let recursion = LaleError::Recursion(vec![
+            vec!["a".to_string()],
+            vec!["b".to_string(), "c".to_string()],
+        ]);
+        assert_eq!(
+            recursion.to_string(),
+            "Recursive functions have no bounded WCET: [a], [b, c]"
+        );
+
+        let io = LaleError::io(
+            "Failed to read IR file",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no such file"),
+        );
+        assert!(std::error::Error::source(&io).is_some());
+        let message: String = io.into();
+        assert_eq!(message, "Failed to read IR file: no such file");
+    }
+
+    #[test]
+    fn test_solver_errors_map_to_variants() {
+        assert!(matches!(
+            LaleError::from(good_lp::ResolutionError::Unbounded),
+            LaleError::UnboundedLoop(_)
+        ));
+        assert!(matches!(
+            LaleError::from(good_lp::ResolutionError::Infeasible),
+            LaleError::Infeasible(_)
+        ));
+        assert!(matches!(
+            LaleError::from(good_lp::ResolutionError::Other("Stopped")),
+            LaleError::Analysis(_)
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! Whether a load may read what an earlier store wrote
+//!
+//! A load after a store is assumed to depend on it unless the IR proves the
+//! two touch different memory. Three kinds of proof are used:
+//!
+//! - provenance: after stripping GEPs and casts, the pointers are based on
+//!   distinct allocas or globals, or on an alloca and a function argument
+//! - scoped `!noalias` metadata: every scope of one access is in the other's
+//!   `!noalias` list
+//! - type-based `!tbaa` metadata: the access types are in the same type tree
+//!   and neither is an ancestor of the other
+//!
+//! Anything else, including missing metadata, is assumed to alias.
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValueEnum, InstructionOpcode, InstructionValue, MetadataValue,
+    PointerValue,
+};
+
+/// Deepest TBAA type tree followed before giving up on a chain
+const MAX_TBAA_DEPTH: usize = 64;
+
+/// Object a pointer is based on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnderlyingObject<'ctx> {
+    /// Stack slot of the function
+    Alloca(InstructionValue<'ctx>),
+
+    /// Global variable or function, by name
+    Global(String),
+
+    /// Function argument, by position
+    Argument(u32),
+
+    /// Anything else, e.g. a pointer loaded from memory
+    Unknown,
+}
+
+impl UnderlyingObject<'_> {
+    /// Whether pointers based on the two objects may overlap
+    pub fn may_alias(&self, other: &Self) -> bool {
+        use UnderlyingObject::*;
+        match (self, other) {
+            (Alloca(a), Alloca(b)) => a == b,
+            (Global(a), Global(b)) => a == b,
+            // A stack slot is neither a global nor reachable from a pointer
+            // the caller passed in before it existed
+            (Alloca(_), Global(_) | Argument(_)) | (Global(_) | Argument(_), Alloca(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+/// What a load or store touches, as far as the IR tells
+#[derive(Debug, Clone)]
+pub struct MemoryAccess<'ctx> {
+    pub object: UnderlyingObject<'ctx>,
+
+    /// Scopes of the access's `!alias.scope`
+    pub scopes: Vec<MetadataValue<'ctx>>,
+
+    /// Scopes of the access's `!noalias`
+    pub noalias: Vec<MetadataValue<'ctx>>,
+
+    /// TBAA access type followed by its ancestors up to the root, empty
+    /// without `!tbaa`
+    pub tbaa_types: Vec<MetadataValue<'ctx>>,
+}
+
+impl<'ctx> MemoryAccess<'ctx> {
+    /// Access of a load or store in `block`; `None` for other instructions
+    pub fn of(block: BasicBlock<'ctx>, instr: InstructionValue<'ctx>) -> Option<Self> {
+        let pointer_operand = match instr.get_opcode() {
+            InstructionOpcode::Load => 0,
+            InstructionOpcode::Store => 1,
+            _ => return None,
+        };
+        let object = match instr.get_operand(pointer_operand)?.value()? {
+            BasicValueEnum::PointerValue(pointer) => underlying_object(block, pointer),
+            _ => UnderlyingObject::Unknown,
+        };
+
+        let context = block.get_context();
+        let scope_list = |kind: &str| {
+            instr
+                .get_metadata(context.get_kind_id(kind))
+                .map(metadata_operands)
+                .unwrap_or_default()
+        };
+        Some(Self {
+            object,
+            scopes: scope_list("alias.scope"),
+            noalias: scope_list("noalias"),
+            tbaa_types: instr
+                .get_metadata(context.get_kind_id("tbaa"))
+                .map(tbaa_type_chain)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Whether the two accesses may touch the same memory
+    pub fn may_alias(&self, other: &Self) -> bool {
+        self.object.may_alias(&other.object)
+            && !Self::scoped_apart(self, other)
+            && !Self::scoped_apart(other, self)
+            && !self.tbaa_apart(other)
+    }
+
+    /// Whether `a` is declared not to alias any scope `b` is in
+    fn scoped_apart(a: &Self, b: &Self) -> bool {
+        !b.scopes.is_empty() && b.scopes.iter().all(|scope| a.noalias.contains(scope))
+    }
+
+    /// Whether the TBAA access types rule out overlap
+    fn tbaa_apart(&self, other: &Self) -> bool {
+        let (Some(own), Some(others)) = (self.tbaa_types.first(), other.tbaa_types.first()) else {
+            return false;
+        };
+        // Types of different trees say nothing about each other
+        self.tbaa_types.last() == other.tbaa_types.last()
+            && !self.tbaa_types.contains(others)
+            && !other.tbaa_types.contains(own)
+    }
+}
+
+/// Object `pointer` is based on, looking through GEPs and casts
+fn underlying_object<'ctx>(
+    block: BasicBlock<'ctx>,
+    mut pointer: PointerValue<'ctx>,
+) -> UnderlyingObject<'ctx> {
+    while let Some(instr) = pointer.as_instruction() {
+        match instr.get_opcode() {
+            InstructionOpcode::Alloca => return UnderlyingObject::Alloca(instr),
+            InstructionOpcode::GetElementPtr
+            | InstructionOpcode::BitCast
+            | InstructionOpcode::AddrSpaceCast => {
+                match instr.get_operand(0).and_then(|op| op.value()) {
+                    Some(BasicValueEnum::PointerValue(base)) => pointer = base,
+                    _ => return UnderlyingObject::Unknown,
+                }
+            }
+            _ => return UnderlyingObject::Unknown,
+        }
+    }
+
+    if pointer.is_null() || pointer.is_undef() {
+        return UnderlyingObject::Unknown;
+    }
+    let value = BasicValueEnum::PointerValue(pointer);
+    if let Some(index) = block
+        .get_parent()
+        .and_then(|function| function.get_param_iter().position(|param| param == value))
+    {
+        return UnderlyingObject::Argument(index as u32);
+    }
+    // Constant expressions have no name and are not looked into
+    match pointer.get_name().to_str() {
+        Ok(name) if !name.is_empty() => UnderlyingObject::Global(name.to_string()),
+        _ => UnderlyingObject::Unknown,
+    }
+}
+
+/// Node operands of a metadata list
+fn metadata_operands(list: MetadataValue) -> Vec<MetadataValue> {
+    list.get_node_values()
+        .into_iter()
+        .filter_map(|operand| match operand {
+            BasicMetadataValueEnum::MetadataValue(node) if node.is_node() => Some(node),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Access type of a `!tbaa` tag followed by its ancestors
+///
+/// A struct-path tag `!{!base, !access, i64 offset}` starts with a node; an
+/// old scalar tag is the access type itself. A scalar type node is
+/// `!{!"name", !parent, ...}` and the root `!{!"name"}`.
+fn tbaa_type_chain(tag: MetadataValue) -> Vec<MetadataValue> {
+    let operands = metadata_operands(tag);
+    let struct_path = matches!(
+        tag.get_node_values().first(),
+        Some(BasicMetadataValueEnum::MetadataValue(base)) if base.is_node()
+    );
+    let mut current = if struct_path {
+        match operands.get(1) {
+            Some(&access) => access,
+            None => return Vec::new(),
+        }
+    } else {
+        tag
+    };
+
+    let mut chain = vec![current];
+    while let Some(&parent) = metadata_operands(current).first() {
+        if chain.contains(&parent) || chain.len() >= MAX_TBAA_DEPTH {
+            return Vec::new();
+        }
+        chain.push(parent);
+        current = parent;
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_rules() {
+        use UnderlyingObject::*;
+        let global = |name: &str| Global(name.to_string());
+
+        assert!(!global("a").may_alias(&global("b")));
+        assert!(global("a").may_alias(&global("a")));
+        assert!(Argument(0).may_alias(&Argument(1)));
+        assert!(Argument(0).may_alias(&global("a")));
+        assert!(Unknown.may_alias(&global("a")));
+    }
+}
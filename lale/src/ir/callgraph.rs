@@ -0,0 +1,583 @@
+//! Call graph built from inkwell modules
+//!
+//! Records direct call sites between functions, including constant integer
+//! arguments, and calls through function pointers, including trait-object
+//! calls through vtables, for interprocedural analysis.
+
+use super::vtable::{pointer_size_bytes, vtable_slot, vtables, Vtable, VtableSlot};
+use crate::error::LaleError;
+use ahash::AHashMap;
+use inkwell::module::Module;
+use inkwell::types::AnyType;
+use inkwell::values::{
+    BasicValue, BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue,
+};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
+
+/// Direct call site
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallSite {
+    pub caller: String,
+    pub callee: String,
+    /// Label of the block containing the call
+    pub block: String,
+    /// Index of that block in the caller, as in [`crate::ir::InkwellCFG`]
+    pub block_id: usize,
+    /// Integer arguments known to be constant at this site (by position)
+    pub constant_args: Vec<Option<u64>>,
+}
+
+/// Call through a function pointer
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndirectCallSite {
+    pub caller: String,
+    /// Label of the block containing the call
+    pub block: String,
+    /// Index of that block in the caller, as in [`crate::ir::InkwellCFG`]
+    pub block_id: usize,
+    /// Type of the called function, e.g. `i32 (ptr, i32)`
+    pub signature: String,
+    /// Vtable entry the called pointer is loaded from, for trait-object calls
+    pub vtable_slot: Option<VtableSlot>,
+}
+
+/// Module call graph
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    /// Functions with a body, in module order
+    pub functions: Vec<String>,
+    pub call_sites: Vec<CallSite>,
+    pub indirect_call_sites: Vec<IndirectCallSite>,
+    /// Type of each function, defined or declared, in the format of
+    /// `IndirectCallSite::signature`
+    pub signatures: AHashMap<String, String>,
+    /// Constant globals holding function pointers
+    pub vtables: Vec<Vtable>,
+}
+
+impl CallGraph {
+    /// Build call graph from all defined functions in a module
+    pub fn from_module(module: &Module) -> Self {
+        let pointer_bytes = pointer_size_bytes(module);
+        let mut graph = Self {
+            vtables: vtables(module, pointer_bytes),
+            ..Self::default()
+        };
+
+        let mut func_iter = module.get_first_function();
+        while let Some(function) = func_iter {
+            let name = function.get_name().to_str().unwrap_or("").to_string();
+
+            // Skip intrinsics and declarations
+            if !name.starts_with("llvm.") && function.count_basic_blocks() > 0 {
+                graph
+                    .call_sites
+                    .extend(Self::extract_call_sites(&name, &function));
+                graph
+                    .indirect_call_sites
+                    .extend(Self::extract_indirect_call_sites(
+                        &name,
+                        &function,
+                        pointer_bytes,
+                    ));
+                graph
+                    .signatures
+                    .insert(name.clone(), Self::function_signature(&function));
+                graph.functions.push(name);
+            } else if !name.starts_with("llvm.") {
+                graph
+                    .signatures
+                    .insert(name, Self::function_signature(&function));
+            }
+
+            func_iter = function.get_next_function();
+        }
+
+        graph
+    }
+
+    /// Extract direct call sites from a function body
+    fn extract_call_sites(caller: &str, function: &FunctionValue) -> Vec<CallSite> {
+        let params: Vec<BasicValueEnum> = function.get_param_iter().collect();
+        let mut call_sites = Vec::new();
+
+        for (block_id, block) in function.get_basic_blocks().into_iter().enumerate() {
+            let block_name = block.get_name().to_str().unwrap_or("").to_string();
+
+            let mut instr_iter = block.get_first_instruction();
+            while let Some(instr) = instr_iter {
+                // A named pointer computed in the body is not a function
+                if matches!(
+                    instr.get_opcode(),
+                    InstructionOpcode::Call | InstructionOpcode::Invoke
+                ) && !Self::is_indirect(&instr, &params)
+                {
+                    if let Some(callee) = Self::direct_callee(&instr) {
+                        if !callee.starts_with("llvm.") {
+                            call_sites.push(CallSite {
+                                caller: caller.to_string(),
+                                callee,
+                                block: block_name.clone(),
+                                block_id,
+                                constant_args: Self::constant_args(&instr),
+                            });
+                        }
+                    }
+                }
+                instr_iter = instr.get_next_instruction();
+            }
+        }
+
+        call_sites
+    }
+
+    /// Extract calls through function pointers from a function body
+    fn extract_indirect_call_sites(
+        caller: &str,
+        function: &FunctionValue,
+        pointer_bytes: u64,
+    ) -> Vec<IndirectCallSite> {
+        let params: Vec<BasicValueEnum> = function.get_param_iter().collect();
+        let mut call_sites = Vec::new();
+
+        for (block_id, block) in function.get_basic_blocks().into_iter().enumerate() {
+            let block_name = block.get_name().to_str().unwrap_or("").to_string();
+
+            let mut instr_iter = block.get_first_instruction();
+            while let Some(instr) = instr_iter {
+                if matches!(
+                    instr.get_opcode(),
+                    InstructionOpcode::Call | InstructionOpcode::Invoke
+                ) && Self::is_indirect(&instr, &params)
+                {
+                    call_sites.push(IndirectCallSite {
+                        caller: caller.to_string(),
+                        block: block_name.clone(),
+                        block_id,
+                        signature: Self::call_signature(&instr),
+                        vtable_slot: Self::called_value(&instr)
+                            .and_then(|callee| vtable_slot(callee, pointer_bytes)),
+                    });
+                }
+                instr_iter = instr.get_next_instruction();
+            }
+        }
+
+        call_sites
+    }
+
+    /// Called value of a call or invoke (its last operand)
+    fn called_value<'ctx>(instr: &InstructionValue<'ctx>) -> Option<BasicValueEnum<'ctx>> {
+        let num_operands = instr.get_num_operands();
+        if num_operands == 0 {
+            return None;
+        }
+        instr.get_operand(num_operands - 1)?.value()
+    }
+
+    /// Name of the directly called function (last operand of a call)
+    pub(crate) fn direct_callee(instr: &InstructionValue) -> Option<String> {
+        match Self::called_value(instr)? {
+            BasicValueEnum::PointerValue(ptr) => {
+                let name = ptr.get_name().to_str().ok()?;
+                (!name.is_empty()).then(|| name.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the called pointer is computed at run time or passed in
+    fn is_indirect(instr: &InstructionValue, params: &[BasicValueEnum]) -> bool {
+        Self::called_value(instr).is_some_and(|callee| {
+            callee.as_instruction_value().is_some() || params.contains(&callee)
+        })
+    }
+
+    /// Type of the function a call expects, from its result and arguments
+    fn call_signature(instr: &InstructionValue) -> String {
+        let arg_count = match instr.get_opcode() {
+            InstructionOpcode::Invoke => instr.get_num_operands().saturating_sub(3),
+            _ => instr.get_num_operands().saturating_sub(1),
+        };
+        let args: Vec<String> = (0..arg_count)
+            .filter_map(|i| instr.get_operand(i)?.value())
+            .map(|arg| arg.get_type().print_to_string().to_string())
+            .collect();
+        format!(
+            "{} ({})",
+            instr.get_type().print_to_string(),
+            args.join(", ")
+        )
+    }
+
+    /// Type of a function, in the same format as `call_signature`
+    fn function_signature(function: &FunctionValue) -> String {
+        let return_type = function
+            .get_type()
+            .get_return_type()
+            .map_or_else(|| "void".to_string(), |ty| ty.print_to_string().to_string());
+        let params: Vec<String> = function
+            .get_param_iter()
+            .map(|param| param.get_type().print_to_string().to_string())
+            .collect();
+        format!("{} ({})", return_type, params.join(", "))
+    }
+
+    /// Constant integer value of each call argument
+    fn constant_args(instr: &InstructionValue) -> Vec<Option<u64>> {
+        let arg_count = match instr.get_opcode() {
+            // invoke also carries normal and unwind destinations
+            InstructionOpcode::Invoke => instr.get_num_operands().saturating_sub(3),
+            _ => instr.get_num_operands().saturating_sub(1),
+        };
+
+        (0..arg_count)
+            .map(|i| match instr.get_operand(i).and_then(|op| op.value()) {
+                Some(BasicValueEnum::IntValue(value)) if value.is_const() => {
+                    value.get_zero_extended_constant()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Call sites inside a function
+    pub fn call_sites_from(&self, caller: &str) -> Vec<&CallSite> {
+        self.call_sites
+            .iter()
+            .filter(|site| site.caller == caller)
+            .collect()
+    }
+
+    /// Call sites targeting a function
+    pub fn call_sites_to(&self, callee: &str) -> Vec<&CallSite> {
+        self.call_sites
+            .iter()
+            .filter(|site| site.callee == callee)
+            .collect()
+    }
+
+    /// Distinct functions called by a function
+    pub fn callees(&self, caller: &str) -> Vec<&str> {
+        let mut callees: Vec<&str> = Vec::new();
+        for site in self.call_sites_from(caller) {
+            if !callees.contains(&site.callee.as_str()) {
+                callees.push(&site.callee);
+            }
+        }
+        callees
+    }
+
+    /// Calls through function pointers inside a function
+    pub fn indirect_call_sites_from(&self, caller: &str) -> Vec<&IndirectCallSite> {
+        self.indirect_call_sites
+            .iter()
+            .filter(|site| site.caller == caller)
+            .collect()
+    }
+
+    /// Functions with a body whose type matches a signature, in module order
+    ///
+    /// These are the targets an indirect call of that signature can reach
+    /// if the module is the whole program.
+    pub fn functions_with_signature(&self, signature: &str) -> Vec<&str> {
+        self.functions
+            .iter()
+            .filter(|name| self.signatures.get(*name).is_some_and(|s| s == signature))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Functions a trait-object call may reach through the module's vtables
+    ///
+    /// Every vtable, or only the one the call loads from if known, that
+    /// stores a function of the call's signature at the loaded offset
+    /// contributes it. Empty if the call doesn't load from a vtable.
+    pub fn vtable_targets(&self, site: &IndirectCallSite) -> Vec<&str> {
+        let Some(slot) = &site.vtable_slot else {
+            return Vec::new();
+        };
+
+        let mut targets: Vec<&str> = Vec::new();
+        for vtable in &self.vtables {
+            if slot
+                .vtable
+                .as_ref()
+                .is_some_and(|name| *name != vtable.name)
+            {
+                continue;
+            }
+            let Some(method) = vtable.method_at(slot.offset) else {
+                continue;
+            };
+            let matches = self
+                .signatures
+                .get(method)
+                .is_some_and(|signature| *signature == site.signature);
+            if matches && !targets.contains(&method) {
+                targets.push(method);
+            }
+        }
+        targets
+    }
+
+    /// Check if a function has a body in this module
+    pub fn is_defined(&self, function: &str) -> bool {
+        self.functions.iter().any(|f| f == function)
+    }
+
+    /// Defined functions followed by external callees, each listed once
+    pub fn nodes(&self) -> Vec<&str> {
+        let mut nodes: Vec<&str> = self.functions.iter().map(String::as_str).collect();
+        for site in &self.call_sites {
+            if !nodes.contains(&site.callee.as_str()) {
+                nodes.push(&site.callee);
+            }
+        }
+        nodes
+    }
+
+    /// Strongly connected components that involve recursion
+    ///
+    /// Includes multi-function cycles and functions that call themselves.
+    pub fn find_cycles(&self) -> Vec<Vec<String>> {
+        let graph = self.to_petgraph();
+        tarjan_scc(&graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || graph.contains_edge(scc[0], scc[0]))
+            .map(|scc| scc.iter().map(|&node| graph[node].to_string()).collect())
+            .collect()
+    }
+
+    /// Fail if any functions call each other or themselves recursively,
+    /// since their WCET has no bound
+    ///
+    /// The error lists each cycle sorted by function name.
+    pub fn reject_recursion(&self) -> Result<(), LaleError> {
+        let cycles = self.find_cycles();
+        if cycles.is_empty() {
+            return Ok(());
+        }
+        let cycles = cycles
+            .into_iter()
+            .map(|mut cycle| {
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        Err(LaleError::Recursion(cycles))
+    }
+
+    /// Defined functions, each after every function it calls
+    ///
+    /// Functions of a recursive cycle come in no particular order among
+    /// themselves.
+    pub fn callees_first(&self) -> Vec<&str> {
+        let graph = self.to_petgraph();
+        // Components come out in reverse topological order, callees first
+        tarjan_scc(&graph)
+            .into_iter()
+            .flatten()
+            .map(|node| graph[node])
+            .filter(|name| self.is_defined(name))
+            .collect()
+    }
+
+    /// Graph with an edge from each caller to each of its direct callees
+    fn to_petgraph(&self) -> DiGraph<&str, ()> {
+        let mut graph = DiGraph::<&str, ()>::new();
+        let mut index = AHashMap::new();
+        for name in self.nodes() {
+            index.insert(name, graph.add_node(name));
+        }
+        for site in &self.call_sites {
+            graph.update_edge(index[site.caller.as_str()], index[site.callee.as_str()], ());
+        }
+        graph
+    }
+}
+
+/// Demangle a Rust symbol name, leaving other names unchanged
+pub fn demangle(symbol: &str) -> String {
+    // Alternate format omits the trailing hash
+    format!("{:#}", rustc_demangle::demangle(symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(caller: &str, callee: &str, args: Vec<Option<u64>>) -> CallSite {
+        CallSite {
+            caller: caller.to_string(),
+            callee: callee.to_string(),
+            block: "entry".to_string(),
+            block_id: 0,
+            constant_args: args,
+        }
+    }
+
+    #[test]
+    fn test_call_graph_queries() {
+        let graph = CallGraph {
+            functions: vec!["main".to_string(), "process".to_string()],
+            call_sites: vec![
+                site("main", "process", vec![None, Some(16)]),
+                site("main", "process", vec![None, Some(64)]),
+                site("main", "memcpy", vec![None, None, Some(8)]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(graph.callees("main"), vec!["process", "memcpy"]);
+        assert_eq!(graph.call_sites_to("process").len(), 2);
+        assert!(graph.call_sites_from("process").is_empty());
+        assert!(graph.is_defined("process"));
+        assert!(!graph.is_defined("memcpy"));
+        assert_eq!(graph.nodes(), vec!["main", "process", "memcpy"]);
+    }
+
+    #[test]
+    fn test_functions_with_signature() {
+        let mut graph = CallGraph {
+            functions: vec![
+                "dispatch".to_string(),
+                "on_tick".to_string(),
+                "on_error".to_string(),
+            ],
+            indirect_call_sites: vec![IndirectCallSite {
+                caller: "dispatch".to_string(),
+                block: "entry".to_string(),
+                block_id: 0,
+                signature: "void (i32)".to_string(),
+                vtable_slot: None,
+            }],
+            ..Default::default()
+        };
+        graph
+            .signatures
+            .insert("dispatch".to_string(), "void (ptr, i32)".to_string());
+        graph
+            .signatures
+            .insert("on_tick".to_string(), "void (i32)".to_string());
+        graph
+            .signatures
+            .insert("on_error".to_string(), "void (i32)".to_string());
+
+        assert_eq!(graph.indirect_call_sites_from("dispatch").len(), 1);
+        assert_eq!(
+            graph.functions_with_signature("void (i32)"),
+            vec!["on_tick", "on_error"]
+        );
+        assert!(graph.functions_with_signature("i32 ()").is_empty());
+    }
+
+    #[test]
+    fn test_vtable_targets() {
+        let vtable = |name: &str, read: &str| Vtable {
+            name: name.to_string(),
+            methods: vec![(0, "drop".to_string()), (24, read.to_string())],
+        };
+        let mut graph = CallGraph {
+            vtables: vec![
+                vtable("vtable.0", "Adc::read"),
+                vtable("vtable.1", "Spi::read"),
+                vtable("vtable.2", "Log::write"),
+            ],
+            ..Default::default()
+        };
+        for (name, signature) in [
+            ("drop", "void (ptr)"),
+            ("Adc::read", "i32 (ptr)"),
+            ("Spi::read", "i32 (ptr)"),
+            ("Log::write", "void (ptr)"),
+        ] {
+            graph
+                .signatures
+                .insert(name.to_string(), signature.to_string());
+        }
+
+        let mut site = IndirectCallSite {
+            caller: "poll".to_string(),
+            block: "entry".to_string(),
+            block_id: 0,
+            signature: "i32 (ptr)".to_string(),
+            vtable_slot: Some(VtableSlot {
+                vtable: None,
+                offset: 24,
+            }),
+        };
+        assert_eq!(graph.vtable_targets(&site), vec!["Adc::read", "Spi::read"]);
+
+        site.vtable_slot = Some(VtableSlot {
+            vtable: Some("vtable.1".to_string()),
+            offset: 24,
+        });
+        assert_eq!(graph.vtable_targets(&site), vec!["Spi::read"]);
+
+        site.vtable_slot = None;
+        assert!(graph.vtable_targets(&site).is_empty());
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let graph = CallGraph {
+            functions: vec![
+                "main".to_string(),
+                "even".to_string(),
+                "odd".to_string(),
+                "fact".to_string(),
+            ],
+            call_sites: vec![
+                site("main", "even", vec![]),
+                site("main", "fact", vec![]),
+                site("even", "odd", vec![]),
+                site("odd", "even", vec![]),
+                site("fact", "fact", vec![]),
+            ],
+            ..Default::default()
+        };
+
+        let mut sccs: Vec<Vec<String>> = graph
+            .find_cycles()
+            .into_iter()
+            .map(|mut scc| {
+                scc.sort();
+                scc
+            })
+            .collect();
+        sccs.sort();
+
+        assert_eq!(sccs, vec![vec!["even", "odd"], vec!["fact"]]);
+    }
+
+    #[test]
+    fn test_callees_first() {
+        let graph = CallGraph {
+            functions: vec![
+                "main".to_string(),
+                "filter".to_string(),
+                "sample".to_string(),
+            ],
+            call_sites: vec![
+                site("main", "filter", vec![]),
+                site("main", "sample", vec![]),
+                site("filter", "sample", vec![]),
+                site("filter", "log", vec![]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(graph.callees_first(), vec!["sample", "filter", "main"]);
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN4core3ptr13drop_in_place17h0123456789abcdefE"),
+            "core::ptr::drop_in_place"
+        );
+        assert_eq!(demangle("process"), "process");
+    }
+}
@@ -16,6 +16,8 @@ pub enum EdgeType {
     ConditionalFalse,
     Switch,
     LoopBack,
+    /// From an `invoke` to its landing pad, taken when the callee unwinds
+    Unwind,
 }
 
 /// Basic block in CFG
@@ -24,6 +26,10 @@ pub struct BasicBlock {
     pub label: String,
     pub instructions: Vec<String>,
     pub execution_count_var: usize,
+
+    /// Minimum and maximum iterations from `!llvm.loop` metadata on the
+    /// block's terminator, if it closes an annotated loop
+    pub loop_bound: Option<(u64, u64)>,
 }
 
 /// Control Flow Graph
@@ -0,0 +1,287 @@
+//! Source locations from the debug info in a module
+//!
+//! A function compiled with debug info carries a `!dbg` attachment pointing
+//! at its `DISubprogram`, which names the file and line it was defined at,
+//! and each of its instructions a `DILocation` with line, column and scope.
+//! inkwell doesn't expose the fields of debug-info nodes, so they are read
+//! from the module's textual IR.
+
+use ahash::AHashMap;
+use inkwell::module::Module;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Scopes followed from a location before giving up on finding its file
+const MAX_SCOPE_DEPTH: usize = 64;
+
+/// Position in a source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// File as recorded by the compiler, usually relative to the build directory
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Source locations of one function
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FunctionDebugInfo {
+    /// Where the function is defined; subprograms record no column, so it
+    /// is always 1
+    pub location: Option<SourceLocation>,
+
+    /// Location of the first instruction with one in each block, by block
+    /// index in function order
+    pub blocks: AHashMap<usize, SourceLocation>,
+}
+
+/// Source locations of the functions of one or more modules, by name
+#[derive(Debug, Clone, Default)]
+pub struct DebugInfo {
+    pub functions: AHashMap<String, FunctionDebugInfo>,
+}
+
+impl DebugInfo {
+    /// Read the debug info of a module
+    pub fn from_module(module: &Module) -> Self {
+        Self::parse(&module.print_to_string().to_string())
+    }
+
+    /// Read the debug info of textual IR
+    ///
+    /// Functions without a `!dbg` attachment or located instruction are
+    /// left out.
+    pub fn parse(ir: &str) -> Self {
+        let metadata: AHashMap<&str, &str> = ir
+            .lines()
+            .filter_map(|line| {
+                let (id, node) = line.split_once(" = ")?;
+                id.starts_with('!').then_some((id, node))
+            })
+            .collect();
+        let nodes = Metadata(metadata);
+
+        let mut functions = AHashMap::new();
+        let mut lines = ir.lines();
+        while let Some(line) = lines.by_ref().find(|line| line.starts_with("define ")) {
+            let Some(name) = defined_name(line) else {
+                continue;
+            };
+            let mut info = FunctionDebugInfo {
+                location: attachment(line, "!dbg").and_then(|id| nodes.subprogram_location(id)),
+                blocks: AHashMap::new(),
+            };
+
+            // Blocks are numbered in order as in the CFG; the entry block may
+            // have no label line
+            let mut block = 0;
+            let mut in_block = false;
+            for body_line in lines.by_ref().take_while(|line| !line.starts_with('}')) {
+                let trimmed = body_line.trim();
+                if trimmed.is_empty() || trimmed.starts_with(';') {
+                    continue;
+                }
+                if !body_line.starts_with(' ') {
+                    if in_block {
+                        block += 1;
+                    }
+                    in_block = true;
+                    continue;
+                }
+                in_block = true;
+                if info.blocks.contains_key(&block) {
+                    continue;
+                }
+                if let Some(location) =
+                    attachment(trimmed, "!dbg").and_then(|id| nodes.instruction_location(id))
+                {
+                    info.blocks.insert(block, location);
+                }
+            }
+
+            if info.location.is_some() || !info.blocks.is_empty() {
+                functions.insert(name.to_string(), info);
+            }
+        }
+
+        Self { functions }
+    }
+
+    /// Add the functions of another module, keeping existing entries
+    pub fn extend(&mut self, other: DebugInfo) {
+        for (name, info) in other.functions {
+            self.functions.entry(name).or_insert(info);
+        }
+    }
+
+    /// Where a function is defined
+    pub fn function_location(&self, function: &str) -> Option<&SourceLocation> {
+        self.functions.get(function)?.location.as_ref()
+    }
+
+    /// Where a block of a function starts, by block index
+    pub fn block_location(&self, function: &str, block_id: usize) -> Option<&SourceLocation> {
+        self.functions.get(function)?.blocks.get(&block_id)
+    }
+}
+
+/// Metadata nodes of a module by id, e.g. `!12`
+struct Metadata<'a>(AHashMap<&'a str, &'a str>);
+
+impl Metadata<'_> {
+    /// Definition site of a `DISubprogram`
+    fn subprogram_location(&self, id: &str) -> Option<SourceLocation> {
+        let subprogram = self.0.get(id)?;
+        if !subprogram.contains("DISubprogram(") {
+            return None;
+        }
+        Some(SourceLocation {
+            file: self.file_name(field(subprogram, "file")?)?,
+            line: field(subprogram, "line")?.parse().ok()?,
+            column: 1,
+        })
+    }
+
+    /// Position of a `DILocation`, in the file of its innermost scope with one
+    fn instruction_location(&self, id: &str) -> Option<SourceLocation> {
+        let location = self.0.get(id)?;
+        if !location.contains("DILocation(") {
+            return None;
+        }
+
+        let mut scope = field(location, "scope")?;
+        let mut file = None;
+        for _ in 0..MAX_SCOPE_DEPTH {
+            let node = self.0.get(scope)?;
+            if let Some(file_id) = field(node, "file") {
+                file = self.file_name(file_id);
+                break;
+            }
+            scope = field(node, "scope")?;
+        }
+
+        Some(SourceLocation {
+            file: file?,
+            line: field(location, "line")?.parse().ok()?,
+            column: field(location, "column")
+                .and_then(|column| column.parse().ok())
+                .unwrap_or(1),
+        })
+    }
+
+    /// File name of a `DIFile`
+    fn file_name(&self, id: &str) -> Option<String> {
+        field(self.0.get(id)?, "filename").map(str::to_string)
+    }
+}
+
+/// Name of the function a `define` line defines, unquoted
+fn defined_name(line: &str) -> Option<&str> {
+    let name = &line[line.find('@')? + 1..];
+    match name.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => name.split('(').next(),
+    }
+}
+
+/// Metadata node a line attaches under a kind, e.g. `!dbg !12`
+fn attachment<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    let (_, rest) = line.rsplit_once(&format!("{} ", kind))?;
+    rest.split([' ', ',', '{']).next()
+}
+
+/// Value of a field of a specialized metadata node, strings unquoted
+fn field<'a>(node: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("{}: ", name);
+    let start = node.match_indices(&key).find_map(|(index, _)| {
+        let preceded_by = node[..index].chars().next_back();
+        matches!(preceded_by, Some('(' | ' ')).then_some(index + key.len())
+    })?;
+
+    let value = &node[start..];
+    match value.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => value.split([',', ')']).next(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBUG_IR: &str = r#"define i32 @"_ZN4demo6sample17h0123456789abcdefE"(i32 %x) unnamed_addr #0 !dbg !8 {
+start:
+  %y = add i32 %x, 1
+  %z = mul i32 %y, 3, !dbg !14
+  br label %bb1, !dbg !15
+
+bb1:                                              ; preds = %start
+  ret i32 %z, !dbg !16
+}
+
+define void @no_debug() {
+entry:
+  ret void
+}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_Rust, file: !1, producer: "clang LLVM (rustc version 1.80.0)")
+!1 = !DIFile(filename: "src/lib.rs/@/demo.0", directory: "/home/dev/demo")
+!2 = !DIFile(filename: "src/sensors.rs", directory: "/home/dev/demo", checksumkind: CSK_MD5)
+!3 = !DIFile(filename: "src/filter.rs", directory: "/home/dev/demo")
+!8 = distinct !DISubprogram(name: "sample", linkageName: "_ZN4demo6sample17h0123456789abcdefE", scope: !9, file: !2, line: 42, type: !10, scopeLine: 42, unit: !0)
+!13 = distinct !DILexicalBlock(scope: !8, file: !3, line: 7, column: 9)
+!14 = !DILocation(line: 43, column: 5, scope: !8)
+!15 = !DILocation(line: 44, column: 9, scope: !8)
+!16 = !DILocation(line: 8, column: 13, scope: !13)
+"#;
+
+    #[test]
+    fn test_function_and_block_locations() {
+        let debug_info = DebugInfo::parse(DEBUG_IR);
+        let name = "_ZN4demo6sample17h0123456789abcdefE";
+
+        assert_eq!(debug_info.functions.len(), 1);
+        let location = debug_info.function_location(name).unwrap();
+        assert_eq!(location.to_string(), "src/sensors.rs:42:1");
+
+        // First located instruction of each block, in its scope's file
+        let start = debug_info.block_location(name, 0).unwrap();
+        assert_eq!(start.to_string(), "src/sensors.rs:43:5");
+        let bb1 = debug_info.block_location(name, 1).unwrap();
+        assert_eq!(bb1.to_string(), "src/filter.rs:8:13");
+        assert!(debug_info.function_location("no_debug").is_none());
+    }
+
+    #[test]
+    fn test_unnamed_blocks_have_locations_of_their_own() {
+        let ir = r#"define void @spin(i32 %n) !dbg !8 {
+  br label %2, !dbg !14
+
+2:
+  %3 = add i32 %n, 1, !dbg !15
+  br label %4
+
+4:
+  ret void, !dbg !16
+}
+
+!2 = !DIFile(filename: "src/spin.rs", directory: "/home/dev/demo")
+!8 = distinct !DISubprogram(name: "spin", scope: !2, file: !2, line: 1, unit: !0)
+!14 = !DILocation(line: 2, column: 5, scope: !8)
+!15 = !DILocation(line: 3, column: 9, scope: !8)
+!16 = !DILocation(line: 4, column: 1, scope: !8)
+"#;
+        let debug_info = DebugInfo::parse(ir);
+
+        let lines: Vec<u32> = (0..3)
+            .map(|block| debug_info.block_location("spin", block).unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+    }
+}
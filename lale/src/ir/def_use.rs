@@ -0,0 +1,168 @@
+//! Register read/write sets from SSA def-use chains
+//!
+//! LLVM IR has no physical registers, so every SSA value, whether a function
+//! argument or an instruction result, stands in for one. The defining
+//! instruction writes it and each instruction using it as an operand reads
+//! it. This gives the hazard detector and the dependency-graph timing real
+//! dependencies to work on; register allocation and spills are not modelled.
+//! Memory carries a dependency from a store to a later load that may read it.
+
+use crate::ir::alias::MemoryAccess;
+use crate::microarch::{InstructionDependency, Register};
+use ahash::AHashMap;
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{
+    BasicValue, BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue,
+};
+
+/// Virtual register of every SSA value in a function
+#[derive(Debug, Default)]
+pub struct SsaRegisters<'ctx> {
+    params: AHashMap<BasicValueEnum<'ctx>, Register>,
+    instructions: AHashMap<InstructionValue<'ctx>, Register>,
+}
+
+impl<'ctx> SsaRegisters<'ctx> {
+    /// Number the arguments and instruction results of a function
+    pub fn from_function(function: &FunctionValue<'ctx>) -> Self {
+        let mut registers = Self::default();
+        for param in function.get_param_iter() {
+            let register = registers.next_register();
+            registers.params.insert(param, register);
+        }
+        registers.add_blocks(function.get_basic_blocks());
+        registers
+    }
+
+    /// Number the instruction results of some blocks
+    ///
+    /// Values defined elsewhere, arguments included, get no register and
+    /// create no dependency.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = BasicBlock<'ctx>>) -> Self {
+        let mut registers = Self::default();
+        registers.add_blocks(blocks);
+        registers
+    }
+
+    fn add_blocks(&mut self, blocks: impl IntoIterator<Item = BasicBlock<'ctx>>) {
+        for block in blocks {
+            let mut instr_iter = block.get_first_instruction();
+            while let Some(instr) = instr_iter {
+                if Self::defines_value(instr.get_opcode()) {
+                    let register = self.next_register();
+                    self.instructions.insert(instr, register);
+                }
+                instr_iter = instr.get_next_instruction();
+            }
+        }
+    }
+
+    fn next_register(&self) -> Register {
+        Register((self.params.len() + self.instructions.len()) as u32)
+    }
+
+    /// Whether an instruction produces an SSA value
+    fn defines_value(opcode: InstructionOpcode) -> bool {
+        !matches!(
+            opcode,
+            InstructionOpcode::Store
+                | InstructionOpcode::Br
+                | InstructionOpcode::Switch
+                | InstructionOpcode::IndirectBr
+                | InstructionOpcode::Return
+                | InstructionOpcode::Unreachable
+                | InstructionOpcode::Fence
+                | InstructionOpcode::Resume
+        )
+    }
+
+    /// Number of registers
+    pub fn len(&self) -> usize {
+        self.params.len() + self.instructions.len()
+    }
+
+    /// Whether no value got a register
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Register holding a value; `None` for constants and unnumbered values
+    pub fn register(&self, value: BasicValueEnum<'ctx>) -> Option<Register> {
+        match value.as_instruction_value() {
+            Some(instr) => self.instructions.get(&instr).copied(),
+            None => self.params.get(&value).copied(),
+        }
+    }
+
+    /// Register written by an instruction, if it defines a value
+    pub fn written(&self, instr: InstructionValue<'ctx>) -> Option<Register> {
+        self.instructions.get(&instr).copied()
+    }
+
+    /// Registers an instruction reads, in operand order without repeats
+    pub fn read(&self, instr: InstructionValue<'ctx>) -> Vec<Register> {
+        let mut reads = Vec::new();
+        for i in 0..instr.get_num_operands() {
+            let register = instr
+                .get_operand(i)
+                .and_then(|op| op.value())
+                .and_then(|value| self.register(value));
+            if let Some(register) = register {
+                if !reads.contains(&register) {
+                    reads.push(register);
+                }
+            }
+        }
+        reads
+    }
+
+    /// Read/write sets of a block's instructions, in program order
+    ///
+    /// Ids and stages are positions in the block, i.e. one instruction
+    /// issued per cycle. A load also depends on each earlier store in the
+    /// block it may alias, through a memory register numbered after the SSA
+    /// registers; see [`crate::ir::alias`] for when a pair is told apart.
+    pub fn block_dependencies(&self, block: BasicBlock<'ctx>) -> Vec<InstructionDependency> {
+        let mut dependencies = Vec::new();
+        let mut stores: Vec<(usize, MemoryAccess<'ctx>)> = Vec::new();
+        let mut memory_registers: AHashMap<usize, Register> = AHashMap::new();
+        let mut instr_iter = block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            let id = dependencies.len();
+            let mut reads = self.read(instr);
+
+            match MemoryAccess::of(block, instr) {
+                Some(access) if instr.get_opcode() == InstructionOpcode::Store => {
+                    stores.push((id, access));
+                }
+                Some(access) => {
+                    for (store, stored) in &stores {
+                        if !stored.may_alias(&access) {
+                            continue;
+                        }
+                        let next = Register((self.len() + memory_registers.len()) as u32);
+                        let register = *memory_registers.entry(*store).or_insert(next);
+                        if !reads.contains(&register) {
+                            reads.push(register);
+                        }
+                    }
+                }
+                None => {}
+            }
+
+            dependencies.push(InstructionDependency {
+                id,
+                reads,
+                writes: self.written(instr).into_iter().collect(),
+                stage: id,
+            });
+            instr_iter = instr.get_next_instruction();
+        }
+
+        // Only stores a load depends on write memory registers
+        for (store, register) in memory_registers {
+            dependencies[store].writes.push(register);
+        }
+        dependencies
+    }
+}
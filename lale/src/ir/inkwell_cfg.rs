@@ -2,9 +2,13 @@
 //!
 //! Builds CFG from inkwell FunctionValue for LLVM 19+ compatibility
 
+use crate::ir::cfg::{BasicBlock as CFGBlock, EdgeType, CFG};
 use ahash::AHashMap;
 use inkwell::basic_block::BasicBlock;
-use inkwell::values::{FunctionValue, InstructionOpcode};
+use inkwell::values::{
+    AnyValue, BasicMetadataValueEnum, FunctionValue, InstructionOpcode, MetadataValue,
+};
+use petgraph::graph::DiGraph;
 use std::collections::VecDeque;
 
 /// Basic block in inkwell CFG
@@ -21,6 +25,9 @@ pub struct InkwellBasicBlock<'ctx> {
 pub struct InkwellCFG<'ctx> {
     pub blocks: Vec<InkwellBasicBlock<'ctx>>,
     pub edges: Vec<(usize, usize)>,
+    /// Edges from an `invoke` to its landing pad, taken only when the
+    /// callee unwinds; also listed in `edges`
+    pub unwind_edges: Vec<(usize, usize)>,
     pub entry_block: usize,
     pub block_map: AHashMap<String, usize>,
 }
@@ -31,6 +38,7 @@ impl<'ctx> InkwellCFG<'ctx> {
         let mut blocks = Vec::new();
         let mut block_map = AHashMap::new();
         let mut edges = Vec::new();
+        let mut unwind_edges = Vec::new();
 
         // Collect all basic blocks
         let basic_blocks: Vec<_> = function.get_basic_blocks();
@@ -59,47 +67,43 @@ impl<'ctx> InkwellCFG<'ctx> {
             });
         }
 
-        // Extract edges by analyzing terminator instructions
-        // We iterate through all blocks and find their successors
+        let block_id = |target: BasicBlock<'ctx>| basic_blocks.iter().position(|bb| *bb == target);
+
+        // Extract edges from the block operands of each terminator
         for (from_id, from_bb) in basic_blocks.iter().enumerate() {
-            // For each block, check all other blocks to see if they're successors
-            // This is done by checking if the terminator references them
-            for (to_id, to_bb) in basic_blocks.iter().enumerate() {
-                if from_id == to_id {
-                    continue;
+            let Some(terminator) = from_bb.get_terminator() else {
+                continue;
+            };
+
+            // invoke ... to label %normal unwind label %lpad: the operands
+            // end with the normal destination, the unwind one and the callee
+            if terminator.get_opcode() == InstructionOpcode::Invoke {
+                let operands = terminator.get_num_operands();
+                let destination = |index: u32| {
+                    terminator
+                        .get_operand(index)
+                        .and_then(|op| op.block())
+                        .and_then(block_id)
+                };
+                let normal = operands.checked_sub(3).and_then(destination);
+                let unwind = operands.checked_sub(2).and_then(destination);
+
+                if let Some(to_id) = normal {
+                    edges.push((from_id, to_id));
+                }
+                if let Some(to_id) = unwind.filter(|&to_id| Some(to_id) != normal) {
+                    edges.push((from_id, to_id));
+                    unwind_edges.push((from_id, to_id));
                 }
+                continue;
+            }
+
+            for i in 0..terminator.get_num_operands() {
+                let target = terminator.get_operand(i).and_then(|op| op.block());
 
-                // Check if from_bb's terminator references to_bb
-                if let Some(terminator) = from_bb.get_terminator() {
-                    let num_operands = terminator.get_num_operands();
-
-                    // Check all operands to see if any reference to_bb
-                    for i in 0..num_operands {
-                        if let Some(operand) = terminator.get_operand(i) {
-                            // Check if this operand is a basic block reference
-                            if operand.is_block() {
-                                // Try to match by comparing block addresses
-                                // This is a heuristic approach since inkwell doesn't provide direct comparison
-                                let to_name = to_bb.get_name().to_str().unwrap_or("");
-
-                                // If we can get the operand as instruction value and check its parent
-                                // For now, we'll use a simple heuristic: if the operand is a block
-                                // and we're in a branch instruction, assume it's a successor
-                                let opcode = terminator.get_opcode();
-                                match opcode {
-                                    InstructionOpcode::Br
-                                    | InstructionOpcode::Switch
-                                    | InstructionOpcode::IndirectBr => {
-                                        // This is a control flow instruction
-                                        // Add edge if we haven't already
-                                        if !edges.contains(&(from_id, to_id)) {
-                                            edges.push((from_id, to_id));
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
+                if let Some(to_id) = target.and_then(block_id) {
+                    if !edges.contains(&(from_id, to_id)) {
+                        edges.push((from_id, to_id));
                     }
                 }
             }
@@ -108,6 +112,7 @@ impl<'ctx> InkwellCFG<'ctx> {
         Self {
             blocks,
             edges,
+            unwind_edges,
             entry_block: 0, // Entry is always first block
             block_map,
         }
@@ -131,6 +136,11 @@ impl<'ctx> InkwellCFG<'ctx> {
             .collect()
     }
 
+    /// Whether an edge is only taken when an invoked callee unwinds
+    pub fn is_unwind_edge(&self, from: usize, to: usize) -> bool {
+        self.unwind_edges.contains(&(from, to))
+    }
+
     /// Check if block is reachable from entry
     pub fn is_reachable(&self, block_id: usize) -> bool {
         let mut visited = vec![false; self.blocks.len()];
@@ -177,6 +187,119 @@ impl<'ctx> InkwellCFG<'ctx> {
 
         reachable
     }
+
+    /// Export as a petgraph `CFG` for `LoopAnalyzer` and `IPETSolver`
+    ///
+    /// Each node's `execution_count_var` is the inkwell block id, so block
+    /// timings keyed by id map directly onto the exported nodes.
+    pub fn to_petgraph(&self) -> CFG {
+        let mut graph = DiGraph::new();
+        let mut label_to_node = AHashMap::new();
+        let mut id_to_node = AHashMap::new();
+
+        // Create nodes
+        for block in &self.blocks {
+            let node = graph.add_node(CFGBlock {
+                label: block.name.clone(),
+                instructions: instruction_text(block.block),
+                execution_count_var: block.id,
+                loop_bound: loop_metadata_bound(block.block),
+            });
+            label_to_node.insert(block.name.clone(), node);
+            id_to_node.insert(block.id, node);
+        }
+
+        // Create edges
+        for &(from, to) in &self.edges {
+            let edge_type = if self.is_unwind_edge(from, to) {
+                EdgeType::Unwind
+            } else {
+                EdgeType::Direct
+            };
+            graph.add_edge(id_to_node[&from], id_to_node[&to], edge_type);
+        }
+
+        let entry = id_to_node[&self.entry_block];
+
+        // Exit nodes have no successors
+        let exits: Vec<_> = graph
+            .node_indices()
+            .filter(|&idx| graph.neighbors(idx).count() == 0)
+            .collect();
+
+        CFG {
+            graph,
+            entry,
+            exits,
+            label_to_node,
+        }
+    }
+}
+
+/// Text of each instruction in a block, for the loop-bound patterns of
+/// `LoopAnalyzer`
+pub(crate) fn instruction_text(block: BasicBlock) -> Vec<String> {
+    let mut text = Vec::new();
+    let mut instr_iter = block.get_first_instruction();
+    while let Some(instr) = instr_iter {
+        text.push(instr.print_to_string().to_string().trim().to_string());
+        instr_iter = instr.get_next_instruction();
+    }
+    text
+}
+
+/// Loop properties read as iteration bounds, by name
+///
+/// `llvm.loop.bound` takes a maximum or a minimum and a maximum. An unroll
+/// count is read as the maximum too, for builds that disable unrolling and
+/// use it to annotate trip counts.
+const LOOP_BOUND_PROPERTIES: [&str; 2] = ["llvm.loop.bound", "llvm.loop.unroll.count"];
+
+/// Minimum and maximum iterations from the `!llvm.loop` metadata on a
+/// block's terminator
+///
+/// Properties that are malformed, e.g. with a non-constant count or a
+/// minimum above the maximum, are ignored.
+pub(crate) fn loop_metadata_bound(block: BasicBlock) -> Option<(u64, u64)> {
+    let kind_id = block.get_context().get_kind_id("llvm.loop");
+    let loop_id = block.get_terminator()?.get_metadata(kind_id)?;
+
+    // The first operand of a loop ID refers to itself
+    loop_id
+        .get_node_values()
+        .into_iter()
+        .skip(1)
+        .find_map(|operand| match operand {
+            BasicMetadataValueEnum::MetadataValue(property) if property.is_node() => {
+                loop_property_bound(property)
+            }
+            _ => None,
+        })
+}
+
+/// Bound of a `!{!"name", i32 ...}` loop property, if it is one
+fn loop_property_bound(property: MetadataValue) -> Option<(u64, u64)> {
+    let mut operands = property.get_node_values().into_iter();
+    let name = match operands.next()? {
+        BasicMetadataValueEnum::MetadataValue(name) if name.is_string() => name,
+        _ => return None,
+    };
+    let name = name.get_string_value()?.to_str().ok()?;
+    if !LOOP_BOUND_PROPERTIES.contains(&name) {
+        return None;
+    }
+
+    let counts = operands
+        .map(|operand| match operand {
+            BasicMetadataValueEnum::IntValue(count) => count.get_zero_extended_constant(),
+            _ => None,
+        })
+        .collect::<Option<Vec<u64>>>()?;
+    match counts[..] {
+        [max] if max > 0 => Some((0, max)),
+        [min, max] if min <= max && max > 0 => Some((min, max)),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
@@ -3,16 +3,54 @@
 //! Provides parsing of LLVM IR using inkwell (LLVM C API bindings)
 //! to support modern LLVM versions (18+) that llvm-ir crate cannot parse.
 
+use crate::error::LaleError;
 use inkwell::basic_block::BasicBlock;
 use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::Module;
 use inkwell::values::{BasicValueEnum, FunctionValue, InstructionValue};
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 
+/// Global header of an `ar` archive
+const AR_MAGIC: &[u8] = b"!<arch>\n";
+
+/// Raw bitcode magic ('BC' 0xC0DE)
+const BITCODE_MAGIC: &[u8] = &[0x42, 0x43, 0xC0, 0xDE];
+
+/// Bitcode wrapper magic (0x0B17C0DE, little-endian), used by Darwin toolchains
+const BITCODE_WRAPPER_MAGIC: &[u8] = &[0xDE, 0xC0, 0x17, 0x0B];
+
 /// Inkwell-based IR parser
 pub struct InkwellParser;
 
+/// Module together with the context that owns it
+///
+/// Drops the module before its context. A `(Context, Module)` tuple drops
+/// the context first, so the module would then free memory the context
+/// already freed.
+pub struct ParsedModule {
+    // Fields drop in declaration order: the module must go first
+    module: Module<'static>,
+    _context: Context,
+}
+
+impl ParsedModule {
+    /// Take ownership of a module and its context
+    pub fn new((context, module): (Context, Module<'static>)) -> Self {
+        Self {
+            module,
+            _context: context,
+        }
+    }
+
+    /// The module, borrowed no longer than its context lives
+    pub fn module(&self) -> &Module<'_> {
+        &self.module
+    }
+}
+
 /// Function information extracted via inkwell
 #[derive(Debug, Clone)]
 pub struct InkwellFunction<'ctx> {
@@ -51,6 +89,10 @@ pub enum TerminatorKind {
     Branch,
     ConditionalBranch,
     Switch,
+    /// Call that continues at one of two blocks, normally or by unwinding
+    Invoke,
+    /// Continues unwinding out of the function
+    Resume,
     Unreachable,
     Other,
 }
@@ -58,12 +100,12 @@ pub enum TerminatorKind {
 impl InkwellParser {
     /// Parse LLVM IR file using inkwell
     /// Returns (Context, Module) - caller must keep context alive
-    pub fn parse_file(path: impl AsRef<Path>) -> Result<(Context, Module<'static>), String> {
+    pub fn parse_file(path: impl AsRef<Path>) -> Result<(Context, Module<'static>), LaleError> {
         let context = Context::create();
 
         // Read file content
         let ir_text = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| format!("Failed to read IR file: {}", e))?;
+            .map_err(|e| LaleError::io("Failed to read IR file", e))?;
 
         // SAFETY: We return both context and module together
         // Caller must ensure context outlives module usage
@@ -76,7 +118,7 @@ impl InkwellParser {
                             path.as_ref().to_str().unwrap_or("ir_module"),
                         ),
                     )
-                    .map_err(|e| format!("Failed to parse IR text: {:?}", e))?,
+                    .map_err(|e| LaleError::Parse(format!("Failed to parse IR text: {:?}", e)))?,
             )
         };
 
@@ -84,7 +126,7 @@ impl InkwellParser {
     }
 
     /// Parse LLVM IR from memory buffer
-    pub fn parse_ir_from_buffer(ir_text: &str) -> Result<(Context, Module<'static>), String> {
+    pub fn parse_ir_from_buffer(ir_text: &str) -> Result<(Context, Module<'static>), LaleError> {
         let context = Context::create();
         let module = unsafe {
             std::mem::transmute::<Module, Module<'static>>(
@@ -95,13 +137,85 @@ impl InkwellParser {
                             "ir_module",
                         ),
                     )
-                    .map_err(|e| format!("Failed to parse IR: {:?}", e))?,
+                    .map_err(|e| LaleError::Parse(format!("Failed to parse IR: {:?}", e)))?,
+            )
+        };
+
+        Ok((context, module))
+    }
+
+    /// Parse every module in an input file
+    ///
+    /// Accepts textual IR, bitcode (`.bc`, or `.o` from LTO builds) and `ar`
+    /// archives of bitcode members (`.a` from LTO builds). Native objects and
+    /// the native members of mixed archives carry no IR and yield no module.
+    pub fn parse_modules(path: impl AsRef<Path>) -> Result<Vec<ParsedModule>, LaleError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| LaleError::io(format!("Failed to read {}", path.display()), e))?;
+        let name = path.to_str().unwrap_or("ir_module");
+
+        if bytes.starts_with(AR_MAGIC) {
+            Self::parse_archive(&bytes, name)
+        } else if is_bitcode(&bytes) {
+            Ok(vec![ParsedModule::new(Self::parse_bitcode(&bytes, name)?)])
+        } else if path.extension().is_some_and(|ext| ext == "o") {
+            Ok(Vec::new())
+        } else {
+            Ok(vec![ParsedModule::new(Self::parse_file(path)?)])
+        }
+    }
+
+    /// Parse LLVM bitcode from memory
+    pub fn parse_bitcode(
+        bytes: &[u8],
+        name: &str,
+    ) -> Result<(Context, Module<'static>), LaleError> {
+        let context = Context::create();
+        let buffer = MemoryBuffer::create_from_memory_range_copy(bytes, name);
+        let module = unsafe {
+            std::mem::transmute::<Module, Module<'static>>(
+                Module::parse_bitcode_from_buffer(&buffer, &context).map_err(|e| {
+                    LaleError::Parse(format!("Failed to parse bitcode {}: {:?}", name, e))
+                })?,
             )
         };
 
         Ok((context, module))
     }
 
+    /// Parse the bitcode members of an `ar` archive
+    ///
+    /// Members are named `archive.a(member.o)` after the usual linker notation.
+    pub fn parse_archive(bytes: &[u8], name: &str) -> Result<Vec<ParsedModule>, LaleError> {
+        let mut archive = ar::Archive::new(bytes);
+        let mut modules = Vec::new();
+
+        while let Some(entry) = archive.next_entry() {
+            let mut entry =
+                entry.map_err(|e| LaleError::io(format!("Failed to read archive {}", name), e))?;
+            let member = format!(
+                "{}({})",
+                name,
+                String::from_utf8_lossy(entry.header().identifier())
+            );
+
+            let mut member_bytes = Vec::new();
+            entry
+                .read_to_end(&mut member_bytes)
+                .map_err(|e| LaleError::io(format!("Failed to read {}", member), e))?;
+
+            if is_bitcode(&member_bytes) {
+                modules.push(ParsedModule::new(Self::parse_bitcode(
+                    &member_bytes,
+                    &member,
+                )?));
+            }
+        }
+
+        Ok(modules)
+    }
+
     /// Extract function information from module
     pub fn extract_functions<'ctx>(module: &Module<'ctx>) -> Vec<InkwellFunction<'ctx>> {
         let mut functions = Vec::new();
@@ -173,6 +287,8 @@ impl InkwellParser {
             inkwell::values::InstructionOpcode::Return => TerminatorKind::Return,
             inkwell::values::InstructionOpcode::Br => TerminatorKind::Branch,
             inkwell::values::InstructionOpcode::Switch => TerminatorKind::Switch,
+            inkwell::values::InstructionOpcode::Invoke => TerminatorKind::Invoke,
+            inkwell::values::InstructionOpcode::Resume => TerminatorKind::Resume,
             inkwell::values::InstructionOpcode::Unreachable => TerminatorKind::Unreachable,
             _ => TerminatorKind::Other,
         };
@@ -184,6 +300,11 @@ impl InkwellParser {
     }
 }
 
+/// Whether the bytes hold an LLVM bitcode module
+fn is_bitcode(bytes: &[u8]) -> bool {
+    bytes.starts_with(BITCODE_MAGIC) || bytes.starts_with(BITCODE_WRAPPER_MAGIC)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +314,28 @@ mod tests {
         // Basic test to ensure module compiles
         assert!(true);
     }
+    #[test]
+    fn test_archive_without_bitcode_yields_no_modules() {
+        let mut builder = ar::Builder::new(Vec::new());
+        let native = b"\x7fELF native object";
+        builder
+            .append(
+                &ar::Header::new(b"native.o".to_vec(), native.len() as u64),
+                &native[..],
+            )
+            .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        assert!(archive.starts_with(AR_MAGIC));
+        let modules = InkwellParser::parse_archive(&archive, "libnative.a").unwrap();
+        assert!(modules.is_empty());
+    }
+
+    #[test]
+    fn test_bitcode_magic() {
+        assert!(is_bitcode(&[0x42, 0x43, 0xC0, 0xDE, 0x35, 0x14]));
+        assert!(is_bitcode(&[0xDE, 0xC0, 0x17, 0x0B, 0x00, 0x00]));
+        assert!(!is_bitcode(b"; ModuleID = 'test'"));
+        assert!(!is_bitcode(b"\x7fELF"));
+    }
 }
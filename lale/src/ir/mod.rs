@@ -1,7 +1,19 @@
+pub mod alias;
+pub mod callgraph;
 pub mod cfg;
+pub mod debug_info;
+pub mod def_use;
 pub mod inkwell_cfg;
 pub mod inkwell_parser;
+pub mod vtable;
 
+pub use alias::{MemoryAccess, UnderlyingObject};
+pub use callgraph::{demangle, CallGraph, CallSite, IndirectCallSite};
 pub use cfg::{BasicBlock, EdgeType, CFG};
+pub use debug_info::{DebugInfo, FunctionDebugInfo, SourceLocation};
+pub use def_use::SsaRegisters;
 pub use inkwell_cfg::{InkwellBasicBlock as InkwellCFGBlock, InkwellCFG};
-pub use inkwell_parser::{InkwellBasicBlock, InkwellFunction, InkwellParser, TerminatorKind};
+pub use inkwell_parser::{
+    InkwellBasicBlock, InkwellFunction, InkwellParser, ParsedModule, TerminatorKind,
+};
+pub use vtable::{Vtable, VtableSlot};
@@ -0,0 +1,209 @@
+//! Resolution of trait-object calls through vtables
+//!
+//! A Rust trait-object call loads its target from a fixed offset into the
+//! vtable carried by the fat pointer. The module's constant globals holding
+//! function pointers are the candidate vtables, and the functions they store
+//! at that offset are the candidate targets.
+
+use inkwell::module::Module;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{AnyValue, BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue};
+
+/// Constant global holding function pointers, laid out like a vtable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vtable {
+    pub name: String,
+    /// Functions stored in the global, by byte offset
+    pub methods: Vec<(u64, String)>,
+}
+
+impl Vtable {
+    /// Function stored at a byte offset
+    pub fn method_at(&self, offset: u64) -> Option<&str> {
+        self.methods
+            .iter()
+            .find(|(at, _)| *at == offset)
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+/// Vtable entry an indirect call loads its target from
+#[derive(Debug, Clone, PartialEq)]
+pub struct VtableSlot {
+    /// Vtable global, if the call site loads from a known one
+    pub vtable: Option<String>,
+    /// Byte offset of the entry
+    pub offset: u64,
+}
+
+/// Pointer size of the module's target in bytes
+pub fn pointer_size_bytes(module: &Module) -> u64 {
+    let layout = module.get_data_layout();
+    layout
+        .as_str()
+        .to_str()
+        .ok()
+        .and_then(parse_pointer_size)
+        // LLVM's default when the layout doesn't say
+        .unwrap_or(8)
+}
+
+/// Pointer size from a data layout string, e.g. `e-m:e-p:32:32-i64:64`
+fn parse_pointer_size(layout: &str) -> Option<u64> {
+    layout.split('-').find_map(|spec| {
+        let rest = spec
+            .strip_prefix("p:")
+            .or_else(|| spec.strip_prefix("p0:"))?;
+        let bits: u64 = rest.split(':').next()?.parse().ok()?;
+        Some(bits / 8)
+    })
+}
+
+/// Constant globals of a module that store function pointers
+pub fn vtables(module: &Module, pointer_bytes: u64) -> Vec<Vtable> {
+    module
+        .get_globals()
+        .filter(|global| global.is_constant())
+        .filter_map(|global| {
+            let mut methods = Vec::new();
+            collect_methods(global.get_initializer()?, 0, pointer_bytes, &mut methods)?;
+            (!methods.is_empty()).then(|| Vtable {
+                name: global.get_name().to_str().unwrap_or("").to_string(),
+                methods,
+            })
+        })
+        .collect()
+}
+
+/// Record the named pointers in a constant, returning its size in bytes
+///
+/// Structs are taken as packed, as rustc emits vtables.
+fn collect_methods(
+    value: BasicValueEnum,
+    offset: u64,
+    pointer_bytes: u64,
+    methods: &mut Vec<(u64, String)>,
+) -> Option<u64> {
+    match value {
+        BasicValueEnum::PointerValue(ptr) => {
+            let name = ptr.get_name().to_str().unwrap_or("");
+            if !name.is_empty() {
+                methods.push((offset, name.to_string()));
+            }
+            Some(pointer_bytes)
+        }
+        BasicValueEnum::StructValue(value) => {
+            let mut size = 0;
+            for index in 0..value.count_fields() {
+                let field = value.get_field_at_index(index)?;
+                size += collect_methods(field, offset + size, pointer_bytes, methods)?;
+            }
+            Some(size)
+        }
+        other => type_size(other.get_type(), pointer_bytes),
+    }
+}
+
+/// Size of a type in bytes, ignoring padding
+fn type_size(ty: BasicTypeEnum, pointer_bytes: u64) -> Option<u64> {
+    match ty {
+        BasicTypeEnum::IntType(int) => Some(int.get_bit_width().div_ceil(8) as u64),
+        BasicTypeEnum::PointerType(_) => Some(pointer_bytes),
+        BasicTypeEnum::ArrayType(array) => {
+            Some(array.len() as u64 * type_size(array.get_element_type(), pointer_bytes)?)
+        }
+        BasicTypeEnum::StructType(fields) => fields
+            .get_field_types()
+            .into_iter()
+            .map(|field| type_size(field, pointer_bytes))
+            .sum(),
+        _ => None,
+    }
+}
+
+/// Vtable entry a called value is loaded from, if it is loaded at a constant offset
+///
+/// Matches `load ptr, ptr %slot` where `%slot` is the vtable pointer itself
+/// or a `getelementptr` with one constant index off it.
+pub fn vtable_slot(called: BasicValueEnum, pointer_bytes: u64) -> Option<VtableSlot> {
+    let load = called.as_instruction_value()?;
+    if load.get_opcode() != InstructionOpcode::Load {
+        return None;
+    }
+    let address = load.get_operand(0)?.value()?;
+
+    let (base, offset) = match address.as_instruction_value() {
+        Some(gep) if gep.get_opcode() == InstructionOpcode::GetElementPtr => {
+            if gep.get_num_operands() != 2 {
+                return None;
+            }
+            let index = match gep.get_operand(1)?.value()? {
+                BasicValueEnum::IntValue(index) => index.get_zero_extended_constant()?,
+                _ => return None,
+            };
+            let element_bytes = gep_element_size(&gep, pointer_bytes)?;
+            (gep.get_operand(0)?.value()?, index * element_bytes)
+        }
+        _ => (address, 0),
+    };
+
+    let vtable = match base {
+        BasicValueEnum::PointerValue(ptr) if ptr.is_const() => {
+            let name = ptr.get_name().to_str().unwrap_or("");
+            (!name.is_empty()).then(|| name.to_string())
+        }
+        _ => None,
+    };
+
+    Some(VtableSlot { vtable, offset })
+}
+
+/// Size of the element type a `getelementptr` indexes over
+///
+/// rustc indexes vtables as `i8` (byte offsets) or, in older versions, as
+/// `ptr` (slots).
+fn gep_element_size(gep: &InstructionValue, pointer_bytes: u64) -> Option<u64> {
+    let text = gep.print_to_string().to_string();
+    let after = text.split_once("getelementptr ")?.1;
+    let element = after
+        .split(',')
+        .next()?
+        .split_whitespace()
+        .find(|token| !matches!(*token, "inbounds" | "nuw" | "nusw"))?;
+
+    match element {
+        "ptr" => Some(pointer_bytes),
+        int => {
+            let bits: u64 = int.strip_prefix('i')?.parse().ok()?;
+            Some(bits.div_ceil(8))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pointer_size() {
+        assert_eq!(
+            parse_pointer_size("e-m:e-p:32:32-Fi8-i64:64-v128:64:128-a:0:32-n32-S64"),
+            Some(4)
+        );
+        assert_eq!(parse_pointer_size("e-p0:64:64-i64:64"), Some(8));
+        assert_eq!(parse_pointer_size("e-m:e-i64:64-n32:64-S128"), None);
+    }
+
+    #[test]
+    fn test_method_at() {
+        let vtable = Vtable {
+            name: "vtable.0".to_string(),
+            methods: vec![
+                (0, "drop_in_place".to_string()),
+                (24, "Sensor::read".to_string()),
+            ],
+        };
+        assert_eq!(vtable.method_at(24), Some("Sensor::read"));
+        assert_eq!(vtable.method_at(8), None);
+    }
+}
@@ -3,6 +3,7 @@ pub mod analysis;
 pub mod analyzers;
 pub mod async_analysis;
 pub mod config;
+pub mod error;
 pub mod ir;
 pub mod microarch;
 pub mod multicore;
@@ -12,28 +13,38 @@ pub mod scheduling;
 pub mod wcet;
 
 // Re-export commonly used types
-pub use analysis::{Cycles, IPETSolver, LoopAnalyzer};
+pub use analysis::{
+    memory_footprint, CacheAssumption, CostFn, Cycles, IPETSolver, InfeasiblePath,
+    InkwellTimingCalculator, LoopAnalyzer, Profile, SmtFeasibility,
+};
 pub use analyzers::{
-    ActorAnalyzer, DirectoryAnalysisResult, DirectoryAnalyzer, FunctionAnalysisResult,
-    FunctionAnalyzer, ModuleAnalysisResult, ModuleAnalyzer,
+    ActorAnalyzer, CallSiteAnalyzer, Confidence, DirectoryAnalysisResult, DirectoryAnalyzer,
+    FunctionAnalysisResult, FunctionAnalyzer, IndirectCallAnalyzer, ModuleAnalysisResult,
+    ModuleAnalyzer, SystemAnalysisResult, SystemAnalyzer,
 };
 pub use async_analysis::{
     Actor, ActorConfig, ActorConfigEntry, ActorConfigLoader, ActorSystem, ActorSystemConfig,
     AsyncFunctionInfo, InkwellAsyncDetector, InkwellSegmentExtractor, InkwellSegmentWCETAnalyzer,
     SchedulingPolicy, VeecleActor, VeecleMetadata, VeecleModel, VeecleService,
 };
-pub use ir::{InkwellCFG, InkwellParser};
+pub use error::LaleError;
+pub use ir::{CallGraph, CallSite, InkwellCFG, InkwellParser};
 pub use multicore::{
-    CoreSchedulabilityResult, DeadlineViolation, MultiCoreResult, MultiCoreScheduler,
+    ActorInterference, BusModel, CoreSchedulabilityResult, DeadlineViolation, MultiCoreResult,
+    MultiCoreScheduler, SharedCache,
+};
+pub use output::{
+    AnalysisReport, CsvOutput, GanttOutput, GraphvizOutput, JSONOutput, OutputFormat,
+    OutputFormatRegistry,
 };
-pub use output::{AnalysisReport, GanttOutput, GraphvizOutput, JSONOutput};
 pub use platform::{
     CortexA53Model, CortexA7Model, CortexM0Model, CortexM33Model, CortexM3Model, CortexM4Model,
-    CortexM7Model, CortexR4Model, CortexR5Model, PlatformModel, RV32GCModel, RV32IMACModel,
-    RV32IModel, RV64GCModel,
+    CortexM7Model, CortexR4Model, CortexR5Model, PlatformCapabilities, PlatformInfo, PlatformModel,
+    RV32GCModel, RV32IMACModel, RV32IModel, RV64GCModel, Wasm32Model,
 };
 pub use scheduling::{
-    EDFScheduler, RMAScheduler, SchedulabilityResult, StaticScheduleGenerator, Task, TaskExtractor,
+    AMCScheduler, DMScheduler, EDFScheduler, FrameScheduleBuilder, RMAScheduler,
+    SchedulabilityResult, StaticScheduleGenerator, SystemSpec, Task, TaskExtractor,
 };
 
 /// LALE version
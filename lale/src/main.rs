@@ -1,14 +1,64 @@
+use ahash::AHashMap;
 use anyhow::{Context, Result};
-use lale::analysis::InkwellTimingCalculator;
+use lale::analysis::footprint::{memory_footprint, DEFAULT_LINE_SIZE_BYTES};
+use lale::analysis::{
+    loop_key, AnalysisWarning, FlowFactStore, LoopAnalyzer, LoopBounds, WarningKind,
+};
+use lale::analysis::{DeadBlock, DeadBlockKind, LoopKind, LoopObservation};
+use lale::analyzers::{
+    function_hotspots, locate_hotspots, top_hotspots, BlockHotspot, IndirectCallBound,
+    LoopBoundDecision, LoopBoundSource, TargetSource, DEFAULT_HOTSPOT_COUNT,
+};
+use lale::config::{IgnoreList, IGNORE_FILE};
+use lale::ir::{demangle, DebugInfo, ParsedModule};
+use lale::output::audit::{
+    external_call_events, function_events, indirect_call_events, AuditEvent, AuditLog,
+};
+use lale::output::color::{set_color_choice, ColorChoice, Palette};
+use lale::output::json::FunctionWCET;
 use lale::{
-    CortexA53Model, CortexA7Model, CortexM0Model, CortexM33Model, CortexM3Model, CortexM4Model,
-    CortexM7Model, CortexR4Model, CortexR5Model, InkwellParser, PlatformModel, RV32GCModel,
-    RV32IMACModel, RV32IModel, RV64GCModel, SchedulingPolicy,
+    CacheAssumption, CallGraph, CallSiteAnalyzer, Confidence, CsvOutput, FunctionAnalyzer,
+    GraphvizOutput, IndirectCallAnalyzer, InkwellCFG, InkwellParser, PlatformModel, Profile,
+    SchedulabilityResult, SchedulingPolicy, SmtFeasibility,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Exit code: analysis ran and no `--fail-on` condition was hit
+const EXIT_OK: i32 = 0;
+/// Exit code: task set is unschedulable (`--fail-on unschedulable`)
+const EXIT_UNSCHEDULABLE: i32 = 1;
+/// Exit code: analysis could not be completed
+const EXIT_ANALYSIS_ERROR: i32 = 2;
+/// Exit code: heuristic loop bounds were used (`--fail-on estimated`)
+const EXIT_ESTIMATED_BOUNDS: i32 = 3;
+/// Exit code: no analyzable functions were found (`--fail-on-empty`)
+const EXIT_NO_FUNCTIONS: i32 = 4;
+/// Exit code: `--clamp-wcet` lowered a WCET, so a schedulable verdict is unsound
+const EXIT_CLAMPED_WCET: i32 = 5;
+
+/// Formats `lale analyze` writes its results in
+const ANALYZE_FORMATS: [&str; 2] = ["json", "csv"];
+
+/// Cycles `lale microarch` explores unless `--max-cycles` is given
+const DEFAULT_MICROARCH_CYCLES: u32 = 1000;
+
+/// `println!` unless `--summary-only` was given
+macro_rules! detail {
+    ($config:expr) => {
+        if !$config.summary_only {
+            println!();
+        }
+    };
+    ($config:expr, $($arg:tt)*) => {
+        if !$config.summary_only {
+            println!($($arg)*);
+        }
+    };
+}
 
 fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+    let (args, color) = take_color_args(std::env::args().collect())?;
+    set_color_choice(color);
 
     if args.len() < 2 {
         print_usage();
@@ -25,27 +75,125 @@ fn main() -> Result<()> {
                 std::process::exit(1);
             }
             let dir = PathBuf::from(&args[2]);
-            let config = parse_config(&args[3..])?;
-            analyze_directory(dir, config)?;
+            let config = parse_config(&args[3..], "wcet_results.json")?;
+            let code = analyze_directory(dir, config)?;
+            if code != EXIT_OK {
+                std::process::exit(code);
+            }
+        }
+        "schedule" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing directory path");
+                print_usage();
+                std::process::exit(EXIT_ANALYSIS_ERROR);
+            }
+            let dir = PathBuf::from(&args[2]);
+            let code = match parse_config(&args[3..], "wcet_results.json")
+                .and_then(|c| schedule_directory(dir, c))
+            {
+                Ok(code) => code,
+                Err(e) => {
+                    eprintln!(
+                        "{} Analysis failed: {:#}",
+                        Palette::stderr().failure("✗"),
+                        e
+                    );
+                    EXIT_ANALYSIS_ERROR
+                }
+            };
+            std::process::exit(code);
+        }
+        "system" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing directory path");
+                print_usage();
+                std::process::exit(EXIT_ANALYSIS_ERROR);
+            }
+            let dir = PathBuf::from(&args[2]);
+            let code = match parse_config(&args[3..], "system_results.json")
+                .and_then(|c| system_directory(dir, c))
+            {
+                Ok(code) => code,
+                Err(e) => {
+                    eprintln!(
+                        "{} Analysis failed: {:#}",
+                        Palette::stderr().failure("✗"),
+                        e
+                    );
+                    EXIT_ANALYSIS_ERROR
+                }
+            };
+            std::process::exit(code);
+        }
+        "callgraph" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing directory path");
+                print_usage();
+                std::process::exit(1);
+            }
+            let dir = PathBuf::from(&args[2]);
+            let config = parse_config(&args[3..], "callgraph.dot")?;
+            export_callgraph(dir, config)?;
+        }
+        "init" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing directory path");
+                eprintln!("Usage: lale init <directory> [--output-dir <dir>] [--force]");
+                std::process::exit(1);
+            }
+            let (output_dir, force) = parse_init_args(&args[3..])?;
+            init_project(PathBuf::from(&args[2]), output_dir, force)?;
+        }
+        "list-platforms" => {
+            let json = args[2..].iter().any(|arg| arg == "--json");
+            list_platforms(json)?;
+        }
+        "platform-matrix" => {
+            let json = args[2..].iter().any(|arg| arg == "--json");
+            platform_matrix(json)?;
         }
         "list-boards" => {
-            list_boards()?;
+            let detailed = args[2..].iter().any(|arg| arg == "--detailed");
+            list_boards(config_dir(&args[2..]), detailed)?;
         }
         "validate-board" => {
             if args.len() < 3 {
                 eprintln!("Error: Missing board name");
-                eprintln!("Usage: lale validate-board <board-name>");
+                eprintln!("Usage: lale validate-board <board-name> [--config-dir <dir>]");
                 std::process::exit(1);
             }
-            validate_board(&args[2])?;
+            validate_board(&args[2], config_dir(&args[3..]))?;
         }
         "export-board" => {
             if args.len() < 3 {
                 eprintln!("Error: Missing board name");
-                eprintln!("Usage: lale export-board <board-name>");
+                eprintln!("Usage: lale export-board <board-name> [--config-dir <dir>]");
                 std::process::exit(1);
             }
-            export_board(&args[2])?;
+            export_board(&args[2], config_dir(&args[3..]))?;
+        }
+        "board-tree" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing board name");
+                eprintln!("Usage: lale board-tree <board-name> [--config-dir <dir>]");
+                std::process::exit(1);
+            }
+            board_tree(&args[2], config_dir(&args[3..]))?;
+        }
+        "microarch" => {
+            if args.len() < 3 {
+                eprintln!("Error: Missing board name");
+                eprintln!(
+                    "Usage: lale microarch <board-name> [--max-unroll <n>] [--max-cycles <n>] \
+                     [--config-dir <dir>]"
+                );
+                std::process::exit(1);
+            }
+            let rest = &args[3..];
+            let max_unroll = numeric_option(rest, "--max-unroll")?.unwrap_or(1);
+            let max_cycles =
+                numeric_option(rest, "--max-cycles")?.unwrap_or(DEFAULT_MICROARCH_CYCLES);
+            explore_microarch(&args[2], config_dir(rest), max_unroll, max_cycles)?;
         }
         "help" | "--help" | "-h" => {
             print_usage();
@@ -66,186 +214,1761 @@ fn main() -> Result<()> {
 #[derive(Debug)]
 struct Config {
     platform: Option<String>,
+    /// Clock to run the platform's core at instead of its default, in MHz
+    frequency_mhz: Option<u32>,
     board: Option<String>,
+    /// Board definitions directory to load `--board` from
+    config_dir: Option<PathBuf>,
     output: PathBuf,
+    period_us: f64,
+    policy: SchedulingPolicy,
+    fail_on: Vec<FailOn>,
+    dry_run: bool,
+    issue_scheduling: bool,
+    max_ipet_blocks: Option<usize>,
+    fail_on_empty: bool,
+    wcet_cap: Option<u64>,
+    clamp_wcet: bool,
+    audit_log: Option<PathBuf>,
+    profile: Option<PathBuf>,
+    flow_facts: Option<PathBuf>,
+    /// Tasks file of the system command
+    tasks: Option<PathBuf>,
+    cache_assumption: CacheAssumption,
+    format: String,
+    relative_wcet: bool,
+    summary_only: bool,
+    /// Print each function's WCET as a compiler diagnostic at its source;
+    /// debug info is only read with it, for source locations in the results
+    diagnostics: bool,
+    margin_percent: Option<f64>,
+    heuristic_loop_min: Option<u64>,
+    heuristic_loop_max: Option<u64>,
+    /// Candidate targets of the indirect calls in a function, by caller
+    indirect_targets: Vec<(String, Vec<String>)>,
+    /// Prune infeasible paths of small functions with an SMT solver
+    smt: bool,
+    smt_max_blocks: Option<usize>,
+    /// Solver command line, e.g. `z3 -in`
+    smt_solver: Option<Vec<String>>,
 }
 
-fn parse_config(args: &[String]) -> Result<Config> {
+/// Conditions that make the schedule command exit non-zero
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FailOn {
+    Unschedulable,
+    Estimated,
+}
+
+/// Remove `--color <when>`, `--color=<when>` and `--no-color` from the
+/// arguments, wherever they appear, so every command accepts them
+fn take_color_args(args: Vec<String>) -> Result<(Vec<String>, ColorChoice)> {
+    let mut color = ColorChoice::Auto;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--no-color" {
+            color = ColorChoice::Never;
+        } else if arg == "--color" {
+            let when = args
+                .next()
+                .context("Missing value for --color, expected auto, always or never")?;
+            color = when.parse().map_err(anyhow::Error::msg)?;
+        } else if let Some(when) = arg.strip_prefix("--color=") {
+            color = when.parse().map_err(anyhow::Error::msg)?;
+        } else {
+            rest.push(arg);
+        }
+    }
+    Ok((rest, color))
+}
+
+fn parse_config(args: &[String], default_output: &str) -> Result<Config> {
     let mut platform: Option<String> = None;
+    let mut frequency_mhz = None;
     let mut board: Option<String> = None;
-    let mut output = PathBuf::from("wcet_results.json");
+    let mut config_dir = None;
+    let mut output = PathBuf::from(default_output);
+    let mut output_given = false;
+    let mut period_us = 10000.0;
+    let mut policy = SchedulingPolicy::RMA;
+    let mut fail_on = Vec::new();
+    let mut dry_run = false;
+    let mut issue_scheduling = false;
+    let mut max_ipet_blocks = None;
+    let mut fail_on_empty = false;
+    let mut wcet_cap = None;
+    let mut clamp_wcet = false;
+    let mut audit_log = None;
+    let mut profile = None;
+    let mut flow_facts = None;
+    let mut tasks = None;
+    let mut cache_assumption = CacheAssumption::default();
+    let mut format = "json".to_string();
+    let mut relative_wcet = false;
+    let mut summary_only = false;
+    let mut diagnostics = false;
+    let mut margin_percent = None;
+    let mut indirect_targets = Vec::new();
+    let mut heuristic_loop_min = None;
+    let mut heuristic_loop_max = None;
+    let mut smt = false;
+    let mut smt_max_blocks = None;
+    let mut smt_solver = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--platform" | "-p" => {
+                i += 1;
+                if i < args.len() {
+                    platform = Some(args[i].clone());
+                }
+            }
+            "--frequency" => {
+                i += 1;
+                if i < args.len() {
+                    let mhz: u32 = args[i]
+                        .trim_end_matches("MHz")
+                        .parse()
+                        .with_context(|| format!("Invalid frequency '{}'", args[i]))?;
+                    if mhz == 0 {
+                        anyhow::bail!("Frequency must be at least 1 MHz");
+                    }
+                    frequency_mhz = Some(mhz);
+                }
+            }
+            "--board" | "-b" => {
+                i += 1;
+                if i < args.len() {
+                    board = Some(args[i].clone());
+                }
+            }
+            "--config-dir" => {
+                i += 1;
+                if i < args.len() {
+                    config_dir = Some(PathBuf::from(&args[i]));
+                }
+            }
+            "--output" | "-o" => {
+                i += 1;
+                if i < args.len() {
+                    output = PathBuf::from(&args[i]);
+                    output_given = true;
+                }
+            }
+            "--period" => {
+                i += 1;
+                if i < args.len() {
+                    period_us = args[i]
+                        .parse()
+                        .with_context(|| format!("Invalid period '{}'", args[i]))?;
+                }
+            }
+            "--policy" => {
+                i += 1;
+                if i < args.len() {
+                    policy = match args[i].to_lowercase().as_str() {
+                        "rma" => SchedulingPolicy::RMA,
+                        "edf" => SchedulingPolicy::EDF,
+                        "dm" => SchedulingPolicy::DM,
+                        other => anyhow::bail!("Unknown scheduling policy '{}'", other),
+                    };
+                }
+            }
+            "--fail-on" => {
+                i += 1;
+                if i < args.len() {
+                    fail_on.push(match args[i].as_str() {
+                        "unschedulable" => FailOn::Unschedulable,
+                        "estimated" => FailOn::Estimated,
+                        other => anyhow::bail!("Unknown --fail-on condition '{}'", other),
+                    });
+                }
+            }
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--issue-scheduling" => {
+                issue_scheduling = true;
+            }
+            "--fail-on-empty" => {
+                fail_on_empty = true;
+            }
+            "--max-ipet-blocks" => {
+                i += 1;
+                if i < args.len() {
+                    max_ipet_blocks = Some(
+                        args[i]
+                            .parse()
+                            .with_context(|| format!("Invalid block count '{}'", args[i]))?,
+                    );
+                }
+            }
+            "--wcet-cap" => {
+                i += 1;
+                if i < args.len() {
+                    wcet_cap = Some(
+                        args[i]
+                            .parse()
+                            .with_context(|| format!("Invalid cycle count '{}'", args[i]))?,
+                    );
+                }
+            }
+            "--clamp-wcet" => {
+                clamp_wcet = true;
+            }
+            "--margin" => {
+                i += 1;
+                if i < args.len() {
+                    let margin: f64 = args[i]
+                        .trim_end_matches('%')
+                        .parse()
+                        .with_context(|| format!("Invalid margin '{}'", args[i]))?;
+                    if !margin.is_finite() || margin < 0.0 {
+                        anyhow::bail!("Margin must be a non-negative percentage, got {}", margin);
+                    }
+                    margin_percent = Some(margin);
+                }
+            }
+            "--heuristic-loop-min" => {
+                i += 1;
+                if i < args.len() {
+                    heuristic_loop_min = Some(
+                        args[i]
+                            .parse()
+                            .with_context(|| format!("Invalid iteration count '{}'", args[i]))?,
+                    );
+                }
+            }
+            "--heuristic-loop-max" => {
+                i += 1;
+                if i < args.len() {
+                    heuristic_loop_max = Some(
+                        args[i]
+                            .parse()
+                            .with_context(|| format!("Invalid iteration count '{}'", args[i]))?,
+                    );
+                }
+            }
+            "--indirect-targets" => {
+                i += 1;
+                if i < args.len() {
+                    let (caller, targets) = args[i].split_once('=').with_context(|| {
+                        format!(
+                            "Invalid indirect targets '{}', expected <caller>=<f1>,<f2>",
+                            args[i]
+                        )
+                    })?;
+                    indirect_targets.push((
+                        caller.to_string(),
+                        targets
+                            .split(',')
+                            .filter(|target| !target.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                    ));
+                }
+            }
+            "--audit-log" => {
+                i += 1;
+                if i < args.len() {
+                    audit_log = Some(PathBuf::from(&args[i]));
+                }
+            }
+            "--profile" => {
+                i += 1;
+                if i < args.len() {
+                    profile = Some(PathBuf::from(&args[i]));
+                }
+            }
+            "--flow-facts" => {
+                i += 1;
+                if i < args.len() {
+                    flow_facts = Some(PathBuf::from(&args[i]));
+                }
+            }
+            "--tasks" => {
+                i += 1;
+                if i < args.len() {
+                    tasks = Some(PathBuf::from(&args[i]));
+                }
+            }
+            "--format" => {
+                i += 1;
+                if i < args.len() {
+                    format = args[i].clone();
+                }
+            }
+            "--relative-wcet" => {
+                relative_wcet = true;
+            }
+            "--summary-only" => {
+                summary_only = true;
+            }
+            "--diagnostics" => {
+                diagnostics = true;
+            }
+            "--assume-cache-cold" => {
+                cache_assumption = CacheAssumption::Cold;
+            }
+            "--assume-cache-warm" => {
+                cache_assumption = CacheAssumption::Warm;
+            }
+            "--smt" => {
+                smt = true;
+            }
+            "--smt-max-blocks" => {
+                i += 1;
+                if i < args.len() {
+                    smt_max_blocks = Some(
+                        args[i]
+                            .parse()
+                            .with_context(|| format!("Invalid block count '{}'", args[i]))?,
+                    );
+                }
+            }
+            "--smt-solver" => {
+                i += 1;
+                if i < args.len() {
+                    let command: Vec<String> =
+                        args[i].split_whitespace().map(str::to_string).collect();
+                    if command.is_empty() {
+                        anyhow::bail!("--smt-solver needs a command");
+                    }
+                    smt_solver = Some(command);
+                }
+            }
+            _ => {
+                eprintln!("Warning: Unknown option '{}'", args[i]);
+            }
+        }
+        i += 1;
+    }
+
+    if let (Some(min), Some(max)) = (heuristic_loop_min, heuristic_loop_max) {
+        if min > max {
+            anyhow::bail!(
+                "--heuristic-loop-min ({}) is above --heuristic-loop-max ({})",
+                min,
+                max
+            );
+        }
+    }
+
+    let final_platform = platform.or(Some("cortex-m4".to_string()));
+    if format == "csv" && !output_given {
+        output.set_extension("csv");
+    }
+
+    Ok(Config {
+        platform: final_platform,
+        frequency_mhz,
+        board,
+        config_dir,
+        output,
+        period_us,
+        policy,
+        fail_on,
+        dry_run,
+        issue_scheduling,
+        max_ipet_blocks,
+        fail_on_empty,
+        wcet_cap,
+        clamp_wcet,
+        audit_log,
+        profile,
+        flow_facts,
+        tasks,
+        cache_assumption,
+        format,
+        relative_wcet,
+        summary_only,
+        diagnostics,
+        margin_percent,
+        heuristic_loop_min,
+        heuristic_loop_max,
+        indirect_targets,
+        smt,
+        smt_max_blocks,
+        smt_solver,
+    })
+}
+
+/// Platform named by `--platform`, clocked at `--frequency` if given
+fn select_platform(name: &str, config: &Config) -> Result<PlatformModel> {
+    let platform = PlatformModel::from_id(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown platform '{}'. Run `lale list-platforms` to see available platforms.",
+            name
+        )
+    })?;
+    Ok(match config.frequency_mhz {
+        Some(mhz) => platform.with_frequency(mhz),
+        None => platform,
+    })
+}
+
+/// Board configuration manager for `config_dir`
+///
+/// Resolving a board's inheritance chain is the slow part of loading it;
+/// resolutions whose files haven't changed are reused across invocations.
+fn board_manager(config_dir: PathBuf) -> lale::config::ConfigManager {
+    use lale::config::ConfigManager;
+
+    let manager = ConfigManager::new(config_dir);
+    match ConfigManager::default_cache_file() {
+        Some(cache_file) => manager.with_persistent_cache(cache_file),
+        None => manager,
+    }
+}
+
+/// Resolve the `--board` definition from `--config-dir`, else the default
+/// search order
+fn load_board(name: &str, config: &Config) -> Result<lale::config::PlatformConfiguration> {
+    use lale::config::ConfigManager;
+
+    let config_dir = ConfigManager::resolve_config_dir(config.config_dir.clone());
+    board_manager(config_dir)
+        .load_platform(name)
+        .with_context(|| format!("Failed to load board '{}'", name))
+}
+
+/// Build a function analyzer from the command-line timing and profile options
+fn function_analyzer(platform: &PlatformModel, config: &Config) -> Result<FunctionAnalyzer> {
+    let mut analyzer = FunctionAnalyzer::new(platform.clone())
+        .with_issue_scheduling(config.issue_scheduling)
+        .with_cache_assumption(config.cache_assumption);
+    if let Some(max_blocks) = config.max_ipet_blocks {
+        analyzer = analyzer.with_max_ipet_blocks(max_blocks);
+    }
+    if let Some(min) = config.heuristic_loop_min {
+        analyzer = analyzer.with_heuristic_loop_min(min);
+    }
+    if let Some(max) = config.heuristic_loop_max {
+        analyzer = analyzer.with_heuristic_loop_max(max);
+    }
+    if let Some(board) = &config.board {
+        let cache = load_board(board, config)?.core.cache;
+        if let Some(instruction_cache) = cache.instruction_cache {
+            analyzer = analyzer.with_instruction_cache(instruction_cache);
+        }
+        if let Some(data_cache) = cache.data_cache {
+            analyzer = analyzer.with_data_cache(data_cache);
+        }
+    }
+    if let Some(path) = &config.profile {
+        analyzer = analyzer.with_profile(Profile::load(path).map_err(anyhow::Error::msg)?);
+    }
+    if let Some(path) = &config.flow_facts {
+        analyzer = analyzer.with_flow_facts(FlowFactStore::load(path).map_err(anyhow::Error::msg)?);
+    }
+    if config.smt {
+        let mut smt = SmtFeasibility::new();
+        if let Some(max_blocks) = config.smt_max_blocks {
+            smt = smt.with_max_blocks(max_blocks);
+        }
+        if let Some((command, args)) = config.smt_solver.as_ref().and_then(|c| c.split_first()) {
+            smt = smt.with_solver(command.clone(), args.to_vec());
+        }
+        analyzer = analyzer.with_smt_feasibility(smt);
+    }
+    Ok(analyzer)
+}
+
+/// Warnings for flow facts of functions or loops that were not analyzed
+///
+/// `loop_headers` maps each analyzed function to its loop headers.
+fn unmatched_flow_facts(
+    analyzer: &FunctionAnalyzer,
+    loop_headers: &AHashMap<String, Vec<String>>,
+) -> Vec<AnalysisWarning> {
+    analyzer
+        .flow_facts()
+        .map(|flow_facts| flow_facts.unmatched(loop_headers))
+        .unwrap_or_default()
+}
+
+fn print_warnings(warnings: &[AnalysisWarning]) {
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// Loop headers of an analyzed function, in loop order
+fn loop_headers(decisions: &[LoopBoundDecision]) -> Vec<String> {
+    decisions
+        .iter()
+        .map(|decision| decision.header.clone())
+        .collect()
+}
+
+/// Charge indirect calls their worst target, with `--indirect-targets` taking precedence
+fn indirect_call_analyzer(config: &Config) -> IndirectCallAnalyzer {
+    config.indirect_targets.iter().fold(
+        IndirectCallAnalyzer::new(),
+        |analyzer, (caller, targets)| analyzer.with_targets(caller.clone(), targets.clone()),
+    )
+}
+
+/// List the blocks contributing most to the WCET across the module
+fn print_hotspots(hotspots: &[BlockHotspot]) {
+    if hotspots.is_empty() {
+        return;
+    }
+
+    println!("Module hotspots (executions x cycles):");
+    for hotspot in hotspots {
+        println!(
+            "  {:>10} cycles  {}:{} ({} x {})",
+            hotspot.total_cycles,
+            hotspot.function,
+            hotspot.block,
+            hotspot.executions,
+            hotspot.cycles
+        );
+    }
+    println!();
+}
+
+/// Report dead blocks by name and panic-only blocks by count
+fn print_dead_blocks(dead_blocks: &[DeadBlock]) {
+    let mut panic_only = 0;
+    for dead in dead_blocks {
+        match dead.kind {
+            DeadBlockKind::NeverExecutes => {
+                println!("    dead block {}: never executes", dead.block)
+            }
+            DeadBlockKind::PanicPathOnly => panic_only += 1,
+        }
+    }
+    if panic_only > 0 {
+        println!("    {} block(s) execute only on panic paths", panic_only);
+    }
+}
+
+/// Report each function's WCET with its indirect calls charged, the targets
+/// resolved through vtables and the calls whose targets are unknown or
+/// unbounded
+fn print_indirect_calls(bounds: &[IndirectCallBound]) {
+    for bound in bounds {
+        println!(
+            "  {} with indirect calls : {} cycles (context-free: {} cycles){}",
+            bound.function,
+            bound.wcet_cycles,
+            bound.context_free_wcet_cycles,
+            if bound.is_sound() {
+                ""
+            } else {
+                " [not soundly bounded]"
+            }
+        );
+        for site in &bound.sites {
+            if let (TargetSource::Vtable, Some(slot)) = (site.source, &site.vtable_slot) {
+                println!(
+                    "    vtable call in {} (offset {}): {}",
+                    site.block,
+                    slot.offset,
+                    site.targets.join(", ")
+                );
+            }
+        }
+        for site in &bound.sites {
+            let Some(reason) = site.unbounded_reason() else {
+                continue;
+            };
+            println!(
+                "    indirect call in {}: {}; pass --indirect-targets {}=<f1>,<f2>",
+                site.block, reason, site.caller
+            );
+        }
+    }
+}
+
+/// WCET of each function with indirect calls, charged their worst targets
+fn indirect_wcets(bounds: &[IndirectCallBound]) -> AHashMap<String, u64> {
+    bounds
+        .iter()
+        .map(|bound| (bound.function.clone(), bound.wcet_cycles))
+        .collect()
+}
+
+/// Note the branches on `undef` or `poison`, costed on their worst side
+fn print_poison_branches(blocks: &[String]) {
+    for block in blocks {
+        println!(
+            "    note: branch in {} depends on undef/poison, both successors assumed feasible",
+            block
+        );
+    }
+}
+
+/// Note the block pairs the SMT check excluded from the worst-case path
+fn print_infeasible_paths(pairs: &[(String, String)]) {
+    for (a, b) in pairs {
+        println!("    note: {} and {} never both run (SMT), not summed", a, b);
+    }
+}
+
+/// Report loops whose bound is not a compile-time constant
+fn print_loop_kinds(decisions: &[LoopBoundDecision]) {
+    for decision in decisions.iter().filter(|d| d.kind != LoopKind::Counted) {
+        println!("    loop {}: {}", decision.header, decision.kind.guidance());
+    }
+}
+
+/// Report heuristic loop bounds moved into the configured range
+fn print_clamped_loops(decisions: &[LoopBoundDecision]) {
+    for decision in decisions {
+        if let (Some(from), Some(to)) = (decision.clamped_from, decision.max_iterations) {
+            println!(
+                "    loop {}: heuristic bound {} clamped to {}",
+                decision.header, from, to
+            );
+        }
+    }
+}
+
+/// Loops left at a heuristic bound although it depends on data
+fn print_loops_to_annotate(loops: &[(String, String)]) {
+    if loops.is_empty() {
+        return;
+    }
+
+    println!("Loops to annotate (data-dependent, heuristic bound):");
+    for (function, header) in loops {
+        println!("  {}:{}", function, header);
+    }
+    println!();
+}
+
+/// Report loop bounds that disagree with the profiled iterations
+fn print_loop_observations(observations: &[LoopObservation]) {
+    for observation in observations {
+        let Some(bound) = observation.bound else {
+            continue;
+        };
+        if observation.exceeds_bound() {
+            println!(
+                "    {} loop {} ran {} iterations per entry, above its bound of {}",
+                Palette::stdout().warning("⚠"),
+                observation.header,
+                observation.observed_iterations,
+                bound
+            );
+        } else if observation.is_loose() {
+            println!(
+                "    note: loop {} is bounded at {} but ran {} iterations per entry",
+                observation.header, bound, observation.observed_iterations
+            );
+        }
+    }
+}
+
+type AuditFile = AuditLog<std::io::BufWriter<std::fs::File>>;
+
+/// Open the `--audit-log` file, if one was requested
+fn open_audit_log(config: &Config) -> Result<Option<AuditFile>> {
+    config
+        .audit_log
+        .as_deref()
+        .map(AuditLog::create)
+        .transpose()
+        .map_err(anyhow::Error::msg)
+}
+
+/// Append decisions to the audit log, if one is open
+fn audit(log: &mut Option<AuditFile>, events: &[AuditEvent]) -> Result<()> {
+    match log {
+        Some(log) => log.record_all(events).map_err(anyhow::Error::msg),
+        None => Ok(()),
+    }
+}
+
+/// Flush the audit log, if one is open
+fn finish_audit_log(log: Option<AuditFile>, config: &Config) -> Result<()> {
+    if let (Some(log), Some(path)) = (log, &config.audit_log) {
+        log.finish().map_err(anyhow::Error::msg)?;
+        println!(
+            "{} Audit log written to: {}",
+            Palette::stdout().success("✓"),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Check a WCET, with any `--margin` already added, against `--wcet-cap`
+///
+/// Returns the WCET to report (the cap with `--clamp-wcet`) and whether the
+/// cap was exceeded.
+fn apply_wcet_cap(wcet_cycles: u64, config: &Config) -> (u64, bool) {
+    match config.wcet_cap {
+        Some(cap) if wcet_cycles > cap => (if config.clamp_wcet { cap } else { wcet_cycles }, true),
+        _ => (wcet_cycles, false),
+    }
+}
+
+/// Whether `--clamp-wcet` lowered any WCET the task set was checked with
+///
+/// A clamped WCET understates the task's demand, so the scheduling verdict
+/// built on it must not pass as a clean run.
+fn clamped_wcets(capped: &[String], config: &Config) -> bool {
+    config.clamp_wcet && !capped.is_empty()
+}
+
+/// WCET with the `--margin` safety margin added, rounded up
+fn apply_margin(wcet_cycles: u64, config: &Config) -> u64 {
+    match config.margin_percent {
+        Some(margin) => (wcet_cycles as f64 * (1.0 + margin / 100.0)).ceil() as u64,
+        None => wcet_cycles,
+    }
+}
+
+/// Report the functions whose WCET exceeded `--wcet-cap`
+fn print_capped_functions(capped: &[String], config: &Config) {
+    let Some(cap) = config.wcet_cap else {
+        return;
+    };
+    if capped.is_empty() {
+        return;
+    }
+    println!(
+        "{} {} function(s) exceeded the WCET cap of {} cycles{}: {}",
+        Palette::stdout().warning("⚠"),
+        capped.len(),
+        cap,
+        if config.clamp_wcet { " (clamped)" } else { "" },
+        capped.join(", ")
+    );
+    println!("  Check them for unbounded loops or missing loop bound annotations");
+}
+
+/// List each task's WCET as a share of its period, largest first
+fn print_relative_wcets(tasks: &[lale::scheduling::Task]) {
+    let mut shares: Vec<_> = tasks
+        .iter()
+        .filter_map(|task| {
+            let period = task.arrival_period_us()?;
+            Some((task, period, task.relative_wcet()?))
+        })
+        .collect();
+    shares.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.name.cmp(&b.0.name)));
+
+    println!("Relative WCET (% of period):");
+    for (task, period, share) in shares {
+        println!(
+            "  {}: {:.1}% ({:.2} us / {:.2} us)",
+            task.name,
+            share * 100.0,
+            task.wcet_us,
+            period
+        );
+    }
+}
+
+/// Print WCETs as `file:line:col: warning: ...` for editors and CI to annotate
+///
+/// Functions without debug info are left out.
+fn print_diagnostics(wcets: &[(&str, u64)], debug_info: &DebugInfo) {
+    for (name, wcet_cycles) in wcets {
+        if let Some(location) = debug_info.function_location(name) {
+            println!("{}: warning: WCET = {} cycles", location, wcet_cycles);
+        }
+    }
+}
+
+/// Final verdict for `--summary-only`: function count, largest WCET and exit code
+fn print_summary(function_wcets: &ahash::AHashMap<String, u64>, code: i32) {
+    println!("Functions: {}", function_wcets.len());
+    let max = function_wcets
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)));
+    if let Some((name, cycles)) = max {
+        println!("Max WCET: {} ({} cycles)", name, cycles);
+    }
+    println!("Exit code: {}", code);
+}
+
+/// One function's results in `lale analyze`
+struct AnalyzedFunction {
+    name: String,
+    /// WCET after `--margin` and then `--wcet-cap`
+    wcet_cycles: u64,
+    wcet_us: f64,
+    /// WCET before `--margin`
+    raw_cycles: u64,
+    approximate: bool,
+    footprint_bytes: usize,
+    capped: bool,
+    typical_cycles: Option<u64>,
+    loop_observations: Vec<LoopObservation>,
+    loops: Vec<LoopBoundDecision>,
+    dead_blocks: Vec<DeadBlock>,
+    poison_branches: Vec<String>,
+}
+
+fn analyze_directory(dir: PathBuf, config: Config) -> Result<i32> {
+    detail!(config, "LALE - LLVM-based WCET Analysis (Inkwell)");
+    detail!(config, "==========================================");
+    detail!(config);
+    // Select platform
+    let platform_name = config
+        .platform
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No platform specified"))?;
+    let platform = select_platform(platform_name, &config)?;
+    if !ANALYZE_FORMATS.contains(&config.format.as_str()) {
+        anyhow::bail!(
+            "Unknown output format '{}' (available: {})",
+            config.format,
+            ANALYZE_FORMATS.join(", ")
+        );
+    }
+
+    detail!(config, "Configuration:");
+    detail!(config, "  Directory: {}", dir.display());
+
+    if let Some(ref board) = config.board {
+        detail!(config, "  Board: {}", board);
+    }
+    detail!(config, "  Platform: {}", platform.summary());
+    detail!(config, "  Cache: {:?}", config.cache_assumption);
+    if let Some(margin) = config.margin_percent {
+        detail!(config, "  Margin: +{}%", margin);
+    }
+
+    detail!(config, "  Output: {}", config.output.display());
+    detail!(config);
+
+    // Find all .ll files in directory
+    let ll_files = find_ll_files(&dir)?;
+    if ll_files.is_empty() {
+        anyhow::bail!(
+            "No .ll, .bc or .a files found in directory: {}",
+            dir.display()
+        );
+    }
+
+    detail!(config, "Found {} LLVM IR file(s)", ll_files.len());
+    detail!(config);
+
+    if config.dry_run {
+        print_dry_run(&platform, &ll_files);
+        return Ok(EXIT_OK);
+    }
+
+    // Parse all modules and analyze
+    let mut all_results = Vec::new();
+    let mut call_site_results = Vec::new();
+    let mut indirect_results = Vec::new();
+    let mut capped_functions = Vec::new();
+    let mut loops_to_annotate = Vec::new();
+    let call_site_analyzer = CallSiteAnalyzer::new(platform.clone());
+    let function_analyzer = function_analyzer(&platform, &config)?;
+    let indirect_call_analyzer = indirect_call_analyzer(&config);
+    let mut audit_log = open_audit_log(&config)?;
+    let mut hotspots = Vec::new();
+    let mut debug_info = DebugInfo::default();
+    let mut warnings = Vec::new();
+    let mut bcets = AHashMap::new();
+
+    for ll_file in &ll_files {
+        detail!(config, "Analyzing: {}", ll_file.display());
+        match InkwellParser::parse_modules(ll_file) {
+            Ok(modules) => {
+                for parsed in &modules {
+                    let module = parsed.module();
+                    let mut file_results = Vec::new();
+                    if config.diagnostics {
+                        debug_info.extend(DebugInfo::from_module(module));
+                    }
+                    let call_graph = CallGraph::from_module(module);
+                    call_graph
+                        .reject_recursion()
+                        .with_context(|| format!("Cannot analyze {}", ll_file.display()))?;
+                    audit(
+                        &mut audit_log,
+                        &external_call_events(&call_graph, &platform),
+                    )?;
+
+                    // Indirect calls charged their worst candidate target
+                    let indirect_bounds =
+                        indirect_call_analyzer.analyze_module(&function_analyzer, module);
+                    let indirect_wcets = indirect_wcets(&indirect_bounds);
+
+                    // Iterate through all functions
+                    for function in module.get_functions() {
+                        let func_name = function
+                            .get_name()
+                            .to_str()
+                            .unwrap_or("unknown")
+                            .to_string();
+
+                        // Skip intrinsics and declarations
+                        if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
+                            continue;
+                        }
+
+                        // Loop-aware IPET WCET over the inkwell CFG
+                        let result = match function_analyzer.analyze(&function) {
+                            Ok(result) => result,
+                            Err(e) => {
+                                warnings.push(AnalysisWarning::in_function(
+                                    WarningKind::AnalysisFailure,
+                                    &func_name,
+                                    e,
+                                ));
+                                continue;
+                            }
+                        };
+                        audit(&mut audit_log, &function_events(&function, &result))?;
+                        hotspots.extend(function_hotspots(&result));
+                        warnings.extend(result.warnings.iter().cloned());
+                        let wcet_cycles = indirect_wcets
+                            .get(&func_name)
+                            .map_or(result.wcet_cycles, |&charged| {
+                                charged.max(result.wcet_cycles)
+                            });
+                        let (total_cycles, capped) =
+                            apply_wcet_cap(apply_margin(wcet_cycles, &config), &config);
+                        bcets.insert(func_name.clone(), result.bcet_cycles.min(total_cycles));
+                        if capped {
+                            capped_functions.push(func_name.clone());
+                        }
+                        let wcet_us = platform.cycles_to_us(total_cycles);
+                        let approximate = result.confidence == Confidence::Approximate;
+
+                        let cfg = InkwellCFG::from_function(&function);
+                        let footprint_bytes =
+                            memory_footprint(&function, &cfg).len() * DEFAULT_LINE_SIZE_BYTES;
+
+                        file_results.push(AnalyzedFunction {
+                            name: func_name.clone(),
+                            wcet_cycles: total_cycles,
+                            wcet_us,
+                            raw_cycles: wcet_cycles,
+                            approximate,
+                            footprint_bytes,
+                            capped,
+                            typical_cycles: result.profiled_typical_cycles,
+                            loop_observations: result.loop_observations.clone(),
+                            loops: result.loop_bound_decisions.clone(),
+                            dead_blocks: result.dead_blocks.clone(),
+                            poison_branches: result.poison_branches.clone(),
+                        });
+                        detail!(
+                            config,
+                            "  {} : {} cycles ({:.2} us), footprint {} bytes{}{}{}",
+                            func_name,
+                            total_cycles,
+                            wcet_us,
+                            footprint_bytes,
+                            if approximate { " [approximate]" } else { "" },
+                            if capped { " [exceeds cap]" } else { "" },
+                            config
+                                .margin_percent
+                                .map(|_| format!(" [{} cycles before margin]", wcet_cycles))
+                                .unwrap_or_default()
+                        );
+                        if let Some(typical) = result.profiled_typical_cycles {
+                            detail!(config, "    typical (profiled): {} cycles", typical);
+                        }
+                        if !config.summary_only {
+                            print_loop_kinds(&result.loop_bound_decisions);
+                            print_clamped_loops(&result.loop_bound_decisions);
+                            print_dead_blocks(&result.dead_blocks);
+                            print_poison_branches(&result.poison_branches);
+                            print_infeasible_paths(&result.infeasible_paths);
+                            print_loop_observations(&result.loop_observations);
+                        }
+                        loops_to_annotate.extend(
+                            result
+                                .loop_bound_decisions
+                                .iter()
+                                .filter(|d| {
+                                    d.kind.needs_annotation()
+                                        && d.source == LoopBoundSource::Heuristic
+                                })
+                                .map(|d| (func_name.clone(), d.header.clone())),
+                        );
+                    }
+
+                    all_results.extend(file_results);
+
+                    // Per-call-site WCET where constant arguments bound callee loops
+                    match call_site_analyzer.analyze_module(module) {
+                        Ok(sites) => {
+                            for site in &sites {
+                                detail!(
+                                    config,
+                                    "  {} -> {} [{}] : {} cycles (context-free: {} cycles)",
+                                    site.caller,
+                                    site.callee,
+                                    site.block,
+                                    site.wcet_cycles,
+                                    site.context_free_wcet_cycles
+                                );
+                            }
+                            call_site_results.extend(sites);
+                        }
+                        Err(e) => {
+                            eprintln!("  Warning: Call-site analysis failed: {}", e);
+                        }
+                    }
+
+                    audit(&mut audit_log, &indirect_call_events(&indirect_bounds))?;
+                    if !config.summary_only {
+                        print_indirect_calls(&indirect_bounds);
+                    }
+                    warnings.extend(indirect_bounds.iter().flat_map(IndirectCallBound::warnings));
+                    indirect_results.extend(indirect_bounds);
+                }
+            }
+            Err(e) => {
+                warnings.push(AnalysisWarning::new(
+                    WarningKind::ParseFailure,
+                    format!("{}: {}", ll_file.display(), e),
+                ));
+            }
+        }
+        detail!(config);
+    }
+
+    detail!(config, "Total functions analyzed: {}", all_results.len());
+    detail!(config);
+    let analyzed_loops: AHashMap<String, Vec<String>> = all_results
+        .iter()
+        .map(|function| (function.name.clone(), loop_headers(&function.loops)))
+        .collect();
+    warnings.extend(unmatched_flow_facts(&function_analyzer, &analyzed_loops));
+    print_warnings(&warnings);
+
+    let mut module_hotspots = top_hotspots(hotspots, DEFAULT_HOTSPOT_COUNT);
+    locate_hotspots(&mut module_hotspots, &debug_info);
+    if !config.summary_only {
+        print_hotspots(&module_hotspots);
+        print_loops_to_annotate(&loops_to_annotate);
+    }
+
+    if !capped_functions.is_empty() && !config.summary_only {
+        print_capped_functions(&capped_functions, &config);
+        detail!(config);
+    }
+
+    if all_results.is_empty() {
+        eprintln!(
+            "Warning: {} .ll file(s) found but none contain analyzable functions \
+             (only declarations/intrinsics). Check the directory path.",
+            ll_files.len()
+        );
+        eprintln!();
+    }
+
+    // Export results to JSON
+    let json_output = serde_json::json!({
+        "platform": platform_name,
+        "cpu_frequency_mhz": platform.cpu_frequency_mhz,
+        "wcet_cap": config.wcet_cap,
+        "margin_percent": config.margin_percent,
+        "functions": all_results.iter().map(|function| {
+            let name = &function.name;
+            serde_json::json!({
+                "name": name,
+                "wcet_cycles": function.wcet_cycles,
+                "wcet_us": function.wcet_us,
+                "bcet_cycles": bcets.get(name),
+                "raw_wcet_cycles": config.margin_percent.map(|_| function.raw_cycles),
+                "confidence": if function.approximate { "approximate" } else { "ipet" },
+                "footprint_bytes": function.footprint_bytes,
+                "exceeds_cap": function.capped,
+                "typical_cycles": function.typical_cycles,
+                "loop_observations": function.loop_observations,
+                "loops": function.loops,
+                "dead_blocks": function.dead_blocks,
+                "poison_branches": function.poison_branches,
+                "source_file": debug_info.function_location(name).map(|location| &location.file),
+                "source_line": debug_info.function_location(name).map(|location| location.line)
+            })
+        }).collect::<Vec<_>>(),
+        "call_sites": call_site_results.iter().map(|site| {
+            serde_json::json!({
+                "caller": site.caller,
+                "callee": site.callee,
+                "block": site.block,
+                "loop_bounds": site.loop_bounds.iter().collect::<std::collections::BTreeMap<_, _>>(),
+                "wcet_cycles": site.wcet_cycles,
+                "wcet_us": platform.cycles_to_us(site.wcet_cycles),
+                "context_free_wcet_cycles": site.context_free_wcet_cycles
+            })
+        }).collect::<Vec<_>>(),
+        "indirect_calls": indirect_results.iter().map(|bound| {
+            serde_json::json!({
+                "function": bound.function,
+                "wcet_cycles": bound.wcet_cycles,
+                "context_free_wcet_cycles": bound.context_free_wcet_cycles,
+                "soundly_bounded": bound.is_sound(),
+                "sites": bound.sites.iter().map(|site| {
+                    serde_json::json!({
+                        "block": site.block,
+                        "signature": site.signature,
+                        "vtable": site.vtable_slot.as_ref().and_then(|slot| slot.vtable.clone()),
+                        "vtable_offset": site.vtable_slot.as_ref().map(|slot| slot.offset),
+                        "targets": site.targets,
+                        "source": site.source,
+                        "wcet_cycles": site.wcet_cycles
+                    })
+                }).collect::<Vec<_>>()
+            })
+        }).collect::<Vec<_>>(),
+        "module_hotspots": module_hotspots,
+        "warnings": warnings
+    });
+
+    let rendered = if config.format == "csv" {
+        let functions: Vec<FunctionWCET> = all_results
+            .iter()
+            .map(|function| {
+                let bcet_cycles = bcets.get(&function.name).copied().unwrap_or(0);
+                let location = debug_info.function_location(&function.name);
+                FunctionWCET {
+                    name: function.name.clone(),
+                    llvm_name: format!("@{}", function.name),
+                    wcet_cycles: function.wcet_cycles,
+                    wcet_us: function.wcet_us,
+                    bcet_cycles,
+                    bcet_us: platform.cycles_to_us(bcet_cycles),
+                    loop_count: function.loops.len(),
+                    raw_wcet_cycles: config.margin_percent.map(|_| function.raw_cycles),
+                    source_file: location.map(|location| location.file.clone()),
+                    source_line: location.map(|location| location.line),
+                }
+            })
+            .collect();
+        CsvOutput::functions_to_csv(&functions)
+    } else {
+        serde_json::to_string_pretty(&json_output)?
+    };
+    std::fs::write(&config.output, &rendered)
+        .with_context(|| format!("Failed to write to {}", config.output.display()))?;
+
+    let ok = Palette::stdout().success("✓");
+    detail!(config, "{} Analysis complete!", ok);
+    detail!(
+        config,
+        "{} Results exported to: {}",
+        ok,
+        config.output.display()
+    );
+    finish_audit_log(audit_log, &config)?;
+    if config.diagnostics {
+        let wcets: Vec<_> = all_results
+            .iter()
+            .map(|function| (function.name.as_str(), function.wcet_cycles))
+            .collect();
+        print_diagnostics(&wcets, &debug_info);
+    }
+
+    let code = if all_results.is_empty() && config.fail_on_empty {
+        EXIT_NO_FUNCTIONS
+    } else {
+        EXIT_OK
+    };
+    if config.summary_only {
+        let wcets: ahash::AHashMap<_, _> = all_results
+            .iter()
+            .map(|function| (function.name.clone(), function.wcet_cycles))
+            .collect();
+        print_summary(&wcets, code);
+    }
+
+    Ok(code)
+}
+
+fn schedule_directory(dir: PathBuf, config: Config) -> Result<i32> {
+    use lale::scheduling::{typical_utilization, DMScheduler, EDFScheduler, RMAScheduler, Task};
+    use lale::{JSONOutput, OutputFormatRegistry, StaticScheduleGenerator};
+
+    detail!(config, "LALE - Schedulability Analysis");
+    detail!(config, "==============================");
+    detail!(config);
+
+    let ll_files = find_ll_files(&dir)?;
+    if ll_files.is_empty() {
+        anyhow::bail!(
+            "No .ll, .bc or .a files found in directory: {}",
+            dir.display()
+        );
+    }
+
+    let platform_name = config
+        .platform
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No platform specified"))?;
+    let platform = select_platform(platform_name, &config)?;
+    let analyzer = function_analyzer(&platform, &config)?;
+    let indirect_call_analyzer = indirect_call_analyzer(&config);
+    let formats = OutputFormatRegistry::new();
+    if formats.get(&config.format).is_none() {
+        anyhow::bail!(
+            "Unknown output format '{}' (available: {})",
+            config.format,
+            formats.names().join(", ")
+        );
+    }
+
+    detail!(config, "Configuration:");
+    detail!(config, "  Directory: {}", dir.display());
+    detail!(config, "  Platform: {}", platform.summary());
+    detail!(config, "  Cache: {:?}", config.cache_assumption);
+    if let Some(margin) = config.margin_percent {
+        detail!(config, "  Margin: +{}%", margin);
+    }
+    detail!(config, "  Policy: {:?}", config.policy);
+    detail!(config, "  Period: {} us", config.period_us);
+    detail!(config);
+
+    let mut function_wcets = ahash::AHashMap::new();
+    let mut raw_wcets = ahash::AHashMap::new();
+    let mut estimated_loop_bounds = 0;
+    let mut capped_functions = Vec::new();
+    let mut typical_cycles = ahash::AHashMap::new();
+    let mut bcets = ahash::AHashMap::new();
+    let mut audit_log = open_audit_log(&config)?;
+    let mut hotspots = Vec::new();
+    let mut debug_info = DebugInfo::default();
+    let mut analyzed_loops = AHashMap::new();
+    let mut warnings = Vec::new();
+
+    for ll_file in &ll_files {
+        let modules = InkwellParser::parse_modules(ll_file)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", ll_file.display(), e))?;
+        for parsed in &modules {
+            let module = parsed.module();
+            if config.diagnostics {
+                debug_info.extend(DebugInfo::from_module(module));
+            }
+            let call_graph = CallGraph::from_module(module);
+            call_graph
+                .reject_recursion()
+                .with_context(|| format!("Cannot schedule {}", ll_file.display()))?;
+            audit(
+                &mut audit_log,
+                &external_call_events(&call_graph, &platform),
+            )?;
+            let indirect_bounds = indirect_call_analyzer.analyze_module(&analyzer, module);
+            audit(&mut audit_log, &indirect_call_events(&indirect_bounds))?;
+            warnings.extend(indirect_bounds.iter().flat_map(IndirectCallBound::warnings));
+            let indirect_wcets = indirect_wcets(&indirect_bounds);
+
+            for function in module.get_functions() {
+                let func_name = function.get_name().to_str().unwrap_or("").to_string();
+
+                // Skip intrinsics and declarations
+                if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
+                    continue;
+                }
+
+                let result = analyzer
+                    .analyze(&function)
+                    .map_err(|e| anyhow::anyhow!("Failed to analyze {}: {}", func_name, e))?;
+
+                audit(&mut audit_log, &function_events(&function, &result))?;
+                hotspots.extend(function_hotspots(&result));
+                warnings.extend(result.warnings.iter().cloned());
+                analyzed_loops.insert(
+                    func_name.clone(),
+                    loop_headers(&result.loop_bound_decisions),
+                );
+                estimated_loop_bounds += result.estimated_loop_bounds;
+                let charged_cycles = indirect_wcets
+                    .get(&func_name)
+                    .map_or(result.wcet_cycles, |&charged| {
+                        charged.max(result.wcet_cycles)
+                    });
+                let (wcet_cycles, capped) =
+                    apply_wcet_cap(apply_margin(charged_cycles, &config), &config);
+                if capped {
+                    capped_functions.push(func_name.clone());
+                }
+                let typical = result.profiled_typical_cycles.unwrap_or(result.bcet_cycles);
+                typical_cycles.insert(func_name.clone(), typical.min(wcet_cycles));
+                bcets.insert(func_name.clone(), result.bcet_cycles.min(wcet_cycles));
+                raw_wcets.insert(func_name.clone(), charged_cycles);
+                function_wcets.insert(func_name, wcet_cycles);
+            }
+        }
+    }
+
+    if function_wcets.is_empty() {
+        anyhow::bail!("No functions found in directory: {}", dir.display());
+    }
+    warnings.extend(unmatched_flow_facts(&analyzer, &analyzed_loops));
+    print_warnings(&warnings);
+
+    let tasks: Vec<Task> = function_wcets
+        .iter()
+        .map(|(name, &wcet_cycles)| Task {
+            name: name.clone(),
+            function: name.clone(),
+            wcet_cycles,
+            wcet_us: platform.cycles_to_us(wcet_cycles),
+            typical_us: typical_cycles
+                .get(name)
+                .map(|&cycles| platform.cycles_to_us(cycles)),
+            period_us: Some(config.period_us),
+            deadline_us: Some(config.period_us),
+            ..Default::default()
+        })
+        .collect();
+
+    let result = match config.policy {
+        SchedulingPolicy::RMA => RMAScheduler::schedulability_test(&tasks),
+        SchedulingPolicy::EDF => EDFScheduler::schedulability_test(&tasks),
+        SchedulingPolicy::DM => DMScheduler::schedulability_test(&tasks),
+    };
+    let schedule = StaticScheduleGenerator::generate_schedule(&tasks);
+
+    let mut report = JSONOutput::generate_report(
+        &function_wcets,
+        &tasks,
+        &result,
+        config.policy,
+        Some(schedule),
+        &platform.name,
+        platform.cpu_frequency_mhz,
+    );
+    report.module_hotspots = top_hotspots(hotspots, DEFAULT_HOTSPOT_COUNT);
+    report.record_source_locations(&debug_info);
+    report.record_bcets(&bcets, platform.cpu_frequency_mhz);
+    report.warnings = warnings;
+    if let Some(margin) = config.margin_percent {
+        report.record_margin(margin, &raw_wcets);
+    }
+    let rendered = formats
+        .render(&config.format, &report)
+        .map_err(anyhow::Error::msg)?;
+    std::fs::write(&config.output, rendered)
+        .with_context(|| format!("Failed to write to {}", config.output.display()))?;
+
+    detail!(config, "Tasks: {}", tasks.len());
+    detail!(
+        config,
+        "Utilization: {:.2}% worst case, {:.2}% typical",
+        RMAScheduler::calculate_utilization(&tasks) * 100.0,
+        typical_utilization(&tasks) * 100.0
+    );
+    if let Some(idle) = report.schedule.as_ref().and_then(|s| s.idle.as_ref()) {
+        detail!(
+            config,
+            "Idle: {:.2} us in {} gap(s), CPU {:.2}% busy over the hyperperiod",
+            idle.total_idle_us,
+            idle.intervals.len(),
+            idle.cpu_utilization * 100.0
+        );
+    }
+    if estimated_loop_bounds > 0 {
+        detail!(config, "Estimated loop bounds: {}", estimated_loop_bounds);
+    }
+    if !config.summary_only {
+        print_capped_functions(&capped_functions, &config);
+        if config.relative_wcet {
+            print_relative_wcets(&tasks);
+        }
+    }
+    detail!(config);
+
+    let palette = Palette::stdout();
+    let schedulable = print_schedulability(&result, &tasks, &palette);
+    detail!(
+        config,
+        "{} Results exported to: {}",
+        palette.success("✓"),
+        config.output.display()
+    );
+    finish_audit_log(audit_log, &config)?;
+
+    let code = if !schedulable && config.fail_on.contains(&FailOn::Unschedulable) {
+        EXIT_UNSCHEDULABLE
+    } else if clamped_wcets(&capped_functions, &config) {
+        EXIT_CLAMPED_WCET
+    } else if estimated_loop_bounds > 0 && config.fail_on.contains(&FailOn::Estimated) {
+        EXIT_ESTIMATED_BOUNDS
+    } else {
+        EXIT_OK
+    };
+    if config.summary_only {
+        print_summary(&function_wcets, code);
+    }
+
+    Ok(code)
+}
+
+/// Analyze a system's entry points and check its whole task set
+///
+/// Each task of the `--tasks` file runs one entry point, whose WCET
+/// includes every function it calls across all modules of the directory.
+/// Unlike `schedule`, an unschedulable system always exits non-zero.
+fn system_directory(dir: PathBuf, config: Config) -> Result<i32> {
+    use lale::scheduling::{
+        typical_utilization, DMScheduler, EDFScheduler, RMAScheduler, SystemSpec,
+    };
+    use lale::{JSONOutput, OutputFormatRegistry, StaticScheduleGenerator, SystemAnalyzer};
+
+    detail!(config, "LALE - System Schedulability Analysis");
+    detail!(config, "=====================================");
+    detail!(config);
+
+    let tasks_path = config
+        .tasks
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No tasks file specified, use --tasks <file>"))?;
+    let spec = SystemSpec::load(tasks_path)?;
+
+    let ll_files = find_ll_files(&dir)?;
+    if ll_files.is_empty() {
+        anyhow::bail!(
+            "No .ll, .bc or .a files found in directory: {}",
+            dir.display()
+        );
+    }
+
+    let platform_name = config
+        .platform
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No platform specified"))?;
+    let platform = select_platform(platform_name, &config)?;
+    let analyzer = function_analyzer(&platform, &config)?;
+    let formats = OutputFormatRegistry::new();
+    if formats.get(&config.format).is_none() {
+        anyhow::bail!(
+            "Unknown output format '{}' (available: {})",
+            config.format,
+            formats.names().join(", ")
+        );
+    }
 
-    let mut i = 0;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--platform" | "-p" => {
-                i += 1;
-                if i < args.len() {
-                    platform = Some(args[i].clone());
-                }
-            }
-            "--board" | "-b" => {
-                i += 1;
-                if i < args.len() {
-                    board = Some(args[i].clone());
-                }
-            }
-            "--output" | "-o" => {
-                i += 1;
-                if i < args.len() {
-                    output = PathBuf::from(&args[i]);
-                }
-            }
-            _ => {
-                eprintln!("Warning: Unknown option '{}'", args[i]);
+    detail!(config, "Configuration:");
+    detail!(config, "  Directory: {}", dir.display());
+    detail!(config, "  Tasks: {}", tasks_path.display());
+    detail!(config, "  Platform: {}", platform.summary());
+    detail!(config, "  Cache: {:?}", config.cache_assumption);
+    if let Some(margin) = config.margin_percent {
+        detail!(config, "  Margin: +{}%", margin);
+    }
+    detail!(config, "  Policy: {:?}", config.policy);
+    detail!(config);
+
+    let mut parsed = Vec::new();
+    let mut debug_info = DebugInfo::default();
+    for ll_file in &ll_files {
+        let modules = InkwellParser::parse_modules(ll_file)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", ll_file.display(), e))?;
+        for module in modules {
+            if config.diagnostics {
+                debug_info.extend(DebugInfo::from_module(module.module()));
             }
+            parsed.push(module);
         }
-        i += 1;
     }
+    let modules: Vec<_> = parsed.iter().map(ParsedModule::module).collect();
 
-    let final_platform = platform.or(Some("cortex-m4".to_string()));
+    let entry_points = spec.entry_points();
+    let system = SystemAnalyzer::new(analyzer).analyze(&modules, &entry_points)?;
+    print_warnings(&system.warnings);
 
-    Ok(Config {
-        platform: final_platform,
-        board,
-        output,
-    })
-}
+    let mut function_wcets = AHashMap::new();
+    let mut raw_wcets = AHashMap::new();
+    let mut capped_functions = Vec::new();
+    for &entry in &entry_points {
+        let raw_cycles = system.function_wcets[entry];
+        let (wcet_cycles, capped) = apply_wcet_cap(apply_margin(raw_cycles, &config), &config);
+        if capped {
+            capped_functions.push(entry.to_string());
+        }
+        raw_wcets.insert(entry.to_string(), raw_cycles);
+        function_wcets.insert(entry.to_string(), wcet_cycles);
+    }
+    let tasks = spec.tasks(&function_wcets, platform.cpu_frequency_mhz)?;
 
-fn select_platform(name: &str) -> Result<PlatformModel> {
-    let model = match name.to_lowercase().as_str() {
-        "cortex-m0" | "m0" => CortexM0Model::new(),
-        "cortex-m3" | "m3" => CortexM3Model::new(),
-        "cortex-m4" | "m4" => CortexM4Model::new(),
-        "cortex-m7" | "m7" => CortexM7Model::new(),
-        "cortex-m33" | "m33" => CortexM33Model::new(),
-        "cortex-r4" | "r4" => CortexR4Model::new(),
-        "cortex-r5" | "r5" => CortexR5Model::new(),
-        "cortex-a7" | "a7" => CortexA7Model::new(),
-        "cortex-a53" | "a53" => CortexA53Model::new(),
-        "rv32i" => RV32IModel::new(),
-        "rv32imac" => RV32IMACModel::new(),
-        "rv32gc" => RV32GCModel::new(),
-        "rv64gc" => RV64GCModel::new(),
-        _ => {
-            anyhow::bail!(
-                "Unknown platform '{}'. Use --help to see available platforms.",
-                name
+    let result = match config.policy {
+        SchedulingPolicy::RMA => RMAScheduler::schedulability_test(&tasks),
+        SchedulingPolicy::EDF => EDFScheduler::schedulability_test(&tasks),
+        SchedulingPolicy::DM => DMScheduler::schedulability_test(&tasks),
+    };
+    let schedule = StaticScheduleGenerator::generate_schedule(&tasks);
+
+    let mut report = JSONOutput::generate_report(
+        &function_wcets,
+        &tasks,
+        &result,
+        config.policy,
+        Some(schedule),
+        &platform.name,
+        platform.cpu_frequency_mhz,
+    );
+    report.record_source_locations(&debug_info);
+    report.warnings = system.warnings;
+    if let Some(margin) = config.margin_percent {
+        report.record_margin(margin, &raw_wcets);
+    }
+    let rendered = formats
+        .render(&config.format, &report)
+        .map_err(anyhow::Error::msg)?;
+    std::fs::write(&config.output, rendered)
+        .with_context(|| format!("Failed to write to {}", config.output.display()))?;
+
+    detail!(
+        config,
+        "Entry points: {} ({} functions analyzed)",
+        entry_points.len(),
+        system.function_wcets.len()
+    );
+    if !config.summary_only {
+        for task in &tasks {
+            println!(
+                "  {}: {} WCET {:.2} us, period {}",
+                task.name,
+                task.function,
+                task.wcet_us,
+                task.arrival_period_us()
+                    .map_or_else(|| "none".to_string(), |period| format!("{:.2} us", period))
             );
         }
-    };
-    Ok(model)
-}
+    }
+    detail!(
+        config,
+        "Utilization: {:.2}% worst case, {:.2}% typical",
+        RMAScheduler::calculate_utilization(&tasks) * 100.0,
+        typical_utilization(&tasks) * 100.0
+    );
+    if let Some(idle) = report.schedule.as_ref().and_then(|s| s.idle.as_ref()) {
+        detail!(
+            config,
+            "Idle: {:.2} us in {} gap(s), CPU {:.2}% busy over the hyperperiod",
+            idle.total_idle_us,
+            idle.intervals.len(),
+            idle.cpu_utilization * 100.0
+        );
+    }
+    if system.estimated_loop_bounds > 0 {
+        detail!(
+            config,
+            "Estimated loop bounds: {}",
+            system.estimated_loop_bounds
+        );
+    }
+    if !config.summary_only {
+        print_capped_functions(&capped_functions, &config);
+        if config.relative_wcet {
+            print_relative_wcets(&tasks);
+        }
+    }
+    detail!(config);
 
-fn analyze_directory(dir: PathBuf, config: Config) -> Result<()> {
-    println!("LALE - LLVM-based WCET Analysis (Inkwell)");
-    println!("==========================================");
-    println!();
-    println!("Configuration:");
-    println!("  Directory: {}", dir.display());
+    let palette = Palette::stdout();
+    let schedulable = print_schedulability(&result, &tasks, &palette);
+    detail!(
+        config,
+        "{} Results exported to: {}",
+        palette.success("✓"),
+        config.output.display()
+    );
 
-    if let Some(ref board) = config.board {
-        println!("  Board: {}", board);
-    } else if let Some(ref platform) = config.platform {
-        println!("  Platform: {}", platform);
+    let code = if !schedulable {
+        EXIT_UNSCHEDULABLE
+    } else if clamped_wcets(&capped_functions, &config) {
+        EXIT_CLAMPED_WCET
+    } else if system.estimated_loop_bounds > 0 && config.fail_on.contains(&FailOn::Estimated) {
+        EXIT_ESTIMATED_BOUNDS
+    } else {
+        EXIT_OK
+    };
+    if config.summary_only {
+        print_summary(&function_wcets, code);
     }
 
-    println!("  Output: {}", config.output.display());
-    println!();
+    Ok(code)
+}
 
-    // Find all .ll files in directory
+/// Print whether a task set is schedulable and why not; true if it is
+fn print_schedulability(
+    result: &SchedulabilityResult,
+    tasks: &[lale::scheduling::Task],
+    palette: &Palette,
+) -> bool {
+    match result {
+        SchedulabilityResult::Schedulable => {
+            println!("{} Task set is schedulable", palette.success("✓"));
+            true
+        }
+        SchedulabilityResult::Unschedulable {
+            failing_task,
+            response_time,
+            deadline,
+        } => {
+            println!(
+                "{} Task set is unschedulable: {} (response {:.2} us > deadline {:.2} us)",
+                palette.failure("✗"),
+                failing_task,
+                response_time,
+                deadline
+            );
+            false
+        }
+        SchedulabilityResult::UnboundedArrivals { tasks } => {
+            println!(
+                "{} Aperiodic tasks without a period mixed into a periodic set: {}",
+                palette.failure("✗"),
+                tasks.join(", ")
+            );
+            println!("  Give them a period, e.g. by serving them from a sporadic server");
+            false
+        }
+        SchedulabilityResult::InfeasibleDeadlines { tasks: infeasible } => {
+            println!(
+                "{} Tasks whose WCET exceeds their deadline (infeasible under any policy):",
+                palette.failure("✗")
+            );
+            for task in tasks.iter().filter(|t| infeasible.contains(&t.name)) {
+                println!(
+                    "  {}: WCET {:.2} us > deadline {:.2} us",
+                    task.name,
+                    task.wcet_us,
+                    task.deadline_us.or(task.arrival_period_us()).unwrap_or(0.0)
+                );
+            }
+            println!("  Relax their deadlines or reduce their WCET");
+            false
+        }
+    }
+}
+
+/// Write the call graph of all modules in a directory as DOT or Mermaid
+///
+/// The format follows the output extension: `.mmd`/`.mermaid` for Mermaid,
+/// anything else for DOT.
+fn export_callgraph(dir: PathBuf, config: Config) -> Result<()> {
     let ll_files = find_ll_files(&dir)?;
     if ll_files.is_empty() {
-        anyhow::bail!("No .ll files found in directory: {}", dir.display());
+        anyhow::bail!(
+            "No .ll, .bc or .a files found in directory: {}",
+            dir.display()
+        );
     }
 
-    println!("Found {} LLVM IR file(s)", ll_files.len());
-    println!();
-
-    // Select platform
     let platform_name = config
         .platform
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("No platform specified"))?;
-    let platform = select_platform(platform_name)?;
+    let platform = select_platform(platform_name, &config)?;
+    let function_analyzer = function_analyzer(&platform, &config)?;
 
-    // Parse all modules and analyze
-    let mut all_results = Vec::new();
+    let mut call_graph = CallGraph::default();
+    let mut wcets = AHashMap::new();
 
     for ll_file in &ll_files {
-        println!("Analyzing: {}", ll_file.display());
-        match InkwellParser::parse_file(ll_file) {
-            Ok((_context, module)) => {
-                let mut file_results = Vec::new();
-
-                // Iterate through all functions
-                for function in module.get_functions() {
-                    let func_name = function
-                        .get_name()
-                        .to_str()
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    // Skip intrinsics and declarations
-                    if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
-                        continue;
-                    }
+        match InkwellParser::parse_modules(ll_file) {
+            Ok(modules) => {
+                for parsed in &modules {
+                    let module = parsed.module();
+                    let module_graph = CallGraph::from_module(module);
 
-                    // Build CFG and calculate timing
-                    let cfg = lale::InkwellCFG::from_function(&function);
-                    let timings = InkwellTimingCalculator::calculate_block_timings(
-                        &function, &cfg, &platform,
-                    );
-
-                    // Sum up all block timings for a simple WCET estimate
-                    let total_cycles: u64 = timings.values().sum();
-                    let wcet_us = total_cycles as f64 / platform.cpu_frequency_mhz as f64;
+                    for name in &module_graph.functions {
+                        let Some(function) = module.get_function(name) else {
+                            continue;
+                        };
+                        match function_analyzer.analyze(&function) {
+                            Ok(result) => {
+                                wcets.insert(name.clone(), result.wcet_cycles);
+                            }
+                            Err(e) => {
+                                eprintln!("Warning: Failed to analyze {}: {}", name, e);
+                            }
+                        }
+                    }
 
-                    file_results.push((func_name.clone(), total_cycles, wcet_us));
-                    println!(
-                        "  {} : {} cycles ({:.2} us)",
-                        func_name, total_cycles, wcet_us
-                    );
+                    call_graph.functions.extend(module_graph.functions);
+                    call_graph.call_sites.extend(module_graph.call_sites);
                 }
-
-                all_results.extend(file_results);
             }
             Err(e) => {
-                eprintln!("  Warning: Failed to parse {}: {}", ll_file.display(), e);
+                eprintln!("Warning: Failed to parse {}: {}", ll_file.display(), e);
             }
         }
-        println!();
     }
 
-    println!("Total functions analyzed: {}", all_results.len());
-    println!();
-
-    // Export results to JSON
-    let json_output = serde_json::json!({
-        "platform": platform_name,
-        "cpu_frequency_mhz": platform.cpu_frequency_mhz,
-        "functions": all_results.iter().map(|(name, cycles, us)| {
-            serde_json::json!({
-                "name": name,
-                "wcet_cycles": cycles,
-                "wcet_us": us
-            })
-        }).collect::<Vec<_>>()
-    });
+    let mermaid = matches!(
+        config.output.extension().and_then(|ext| ext.to_str()),
+        Some("mmd") | Some("mermaid")
+    );
+    let rendered = if mermaid {
+        GraphvizOutput::callgraph_to_mermaid(&call_graph, &wcets)
+    } else {
+        GraphvizOutput::callgraph_to_dot(&call_graph, &wcets)
+    };
 
-    let json_str = serde_json::to_string_pretty(&json_output)?;
-    std::fs::write(&config.output, &json_str)
+    std::fs::write(&config.output, rendered)
         .with_context(|| format!("Failed to write to {}", config.output.display()))?;
 
-    println!("✓ Analysis complete!");
-    println!("✓ Results exported to: {}", config.output.display());
+    println!(
+        "{} Call graph with {} function(s) and {} recursive cycle(s) exported to: {}",
+        Palette::stdout().success("✓"),
+        call_graph.functions.len(),
+        call_graph.find_cycles().len(),
+        config.output.display()
+    );
 
     Ok(())
 }
 
-fn find_ll_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
-    let mut ll_files = Vec::new();
+/// List the platform, files and functions an analysis would cover
+fn print_dry_run(platform: &PlatformModel, ll_files: &[PathBuf]) {
+    println!("Dry run: no analysis will be performed");
+    println!();
+    println!("Platform: {}", platform.summary());
+    println!();
+
+    let mut total_functions = 0;
+    for ll_file in ll_files {
+        println!("{}", ll_file.display());
+        match InkwellParser::parse_modules(ll_file) {
+            Ok(modules) => {
+                for parsed in &modules {
+                    let module = parsed.module();
+                    for function in module.get_functions() {
+                        let func_name = function.get_name().to_str().unwrap_or("unknown");
+
+                        // Same selection as the analysis: skip intrinsics and declarations
+                        if func_name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
+                            continue;
+                        }
+
+                        println!("  {}", func_name);
+                        total_functions += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("  Warning: Failed to parse {}: {}", ll_file.display(), e);
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "Would analyze {} function(s) in {} file(s)",
+        total_functions,
+        ll_files.len()
+    );
+}
 
+/// Find analysis inputs in a directory recursively
+///
+/// Textual IR (`.ll`), bitcode (`.bc`, or `.o` from LTO builds) and archives
+/// of bitcode (`.a`); native objects are skipped when parsed. Paths matching
+/// the directory's `.laleignore` are left out.
+fn find_ll_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
     if !dir.exists() {
         anyhow::bail!("Directory does not exist: {}", dir.display());
     }
@@ -254,29 +1977,231 @@ fn find_ll_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
         anyhow::bail!("Path is not a directory: {}", dir.display());
     }
 
+    let ignore = IgnoreList::load(dir).map_err(anyhow::Error::msg)?;
+    let mut ll_files = Vec::new();
+    collect_ir_inputs(dir, dir, &ignore, &mut ll_files)?;
+    Ok(ll_files)
+}
+
+fn collect_ir_inputs(
+    root: &Path,
+    dir: &Path,
+    ignore: &IgnoreList,
+    ll_files: &mut Vec<PathBuf>,
+) -> Result<()> {
     for entry in std::fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
+        if ignore.is_ignored(path.strip_prefix(root).unwrap_or(&path)) {
+            continue;
+        }
 
         if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext == "ll" {
-                    ll_files.push(path);
-                }
+            if is_ir_input(&path) {
+                ll_files.push(path);
             }
         } else if path.is_dir() {
-            ll_files.extend(find_ll_files(&path)?);
+            collect_ir_inputs(root, &path, ignore, ll_files)?;
         }
     }
 
-    Ok(ll_files)
+    Ok(())
 }
 
-fn list_boards() -> Result<()> {
-    use lale::config::ConfigManager;
+/// Whether a file is an IR, bitcode, object or archive input
+fn is_ir_input(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("ll") | Some("bc") | Some("o") | Some("a")
+    )
+}
 
-    let config_dir = PathBuf::from("config");
-    let manager = ConfigManager::new(config_dir);
+/// Board definitions directory from `--config-dir`, else the default search order
+fn config_dir(args: &[String]) -> PathBuf {
+    let explicit = args
+        .iter()
+        .position(|arg| arg == "--config-dir")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    lale::config::ConfigManager::resolve_config_dir(explicit)
+}
+
+/// `--output-dir` (default: the working directory) and `--force` of `init`
+fn parse_init_args(args: &[String]) -> Result<(PathBuf, bool)> {
+    let mut output_dir = PathBuf::from(".");
+    let mut force = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output-dir" => {
+                output_dir = args
+                    .next()
+                    .map(PathBuf::from)
+                    .context("Missing value for --output-dir")?;
+            }
+            "--force" => force = true,
+            _ => anyhow::bail!(
+                "Unknown option '{}' for init (expected --output-dir <dir> or --force)",
+                arg
+            ),
+        }
+    }
+    Ok((output_dir, force))
+}
+
+/// Write a starter board definition, flow facts for the functions in `dir`
+/// and a `.laleignore` to `output_dir`
+///
+/// Existing files are kept unless `force` is set.
+fn init_project(dir: PathBuf, output_dir: PathBuf, force: bool) -> Result<()> {
+    use lale::config::scaffold::{
+        flow_facts_template, DiscoveredFunction, BOARD_FILE, BOARD_TEMPLATE, FLOW_FACTS_FILE,
+        IGNORE_TEMPLATE,
+    };
+
+    let mut functions = Vec::new();
+    for ll_file in find_ll_files(&dir)? {
+        let modules = match InkwellParser::parse_modules(&ll_file) {
+            Ok(modules) => modules,
+            Err(e) => {
+                eprintln!("  Warning: Failed to parse {}: {}", ll_file.display(), e);
+                continue;
+            }
+        };
+        for parsed in &modules {
+            let module = parsed.module();
+            for function in module.get_functions() {
+                let name = function.get_name().to_str().unwrap_or("").to_string();
+                if name.is_empty()
+                    || name.starts_with("llvm.")
+                    || function.count_basic_blocks() == 0
+                {
+                    continue;
+                }
+
+                let cfg = InkwellCFG::from_function(&function).to_petgraph();
+                let loops = LoopAnalyzer::analyze_loops(&cfg)
+                    .into_iter()
+                    .map(|loop_info| {
+                        let estimated_max = match loop_info.bounds {
+                            LoopBounds::Constant { max, .. } => Some(max),
+                            _ => None,
+                        };
+                        let header = &cfg.graph[loop_info.header];
+                        (
+                            loop_key(&header.label, header.execution_count_var),
+                            estimated_max,
+                        )
+                    })
+                    .collect();
+                let demangled = demangle(&name);
+                functions.push(DiscoveredFunction {
+                    demangled: (demangled != name).then_some(demangled),
+                    name,
+                    loops,
+                });
+            }
+        }
+    }
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    functions.dedup_by(|a, b| a.name == b.name);
+
+    let loop_count: usize = functions.iter().map(|function| function.loops.len()).sum();
+    let files = [
+        (BOARD_FILE, BOARD_TEMPLATE.to_string()),
+        (FLOW_FACTS_FILE, flow_facts_template(&functions)),
+        (IGNORE_FILE, IGNORE_TEMPLATE.to_string()),
+    ];
+    for (file, content) in files {
+        let path = output_dir.join(file);
+        if path.exists() && !force {
+            println!(
+                "  kept    {} (exists; --force to overwrite)",
+                path.display()
+            );
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("  created {}", path.display());
+    }
+
+    println!();
+    println!(
+        "{} Flow facts list {} loop(s) in {} function(s); uncomment the bounds you know",
+        Palette::stdout().success("✓"),
+        loop_count,
+        functions.len()
+    );
+    println!(
+        "  Fill in {} and check it with: lale validate-board platforms/my-board --config-dir {}",
+        BOARD_FILE,
+        output_dir.join("config").display()
+    );
+
+    Ok(())
+}
+
+fn list_platforms(json: bool) -> Result<()> {
+    let platforms = PlatformModel::catalog();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&platforms)?);
+        return Ok(());
+    }
+
+    println!("Built-in Platforms:");
+    println!("===================");
+
+    let mut category = "";
+    for platform in &platforms {
+        if platform.category != category {
+            category = &platform.category;
+            println!();
+            println!("{}:", category);
+        }
+        println!("  {:<12} {}", platform.id, platform.summary);
+    }
+
+    Ok(())
+}
+
+/// Print the worst-case cycles of every instruction class on every
+/// built-in platform
+fn platform_matrix(json: bool) -> Result<()> {
+    let matrix = PlatformModel::timing_matrix();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&matrix)?);
+        return Ok(());
+    }
+
+    println!("Worst-case Cycles by Instruction Class:");
+    println!("=======================================");
+    println!();
+    print!("{}", matrix.to_table());
+    println!();
+    println!("-: no entry in the model, timed at the 1-cycle default");
+
+    let incomplete = matrix.incomplete_rows();
+    if !incomplete.is_empty() {
+        let classes: Vec<&str> = incomplete.iter().map(|row| row.class.as_str()).collect();
+        println!(
+            "{} Classes missing from some models: {}",
+            Palette::stdout().warning("⚠"),
+            classes.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn list_boards(config_dir: PathBuf, detailed: bool) -> Result<()> {
+    let mut manager = board_manager(config_dir);
 
     println!("Available Board Configurations:");
     println!("================================");
@@ -285,7 +2210,10 @@ fn list_boards() -> Result<()> {
     match manager.list_platforms() {
         Ok(platforms) => {
             if platforms.is_empty() {
-                println!("No board configurations found in config/ directory");
+                println!(
+                    "No board configurations found in {}",
+                    manager.config_dir().display()
+                );
                 return Ok(());
             }
 
@@ -310,8 +2238,12 @@ fn list_boards() -> Result<()> {
 
             if !platforms_list.is_empty() {
                 println!("Platform Configurations:");
-                for platform in &platforms_list {
-                    println!("  {}", platform);
+                if detailed {
+                    print_board_table(&mut manager, &platforms_list);
+                } else {
+                    for platform in &platforms_list {
+                        println!("  {}", platform);
+                    }
                 }
                 println!();
             }
@@ -330,19 +2262,102 @@ fn list_boards() -> Result<()> {
     Ok(())
 }
 
-fn validate_board(board_name: &str) -> Result<()> {
-    use lale::config::ConfigManager;
+/// Resolve each board and print its key specs as a table
+fn print_board_table(manager: &mut lale::config::ConfigManager, boards: &[String]) {
+    let header = ["Board", "ISA", "Core", "MHz", "I-Cache", "D-Cache"].map(String::from);
+    let mut rows = Vec::new();
+    let mut failures = Vec::new();
+
+    for board in boards {
+        match manager.load_platform(board) {
+            Ok(config) => {
+                let frequency = config
+                    .soc
+                    .as_ref()
+                    .map(|soc| soc.cpu_frequency_mhz.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+
+                rows.push([
+                    board.clone(),
+                    config.isa.name.clone(),
+                    config.core.name.clone(),
+                    frequency,
+                    cache_summary(config.core.cache.instruction_cache.as_ref()),
+                    cache_summary(config.core.cache.data_cache.as_ref()),
+                ]);
+            }
+            Err(e) => failures.push((board, e)),
+        }
+    }
+
+    let mut widths = header.clone().map(|column| column.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
 
-    let config_dir = PathBuf::from("config");
-    let mut manager = ConfigManager::new(config_dir);
+    let format_row = |row: &[String; 6]| {
+        row.iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    println!("  {}", format_row(&header).trim_end());
+    println!(
+        "  {}",
+        "-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1))
+    );
+    for row in &rows {
+        println!("  {}", format_row(row).trim_end());
+    }
+
+    // Keep the listing going; validate-board shows the full error
+    let palette = Palette::stdout();
+    for (board, e) in failures {
+        println!(
+            "  {}  {} {}",
+            board,
+            palette.failure("✗"),
+            e.to_string().lines().next().unwrap_or("")
+        );
+    }
+}
+
+/// Short cache description, e.g. `16KB/4-way`
+fn cache_summary(cache: Option<&lale::config::types::CacheLevelConfig>) -> String {
+    match cache {
+        Some(cache) => format!("{}KB/{}-way", cache.size_kb, cache.associativity),
+        None => "-".to_string(),
+    }
+}
+
+fn validate_board(board_name: &str, config_dir: PathBuf) -> Result<()> {
+    let mut manager = board_manager(config_dir);
 
     println!("Validating board configuration: {}", board_name);
     println!();
 
-    match manager.load_platform(board_name) {
+    // Bare core configurations load as boards too; `--board` only takes
+    // their caches, so a missing SoC is a warning rather than an error
+    let loaded = manager
+        .load_platform(board_name)
+        .and_then(|config| manager.validate(&config).map(|_| config));
+    match loaded {
         Ok(config) => {
-            println!("✓ Configuration loaded successfully");
+            println!(
+                "{} Configuration loaded successfully",
+                Palette::stdout().success("✓")
+            );
             println!();
+            if let Ok(model) =
+                lale::ActorConfigLoader::platform_model_from_config(board_name, &config)
+            {
+                println!("  {}", model.summary());
+                println!();
+            }
             println!("Configuration Details:");
             println!("  ISA: {}", config.isa.name);
             println!("  Core: {}", config.core.name);
@@ -356,26 +2371,61 @@ fn validate_board(board_name: &str) -> Result<()> {
             }
 
             if let Some(ref dcache) = config.core.cache.data_cache {
+                let write_policy = match dcache.write_policy {
+                    lale::config::types::WritePolicy::WriteBack => "write-back",
+                    lale::config::types::WritePolicy::WriteThrough => "write-through",
+                };
                 println!(
-                    "  D-Cache: {} KB, {}-way",
-                    dcache.size_kb, dcache.associativity
+                    "  D-Cache: {} KB, {}-way, {}",
+                    dcache.size_kb, dcache.associativity, write_policy
                 );
             }
 
             if let Some(ref soc) = config.soc {
                 println!("  SoC: {} @ {} MHz", soc.name, soc.cpu_frequency_mhz);
                 println!("  Memory regions: {}", soc.memory_regions.len());
+                if let Some(ref bus) = soc.bus {
+                    let arbitration = match bus.arbitration {
+                        lale::config::types::BusArbitration::RoundRobin => {
+                            "round-robin".to_string()
+                        }
+                        lale::config::types::BusArbitration::Tdma { slot_cycles } => {
+                            format!("TDMA, {}-cycle slots", slot_cycles)
+                        }
+                    };
+                    println!(
+                        "  Bus: {} B/cycle, {}",
+                        bus.bandwidth_bytes_per_cycle, arbitration
+                    );
+                }
             }
 
             if let Some(ref board) = config.board {
                 println!("  Board: {}", board.name);
             }
 
+            let mut warnings = manager.precision_warnings(&config);
+            if config.soc.is_none() {
+                warnings.push(format!(
+                    "No SoC configured for core '{}', so the CPU frequency is unknown; \
+                     pass --platform with --board, or add a [soc] section to analyze against it \
+                     on its own",
+                    config.core.name
+                ));
+            }
+            if !warnings.is_empty() {
+                println!();
+                println!("Precision warnings:");
+                for warning in &warnings {
+                    println!("  {} {}", Palette::stdout().warning("⚠"), warning);
+                }
+            }
+
             println!();
-            println!("✓ Validation passed");
+            println!("{} Validation passed", Palette::stdout().success("✓"));
         }
         Err(e) => {
-            eprintln!("✗ Validation failed:");
+            eprintln!("{} Validation failed:", Palette::stderr().failure("✗"));
             eprintln!("{}", e);
             std::process::exit(1);
         }
@@ -384,11 +2434,114 @@ fn validate_board(board_name: &str) -> Result<()> {
     Ok(())
 }
 
-fn export_board(board_name: &str) -> Result<()> {
+/// Value of a numeric `name <n>` option, if given
+fn numeric_option<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>> {
+    let Some(i) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    let value = args
+        .get(i + 1)
+        .with_context(|| format!("Missing value for {}", name))?;
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("Invalid value '{}' for {}", value, name))
+}
+
+/// Explore a board's microarchitectural state space into an abstract
+/// execution graph
+///
+/// Loops are unrolled for `max_unroll` iterations, then their states are
+/// widened into the last unrolled one; the program counters where that
+/// happened are reported, since the bound is less precise there.
+fn explore_microarch(
+    board_name: &str,
+    config_dir: PathBuf,
+    max_unroll: usize,
+    max_cycles: u32,
+) -> Result<()> {
+    use lale::aeg::AEGBuilder;
+    use lale::config::ConfigLoader;
+    use lale::microarch::{MicroArchSimulator, MicroArchState, PipelineState};
+
+    let board = board_manager(config_dir)
+        .load_platform(board_name)
+        .with_context(|| format!("Failed to load board '{}'", board_name))?;
+    let platform = ConfigLoader::to_platform_config(&board);
+    if !PipelineState::supports_depth(platform.pipeline_depth) {
+        anyhow::bail!(
+            "Board '{}' has a {}-stage pipeline; the microarchitectural model supports 3, 5 or 6",
+            board_name,
+            platform.pipeline_depth
+        );
+    }
+    let initial_state = MicroArchState::initial(&platform);
+    let aeg = AEGBuilder::new(MicroArchSimulator::new(platform))
+        .with_max_unroll(max_unroll)
+        .build(initial_state, max_cycles)?;
+
+    println!("Microarchitectural analysis: {}", board_name);
+    println!("  Max unroll: {}", max_unroll);
+    println!("  States: {}", aeg.node_count());
+    println!("  Transitions: {}", aeg.edge_count());
+    match aeg.max_final_cycles() {
+        Some(cycles) => println!("  Cycle bound: {} cycles", cycles),
+        None => println!("  Cycle bound: none, the explored states loop"),
+    }
+    if aeg.widening_points.is_empty() {
+        println!("  Widening: none, every loop unrolled fully");
+    } else {
+        let points: Vec<String> = aeg
+            .widening_points
+            .iter()
+            .map(|pc| format!("{:#x}", pc))
+            .collect();
+        println!(
+            "  Widening after {} iteration(s) at: {}",
+            max_unroll,
+            points.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn board_tree(board_name: &str, config_dir: PathBuf) -> Result<()> {
     use lale::config::ConfigManager;
 
-    let config_dir = PathBuf::from("config");
-    let mut manager = ConfigManager::new(config_dir);
+    let manager = ConfigManager::new(config_dir);
+
+    let chain = match manager.inheritance_chain(board_name) {
+        Ok(chain) => chain,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let names: Vec<&str> = chain.iter().map(|level| level.name.as_str()).collect();
+    println!("Inheritance chain: {}", names.join(" → "));
+    println!();
+
+    for level in &chain {
+        println!("{}", level.name);
+        for section in &level.sections {
+            match section.overridden_by {
+                Some(ref by) => println!(
+                    "  {:<6} {:<32} (overridden by {})",
+                    section.section, section.summary, by
+                ),
+                None => println!("  {:<6} {}", section.section, section.summary),
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+fn export_board(board_name: &str, config_dir: PathBuf) -> Result<()> {
+    let mut manager = board_manager(config_dir);
 
     match manager.load_platform(board_name) {
         Ok(config) => match manager.export_platform(&config) {
@@ -414,41 +2567,142 @@ fn print_usage() {
     println!();
     println!("USAGE:");
     println!("    lale analyze <directory> [OPTIONS]");
+    println!("    lale schedule <directory> [OPTIONS]");
+    println!("    lale system <directory> --tasks <file> [OPTIONS]");
+    println!("    lale callgraph <directory> [OPTIONS]");
+    println!("    lale init <directory> [--output-dir <dir>] [--force]");
+    println!("    lale list-platforms [--json]");
+    println!("    lale platform-matrix [--json]");
     println!();
     println!("OPTIONS:");
     println!("    --platform, -p <platform>    Target platform (default: cortex-m4)");
-    println!("    --output, -o <file>          Output file (default: wcet_results.json)");
+    println!("    --frequency <mhz>            Clock the platform's core at <mhz>, e.g. 80");
+    println!(
+        "    --board, -b <name>           Board definition, e.g. platforms/stm32f746-discovery;"
+    );
+    println!("                                 its instruction and data caches are analyzed");
+    println!("    --config-dir <dir>           Board definitions directory for --board");
+    println!("    --output, -o <file>          Output file (default: wcet_results.json,");
+    println!("                                 callgraph.dot for callgraph; .mmd writes Mermaid)");
+    println!(
+        "    --dry-run                    List platform, files and functions without analyzing"
+    );
+    println!("    --wcet-cap <cycles>          Flag functions whose WCET exceeds the cap");
+    println!("    --clamp-wcet                 Report flagged functions at the cap (schedule");
+    println!("                                 and system then exit with code 5)");
+    println!("    --margin <pct>               Add a safety margin to every WCET, e.g. 20");
+    println!("    --heuristic-loop-min <n>     Never guess fewer iterations for a loop");
+    println!("    --heuristic-loop-max <n>     Never guess more iterations for a loop, e.g. 256");
+    println!("    --indirect-targets <caller>=<f1>,<f2>");
+    println!("                                 Functions the indirect calls in <caller> may");
+    println!("                                 reach (repeatable; default: same signature)");
+    println!("    --audit-log <file>           Write every analysis decision as JSONL");
+    println!("    --profile <file>             Profiled edge counts (JSON) for the typical case");
+    println!("                                 and a check of loop bounds");
+    println!("    --flow-facts <file>          Loop bounds by function and loop header (TOML,");
+    println!("                                 or JSON by extension); see `lale init`");
+    println!("    --assume-cache-cold          Every load and store misses: safe upper bound");
+    println!("                                 (default)");
+    println!("    --assume-cache-warm          Every load and store hits: optimistic lower bound");
+    println!("    --smt                        Prune infeasible paths of small functions with");
+    println!("                                 an SMT solver (tighter, slower)");
+    println!("    --smt-max-blocks <n>         Largest function checked by --smt (default: 32)");
+    println!("    --smt-solver <command>       Solver reading SMT-LIB on stdin (default: z3 -in)");
+    println!("    --summary-only               Print only the final verdict and exit code");
+    println!("    --diagnostics                Print each WCET as file:line:col: warning: ...");
+    println!("                                 at the function's source (needs debug info),");
+    println!("                                 and add source locations to the results");
+    println!("    --format <json|csv>          Results format for analyze (default: json; csv");
+    println!("                                 writes wcet_results.csv unless --output is given)");
     println!();
-    println!("AVAILABLE PLATFORMS:");
-    println!("    ARM Cortex-M:");
-    println!("      cortex-m0, m0      - Cortex-M0/M0+/M1 @ 48MHz");
-    println!("      cortex-m3, m3      - Cortex-M3 @ 72MHz");
-    println!("      cortex-m4, m4      - Cortex-M4 @ 168MHz (default)");
-    println!("      cortex-m7, m7      - Cortex-M7 @ 400MHz");
-    println!("      cortex-m33, m33    - Cortex-M33 @ 120MHz");
+    println!("GLOBAL OPTIONS:");
+    println!("    --color <auto|always|never>  Color terminal output (default: auto, which");
+    println!("                                 colors terminals unless NO_COLOR is set)");
+    println!("    --no-color                   Same as --color never");
+    println!();
+    println!("SCHEDULE OPTIONS:");
+    println!(
+        "    --period <us>                Period and deadline for every task (default: 10000)"
+    );
+    println!("    --policy <rma|edf|dm>        Scheduling policy (default: rma)");
+    println!("    --format <json|csv|gantt|chrome-trace|gantt-svg>");
+    println!("                                 Report format (default: json)");
+    println!("    --relative-wcet              List each task's WCET as a % of its period");
+    println!("    --fail-on <condition>        Exit non-zero on a condition (repeatable):");
+    println!("                                   unschedulable  task set misses a deadline");
+    println!("                                   estimated      heuristic loop bounds were used");
+    println!();
+    println!("SYSTEM OPTIONS:");
+    println!("    --tasks <file>               Entry point, period and deadline of each task");
+    println!("                                 ([[task]] tables in TOML, or JSON by extension);");
+    println!("                                 WCETs include every callee across all modules");
+    println!("    --policy, --format, --relative-wcet and --fail-on estimated as for schedule");
     println!();
-    println!("    ARM Cortex-R:");
-    println!("      cortex-r4, r4      - Cortex-R4 @ 600MHz");
-    println!("      cortex-r5, r5      - Cortex-R5 @ 800MHz");
+    println!("EXIT CODES (schedule, system):");
+    println!("    0    Analysis completed, no --fail-on condition hit");
+    println!("    1    Task set is unschedulable (--fail-on unschedulable; always for system)");
+    println!("    2    Analysis error");
+    println!("    3    Heuristic loop bounds present (--fail-on estimated)");
+    println!("    5    A WCET was clamped (--clamp-wcet), the verdict is unsound");
     println!();
-    println!("    ARM Cortex-A:");
-    println!("      cortex-a7, a7      - Cortex-A7 @ 1200MHz");
-    println!("      cortex-a53, a53    - Cortex-A53 @ 1400MHz");
+    println!("EXIT CODES (analyze):");
+    println!("    0    Analysis completed");
+    println!("    4    No analyzable functions found (--fail-on-empty)");
     println!();
-    println!("    RISC-V:");
-    println!("      rv32i              - RV32I @ 100MHz");
-    println!("      rv32imac           - RV32IMAC @ 320MHz");
-    println!("      rv32gc             - RV32GC @ 1000MHz");
-    println!("      rv64gc             - RV64GC @ 1500MHz");
+    println!("AVAILABLE PLATFORMS:");
+    let mut category = String::new();
+    for platform in PlatformModel::catalog() {
+        if platform.category != category {
+            if !category.is_empty() {
+                println!();
+            }
+            category = platform.category.clone();
+            println!("    {}:", category);
+        }
+        let mut ids = platform.id.clone();
+        for alias in &platform.aliases {
+            ids.push_str(", ");
+            ids.push_str(alias);
+        }
+        println!(
+            "      {:<18} - {} @ {}MHz{}",
+            ids,
+            platform.name,
+            platform.frequency_mhz,
+            if platform.id == "cortex-m4" {
+                " (default)"
+            } else {
+                ""
+            }
+        );
+    }
     println!();
     println!("EXAMPLES:");
     println!("    lale analyze ./data/armv7e-m --platform cortex-m4");
     println!("    lale analyze ./ir_files --platform cortex-m7 --output results.json");
+    println!("    lale schedule ./ir_files --period 1000 --fail-on unschedulable");
+    println!("    lale callgraph ./ir_files -o cg.dot");
+    println!("    lale callgraph ./ir_files -o cg.mmd");
     println!();
     println!("BOARD CONFIGURATION COMMANDS:");
     println!("    lale list-boards                List available board configurations");
+    println!(
+        "    lale list-boards --detailed     List boards with ISA, core, frequency and caches"
+    );
     println!("    lale validate-board <name>      Validate a board configuration");
     println!("    lale export-board <name>        Export resolved board configuration");
+    println!(
+        "    lale board-tree <name>          Show the inherits chain and what each level sets"
+    );
+    println!("    lale microarch <name>           Explore the board's pipeline and cache states");
+    println!("      --max-unroll <n>              Loop iterations unrolled before widening");
+    println!("                                    (default: 1); widening points are reported");
+    println!("      --max-cycles <n>              Cycles to explore (default: 1000)");
+    println!();
+    println!("    --config-dir <dir>              Board definitions directory");
+    println!("                                    (default: first of $LALE_CONFIG_DIR,");
+    println!("                                    $XDG_CONFIG_HOME/lale, the install default,");
+    println!("                                    ./config)");
     println!();
     println!("OTHER COMMANDS:");
     println!("    lale help              Show this help message");
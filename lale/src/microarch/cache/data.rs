@@ -0,0 +1,313 @@
+//! Data-cache analysis of a function's loads and stores
+//!
+//! IR carries no data layout, so each global a load or store accesses by
+//! name gets a line of its own, in the order the function first accesses
+//! them. The linker may place any two globals in the same set, so every
+//! line is assumed to map to one set: a line is known to be cached only if
+//! fewer lines than the associativity were accessed since its last access.
+//!
+//! Accesses through any other pointer, such as stack slots and computed
+//! addresses, have no known address. They may miss and may evict any line.
+//! Volatile accesses bypass the cache. Under a write-through policy a
+//! store always writes to memory: whether it hits or allocates its line is
+//! left open, so it may age every line. A call may evict any line, and no
+//! line of a loop that calls out or accesses unknown addresses is
+//! persistent in it.
+
+use super::instruction::{CacheGeometry, LineAges};
+use super::may::{self, MayCacheState};
+use super::must::{self, MustCacheState};
+use super::persistence::{CacheAnalysisResult, LoopPersistence, PersistentBlocks};
+use crate::analysis::{InkwellTimingCalculator, LoopAnalyzer};
+use crate::config::types::{CacheLevelConfig, WritePolicy};
+use crate::ir::{CallGraph, InkwellCFG, InkwellCFGBlock, CFG};
+use ahash::{AHashMap, AHashSet};
+use inkwell::values::{BasicValueEnum, InstructionOpcode, InstructionValue};
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+
+/// Address of the first global in a [`GlobalLayout`]
+pub const DATA_BASE_ADDRESS: u64 = 0x2000_0000;
+
+/// Bytes between consecutive globals in a [`GlobalLayout`]
+///
+/// Every line is assumed to map to one set, so the spacing only keeps the
+/// globals' lines distinct.
+pub const GLOBAL_SPACING: u64 = 64;
+
+/// Address of each global a function loads or stores by name
+#[derive(Debug, Clone, Default)]
+pub struct GlobalLayout {
+    addresses: AHashMap<String, u64>,
+}
+
+impl GlobalLayout {
+    /// Globals laid out [`GLOBAL_SPACING`] bytes apart from
+    /// [`DATA_BASE_ADDRESS`], in order of first access
+    pub fn of(cfg: &InkwellCFG) -> Self {
+        let mut addresses = AHashMap::new();
+        for block in &cfg.blocks {
+            let mut instr_iter = block.block.get_first_instruction();
+            while let Some(instr) = instr_iter {
+                if let Some(global) = Self::accessed_global(&instr) {
+                    let next = DATA_BASE_ADDRESS + addresses.len() as u64 * GLOBAL_SPACING;
+                    addresses.entry(global).or_insert(next);
+                }
+                instr_iter = instr.get_next_instruction();
+            }
+        }
+        Self { addresses }
+    }
+
+    /// Address a non-volatile load or store accesses, if it is a global
+    pub fn address(&self, instr: &InstructionValue) -> Option<u64> {
+        self.addresses.get(&Self::accessed_global(instr)?).copied()
+    }
+
+    /// Name of the global a non-volatile load or store accesses directly
+    fn accessed_global(instr: &InstructionValue) -> Option<String> {
+        if InkwellTimingCalculator::is_volatile_access(instr) {
+            return None;
+        }
+        let index = match instr.get_opcode() {
+            InstructionOpcode::Load => 0,
+            InstructionOpcode::Store => 1,
+            _ => return None,
+        };
+        let BasicValueEnum::PointerValue(pointer) = instr.get_operand(index)?.value()? else {
+            return None;
+        };
+        // Constant expressions such as a `getelementptr` are unnamed
+        if !pointer.is_const() || pointer.as_instruction().is_some() {
+            return None;
+        }
+        let name = pointer.get_name().to_str().ok()?;
+        (!name.is_empty()).then(|| name.to_string())
+    }
+}
+
+/// One step of a block's data access stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessStep {
+    /// Access to the line at this address
+    Line(u64),
+
+    /// Write-through store to the line at this address
+    WriteThrough(u64),
+
+    /// Load or store through a pointer of unknown address
+    Unknown,
+
+    /// Return from a called function, whose accesses may have evicted any line
+    Call,
+}
+
+/// Must/may/persistence analysis of the data accesses of a function
+#[derive(Debug, Clone)]
+pub struct DataCacheAnalysis {
+    associativity: u32,
+    write_policy: WritePolicy,
+}
+
+impl DataCacheAnalysis {
+    pub fn new(config: &CacheLevelConfig) -> Self {
+        Self {
+            associativity: config.associativity.max(1) as u32,
+            write_policy: config.write_policy,
+        }
+    }
+
+    /// Classify the data accesses of the function, by block
+    ///
+    /// The cache is assumed empty at function entry. A block's must state
+    /// holds the lines every access of the block to which hits, and its may
+    /// state the lines some access of the block to which may hit.
+    pub fn analyze(&self, cfg: &InkwellCFG) -> CacheAnalysisResult {
+        let layout = GlobalLayout::of(cfg);
+        let graph = cfg.to_petgraph();
+        let streams: AHashMap<usize, Vec<AccessStep>> = cfg
+            .blocks
+            .iter()
+            .map(|block| (block.id, self.access_stream(block, &layout)))
+            .collect();
+        let steps: AHashMap<NodeIndex, Vec<AccessStep>> = graph
+            .graph
+            .node_indices()
+            .map(|node| {
+                let id = graph.graph[node].execution_count_var;
+                (node, streams.get(&id).cloned().unwrap_or_default())
+            })
+            .collect();
+
+        let must_in = self.fixpoint(&graph, &steps, LineAges::must_join, false);
+        let may_in = self.fixpoint(&graph, &steps, LineAges::may_join, true);
+
+        let associativity = self.associativity as usize;
+        let mut must = MustCacheState::new();
+        let mut may = MayCacheState::new();
+        for node in graph.graph.node_indices() {
+            // Unreachable blocks access nothing
+            let (Some(must_entry), Some(may_entry)) = (must_in.get(&node), may_in.get(&node))
+            else {
+                continue;
+            };
+            let mut must_state = must_entry.clone();
+            let mut may_state = may_entry.clone();
+            let mut always_hit: AHashMap<u64, bool> = AHashMap::new();
+            let mut may_hit = AHashSet::new();
+            for &step in &steps[&node] {
+                match step {
+                    AccessStep::Line(line) => {
+                        *always_hit.entry(line).or_insert(true) &= must_state.contains(line);
+                        if may_state.contains(line) {
+                            may_hit.insert(line);
+                        }
+                        must_state.access(line, self);
+                        may_state.access(line, self);
+                    }
+                    AccessStep::WriteThrough(line) => {
+                        must_state.access_unknown(self);
+                        may_state.access(line, self);
+                    }
+                    AccessStep::Unknown => must_state.access_unknown(self),
+                    AccessStep::Call => must_state = LineAges::default(),
+                }
+            }
+            let hits = always_hit
+                .into_iter()
+                .filter_map(|(line, hit)| hit.then_some(line));
+            must.states.insert(
+                node,
+                must::CacheAbstractState::from_lines(hits, associativity),
+            );
+            may.states.insert(
+                node,
+                may::CacheAbstractState::from_lines(may_hit, associativity),
+            );
+        }
+
+        let mut result = CacheAnalysisResult::new(must, may);
+        result.write_policy = self.write_policy;
+        result.persistence = self.persistence(&graph, &steps);
+        result
+    }
+
+    /// Data accesses of a block in order, with a [`AccessStep::Call`] after
+    /// each call out of the function
+    ///
+    /// Debug-info intrinsics access no memory and are skipped.
+    fn access_stream(&self, block: &InkwellCFGBlock, layout: &GlobalLayout) -> Vec<AccessStep> {
+        let mut steps = Vec::new();
+        let mut instr_iter = block.block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            match instr.get_opcode() {
+                InstructionOpcode::Load | InstructionOpcode::Store
+                    if !InkwellTimingCalculator::is_volatile_access(&instr) =>
+                {
+                    let write_through = instr.get_opcode() == InstructionOpcode::Store
+                        && self.write_policy == WritePolicy::WriteThrough;
+                    steps.push(match layout.address(&instr) {
+                        None => AccessStep::Unknown,
+                        Some(line) if write_through => AccessStep::WriteThrough(line),
+                        Some(line) => AccessStep::Line(line),
+                    });
+                }
+                InstructionOpcode::Call | InstructionOpcode::Invoke
+                    if !CallGraph::direct_callee(&instr)
+                        .is_some_and(|callee| callee.starts_with("llvm.dbg.")) =>
+                {
+                    steps.push(AccessStep::Call);
+                }
+                _ => {}
+            }
+            instr_iter = instr.get_next_instruction();
+        }
+        steps
+    }
+
+    /// Line ages at the entry of each reachable block
+    fn fixpoint(
+        &self,
+        graph: &CFG,
+        steps: &AHashMap<NodeIndex, Vec<AccessStep>>,
+        join: fn(&LineAges, &LineAges) -> LineAges,
+        may: bool,
+    ) -> AHashMap<NodeIndex, LineAges> {
+        let mut states = AHashMap::new();
+        states.insert(graph.entry, LineAges::default());
+        let mut worklist = vec![graph.entry];
+
+        while let Some(node) = worklist.pop() {
+            let mut state = states[&node].clone();
+            for &step in &steps[&node] {
+                match step {
+                    AccessStep::Line(line) => state.access(line, self),
+                    AccessStep::WriteThrough(line) if may => state.access(line, self),
+                    AccessStep::WriteThrough(_) => state.access_unknown(self),
+                    // Evictions only shrink what may be cached
+                    AccessStep::Unknown | AccessStep::Call if may => {}
+                    AccessStep::Unknown => state.access_unknown(self),
+                    AccessStep::Call => state = LineAges::default(),
+                }
+            }
+
+            for successor in graph.graph.neighbors_directed(node, Direction::Outgoing) {
+                let joined = match states.get(&successor) {
+                    None => state.clone(),
+                    Some(old) => join(old, &state),
+                };
+                if states.get(&successor) != Some(&joined) {
+                    states.insert(successor, joined);
+                    worklist.push(successor);
+                }
+            }
+        }
+        states
+    }
+
+    /// Lines of each loop, if they all fit in the one set and nothing else
+    /// can evict them
+    fn persistence(
+        &self,
+        graph: &CFG,
+        steps: &AHashMap<NodeIndex, Vec<AccessStep>>,
+    ) -> AHashMap<NodeIndex, LoopPersistence> {
+        LoopAnalyzer::analyze_loops(graph)
+            .into_iter()
+            .map(|l| {
+                let mut evicting = false;
+                let mut lines = AHashSet::new();
+                for step in l.body_blocks.iter().flat_map(|node| steps[node].iter()) {
+                    match *step {
+                        AccessStep::Line(line) | AccessStep::WriteThrough(line) => {
+                            lines.insert(line);
+                        }
+                        AccessStep::Unknown | AccessStep::Call => evicting = true,
+                    }
+                }
+
+                let mut persistent_blocks = PersistentBlocks::new();
+                if !evicting && lines.len() <= self.associativity as usize {
+                    persistent_blocks.blocks = lines;
+                }
+                (
+                    l.header,
+                    LoopPersistence {
+                        header: l.header,
+                        persistent_blocks,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl CacheGeometry for DataCacheAnalysis {
+    fn set_of(&self, _line: u64) -> u64 {
+        0
+    }
+
+    fn associativity(&self) -> u32 {
+        self.associativity
+    }
+}
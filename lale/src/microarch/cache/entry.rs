@@ -0,0 +1,320 @@
+//! Instruction-cache state carried from callers into callees
+//!
+//! Analyzed on its own, every function starts from a cold cache, so a callee
+//! pays a full cold miss on each call even when its code was fetched by the
+//! call just before. Here each function's code is one abstract cache line. A
+//! Must analysis over each caller's CFG gives the state at every direct call
+//! site, and a callee's own call sites are analyzed from the join of the
+//! states at all calls to it.
+//!
+//! Functions that may be entered other than by a direct call in the module,
+//! i.e. those without direct callers, possible targets of indirect calls and
+//! recursive ones, start cold. A call that may reach external code or a
+//! function pointer may evict anything. Like indirect call resolution, this
+//! assumes the module is the whole program.
+//!
+//! This is library API only: the `lale` commands still time every function
+//! from a cold entry.
+
+use super::state::CacheState;
+use super::types::AccessClassification;
+use crate::ir::{CallGraph, CallSite, InkwellCFG};
+use crate::microarch::state::CacheConfig;
+use ahash::{AHashMap, AHashSet};
+use inkwell::module::Module;
+use inkwell::values::FunctionValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Abstract address of a function's code
+pub fn code_address(function: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    function.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether a function's code is guaranteed to be in the instruction cache
+pub fn is_code_cached(state: &CacheState, function: &str) -> bool {
+    state.i_cache.as_ref().is_some_and(|cache| {
+        cache.classify(code_address(function)) == AccessClassification::AlwaysHit
+    })
+}
+
+/// Cache state at a direct call, before the callee runs
+#[derive(Debug, Clone)]
+pub struct CallSiteCacheState {
+    pub site: CallSite,
+    pub state: CacheState,
+}
+
+/// Instruction-cache states at function entries and direct call sites
+#[derive(Debug, Clone)]
+pub struct EntryCacheStates {
+    /// State at the entry of each defined function, joined over its calls
+    entries: AHashMap<String, CacheState>,
+
+    /// State at each reachable direct call to a defined function, callers
+    /// before callees
+    call_sites: Vec<CallSiteCacheState>,
+}
+
+/// What a call to a defined function does to the instruction cache
+struct CallEffect {
+    /// Code of the functions it may call in turn, itself excluded
+    callees: Vec<u64>,
+
+    /// Whether it may reach external code or call through a pointer
+    opaque: bool,
+}
+
+impl CallEffect {
+    /// Functions reachable from `callee`, and whether all of them are known
+    fn of(call_graph: &CallGraph, callee: &str) -> Self {
+        let mut reached = vec![callee];
+        let mut visited: AHashSet<&str> = reached.iter().copied().collect();
+        let mut opaque = false;
+
+        let mut next = 0;
+        while let Some(&function) = reached.get(next) {
+            next += 1;
+            if !call_graph.is_defined(function)
+                || !call_graph.indirect_call_sites_from(function).is_empty()
+            {
+                opaque = true;
+                continue;
+            }
+            for called in call_graph.callees(function) {
+                if visited.insert(called) {
+                    reached.push(called);
+                }
+            }
+        }
+
+        Self {
+            callees: reached
+                .into_iter()
+                .filter(|&function| function != callee)
+                .map(code_address)
+                .collect(),
+            opaque,
+        }
+    }
+
+    /// State back in the caller after the call returns
+    ///
+    /// The callee's code runs last in the call, and the caller's right after.
+    fn apply(&self, state: &mut CacheState, cold: &CacheState, caller: &str, callee: &str) {
+        if self.opaque {
+            *state = cold.clone();
+        } else {
+            for &line in &self.callees {
+                state.may_access_instruction(line);
+            }
+        }
+        state.access_instruction(code_address(callee));
+        state.access_instruction(code_address(caller));
+    }
+}
+
+/// Per-function inputs to the call-site analysis
+struct Caller<'a> {
+    name: &'a str,
+    /// Direct calls, in instruction order
+    sites: Vec<&'a CallSite>,
+    /// Indices of the blocks calling through a function pointer
+    indirect_blocks: AHashSet<usize>,
+    effects: &'a AHashMap<String, CallEffect>,
+    cold: &'a CacheState,
+}
+
+impl EntryCacheStates {
+    /// Analyze every defined function of a module
+    pub fn analyze(module: &Module, config: &CacheConfig) -> Self {
+        let call_graph = CallGraph::from_module(module);
+        let cold = CacheState::new(config);
+
+        let mut cold_entry: AHashSet<String> =
+            call_graph.find_cycles().into_iter().flatten().collect();
+        for site in &call_graph.indirect_call_sites {
+            cold_entry.extend(
+                call_graph
+                    .functions_with_signature(&site.signature)
+                    .into_iter()
+                    .map(str::to_string),
+            );
+        }
+
+        let effects: AHashMap<String, CallEffect> = call_graph
+            .functions
+            .iter()
+            .map(|name| (name.clone(), CallEffect::of(&call_graph, name)))
+            .collect();
+
+        // A function is analyzed once every caller has contributed its calls
+        let mut pending: AHashMap<&str, usize> = AHashMap::new();
+        for name in &call_graph.functions {
+            if !cold_entry.contains(name) {
+                let callers: AHashSet<&str> = call_graph
+                    .call_sites_to(name)
+                    .iter()
+                    .map(|site| site.caller.as_str())
+                    .collect();
+                pending.insert(name, callers.len());
+            }
+        }
+        let mut ready: VecDeque<&str> = call_graph
+            .functions
+            .iter()
+            .map(String::as_str)
+            .filter(|name| pending.get(name).is_none_or(|&callers| callers == 0))
+            .collect();
+
+        let mut incoming: AHashMap<String, CacheState> = AHashMap::new();
+        let mut entries = AHashMap::new();
+        let mut call_sites = Vec::new();
+
+        while let Some(name) = ready.pop_front() {
+            let entry = match incoming.remove(name) {
+                Some(state) if !cold_entry.contains(name) => state,
+                _ => cold.clone(),
+            };
+
+            if let Some(function) = module.get_function(name) {
+                let caller = Caller {
+                    name,
+                    sites: call_graph.call_sites_from(name),
+                    indirect_blocks: call_graph
+                        .indirect_call_sites_from(name)
+                        .into_iter()
+                        .map(|site| site.block_id)
+                        .collect(),
+                    effects: &effects,
+                    cold: &cold,
+                };
+
+                for site_state in caller.call_site_states(&function, &entry) {
+                    let callee = site_state.site.callee.clone();
+                    let joined = match incoming.remove(&callee) {
+                        Some(state) => state.must_join(&site_state.state),
+                        None => site_state.state.clone(),
+                    };
+                    incoming.insert(callee, joined);
+                    call_sites.push(site_state);
+                }
+            }
+
+            for callee in call_graph.callees(name) {
+                if let Some(callers) = pending.get_mut(callee) {
+                    *callers -= 1;
+                    if *callers == 0 {
+                        ready.push_back(callee);
+                    }
+                }
+            }
+
+            entries.insert(name.to_string(), entry);
+        }
+
+        Self {
+            entries,
+            call_sites,
+        }
+    }
+
+    /// State at the entry of a function, cold if nothing is known
+    pub fn entry_state(&self, function: &str) -> Option<&CacheState> {
+        self.entries.get(function)
+    }
+
+    /// State at every reachable direct call to a defined function
+    pub fn call_sites(&self) -> &[CallSiteCacheState] {
+        &self.call_sites
+    }
+}
+
+impl Caller<'_> {
+    /// State at each call in the function, from the state at its entry
+    fn call_site_states(
+        &self,
+        function: &FunctionValue,
+        entry: &CacheState,
+    ) -> Vec<CallSiteCacheState> {
+        let cfg = InkwellCFG::from_function(function);
+
+        let mut start = entry.clone();
+        start.access_instruction(code_address(self.name));
+
+        // Must analysis to a fixpoint over the CFG
+        let mut block_in: AHashMap<usize, CacheState> = AHashMap::new();
+        let mut block_out_hash: AHashMap<usize, u64> = AHashMap::new();
+        block_in.insert(cfg.entry_block, start);
+        let mut worklist = VecDeque::from([cfg.entry_block]);
+
+        while let Some(id) = worklist.pop_front() {
+            let (out, _) = self.transfer(id, block_in[&id].clone());
+            if block_out_hash.get(&id) == Some(&out.hash()) {
+                continue;
+            }
+            block_out_hash.insert(id, out.hash());
+
+            for succ in cfg.successors(id) {
+                let joined = match block_in.get(&succ) {
+                    Some(state) => state.must_join(&out),
+                    None => out.clone(),
+                };
+                let changed = block_in
+                    .get(&succ)
+                    .is_none_or(|state| state.hash() != joined.hash());
+                if changed || !block_out_hash.contains_key(&succ) {
+                    block_in.insert(succ, joined);
+                    worklist.push_back(succ);
+                }
+            }
+        }
+
+        cfg.blocks
+            .iter()
+            .filter_map(|block| {
+                let state = block_in.get(&block.id)?.clone();
+                Some(self.transfer(block.id, state).1)
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// State leaving the block with index `block`, and the state at each of
+    /// its calls
+    fn transfer(
+        &self,
+        block: usize,
+        mut state: CacheState,
+    ) -> (CacheState, Vec<CallSiteCacheState>) {
+        // The order of direct and indirect calls in a block is not kept
+        let indirect = self.indirect_blocks.contains(&block);
+        if indirect {
+            state = self.cold.clone();
+        }
+
+        let mut site_states = Vec::new();
+        for site in self.sites.iter().filter(|site| site.block_id == block) {
+            // External code may evict anything
+            let Some(effect) = self.effects.get(&site.callee) else {
+                state = self.cold.clone();
+                state.access_instruction(code_address(self.name));
+                continue;
+            };
+            site_states.push(CallSiteCacheState {
+                site: (*site).clone(),
+                state: state.clone(),
+            });
+            effect.apply(&mut state, self.cold, self.name, &site.callee);
+        }
+
+        if indirect {
+            state = self.cold.clone();
+            state.access_instruction(code_address(self.name));
+        }
+        (state, site_states)
+    }
+}
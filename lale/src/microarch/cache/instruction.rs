@@ -0,0 +1,489 @@
+//! Instruction-cache analysis of a function's fetch stream
+//!
+//! IR carries no code layout, so blocks are laid out back to back in
+//! function order, each `instruction count × instruction size` bytes long.
+//! Executing a block fetches every cache line its address range touches, in
+//! order. Must and may analyses of the LRU ages of those lines classify each
+//! fetch, and a line that no other line of a loop can evict from its set is
+//! persistent in that loop: it misses at most once per entry to the loop.
+//!
+//! A call runs code outside the analyzed function, which may evict any
+//! line: nothing is known to be cached when it returns, and no line of a
+//! loop that calls out is persistent.
+
+use super::persistence::{CacheAccessClass, LoopPersistence, PersistentBlocks};
+use crate::analysis::LoopAnalyzer;
+use crate::config::types::CacheLevelConfig;
+use crate::ir::{CallGraph, InkwellCFG, InkwellCFGBlock, CFG};
+use ahash::{AHashMap, AHashSet};
+use inkwell::values::InstructionOpcode;
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+
+/// Bytes per instruction assumed when laying out blocks, e.g. Thumb-2 wide
+/// or A64 encodings
+pub const DEFAULT_INSTRUCTION_SIZE: u64 = 4;
+
+/// Address range of each block, by block id
+#[derive(Debug, Clone, Default)]
+pub struct BlockLayout {
+    ranges: AHashMap<usize, (u64, u64)>,
+}
+
+impl BlockLayout {
+    /// Blocks laid out back to back from `base`, in function order
+    pub fn sequential(cfg: &InkwellCFG, base: u64, instruction_size: u64) -> Self {
+        let mut ranges = AHashMap::new();
+        let mut address = base;
+        for block in &cfg.blocks {
+            let end = address + block.instruction_count as u64 * instruction_size;
+            ranges.insert(block.id, (address, end));
+            address = end;
+        }
+        Self { ranges }
+    }
+
+    /// Start address of a block
+    pub fn address(&self, block_id: usize) -> Option<u64> {
+        self.ranges.get(&block_id).map(|&(start, _)| start)
+    }
+
+    /// Size of a block in bytes
+    pub fn size(&self, block_id: usize) -> Option<u64> {
+        self.ranges.get(&block_id).map(|&(start, end)| end - start)
+    }
+
+    /// Start addresses of the cache lines a block's fetches touch, in order
+    pub fn fetched_lines(&self, block_id: usize, line_size: u64) -> Vec<u64> {
+        let Some(&(start, end)) = self.ranges.get(&block_id) else {
+            return Vec::new();
+        };
+        if start == end {
+            return Vec::new();
+        }
+        let first = start / line_size;
+        let last = (end - 1) / line_size;
+        (first..=last).map(|line| line * line_size).collect()
+    }
+}
+
+/// One step of a block's fetch stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FetchStep {
+    /// Fetch of the line starting at this address
+    Line(u64),
+
+    /// Return from a called function, whose fetches may have evicted any line
+    Call,
+}
+
+/// One cache-line fetch of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionFetch {
+    /// Start address of the fetched line
+    pub line: u64,
+
+    pub class: CacheAccessClass,
+
+    /// Header of the outermost enclosing loop the line is persistent in
+    pub persistent_in: Option<NodeIndex>,
+}
+
+/// Classified fetches of a function
+#[derive(Debug, Clone)]
+pub struct InstructionCacheResult {
+    pub layout: BlockLayout,
+
+    /// Fetches of each block, by block id, in fetch order
+    pub fetches: AHashMap<usize, Vec<InstructionFetch>>,
+
+    /// Lines persistent in each loop, by loop header
+    pub persistence: AHashMap<NodeIndex, LoopPersistence>,
+}
+
+impl InstructionCacheResult {
+    /// Number of fetches of a block that may miss on every execution
+    pub fn misses(&self, block_id: usize) -> usize {
+        self.fetches.get(&block_id).map_or(0, |fetches| {
+            fetches
+                .iter()
+                .filter(|fetch| {
+                    matches!(
+                        fetch.class,
+                        CacheAccessClass::AlwaysMiss | CacheAccessClass::Unknown
+                    )
+                })
+                .count()
+        })
+    }
+}
+
+/// Must/may/persistence analysis of the instruction fetches of a function
+#[derive(Debug, Clone)]
+pub struct InstructionCacheAnalysis {
+    line_size: u64,
+    num_sets: u64,
+    associativity: u32,
+    instruction_size: u64,
+    base_address: u64,
+}
+
+impl InstructionCacheAnalysis {
+    pub fn new(config: &CacheLevelConfig) -> Self {
+        let line_size = config.line_size_bytes.max(1) as u64;
+        let associativity = config.associativity.max(1) as u64;
+        let num_sets = (config.size_kb as u64 * 1024 / (line_size * associativity)).max(1);
+        Self {
+            line_size,
+            num_sets,
+            associativity: associativity as u32,
+            instruction_size: DEFAULT_INSTRUCTION_SIZE,
+            base_address: 0,
+        }
+    }
+
+    /// Bytes per instruction
+    pub fn with_instruction_size(mut self, instruction_size: u64) -> Self {
+        self.instruction_size = instruction_size;
+        self
+    }
+
+    /// Address of the function's first block
+    pub fn with_base_address(mut self, base_address: u64) -> Self {
+        self.base_address = base_address;
+        self
+    }
+
+    /// Classify every instruction fetch of the function
+    ///
+    /// The cache is assumed empty at function entry and after every call.
+    pub fn analyze(&self, cfg: &InkwellCFG) -> InstructionCacheResult {
+        let layout = BlockLayout::sequential(cfg, self.base_address, self.instruction_size);
+        let graph = cfg.to_petgraph();
+        let streams: AHashMap<usize, Vec<FetchStep>> = cfg
+            .blocks
+            .iter()
+            .map(|block| (block.id, self.fetch_stream(block, &layout)))
+            .collect();
+        let lines: AHashMap<NodeIndex, Vec<FetchStep>> = graph
+            .graph
+            .node_indices()
+            .map(|node| {
+                let id = graph.graph[node].execution_count_var;
+                (node, streams.get(&id).cloned().unwrap_or_default())
+            })
+            .collect();
+
+        let must = self.fixpoint(&graph, &lines, LineAges::must_join, false);
+        let may = self.fixpoint(&graph, &lines, LineAges::may_join, true);
+        let persistence = self.persistence(&graph, &lines);
+
+        let mut loops: Vec<_> = LoopAnalyzer::analyze_loops(&graph);
+        // Enclosing loops are listed outermost first
+        loops.sort_by_key(|l| std::cmp::Reverse(l.body_blocks.len()));
+
+        let mut fetches = AHashMap::new();
+        for node in graph.graph.node_indices() {
+            // Unreachable blocks are never fetched
+            let (Some(must_in), Some(may_in)) = (must.get(&node), may.get(&node)) else {
+                continue;
+            };
+            let mut must_state = must_in.clone();
+            let mut may_state = may_in.clone();
+            let headers: Vec<NodeIndex> = loops
+                .iter()
+                .filter(|l| l.body_blocks.contains(&node))
+                .map(|l| l.header)
+                .collect();
+
+            let block_fetches = lines[&node]
+                .iter()
+                .filter_map(|&step| {
+                    let FetchStep::Line(line) = step else {
+                        must_state = LineAges::default();
+                        return None;
+                    };
+                    let persistent_in = headers.iter().copied().find(|header| {
+                        persistence.get(header).is_some_and(|p| p.is_loop_hit(line))
+                    });
+                    let class = if must_state.contains(line) {
+                        CacheAccessClass::AlwaysHit
+                    } else if persistent_in.is_some() {
+                        CacheAccessClass::FirstMiss
+                    } else if may_state.contains(line) {
+                        CacheAccessClass::Unknown
+                    } else {
+                        CacheAccessClass::AlwaysMiss
+                    };
+                    must_state.access(line, self);
+                    may_state.access(line, self);
+                    Some(InstructionFetch {
+                        line,
+                        class,
+                        persistent_in,
+                    })
+                })
+                .collect();
+            fetches.insert(graph.graph[node].execution_count_var, block_fetches);
+        }
+
+        InstructionCacheResult {
+            layout,
+            fetches,
+            persistence,
+        }
+    }
+
+    /// Lines a block fetches in order, with a [`FetchStep::Call`] after each
+    /// call out of the function
+    ///
+    /// Execution resumes after a call by fetching the next instruction's
+    /// line again, even if the call shares it. Intrinsics are expanded in
+    /// place and call nothing.
+    fn fetch_stream(&self, block: &InkwellCFGBlock, layout: &BlockLayout) -> Vec<FetchStep> {
+        let Some(start) = layout.address(block.id) else {
+            return Vec::new();
+        };
+        let mut steps = Vec::new();
+        let mut last_line = None;
+        let mut address = start;
+        let mut instr_iter = block.block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            let line = address / self.line_size * self.line_size;
+            if last_line != Some(line) {
+                steps.push(FetchStep::Line(line));
+                last_line = Some(line);
+            }
+            let is_call = matches!(
+                instr.get_opcode(),
+                InstructionOpcode::Call | InstructionOpcode::Invoke
+            ) && !CallGraph::direct_callee(&instr)
+                .is_some_and(|callee| callee.starts_with("llvm."));
+            if is_call {
+                steps.push(FetchStep::Call);
+                last_line = None;
+            }
+            address += self.instruction_size;
+            instr_iter = instr.get_next_instruction();
+        }
+        steps
+    }
+
+    /// Line ages at the entry of each reachable block
+    fn fixpoint(
+        &self,
+        graph: &CFG,
+        lines: &AHashMap<NodeIndex, Vec<FetchStep>>,
+        join: fn(&LineAges, &LineAges) -> LineAges,
+        may: bool,
+    ) -> AHashMap<NodeIndex, LineAges> {
+        let mut states = AHashMap::new();
+        states.insert(graph.entry, LineAges::default());
+        let mut worklist = vec![graph.entry];
+
+        while let Some(node) = worklist.pop() {
+            let mut state = states[&node].clone();
+            for &step in &lines[&node] {
+                match step {
+                    FetchStep::Line(line) => state.access(line, self),
+                    // Evictions only shrink what may be cached
+                    FetchStep::Call if may => {}
+                    FetchStep::Call => state = LineAges::default(),
+                }
+            }
+
+            for successor in graph.graph.neighbors_directed(node, Direction::Outgoing) {
+                let joined = match states.get(&successor) {
+                    None => state.clone(),
+                    Some(old) => join(old, &state),
+                };
+                if states.get(&successor) != Some(&joined) {
+                    states.insert(successor, joined);
+                    worklist.push(successor);
+                }
+            }
+        }
+        states
+    }
+
+    /// Lines of each loop that fit in their set alongside every other line
+    /// of the loop; none for a loop that calls out
+    fn persistence(
+        &self,
+        graph: &CFG,
+        lines: &AHashMap<NodeIndex, Vec<FetchStep>>,
+    ) -> AHashMap<NodeIndex, LoopPersistence> {
+        LoopAnalyzer::analyze_loops(graph)
+            .into_iter()
+            .map(|l| {
+                let steps = || l.body_blocks.iter().flat_map(|node| lines[node].iter());
+                let calls_out = steps().any(|&step| step == FetchStep::Call);
+                let loop_lines: AHashSet<u64> = steps()
+                    .filter_map(|&step| match step {
+                        FetchStep::Line(line) if !calls_out => Some(line),
+                        _ => None,
+                    })
+                    .collect();
+                let mut per_set: AHashMap<u64, u32> = AHashMap::new();
+                for &line in &loop_lines {
+                    *per_set.entry(self.set_of(line)).or_insert(0) += 1;
+                }
+
+                let mut persistent_blocks = PersistentBlocks::new();
+                persistent_blocks.blocks = loop_lines
+                    .into_iter()
+                    .filter(|&line| per_set[&self.set_of(line)] <= self.associativity)
+                    .collect();
+                (
+                    l.header,
+                    LoopPersistence {
+                        header: l.header,
+                        persistent_blocks,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl CacheGeometry for InstructionCacheAnalysis {
+    fn set_of(&self, line: u64) -> u64 {
+        (line / self.line_size) % self.num_sets
+    }
+
+    fn associativity(&self) -> u32 {
+        self.associativity
+    }
+}
+
+/// Mapping of lines to the sets of an LRU cache
+pub(super) trait CacheGeometry {
+    fn set_of(&self, line: u64) -> u64;
+
+    fn associativity(&self) -> u32;
+}
+
+/// LRU ages of cached lines
+///
+/// In the must analysis an age is an upper bound, and a line is cached if
+/// it has one; in the may analysis it is a lower bound, and a line without
+/// one is not cached on any path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct LineAges {
+    ages: AHashMap<u64, u32>,
+}
+
+impl LineAges {
+    pub(super) fn contains(&self, line: u64) -> bool {
+        self.ages.contains_key(&line)
+    }
+
+    /// Make `line` the youngest of its set
+    ///
+    /// Lines of the set younger than it age by one; lines that reach the
+    /// associativity are evicted.
+    pub(super) fn access(&mut self, line: u64, geometry: &impl CacheGeometry) {
+        let set = geometry.set_of(line);
+        let associativity = geometry.associativity();
+        let old_age = self.ages.get(&line).copied().unwrap_or(associativity);
+        for (&other, age) in self.ages.iter_mut() {
+            if geometry.set_of(other) == set && *age < old_age {
+                *age += 1;
+            }
+        }
+        self.ages.retain(|_, age| *age < associativity);
+        self.ages.insert(line, 0);
+    }
+
+    /// Age every line by one, as an access to a line of unknown set that
+    /// was not cached would in the worst case
+    pub(super) fn access_unknown(&mut self, geometry: &impl CacheGeometry) {
+        let associativity = geometry.associativity();
+        for age in self.ages.values_mut() {
+            *age += 1;
+        }
+        self.ages.retain(|_, age| *age < associativity);
+    }
+
+    /// Lines cached on both paths, at the older age
+    pub(super) fn must_join(&self, other: &Self) -> Self {
+        let ages = self
+            .ages
+            .iter()
+            .filter_map(|(line, &age)| other.ages.get(line).map(|&o| (*line, age.max(o))))
+            .collect();
+        Self { ages }
+    }
+
+    /// Lines cached on either path, at the younger age
+    pub(super) fn may_join(&self, other: &Self) -> Self {
+        let mut ages = self.ages.clone();
+        for (&line, &age) in &other.ages {
+            ages.entry(line)
+                .and_modify(|own| *own = (*own).min(age))
+                .or_insert(age);
+        }
+        Self { ages }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::{ReplacementPolicy, WritePolicy};
+
+    /// 2-way cache of two 32-byte sets
+    fn tiny_cache() -> InstructionCacheAnalysis {
+        let mut analysis = InstructionCacheAnalysis::new(&CacheLevelConfig {
+            size_kb: 1,
+            line_size_bytes: 32,
+            associativity: 2,
+            replacement_policy: ReplacementPolicy::LRU,
+            hit_latency: 1,
+            miss_latency: 10,
+            write_policy: WritePolicy::WriteBack,
+        });
+        analysis.num_sets = 2;
+        analysis
+    }
+
+    #[test]
+    fn test_lru_ages_evict_oldest_line_of_set() {
+        let cache = tiny_cache();
+        let mut state = LineAges::default();
+
+        // 0x00, 0x40 and 0x80 share set 0; 0x20 is in set 1
+        state.access(0x00, &cache);
+        state.access(0x20, &cache);
+        state.access(0x40, &cache);
+        assert!(state.contains(0x00) && state.contains(0x20));
+
+        state.access(0x80, &cache);
+        assert!(!state.contains(0x00));
+        assert!(state.contains(0x20) && state.contains(0x40) && state.contains(0x80));
+
+        // Re-accessing the youngest line ages nothing
+        state.access(0x80, &cache);
+        state.access(0x40, &cache);
+        assert!(state.contains(0x80));
+    }
+
+    #[test]
+    fn test_joins() {
+        let cache = tiny_cache();
+        let mut left = LineAges::default();
+        left.access(0x00, &cache);
+        left.access(0x40, &cache);
+        let mut right = LineAges::default();
+        right.access(0x40, &cache);
+        right.access(0x20, &cache);
+
+        let must = left.must_join(&right);
+        assert!(must.contains(0x40));
+        assert!(!must.contains(0x00) && !must.contains(0x20));
+        assert_eq!(must.ages[&0x40], 0);
+
+        let may = left.may_join(&right);
+        assert!(may.contains(0x00) && may.contains(0x20) && may.contains(0x40));
+    }
+}
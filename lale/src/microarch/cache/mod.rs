@@ -1,3 +1,6 @@
+pub mod data;
+pub mod entry;
+pub mod instruction;
 pub mod lru;
 pub mod may;
 pub mod must;
@@ -5,6 +8,12 @@ pub mod persistence;
 pub mod state;
 pub mod types;
 
+pub use data::{DataCacheAnalysis, GlobalLayout, DATA_BASE_ADDRESS, GLOBAL_SPACING};
+pub use entry::{code_address, is_code_cached, CallSiteCacheState, EntryCacheStates};
+pub use instruction::{
+    BlockLayout, InstructionCacheAnalysis, InstructionCacheResult, InstructionFetch,
+    DEFAULT_INSTRUCTION_SIZE,
+};
 pub use lru::{LRUCache, LRUStack};
 pub use may::{MayAnalysis, MayCacheState};
 pub use must::{MustAnalysis, MustCacheState};
@@ -1,4 +1,4 @@
-use super::types::{CacheSet, MemoryBlock};
+use super::types::{Age, CacheSet, MemoryBlock};
 use crate::ir::CFG;
 use ahash::AHashMap;
 use petgraph::graph::NodeIndex;
@@ -115,6 +115,20 @@ impl CacheAbstractState {
         Self { sets: Vec::new() }
     }
 
+    /// State holding exactly `lines`, e.g. as classified by another analysis
+    pub fn from_lines(lines: impl IntoIterator<Item = u64>, associativity: usize) -> Self {
+        let mut state = Self::empty();
+        for addr in lines {
+            let block = MemoryBlock::new(addr);
+            let set_index = block.set_index(32, 4);
+            while state.sets.len() <= set_index {
+                state.sets.push(CacheSet::new(associativity));
+            }
+            state.sets[set_index].ages.insert(block, Age::new(0));
+        }
+        state
+    }
+
     /// Access a memory block
     pub fn access(&mut self, addr: u64, associativity: usize) {
         let block = MemoryBlock::new(addr);
@@ -1,6 +1,7 @@
 use super::may::MayCacheState;
 use super::must::MustCacheState;
 use super::types::{CacheSet, MemoryBlock};
+use crate::config::types::WritePolicy;
 use crate::ir::CFG;
 use ahash::{AHashMap, AHashSet};
 use petgraph::graph::NodeIndex;
@@ -159,6 +160,10 @@ pub struct CacheAnalysisResult {
 
     /// Persistence analysis for loops
     pub persistence: AHashMap<NodeIndex, LoopPersistence>,
+
+    /// Write policy of the analyzed cache; write-through stores always pay
+    /// the memory latency
+    pub write_policy: WritePolicy,
 }
 
 impl CacheAnalysisResult {
@@ -167,6 +172,7 @@ impl CacheAnalysisResult {
             must,
             may,
             persistence: AHashMap::new(),
+            write_policy: WritePolicy::WriteBack,
         }
     }
 
@@ -190,6 +196,35 @@ impl CacheAnalysisResult {
             (false, true) => CacheAccessClass::Unknown,
         }
     }
+
+    /// Outermost of the given loops in which a block is persistent
+    ///
+    /// `loop_headers` are the headers of the loops enclosing an access,
+    /// outermost first.
+    pub fn persistent_loop(&self, addr: u64, loop_headers: &[NodeIndex]) -> Option<NodeIndex> {
+        loop_headers.iter().copied().find(|header| {
+            self.persistence
+                .get(header)
+                .is_some_and(|persistence| persistence.is_loop_hit(addr))
+        })
+    }
+
+    /// Classify cache access at a program point inside loops
+    ///
+    /// An access that may miss but is persistent in an enclosing loop misses
+    /// at most once per entry to that loop.
+    pub fn classify_access_in_loops(
+        &self,
+        node: NodeIndex,
+        addr: u64,
+        loop_headers: &[NodeIndex],
+    ) -> CacheAccessClass {
+        match self.classify_access(node, addr) {
+            CacheAccessClass::AlwaysHit => CacheAccessClass::AlwaysHit,
+            _ if self.persistent_loop(addr, loop_headers).is_some() => CacheAccessClass::FirstMiss,
+            class => class,
+        }
+    }
 }
 
 /// Cache access classification
@@ -201,6 +236,9 @@ pub enum CacheAccessClass {
     /// Definitely a cache miss
     AlwaysMiss,
 
+    /// Misses on the first iteration of an enclosing loop, hits afterwards
+    FirstMiss,
+
     /// Unknown (may hit or miss)
     Unknown,
 }
@@ -239,4 +277,37 @@ mod tests {
         let class = result.classify_access(node, 0x1000);
         assert_eq!(class, CacheAccessClass::AlwaysMiss);
     }
+
+    #[test]
+    fn test_persistent_access_is_first_miss() {
+        let node = NodeIndex::new(2);
+        let header = NodeIndex::new(1);
+        let mut result = CacheAnalysisResult::new(MustCacheState::new(), MayCacheState::new());
+
+        let mut persistent_blocks = PersistentBlocks::new();
+        persistent_blocks.blocks.insert(0x1000);
+        result.persistence.insert(
+            header,
+            LoopPersistence {
+                header,
+                persistent_blocks,
+            },
+        );
+
+        assert_eq!(
+            result.classify_access_in_loops(node, 0x1000, &[header]),
+            CacheAccessClass::FirstMiss
+        );
+        assert_eq!(result.persistent_loop(0x1000, &[header]), Some(header));
+
+        // Not persistent, or outside the loop
+        assert_eq!(
+            result.classify_access_in_loops(node, 0x2000, &[header]),
+            CacheAccessClass::AlwaysMiss
+        );
+        assert_eq!(
+            result.classify_access_in_loops(node, 0x1000, &[]),
+            CacheAccessClass::AlwaysMiss
+        );
+    }
 }
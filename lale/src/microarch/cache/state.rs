@@ -1,4 +1,4 @@
-use super::types::{AccessClassification, CacheSet, MemoryBlock};
+use super::types::{AccessClassification, Age, CacheSet, MemoryBlock};
 use crate::microarch::state::{CacheConfig, CacheLevelConfig};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -85,6 +85,30 @@ impl CacheState {
             AccessClassification::AlwaysHit // No cache = always hit
         }
     }
+
+    /// Instruction fetch that may or may not happen
+    pub fn may_access_instruction(&mut self, address: u64) {
+        if let Some(cache) = &mut self.i_cache {
+            cache.may_access(address);
+        }
+    }
+
+    /// Lines cached in both states, each at the older of its two ages
+    ///
+    /// Unlike `join`, a line cached on one side only is dropped, so an
+    /// always-hit classification holds on every path into the join.
+    pub fn must_join(&self, other: &Self) -> Self {
+        Self {
+            i_cache: match (&self.i_cache, &other.i_cache) {
+                (Some(c1), Some(c2)) => Some(c1.must_join(c2)),
+                _ => None,
+            },
+            d_cache: match (&self.d_cache, &other.d_cache) {
+                (Some(c1), Some(c2)) => Some(c1.must_join(c2)),
+                _ => None,
+            },
+        }
+    }
 }
 
 /// Abstract cache (single level)
@@ -139,6 +163,50 @@ impl AbstractCache {
         self.sets[set_index].classify(block)
     }
 
+    /// Access that may or may not happen
+    ///
+    /// Every line of the address's set may be one step older afterwards;
+    /// the accessed line is not guaranteed to be cached.
+    pub fn may_access(&mut self, address: u64) {
+        let set_index = self.get_set_index(address);
+        let set = &mut self.sets[set_index];
+        for age in set.ages.values_mut() {
+            age.must_age = age.must_age.saturating_add(1);
+        }
+        let associativity = set.associativity;
+        set.ages
+            .retain(|_, age| (age.must_age as usize) < associativity * 2);
+    }
+
+    /// Join keeping only the lines cached in both, at the older age
+    pub fn must_join(&self, other: &Self) -> Self {
+        assert!(self.is_compatible(other), "Incompatible caches");
+
+        let sets = self
+            .sets
+            .iter()
+            .zip(other.sets.iter())
+            .map(|(s1, s2)| {
+                let mut set = CacheSet::new(s1.associativity);
+                for (block, age1) in &s1.ages {
+                    if let Some(age2) = s2.ages.get(block) {
+                        let age = Age::range(
+                            age1.must_age.max(age2.must_age),
+                            age1.may_age.max(age2.may_age),
+                        );
+                        set.ages.insert(*block, age);
+                    }
+                }
+                set
+            })
+            .collect();
+
+        Self {
+            sets,
+            config: self.config.clone(),
+        }
+    }
+
     /// Check if two caches are compatible
     pub fn is_compatible(&self, other: &Self) -> bool {
         self.sets.len() == other.sets.len()
@@ -174,7 +242,7 @@ impl AbstractCache {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::microarch::state::ReplacementPolicy;
+    use crate::microarch::state::{ReplacementPolicy, WritePolicy};
 
     fn test_cache_config() -> CacheLevelConfig {
         CacheLevelConfig {
@@ -182,6 +250,7 @@ mod tests {
             line_size_bytes: 32,
             associativity: 4,
             replacement_policy: ReplacementPolicy::LRU,
+            write_policy: WritePolicy::WriteBack,
         }
     }
 
@@ -260,4 +329,32 @@ mod tests {
         let joined = state1.join(&state2);
         assert!(joined.i_cache.is_some());
     }
+
+    #[test]
+    fn test_must_join_keeps_lines_cached_on_both_sides() {
+        let config = CacheConfig {
+            instruction_cache: Some(test_cache_config()),
+            data_cache: None,
+        };
+
+        let mut state1 = CacheState::new(&config);
+        let mut state2 = CacheState::new(&config);
+        state1.access_instruction(0x1000);
+        state1.access_instruction(0x2000);
+        state2.access_instruction(0x1000);
+
+        let joined = state1.must_join(&state2);
+        let cache = joined.i_cache.as_ref().unwrap();
+        assert_eq!(cache.classify(0x1000), AccessClassification::AlwaysHit);
+        assert_eq!(cache.classify(0x2000), AccessClassification::AlwaysMiss);
+
+        // A possible access to the same set ages the line without caching itself
+        let mut aged = joined.clone();
+        for _ in 0..4 {
+            aged.may_access_instruction(0x2000);
+        }
+        let cache = aged.i_cache.as_ref().unwrap();
+        assert_ne!(cache.classify(0x1000), AccessClassification::AlwaysHit);
+        assert_eq!(cache.classify(0x2000), AccessClassification::AlwaysMiss);
+    }
 }
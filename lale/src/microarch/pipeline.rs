@@ -10,6 +10,11 @@ pub struct PipelineState {
 }
 
 impl PipelineState {
+    /// Whether [`PipelineState::new`] models a pipeline of `depth` stages
+    pub fn supports_depth(depth: usize) -> bool {
+        matches!(depth, 3 | 5 | 6)
+    }
+
     /// Create new empty pipeline with given depth
     pub fn new(depth: usize) -> Self {
         let stage_types = match depth {
@@ -1,6 +1,6 @@
 use super::cache::types::AccessClassification;
 use super::pipeline::{AbstractAddress, InstructionSlot, MemoryAccessType, Opcode, StageType};
-use super::state::{MicroArchState, PlatformConfig};
+use super::state::{MicroArchState, PlatformConfig, WritePolicy};
 
 /// Microarchitectural simulator - advances state cycle-by-cycle
 pub struct MicroArchSimulator {
@@ -86,12 +86,20 @@ impl MicroArchSimulator {
                             .as_ref()
                             .map(|c| c.classify(address))
                             .unwrap_or(AccessClassification::AlwaysHit),
-                        MemoryAccessType::Store => state
-                            .cache
-                            .d_cache
-                            .as_ref()
-                            .map(|c| c.classify(address))
-                            .unwrap_or(AccessClassification::AlwaysHit),
+                        MemoryAccessType::Store => match self.config.cache_config.data_cache {
+                            // Write-through stores always reach memory
+                            Some(ref dcache)
+                                if dcache.write_policy == WritePolicy::WriteThrough =>
+                            {
+                                AccessClassification::AlwaysMiss
+                            }
+                            _ => state
+                                .cache
+                                .d_cache
+                                .as_ref()
+                                .map(|c| c.classify(address))
+                                .unwrap_or(AccessClassification::AlwaysHit),
+                        },
                     };
 
                     // Split state if uncertain
@@ -179,7 +187,7 @@ mod tests {
     use super::*;
     use crate::microarch::pipeline::{DepType, MemoryAccess, RegisterDep};
     use crate::microarch::state::{
-        CacheConfig, CacheLevelConfig, MemoryConfig, MemoryLatency, ReplacementPolicy,
+        CacheConfig, CacheLevelConfig, MemoryConfig, MemoryLatency, ReplacementPolicy, WritePolicy,
     };
 
     fn test_config() -> PlatformConfig {
@@ -191,12 +199,14 @@ mod tests {
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
                 data_cache: Some(CacheLevelConfig {
                     size_kb: 16,
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
             },
             memory_config: MemoryConfig {
@@ -128,6 +128,7 @@ pub struct CacheLevelConfig {
     pub line_size_bytes: usize,
     pub associativity: usize,
     pub replacement_policy: ReplacementPolicy,
+    pub write_policy: WritePolicy,
 }
 
 /// Cache replacement policy
@@ -138,6 +139,15 @@ pub enum ReplacementPolicy {
     FIFO,
 }
 
+/// Cache write policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Stores complete in the cache and can be buffered
+    WriteBack,
+    /// Every store pays the memory latency
+    WriteThrough,
+}
+
 /// Memory system configuration
 #[derive(Debug, Clone)]
 pub struct MemoryConfig {
@@ -166,12 +176,14 @@ mod tests {
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
                 data_cache: Some(CacheLevelConfig {
                     size_kb: 16,
                     line_size_bytes: 32,
                     associativity: 4,
                     replacement_policy: ReplacementPolicy::LRU,
+                    write_policy: WritePolicy::WriteBack,
                 }),
             },
             memory_config: MemoryConfig {
@@ -0,0 +1,161 @@
+//! Memory-bus contention between cores
+//!
+//! Every memory access that leaves the core has to win the shared bus first.
+//! The worst-case wait depends on the arbitration policy: round-robin lets
+//! every other contending core transfer once, TDMA may have to wait for the
+//! rest of the frame until the core's own slot comes around again.
+
+use crate::async_analysis::Actor;
+use crate::config::types::{BusArbitration, BusConfig};
+use crate::multicore::interference::inflate_wcet;
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+
+/// Shared memory bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusModel {
+    pub arbitration: BusArbitration,
+
+    /// Cycles one access occupies the bus
+    pub transfer_cycles: u32,
+}
+
+/// Bus contention suffered by one actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorBusContention {
+    pub actor_name: String,
+    pub core_id: usize,
+
+    /// Memory accesses per activation
+    pub memory_accesses: u64,
+
+    /// Worst-case wait for the bus per access
+    pub wait_cycles_per_access: u64,
+
+    /// WCET increase of this actor
+    pub contention_cycles: u64,
+}
+
+impl BusModel {
+    /// Bus described by the SoC configuration, moving `access_bytes` per access
+    pub fn from_config(config: &BusConfig, access_bytes: u32) -> Self {
+        Self {
+            arbitration: config.arbitration,
+            transfer_cycles: access_bytes.div_ceil(config.bandwidth_bytes_per_cycle.max(1)),
+        }
+    }
+
+    /// Worst-case cycles an access waits before it is granted the bus
+    ///
+    /// Round-robin depends on the cores actually contending; a TDMA frame
+    /// has one slot per core whether it is used or not.
+    pub fn worst_case_wait(&self, num_cores: usize, contending_cores: usize) -> u64 {
+        let transfer = self.transfer_cycles as u64;
+        match self.arbitration {
+            BusArbitration::RoundRobin => contending_cores.saturating_sub(1) as u64 * transfer,
+            BusArbitration::Tdma { slot_cycles } => {
+                if num_cores <= 1 {
+                    return 0;
+                }
+                // Request arrives too late in the own slot to complete
+                (num_cores - 1) as u64 * slot_cycles as u64 + transfer
+            }
+        }
+    }
+
+    /// Bus contention of each actor given its memory accesses (by actor name)
+    pub fn analyze(
+        &self,
+        num_cores: usize,
+        actors: &[Actor],
+        memory_accesses: &AHashMap<String, u64>,
+    ) -> Vec<ActorBusContention> {
+        let accesses = |actor: &Actor| memory_accesses.get(&actor.name).copied().unwrap_or(0);
+
+        let contending_cores = actors
+            .iter()
+            .filter(|a| accesses(a) > 0)
+            .map(|a| a.core_affinity.unwrap_or(0))
+            .collect::<AHashSet<_>>()
+            .len();
+        let wait = self.worst_case_wait(num_cores, contending_cores);
+
+        actors
+            .iter()
+            .map(|actor| {
+                let memory_accesses = accesses(actor);
+                ActorBusContention {
+                    actor_name: actor.name.clone(),
+                    core_id: actor.core_affinity.unwrap_or(0),
+                    memory_accesses,
+                    wait_cycles_per_access: wait,
+                    contention_cycles: memory_accesses * wait,
+                }
+            })
+            .collect()
+    }
+
+    /// Add each actor's contention penalty to its WCET
+    pub fn inflate(actors: &mut [Actor], contention: &[ActorBusContention]) {
+        for actor in actors.iter_mut() {
+            if let Some(entry) = contention.iter().find(|e| e.actor_name == actor.name) {
+                inflate_wcet(actor, entry.contention_cycles);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor(name: &str, core: usize) -> Actor {
+        Actor::new(
+            name.to_string(),
+            format!("{}_fn", name),
+            1,
+            1000.0,
+            Some(1000.0),
+            Some(core),
+        )
+    }
+
+    #[test]
+    fn test_worst_case_wait() {
+        let config = BusConfig {
+            arbitration: BusArbitration::RoundRobin,
+            bandwidth_bytes_per_cycle: 4,
+        };
+        // 32-byte line over a 4-byte bus
+        let round_robin = BusModel::from_config(&config, 32);
+        assert_eq!(round_robin.transfer_cycles, 8);
+        assert_eq!(round_robin.worst_case_wait(4, 1), 0);
+        assert_eq!(round_robin.worst_case_wait(4, 3), 16);
+
+        let tdma = BusModel {
+            arbitration: BusArbitration::Tdma { slot_cycles: 10 },
+            transfer_cycles: 8,
+        };
+        assert_eq!(tdma.worst_case_wait(1, 1), 0);
+        assert_eq!(tdma.worst_case_wait(4, 1), 38);
+    }
+
+    #[test]
+    fn test_contention_counts_only_memory_accessing_cores() {
+        let actors = vec![actor("dma", 0), actor("filter", 1), actor("idle", 2)];
+        let mut accesses = AHashMap::new();
+        accesses.insert("dma".to_string(), 100);
+        accesses.insert("filter".to_string(), 10);
+
+        let bus = BusModel {
+            arbitration: BusArbitration::RoundRobin,
+            transfer_cycles: 2,
+        };
+        let result = bus.analyze(3, &actors, &accesses);
+
+        // Two contending cores: each access waits for one transfer
+        assert_eq!(result[0].contention_cycles, 200);
+        assert_eq!(result[1].contention_cycles, 20);
+        assert_eq!(result[2].contention_cycles, 0);
+    }
+}
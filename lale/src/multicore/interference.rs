@@ -0,0 +1,231 @@
+//! Shared-cache interference between co-running actors
+//!
+//! Actors on different cores share the last-level cache and evict each
+//! other's lines. When the combined footprint of an actor and its co-runners
+//! exceeds the cache capacity, the displaced part of the actor's footprint is
+//! assumed to be reloaded once per activation, inflating its WCET by one miss
+//! penalty per displaced line.
+
+use crate::async_analysis::Actor;
+use crate::config::types::CacheLevelConfig;
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Shared last-level cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedCache {
+    pub size_bytes: usize,
+    pub line_size_bytes: usize,
+
+    /// Extra cycles to reload a line that was evicted by a co-runner
+    pub miss_penalty_cycles: u32,
+}
+
+/// Interference suffered and caused by one actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorInterference {
+    pub actor_name: String,
+    pub core_id: usize,
+
+    /// Cache lines the actor touches
+    pub footprint_lines: usize,
+
+    /// Lines co-runners can evict from this actor
+    pub displaced_lines: usize,
+
+    /// WCET increase of this actor
+    pub interference_cycles: u64,
+
+    /// WCET increase this actor inflicts on actors of other cores
+    pub contribution_cycles: u64,
+}
+
+/// Shared-cache interference analysis
+pub struct InterferenceAnalyzer {
+    cache: SharedCache,
+}
+
+impl SharedCache {
+    /// Shared cache described by a board cache level (e.g. the L2)
+    pub fn from_config(config: &CacheLevelConfig) -> Self {
+        Self {
+            size_bytes: config.size_kb * 1024,
+            line_size_bytes: config.line_size_bytes,
+            miss_penalty_cycles: config.miss_latency.saturating_sub(config.hit_latency),
+        }
+    }
+
+    /// Number of lines the cache holds
+    pub fn capacity_lines(&self) -> usize {
+        self.size_bytes / self.line_size_bytes.max(1)
+    }
+
+    /// Number of lines needed to hold a footprint
+    pub fn lines_for(&self, footprint_bytes: usize) -> usize {
+        footprint_bytes.div_ceil(self.line_size_bytes.max(1))
+    }
+}
+
+impl InterferenceAnalyzer {
+    /// Create analyzer for a shared cache
+    pub fn new(cache: SharedCache) -> Self {
+        Self { cache }
+    }
+
+    /// Interference of each actor given its footprint in bytes (by actor name)
+    ///
+    /// Actors without a footprint entry touch no shared lines. Actors share
+    /// the cache only with actors pinned to other cores; same-core actors
+    /// don't run concurrently.
+    pub fn analyze(
+        &self,
+        actors: &[Actor],
+        footprints: &AHashMap<String, usize>,
+    ) -> Vec<ActorInterference> {
+        let capacity = self.cache.capacity_lines();
+        let penalty = self.cache.miss_penalty_cycles as u64;
+
+        let lines: Vec<usize> = actors
+            .iter()
+            .map(|a| {
+                self.cache
+                    .lines_for(footprints.get(&a.name).copied().unwrap_or(0))
+            })
+            .collect();
+        let cores: Vec<usize> = actors
+            .iter()
+            .map(|a| a.core_affinity.unwrap_or(0))
+            .collect();
+
+        let mut results: Vec<ActorInterference> = actors
+            .iter()
+            .enumerate()
+            .map(|(i, actor)| ActorInterference {
+                actor_name: actor.name.clone(),
+                core_id: cores[i],
+                footprint_lines: lines[i],
+                displaced_lines: 0,
+                interference_cycles: 0,
+                contribution_cycles: 0,
+            })
+            .collect();
+
+        for victim in 0..actors.len() {
+            let co_runners: Vec<usize> = (0..actors.len())
+                .filter(|&j| cores[j] != cores[victim] && lines[j] > 0)
+                .collect();
+            let co_runner_lines: usize = co_runners.iter().map(|&j| lines[j]).sum();
+
+            let overflow = (lines[victim] + co_runner_lines).saturating_sub(capacity);
+            let displaced = overflow.min(lines[victim]).min(co_runner_lines);
+            if displaced == 0 {
+                continue;
+            }
+
+            let interference = displaced as u64 * penalty;
+            results[victim].displaced_lines = displaced;
+            results[victim].interference_cycles = interference;
+
+            // Attribute the victim's penalty to co-runners by footprint share
+            for &j in &co_runners {
+                results[j].contribution_cycles +=
+                    interference * lines[j] as u64 / co_runner_lines as u64;
+            }
+        }
+
+        results
+    }
+
+    /// Add each actor's interference penalty to its WCET
+    pub fn inflate(actors: &mut [Actor], interference: &[ActorInterference]) {
+        for actor in actors.iter_mut() {
+            if let Some(entry) = interference.iter().find(|e| e.actor_name == actor.name) {
+                inflate_wcet(actor, entry.interference_cycles);
+            }
+        }
+    }
+}
+
+/// Add cycles to an actor's WCET, keeping its cycles-to-time ratio
+pub(crate) fn inflate_wcet(actor: &mut Actor, cycles: u64) {
+    if cycles == 0 {
+        return;
+    }
+    if actor.actor_wcet_cycles > 0 {
+        let us_per_cycle = actor.actor_wcet_us / actor.actor_wcet_cycles as f64;
+        actor.actor_wcet_us += cycles as f64 * us_per_cycle;
+    }
+    actor.actor_wcet_cycles += cycles;
+    for platform_cycles in actor.platform_wcet_cycles.values_mut() {
+        *platform_cycles += cycles;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor(name: &str, core: usize) -> Actor {
+        let mut actor = Actor::new(
+            name.to_string(),
+            format!("{}_fn", name),
+            1,
+            1000.0,
+            Some(1000.0),
+            Some(core),
+        );
+        actor.actor_wcet_cycles = 1000;
+        actor.actor_wcet_us = 10.0;
+        actor
+    }
+
+    fn cache() -> SharedCache {
+        // 8 lines of 32 bytes, 10 cycle reload
+        SharedCache {
+            size_bytes: 256,
+            line_size_bytes: 32,
+            miss_penalty_cycles: 10,
+        }
+    }
+
+    #[test]
+    fn test_no_interference_when_footprints_fit() {
+        let actors = vec![actor("a", 0), actor("b", 1)];
+        let mut footprints = AHashMap::new();
+        footprints.insert("a".to_string(), 128);
+        footprints.insert("b".to_string(), 128);
+
+        let result = InterferenceAnalyzer::new(cache()).analyze(&actors, &footprints);
+
+        assert!(result.iter().all(|r| r.interference_cycles == 0));
+        assert!(result.iter().all(|r| r.contribution_cycles == 0));
+    }
+
+    #[test]
+    fn test_interference_when_footprints_exceed_capacity() {
+        // a: 6 lines on core 0; b: 4 lines, c: 2 lines on core 1
+        let mut actors = vec![actor("a", 0), actor("b", 1), actor("c", 1)];
+        let mut footprints = AHashMap::new();
+        footprints.insert("a".to_string(), 192);
+        footprints.insert("b".to_string(), 128);
+        footprints.insert("c".to_string(), 64);
+
+        let result = InterferenceAnalyzer::new(cache()).analyze(&actors, &footprints);
+
+        // a vs 6 co-runner lines: overflow 4 -> 40 cycles, split 2:1
+        assert_eq!(result[0].displaced_lines, 4);
+        assert_eq!(result[0].interference_cycles, 40);
+        // b vs a: overflow 2; c vs a: fits exactly
+        assert_eq!(result[1].interference_cycles, 20);
+        assert_eq!(result[2].interference_cycles, 0);
+
+        assert_eq!(result[0].contribution_cycles, 20);
+        assert_eq!(result[1].contribution_cycles, 26);
+        assert_eq!(result[2].contribution_cycles, 13);
+
+        InterferenceAnalyzer::inflate(&mut actors, &result);
+        assert_eq!(actors[0].actor_wcet_cycles, 1040);
+        assert!((actors[0].actor_wcet_us - 10.4).abs() < 1e-9);
+        assert_eq!(actors[2].actor_wcet_cycles, 1000);
+    }
+}
@@ -2,8 +2,12 @@
 //!
 //! Provides schedulability analysis for actor systems on multi-core platforms.
 
+pub mod bus;
+pub mod interference;
 pub mod schedulability;
 
+pub use bus::{ActorBusContention, BusModel};
+pub use interference::{ActorInterference, InterferenceAnalyzer, SharedCache};
 pub use schedulability::{
     CoreSchedulabilityResult, DeadlineViolation, MultiCoreResult, MultiCoreScheduler,
 };
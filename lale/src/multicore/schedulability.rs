@@ -1,13 +1,32 @@
 //! Multi-core schedulability analysis for actor systems
 
 use crate::async_analysis::{Actor, SchedulingPolicy};
-use crate::scheduling::{EDFScheduler, RMAScheduler, SchedulabilityResult, Task};
+use crate::multicore::bus::{ActorBusContention, BusModel};
+use crate::multicore::interference::{ActorInterference, InterferenceAnalyzer, SharedCache};
+use crate::platform::PlatformModel;
+use crate::scheduling::{DMScheduler, EDFScheduler, RMAScheduler, SchedulabilityResult, Task};
+use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
 
 /// Multi-core scheduler
 pub struct MultiCoreScheduler {
     pub num_cores: usize,
     pub policy: SchedulingPolicy,
+
+    /// Timing model per core (index = core id)
+    pub core_platforms: Vec<PlatformModel>,
+
+    /// Shared last-level cache, if co-running actors contend for one
+    pub shared_cache: Option<SharedCache>,
+
+    /// Memory footprint in bytes per actor name
+    pub footprints: AHashMap<String, usize>,
+
+    /// Shared memory bus, if cores contend for one
+    pub bus: Option<BusModel>,
+
+    /// Memory accesses per activation per actor name
+    pub memory_accesses: AHashMap<String, u64>,
 }
 
 /// Multi-core schedulability result
@@ -24,16 +43,36 @@ pub struct MultiCoreResult {
 
     /// Per-core utilization
     pub core_utilizations: Vec<f64>,
+
+    /// Per-actor shared-cache interference (empty without a shared cache)
+    #[serde(default)]
+    pub interference: Vec<ActorInterference>,
+
+    /// Per-actor memory-bus contention (empty without a bus model)
+    #[serde(default)]
+    pub bus_contention: Vec<ActorBusContention>,
 }
 
 /// Per-core schedulability result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreSchedulabilityResult {
     pub core_id: usize,
+    #[serde(default)]
+    pub core_platform: String,
+    #[serde(default)]
+    pub cpu_frequency_mhz: u32,
     pub schedulable: bool,
     pub utilization: f64,
     pub actors: Vec<String>,
+
+    /// WCET of each actor on this core, in the order of `actors`
+    #[serde(default)]
+    pub actor_wcets_us: Vec<f64>,
     pub violations: Vec<DeadlineViolation>,
+
+    /// Aperiodic actors whose arrivals can't be bounded on this core
+    #[serde(default)]
+    pub unbounded_arrivals: Vec<String>,
 }
 
 /// Deadline violation
@@ -48,11 +87,83 @@ pub struct DeadlineViolation {
 impl MultiCoreScheduler {
     /// Create new multi-core scheduler
     pub fn new(num_cores: usize, policy: SchedulingPolicy) -> Self {
-        Self { num_cores, policy }
+        Self {
+            num_cores,
+            policy,
+            core_platforms: vec![],
+            shared_cache: None,
+            footprints: AHashMap::new(),
+            bus: None,
+            memory_accesses: AHashMap::new(),
+        }
+    }
+
+    /// Create a scheduler for cores with their own timing models
+    ///
+    /// One core per platform, e.g. a Cortex-A53 next to a Cortex-M4 on a
+    /// big.LITTLE board.
+    pub fn heterogeneous(platforms: Vec<PlatformModel>, policy: SchedulingPolicy) -> Self {
+        Self::new(platforms.len(), policy).with_core_platforms(platforms)
+    }
+
+    /// Set the timing model used for each core
+    ///
+    /// Actor WCETs are re-costed in the model of the core they are assigned
+    /// to: its cycles if the actor was analyzed on that platform, at its
+    /// frequency. Cores without an entry keep the actors' own WCETs.
+    pub fn with_core_platforms(mut self, platforms: Vec<PlatformModel>) -> Self {
+        self.core_platforms = platforms;
+        self
+    }
+
+    /// Account for interference on a shared cache
+    ///
+    /// Actor WCETs are inflated by the cost of reloading lines evicted by
+    /// actors on other cores before the per-core analysis.
+    pub fn with_shared_cache(
+        mut self,
+        cache: SharedCache,
+        footprints: AHashMap<String, usize>,
+    ) -> Self {
+        self.shared_cache = Some(cache);
+        self.footprints = footprints;
+        self
+    }
+
+    /// Account for contention on a shared memory bus
+    ///
+    /// Every memory access of an actor is delayed by the worst-case bus
+    /// arbitration wait before the per-core analysis.
+    pub fn with_bus(mut self, bus: BusModel, memory_accesses: AHashMap<String, u64>) -> Self {
+        self.bus = Some(bus);
+        self.memory_accesses = memory_accesses;
+        self
     }
 
     /// Analyze schedulability for actor system
     pub fn analyze(&self, actors: &[Actor]) -> MultiCoreResult {
+        let mut inflated = actors.to_vec();
+
+        let interference = match &self.shared_cache {
+            Some(cache) => {
+                let interference =
+                    InterferenceAnalyzer::new(cache.clone()).analyze(actors, &self.footprints);
+                InterferenceAnalyzer::inflate(&mut inflated, &interference);
+                interference
+            }
+            None => vec![],
+        };
+
+        let bus_contention = match &self.bus {
+            Some(bus) => {
+                let contention = bus.analyze(self.num_cores, actors, &self.memory_accesses);
+                BusModel::inflate(&mut inflated, &contention);
+                contention
+            }
+            None => vec![],
+        };
+        let actors = inflated.as_slice();
+
         // Partition actors by core affinity
         let partitions = self.partition_actors(actors);
 
@@ -71,13 +182,15 @@ impl MultiCoreScheduler {
             per_core.push(result);
         }
 
-        let total_utilization = actors.iter().map(|a| a.utilization()).sum();
+        let total_utilization = core_utilizations.iter().sum();
 
         MultiCoreResult {
             per_core,
             overall_schedulable,
             total_utilization,
             core_utilizations,
+            interference,
+            bus_contention,
         }
     }
 
@@ -95,28 +208,71 @@ impl MultiCoreScheduler {
 
     /// Analyze single core
     fn analyze_core(&self, core_id: usize, actors: &[&Actor]) -> CoreSchedulabilityResult {
+        let platform = self.core_platforms.get(core_id);
+        let core_platform = platform.map(|p| p.name.clone()).unwrap_or_default();
+        let cpu_frequency_mhz = platform.map(|p| p.cpu_frequency_mhz).unwrap_or(0);
+
         if actors.is_empty() {
             return CoreSchedulabilityResult {
                 core_id,
+                core_platform,
+                cpu_frequency_mhz,
                 schedulable: true,
                 utilization: 0.0,
                 actors: vec![],
+                actor_wcets_us: vec![],
                 violations: vec![],
+                unbounded_arrivals: vec![],
             };
         }
 
-        // Convert actors to tasks
-        let tasks: Vec<_> = actors.iter().map(|a| a.to_task()).collect();
+        // Convert actors to tasks, re-costed in this core's timing model
+        let tasks: Vec<Task> = actors
+            .iter()
+            .map(|a| {
+                let mut task = a.to_task();
+                if let Some(platform) = platform {
+                    task.wcet_cycles = a.wcet_cycles_on(&platform.name);
+                    if platform.cpu_frequency_mhz > 0 {
+                        task.wcet_us = platform.cycles_to_us(task.wcet_cycles);
+                    }
+                }
+                task
+            })
+            .collect();
 
         // Perform schedulability analysis
         let result = match self.policy {
             SchedulingPolicy::RMA => RMAScheduler::schedulability_test(&tasks),
             SchedulingPolicy::EDF => EDFScheduler::schedulability_test(&tasks),
+            SchedulingPolicy::DM => DMScheduler::schedulability_test(&tasks),
         };
 
         // Check if schedulable and extract violations
+        let mut unbounded_arrivals = vec![];
         let (schedulable, violations) = match result {
             SchedulabilityResult::Schedulable => (true, vec![]),
+            SchedulabilityResult::UnboundedArrivals { tasks } => {
+                unbounded_arrivals = tasks;
+                (false, vec![])
+            }
+            SchedulabilityResult::InfeasibleDeadlines { tasks: infeasible } => {
+                // Running alone, the response time is at least the WCET
+                let violations = tasks
+                    .iter()
+                    .filter(|t| infeasible.contains(&t.name))
+                    .map(|t| {
+                        let deadline = t.deadline_us.or(t.arrival_period_us()).unwrap_or(0.0);
+                        DeadlineViolation {
+                            actor_name: t.name.clone(),
+                            response_time_us: t.wcet_us,
+                            deadline_us: deadline,
+                            slack_us: deadline - t.wcet_us,
+                        }
+                    })
+                    .collect();
+                (false, violations)
+            }
             SchedulabilityResult::Unschedulable {
                 failing_task,
                 response_time,
@@ -132,14 +288,18 @@ impl MultiCoreScheduler {
             }
         };
 
-        let utilization = actors.iter().map(|a| a.utilization()).sum();
+        let utilization = RMAScheduler::calculate_utilization(&tasks);
 
         CoreSchedulabilityResult {
             core_id,
+            core_platform,
+            cpu_frequency_mhz,
             schedulable,
             utilization,
             actors: actors.iter().map(|a| a.name.clone()).collect(),
+            actor_wcets_us: tasks.iter().map(|t| t.wcet_us).collect(),
             violations,
+            unbounded_arrivals,
         }
     }
 }
@@ -188,4 +348,114 @@ mod tests {
         assert_eq!(result.total_utilization, 0.0);
         assert_eq!(result.per_core.len(), 2);
     }
+
+    #[test]
+    fn test_core_platform_recorded() {
+        let mut platform = crate::platform::CortexM4Model::new();
+        platform.cpu_frequency_mhz = 100;
+
+        let mut actor = Actor::new(
+            "sensor".to_string(),
+            "sensor_fn".to_string(),
+            1,
+            1000.0,
+            Some(1000.0),
+            Some(0),
+        );
+        actor.actor_wcet_cycles = 10_000;
+        actor.actor_wcet_us = 1.0;
+
+        let scheduler =
+            MultiCoreScheduler::new(1, SchedulingPolicy::RMA).with_core_platforms(vec![platform]);
+        let result = scheduler.analyze(&[actor]);
+
+        let core = &result.per_core[0];
+        assert_eq!(core.core_platform, "ARM Cortex-M4");
+        assert_eq!(core.cpu_frequency_mhz, 100);
+        // 10_000 cycles at 100 MHz = 100us over a 1000us period
+        assert!((core.utilization - 0.1).abs() < 1e-9);
+        assert!((result.total_utilization - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_heterogeneous_cores_recost_actor() {
+        let mut fast = crate::platform::CortexA53Model::new();
+        fast.cpu_frequency_mhz = 1000;
+        let mut slow = crate::platform::CortexM4Model::new();
+        slow.cpu_frequency_mhz = 100;
+
+        // The same actor pinned to each core, analyzed on both models
+        let actors: Vec<Actor> = [("control_big", 0), ("control_little", 1)]
+            .into_iter()
+            .map(|(name, core)| {
+                let mut actor = Actor::new(
+                    name.to_string(),
+                    "control_fn".to_string(),
+                    1,
+                    1000.0,
+                    Some(1000.0),
+                    Some(core),
+                );
+                actor.actor_wcet_cycles = 20_000;
+                actor.actor_wcet_us = 20.0;
+                actor.platform_wcet_cycles.insert(fast.name.clone(), 20_000);
+                actor.platform_wcet_cycles.insert(slow.name.clone(), 30_000);
+                actor
+            })
+            .collect();
+
+        let scheduler = MultiCoreScheduler::heterogeneous(vec![fast, slow], SchedulingPolicy::RMA);
+        assert_eq!(scheduler.num_cores, 2);
+        let result = scheduler.analyze(&actors);
+
+        let big = &result.per_core[0];
+        let little = &result.per_core[1];
+        assert_eq!(big.core_platform, "ARM Cortex-A53");
+        assert_eq!(big.cpu_frequency_mhz, 1000);
+        assert_eq!(little.core_platform, "ARM Cortex-M4");
+        assert_eq!(little.cpu_frequency_mhz, 100);
+
+        // 20_000 cycles at 1000 MHz against 30_000 cycles at 100 MHz
+        assert!((big.actor_wcets_us[0] - 20.0).abs() < 1e-9);
+        assert!((little.actor_wcets_us[0] - 300.0).abs() < 1e-9);
+        assert!((result.total_utilization - 0.32).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shared_cache_interference_inflates_utilization() {
+        let mut actors = Vec::new();
+        for (name, core) in [("producer", 0), ("consumer", 1)] {
+            let mut actor = Actor::new(
+                name.to_string(),
+                format!("{}_fn", name),
+                1,
+                1000.0,
+                Some(1000.0),
+                Some(core),
+            );
+            actor.actor_wcet_cycles = 100;
+            actor.actor_wcet_us = 100.0;
+            actors.push(actor);
+        }
+
+        let mut footprints = AHashMap::new();
+        footprints.insert("producer".to_string(), 1024);
+        footprints.insert("consumer".to_string(), 1024);
+
+        // 16 lines of 64 bytes; each actor alone fills the cache
+        let cache = SharedCache {
+            size_bytes: 1024,
+            line_size_bytes: 64,
+            miss_penalty_cycles: 5,
+        };
+        let result = MultiCoreScheduler::new(2, SchedulingPolicy::RMA)
+            .with_shared_cache(cache, footprints)
+            .analyze(&actors);
+
+        assert_eq!(result.interference.len(), 2);
+        assert_eq!(result.interference[0].interference_cycles, 80);
+        assert_eq!(result.interference[0].contribution_cycles, 80);
+        // (100 + 80) us over a 1000 us period
+        assert!((result.core_utilizations[0] - 0.18).abs() < 1e-9);
+    }
 }
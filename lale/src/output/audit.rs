@@ -0,0 +1,262 @@
+//! Audit log of analysis decisions
+//!
+//! Records the assumptions behind each WCET bound, one JSON object per
+//! line, as traceability evidence for certification: where every loop bound
+//! came from, how memory accesses were classified, what was assumed for
+//! calls into code without a body or through function pointers and how the
+//! function bound was obtained.
+
+use crate::analysis::timing::InstructionClass;
+use crate::analysis::{CacheAssumption, LoopKind};
+use crate::analyzers::{
+    Confidence, FunctionAnalysisResult, IndirectCallBound, LoopBoundSource, TargetSource,
+};
+use crate::ir::CallGraph;
+use crate::platform::PlatformModel;
+use inkwell::values::{FunctionValue, InstructionOpcode};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// One analysis decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "kebab-case")]
+pub enum AuditEvent {
+    /// How the function's WCET bound was obtained
+    FunctionBound {
+        function: String,
+        /// "ipet" or "approximate"
+        method: String,
+        wcet_cycles: u64,
+    },
+
+    /// Where a loop's iteration bound came from
+    LoopBound {
+        function: String,
+        header: String,
+        source: LoopBoundSource,
+        max_iterations: Option<u64>,
+        /// What the loop's exit condition depends on
+        kind: LoopKind,
+        /// Heuristic bound before it was clamped
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clamped_from: Option<u64>,
+    },
+
+    /// Cache classification assumed for the memory accesses of a block
+    CacheClassification {
+        function: String,
+        block: String,
+        accesses: usize,
+        classification: String,
+    },
+
+    /// Cost assumed for a call to a function without a body
+    ExternalCall {
+        caller: String,
+        callee: String,
+        block: String,
+        assumed_cycles: u64,
+    },
+
+    /// Branch on `undef` or `poison`, costed with both successors feasible
+    PoisonBranch { function: String, block: String },
+
+    /// Targets assumed for a call through a function pointer
+    IndirectCall {
+        caller: String,
+        block: String,
+        targets: Vec<String>,
+        source: TargetSource,
+        /// WCET of the worst target, absent if the call is not soundly bounded
+        assumed_cycles: Option<u64>,
+    },
+}
+
+/// JSONL audit log writer
+pub struct AuditLog<W: Write> {
+    writer: W,
+}
+
+impl AuditLog<BufWriter<File>> {
+    /// Create (or truncate) an audit log file
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create audit log {}: {}", path.display(), e))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> AuditLog<W> {
+    /// Audit log writing to any writer
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Append one decision
+    pub fn record(&mut self, event: &AuditEvent) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, event)
+            .map_err(|e| format!("Failed to write audit event: {}", e))?;
+        writeln!(self.writer).map_err(|e| format!("Failed to write audit event: {}", e))
+    }
+
+    /// Append several decisions
+    pub fn record_all(&mut self, events: &[AuditEvent]) -> Result<(), String> {
+        events.iter().try_for_each(|event| self.record(event))
+    }
+
+    /// Flush buffered events and return the writer
+    pub fn finish(mut self) -> Result<W, String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush audit log: {}", e))?;
+        Ok(self.writer)
+    }
+}
+
+/// Decisions taken while analyzing one function
+///
+/// The timing model has no cache state, so every load and store is
+/// classified by the blanket cache assumption: always-miss when cold (the
+/// default), always-hit when warm.
+pub fn function_events(
+    function: &FunctionValue,
+    result: &FunctionAnalysisResult,
+) -> Vec<AuditEvent> {
+    let name = &result.function_name;
+    let mut events = vec![AuditEvent::FunctionBound {
+        function: name.clone(),
+        method: match result.confidence {
+            Confidence::Ipet => "ipet",
+            Confidence::Approximate => "approximate",
+        }
+        .to_string(),
+        wcet_cycles: result.wcet_cycles,
+    }];
+
+    events.extend(
+        result
+            .loop_bound_decisions
+            .iter()
+            .map(|decision| AuditEvent::LoopBound {
+                function: name.clone(),
+                header: decision.header.clone(),
+                source: decision.source,
+                max_iterations: decision.max_iterations,
+                kind: decision.kind,
+                clamped_from: decision.clamped_from,
+            }),
+    );
+
+    events.extend(
+        result
+            .poison_branches
+            .iter()
+            .map(|block| AuditEvent::PoisonBranch {
+                function: name.clone(),
+                block: block.clone(),
+            }),
+    );
+
+    for block in function.get_basic_blocks() {
+        let mut accesses = 0;
+        let mut instr_iter = block.get_first_instruction();
+        while let Some(instr) = instr_iter {
+            if matches!(
+                instr.get_opcode(),
+                InstructionOpcode::Load | InstructionOpcode::Store
+            ) {
+                accesses += 1;
+            }
+            instr_iter = instr.get_next_instruction();
+        }
+
+        if accesses > 0 {
+            events.push(AuditEvent::CacheClassification {
+                function: name.clone(),
+                block: block.get_name().to_str().unwrap_or("").to_string(),
+                accesses,
+                classification: match result.cache_assumption {
+                    CacheAssumption::Cold => "always-miss",
+                    CacheAssumption::Warm => "always-hit",
+                }
+                .to_string(),
+            });
+        }
+    }
+
+    events
+}
+
+/// Calls into functions without a body, costed as the call instruction alone
+pub fn external_call_events(call_graph: &CallGraph, platform: &PlatformModel) -> Vec<AuditEvent> {
+    let call_cycles = platform.get_timing(&InstructionClass::Call).worst_case as u64;
+
+    call_graph
+        .call_sites
+        .iter()
+        .filter(|site| !call_graph.is_defined(&site.callee))
+        .map(|site| AuditEvent::ExternalCall {
+            caller: site.caller.clone(),
+            callee: site.callee.clone(),
+            block: site.block.clone(),
+            assumed_cycles: call_cycles,
+        })
+        .collect()
+}
+
+/// Indirect calls, costed as their worst candidate target
+pub fn indirect_call_events(bounds: &[IndirectCallBound]) -> Vec<AuditEvent> {
+    bounds
+        .iter()
+        .flat_map(|bound| &bound.sites)
+        .map(|site| AuditEvent::IndirectCall {
+            caller: site.caller.clone(),
+            block: site.block.clone(),
+            targets: site.targets.clone(),
+            source: site.source,
+            assumed_cycles: site.wcet_cycles,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log_writes_one_event_per_line() {
+        let events = vec![
+            AuditEvent::LoopBound {
+                function: "process".to_string(),
+                header: "for.body".to_string(),
+                source: LoopBoundSource::Heuristic,
+                max_iterations: Some(100),
+                kind: LoopKind::DataDependent,
+                clamped_from: None,
+            },
+            AuditEvent::ExternalCall {
+                caller: "main".to_string(),
+                callee: "memcpy".to_string(),
+                block: "entry".to_string(),
+                assumed_cycles: 3,
+            },
+        ];
+
+        let mut log = AuditLog::new(Vec::new());
+        log.record_all(&events).unwrap();
+        let written = String::from_utf8(log.finish().unwrap()).unwrap();
+
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""decision":"loop-bound""#));
+        assert!(lines[0].contains(r#""source":"heuristic""#));
+
+        let parsed: Vec<AuditEvent> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed, events);
+    }
+}
@@ -0,0 +1,166 @@
+//! Colored terminal output
+//!
+//! Every colored line goes through a [`Palette`], so one setting decides
+//! whether escape codes are written. With [`ColorChoice::Auto`] a stream
+//! is colored only when it is a terminal and `NO_COLOR` is unset or empty
+//! (<https://no-color.org>); `always` and `never` override both.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Environment variable that turns off automatic coloring
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// Choice made once per process by [`set_color_choice`]
+static COLOR_CHOICE: OnceLock<ColorChoice> = OnceLock::new();
+
+/// When to color terminal output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color terminals, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    /// Color even when writing to a file or pipe
+    Always,
+    /// Never write escape codes
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether a stream gets colors, given if it is a terminal and the
+    /// value of `NO_COLOR`
+    pub fn enabled(self, is_terminal: bool, no_color: Option<&str>) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => is_terminal && no_color.is_none_or(str::is_empty),
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            other => Err(format!(
+                "Unknown color choice '{}', expected auto, always or never",
+                other
+            )),
+        }
+    }
+}
+
+/// Set the process-wide color choice; only the first call has an effect
+pub fn set_color_choice(choice: ColorChoice) {
+    let _ = COLOR_CHOICE.set(choice);
+}
+
+/// The process-wide color choice, [`ColorChoice::Auto`] if never set
+pub fn color_choice() -> ColorChoice {
+    COLOR_CHOICE.get().copied().unwrap_or_default()
+}
+
+/// Color of a piece of output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Red => "31",
+            Self::Green => "32",
+            Self::Yellow => "33",
+        }
+    }
+}
+
+/// Colors text for one output stream, or leaves it plain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Palette for standard output under the process-wide choice
+    pub fn stdout() -> Self {
+        Self::for_stream(std::io::stdout().is_terminal())
+    }
+
+    /// Palette for standard error under the process-wide choice
+    pub fn stderr() -> Self {
+        Self::for_stream(std::io::stderr().is_terminal())
+    }
+
+    fn for_stream(is_terminal: bool) -> Self {
+        let no_color = std::env::var(NO_COLOR_ENV).ok();
+        Self::new(color_choice().enabled(is_terminal, no_color.as_deref()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// `text` in `color`, plain if colors are off
+    pub fn paint(&self, text: impl Display, color: Color) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Something that passed, e.g. a schedulable task set
+    pub fn success(&self, text: impl Display) -> String {
+        self.paint(text, Color::Green)
+    }
+
+    /// Something that failed
+    pub fn failure(&self, text: impl Display) -> String {
+        self.paint(text, Color::Red)
+    }
+
+    /// Something that passed but deserves a look
+    pub fn warning(&self, text: impl Display) -> String {
+        self.paint(text, Color::Yellow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choice_honors_terminal_and_no_color() {
+        assert!(ColorChoice::Auto.enabled(true, None));
+        assert!(ColorChoice::Auto.enabled(true, Some("")));
+        assert!(!ColorChoice::Auto.enabled(true, Some("1")));
+        assert!(!ColorChoice::Auto.enabled(false, None));
+
+        // An explicit choice wins over both
+        assert!(ColorChoice::Always.enabled(false, Some("1")));
+        assert!(!ColorChoice::Never.enabled(true, None));
+
+        assert_eq!("always".parse(), Ok(ColorChoice::Always));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+
+    #[test]
+    fn test_paint_only_when_enabled() {
+        assert_eq!(Palette::new(false).failure("✗"), "✗");
+        assert_eq!(Palette::new(true).success("✓"), "\x1b[32m✓\x1b[0m");
+    }
+}
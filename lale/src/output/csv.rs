@@ -0,0 +1,122 @@
+//! Per-function WCET results as CSV
+//!
+//! One row per function, sorted by name so results of two commits can be
+//! diffed line by line or side by side in a spreadsheet.
+
+use crate::ir::demangle;
+use crate::output::json::FunctionWCET;
+use crate::output::AnalysisReport;
+
+/// Columns of the CSV, in order
+pub const CSV_HEADER: [&str; 6] = [
+    "name",
+    "demangled_name",
+    "wcet_cycles",
+    "wcet_us",
+    "bcet_cycles",
+    "loop_count",
+];
+
+/// CSV output generator
+pub struct CsvOutput;
+
+impl CsvOutput {
+    /// The report's function results, with a header row
+    pub fn to_csv(report: &AnalysisReport) -> String {
+        Self::functions_to_csv(&report.wcet_analysis.functions)
+    }
+
+    /// Function results, with a header row
+    pub fn functions_to_csv(functions: &[FunctionWCET]) -> String {
+        let mut sorted: Vec<&FunctionWCET> = functions.iter().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut csv = CSV_HEADER.join(",");
+        csv.push('\n');
+        for function in sorted {
+            let row = [
+                escape(&function.name),
+                escape(&demangle(&function.name)),
+                function.wcet_cycles.to_string(),
+                function.wcet_us.to_string(),
+                function.bcet_cycles.to_string(),
+                function.loop_count.to_string(),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// A field, quoted if it contains a separator, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_analysis::SchedulingPolicy;
+    use crate::output::JSONOutput;
+    use crate::scheduling::rma::SchedulabilityResult;
+    use ahash::AHashMap;
+
+    /// Fields of each line, undoing the quoting of [`escape`]
+    fn parse(csv: &str) -> Vec<Vec<String>> {
+        csv.lines()
+            .map(|line| {
+                let mut fields = vec![String::new()];
+                let mut quoted = false;
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' if quoted && chars.peek() == Some(&'"') => {
+                            chars.next();
+                            fields.last_mut().unwrap().push('"');
+                        }
+                        '"' => quoted = !quoted,
+                        ',' if !quoted => fields.push(String::new()),
+                        _ => fields.last_mut().unwrap().push(c),
+                    }
+                }
+                fields
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let wcets: AHashMap<String, u64> = [
+            ("sensor_filter".to_string(), 1200),
+            ("_ZN4demo6filter17h0123456789abcdefE".to_string(), 800),
+            ("<impl Read for Spi, \"bus\">::read".to_string(), 96),
+        ]
+        .into();
+        let report = JSONOutput::generate_report(
+            &wcets,
+            &[],
+            &SchedulabilityResult::Schedulable,
+            SchedulingPolicy::RMA,
+            None,
+            "cortex-m4",
+            100,
+        );
+
+        let rows = parse(&CsvOutput::to_csv(&report));
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(rows.len() - 1, report.wcet_analysis.functions.len());
+        assert!(rows.iter().all(|row| row.len() == CSV_HEADER.len()));
+
+        // Names with commas and quotes survive, and rows are sorted by name
+        assert_eq!(rows[1][0], "<impl Read for Spi, \"bus\">::read");
+        assert_eq!(rows[2][1], "demo::filter");
+        assert_eq!(rows[3][0], "sensor_filter");
+        assert_eq!(rows[3][2], "1200");
+        assert_eq!(rows[3][3], "12");
+    }
+}
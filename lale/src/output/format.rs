@@ -0,0 +1,221 @@
+//! Pluggable report formats
+//!
+//! The schedule command renders its [`AnalysisReport`] through a format
+//! picked by name with `--format`. Library users can register their own
+//! formats, e.g. an in-house XML schema, next to the built-in ones.
+
+use crate::output::{AnalysisReport, CsvOutput, GanttOutput, JSONOutput};
+
+/// Renders an analysis report as text
+pub trait OutputFormat: Send + Sync {
+    /// Name the format is selected by
+    fn name(&self) -> &str;
+
+    /// Render a report
+    fn render(&self, report: &AnalysisReport) -> Result<String, String>;
+}
+
+/// Complete report as pretty-printed JSON
+pub struct JsonFormat;
+
+impl OutputFormat for JsonFormat {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+        JSONOutput::to_json(report).map_err(|e| format!("Failed to render JSON: {}", e))
+    }
+}
+
+/// One row per function, for spreadsheets
+pub struct CsvFormat;
+
+impl OutputFormat for CsvFormat {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+        Ok(CsvOutput::to_csv(report))
+    }
+}
+
+/// Gantt chart data of the report's static schedule
+pub struct GanttFormat;
+
+impl OutputFormat for GanttFormat {
+    fn name(&self) -> &str {
+        "gantt"
+    }
+
+    fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+        let schedule = report
+            .schedule
+            .as_ref()
+            .ok_or_else(|| "Report has no schedule to chart".to_string())?;
+        GanttOutput::to_json(&GanttOutput::generate_gantt_data(schedule))
+            .map_err(|e| format!("Failed to render Gantt data: {}", e))
+    }
+}
+
+/// Static schedule in the Chrome Trace Event Format
+pub struct ChromeTraceFormat;
+
+impl OutputFormat for ChromeTraceFormat {
+    fn name(&self) -> &str {
+        "chrome-trace"
+    }
+
+    fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+        let schedule = report
+            .schedule
+            .as_ref()
+            .ok_or_else(|| "Report has no schedule to trace".to_string())?;
+        Ok(GanttOutput::to_chrome_trace(schedule))
+    }
+}
+
+/// Static schedule as a standalone SVG Gantt chart
+pub struct GanttSvgFormat;
+
+/// Width of the chart rendered by [`GanttSvgFormat`]
+const GANTT_SVG_WIDTH_PX: u32 = 1200;
+
+impl OutputFormat for GanttSvgFormat {
+    fn name(&self) -> &str {
+        "gantt-svg"
+    }
+
+    fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+        let schedule = report
+            .schedule
+            .as_ref()
+            .ok_or_else(|| "Report has no schedule to chart".to_string())?;
+        Ok(GanttOutput::to_svg(
+            schedule,
+            &report.task_model.tasks,
+            GANTT_SVG_WIDTH_PX,
+        ))
+    }
+}
+
+/// Output formats by name
+pub struct OutputFormatRegistry {
+    formats: Vec<Box<dyn OutputFormat>>,
+}
+
+impl OutputFormatRegistry {
+    /// Registry with the built-in formats
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(JsonFormat));
+        registry.register(Box::new(CsvFormat));
+        registry.register(Box::new(GanttFormat));
+        registry.register(Box::new(ChromeTraceFormat));
+        registry.register(Box::new(GanttSvgFormat));
+        registry
+    }
+
+    /// Registry without any formats
+    pub fn empty() -> Self {
+        Self {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Add a format, replacing any format of the same name
+    pub fn register(&mut self, format: Box<dyn OutputFormat>) {
+        self.formats
+            .retain(|existing| existing.name() != format.name());
+        self.formats.push(format);
+    }
+
+    /// Look up a format by name
+    pub fn get(&self, name: &str) -> Option<&dyn OutputFormat> {
+        self.formats
+            .iter()
+            .find(|format| format.name() == name)
+            .map(|format| format.as_ref())
+    }
+
+    /// Names of all registered formats, in registration order
+    pub fn names(&self) -> Vec<&str> {
+        self.formats.iter().map(|format| format.name()).collect()
+    }
+
+    /// Render a report with the named format
+    pub fn render(&self, name: &str, report: &AnalysisReport) -> Result<String, String> {
+        let format = self.get(name).ok_or_else(|| {
+            format!(
+                "Unknown output format '{}' (available: {})",
+                name,
+                self.names().join(", ")
+            )
+        })?;
+        format.render(report)
+    }
+}
+
+impl Default for OutputFormatRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_analysis::SchedulingPolicy;
+    use crate::scheduling::rma::SchedulabilityResult;
+    use ahash::AHashMap;
+
+    struct TaskCount;
+
+    impl OutputFormat for TaskCount {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn render(&self, report: &AnalysisReport) -> Result<String, String> {
+            Ok(report.task_model.tasks.len().to_string())
+        }
+    }
+
+    fn report() -> AnalysisReport {
+        JSONOutput::generate_report(
+            &AHashMap::new(),
+            &[],
+            &SchedulabilityResult::Schedulable,
+            SchedulingPolicy::RMA,
+            None,
+            "ARM Cortex-M4",
+            168,
+        )
+    }
+
+    #[test]
+    fn test_registry_dispatches_to_registered_format() {
+        let mut registry = OutputFormatRegistry::new();
+        registry.register(Box::new(TaskCount));
+        assert_eq!(
+            registry.names(),
+            vec!["json", "csv", "gantt", "chrome-trace", "gantt-svg", "count"]
+        );
+
+        let report = report();
+        assert_eq!(registry.render("count", &report).unwrap(), "0");
+        assert!(registry
+            .render("json", &report)
+            .unwrap()
+            .contains("ARM Cortex-M4"));
+
+        // No schedule in the report, nothing to chart
+        assert!(registry.render("gantt", &report).is_err());
+        assert!(registry.render("chrome-trace", &report).is_err());
+        assert!(registry.render("gantt-svg", &report).is_err());
+
+        let unknown = registry.render("xml", &report).unwrap_err();
+        assert!(unknown.contains("json, csv, gantt, chrome-trace, gantt-svg, count"));
+    }
+}
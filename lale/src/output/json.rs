@@ -1,34 +1,129 @@
-use crate::scheduling::rma::SchedulabilityResult;
-use crate::scheduling::{static_gen::ScheduleTimeline, Task};
-use ahash::AHashMap;
+use crate::analysis::AnalysisWarning;
+use crate::analyzers::{locate_hotspots, BlockHotspot};
+use crate::async_analysis::SchedulingPolicy;
+use crate::ir::DebugInfo;
+use crate::scheduling::dm::DMScheduler;
+use crate::scheduling::rma::{RMAScheduler, SchedulabilityResult};
+use crate::scheduling::static_gen::{ScheduleTimeline, StaticScheduleGenerator};
+use crate::scheduling::{relative_wcets, typical_utilization, Task};
+use ahash::{AHashMap, AHashSet};
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 
 /// Complete analysis report
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalysisReport {
     pub analysis_info: AnalysisInfo,
     pub wcet_analysis: WCETAnalysis,
     pub task_model: TaskModel,
     pub schedulability: SchedulabilityAnalysis,
     pub schedule: Option<ScheduleTimeline>,
+
+    /// Blocks contributing most to the WCET across all functions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub module_hotspots: Vec<BlockHotspot>,
+
+    /// Caveats of the analysis, e.g. inputs that failed to parse
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+impl AnalysisReport {
+    /// Record a safety margin the reported WCETs already include
+    ///
+    /// `raw_wcets` maps function names to their WCET before the margin.
+    pub fn record_margin(&mut self, margin_percent: f64, raw_wcets: &AHashMap<String, u64>) {
+        self.analysis_info.margin_percent = Some(margin_percent);
+        for function in &mut self.wcet_analysis.functions {
+            function.raw_wcet_cycles = raw_wcets.get(&function.name).copied();
+        }
+    }
+
+    /// Record the BCET of each function, in place of the placeholder half
+    /// of its WCET
+    ///
+    /// `bcets` maps function names to their BCET in cycles; functions it
+    /// doesn't list keep the placeholder.
+    pub fn record_bcets(&mut self, bcets: &AHashMap<String, u64>, cpu_freq_mhz: u32) {
+        for function in &mut self.wcet_analysis.functions {
+            if let Some(&bcet_cycles) = bcets.get(&function.name) {
+                function.bcet_cycles = bcet_cycles;
+                function.bcet_us = bcet_cycles as f64 / cpu_freq_mhz as f64;
+            }
+        }
+    }
+
+    /// Record where each function and hotspot is in the source
+    pub fn record_source_locations(&mut self, debug_info: &DebugInfo) {
+        for function in &mut self.wcet_analysis.functions {
+            let location = debug_info.function_location(&function.name);
+            function.source_file = location.map(|location| location.file.clone());
+            function.source_line = location.map(|location| location.line);
+        }
+        locate_hotspots(&mut self.module_hotspots, debug_info);
+    }
 }
 
 /// Analysis metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalysisInfo {
     pub tool: String,
     pub version: String,
     pub timestamp: String,
     pub platform: String,
+
+    /// Safety margin applied to every WCET, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margin_percent: Option<f64>,
 }
 
 /// WCET analysis results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WCETAnalysis {
     pub functions: Vec<FunctionWCET>,
+
+    /// Functions whose IPET solve timed out (no WCET available)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timed_out: Vec<String>,
+}
+
+impl WCETAnalysis {
+    /// Drop functions identical to an earlier entry, keeping the first
+    ///
+    /// Returns the names of functions that appear more than once with
+    /// different results; all their entries are kept for the caller to
+    /// resolve.
+    pub fn dedup_functions(&mut self) -> Vec<String> {
+        let mut seen = AHashSet::new();
+        self.functions
+            .retain(|function| seen.insert(function.clone()));
+
+        let mut conflicts: Vec<String> = Vec::new();
+        for (i, function) in self.functions.iter().enumerate() {
+            let conflicting = self.functions[i + 1..]
+                .iter()
+                .any(|other| function.conflicts_with(other));
+            if conflicting && !conflicts.contains(&function.name) {
+                conflicts.push(function.name.clone());
+            }
+        }
+        conflicts
+    }
+
+    /// Whether both analyses report the same functions, in any order and
+    /// ignoring exact duplicates
+    pub fn same_functions(&self, other: &WCETAnalysis) -> bool {
+        let mine: AHashSet<&FunctionWCET> = self.functions.iter().collect();
+        let theirs: AHashSet<&FunctionWCET> = other.functions.iter().collect();
+        mine == theirs
+    }
 }
 
 /// WCET for a single function
+///
+/// Equality and hashing compare every field, with floats compared bit for
+/// bit (and the two zeros equal), so identical results from different files
+/// or runs deduplicate.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionWCET {
     pub name: String,
@@ -38,21 +133,106 @@ pub struct FunctionWCET {
     pub bcet_cycles: u64,
     pub bcet_us: f64,
     pub loop_count: usize,
+
+    /// WCET as analyzed, before the safety margin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_wcet_cycles: Option<u64>,
+
+    /// Source file and line the function is defined at, if the module has
+    /// debug info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<u32>,
+}
+
+impl FunctionWCET {
+    /// Whether `other` is the same function with a different result
+    pub fn conflicts_with(&self, other: &FunctionWCET) -> bool {
+        self.name == other.name && self.llvm_name == other.llvm_name && self != other
+    }
+
+    /// Fields that define equality, floats as comparable bits
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        &str,
+        &str,
+        u64,
+        u64,
+        u64,
+        u64,
+        usize,
+        Option<u64>,
+        Option<&str>,
+        Option<u32>,
+    ) {
+        (
+            &self.name,
+            &self.llvm_name,
+            self.wcet_cycles,
+            float_bits(self.wcet_us),
+            self.bcet_cycles,
+            float_bits(self.bcet_us),
+            self.loop_count,
+            self.raw_wcet_cycles,
+            self.source_file.as_deref(),
+            self.source_line,
+        )
+    }
+}
+
+impl PartialEq for FunctionWCET {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for FunctionWCET {}
+
+impl Hash for FunctionWCET {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Bits of a float, with -0.0 folded into 0.0
+fn float_bits(value: f64) -> u64 {
+    if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
 }
 
 /// Task model
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskModel {
     pub tasks: Vec<Task>,
+
+    /// WCET as a fraction of the period, by task name
+    #[serde(default)]
+    pub relative_wcet: AHashMap<String, f64>,
 }
 
 /// Schedulability analysis results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SchedulabilityAnalysis {
     pub method: String,
     pub result: String,
     pub utilization: f64,
     pub utilization_bound: Option<f64>,
+    #[serde(default)]
+    pub harmonic: bool,
+
+    /// Utilization at WCET, the load the system is guaranteed to handle
+    #[serde(default)]
+    pub worst_case_utilization: f64,
+
+    /// Utilization at typical execution times, the expected load
+    #[serde(default)]
+    pub typical_utilization: f64,
     pub response_times: AHashMap<String, f64>,
 }
 
@@ -61,10 +241,14 @@ pub struct JSONOutput;
 
 impl JSONOutput {
     /// Generate complete analysis report
+    ///
+    /// `schedulability` is the verdict of the test for `policy`, which also
+    /// picks the response-time analysis reported.
     pub fn generate_report(
         wcet_results: &AHashMap<String, u64>,
         tasks: &[Task],
         schedulability: &SchedulabilityResult,
+        policy: SchedulingPolicy,
         schedule: Option<ScheduleTimeline>,
         platform_name: &str,
         cpu_freq_mhz: u32,
@@ -74,6 +258,7 @@ impl JSONOutput {
             version: env!("CARGO_PKG_VERSION").to_string(),
             timestamp: chrono::Utc::now().to_rfc3339(),
             platform: platform_name.to_string(),
+            margin_percent: None,
         };
 
         let functions: Vec<FunctionWCET> = wcet_results
@@ -88,52 +273,71 @@ impl JSONOutput {
                     bcet_cycles: wcet_cycles / 2, // Simplified
                     bcet_us: wcet_us / 2.0,
                     loop_count: 0, // Would need loop analysis results
+                    raw_wcet_cycles: None,
+                    source_file: None,
+                    source_line: None,
                 }
             })
             .collect();
 
-        let wcet_analysis = WCETAnalysis { functions };
+        let wcet_analysis = WCETAnalysis {
+            functions,
+            timed_out: vec![],
+        };
 
         let task_model = TaskModel {
             tasks: tasks.to_vec(),
+            relative_wcet: relative_wcets(tasks),
         };
 
-        let (result_str, utilization, utilization_bound) = match schedulability {
-            SchedulabilityResult::Schedulable => {
-                let util: f64 = tasks
-                    .iter()
-                    .filter(|t| t.period_us.is_some())
-                    .map(|t| t.wcet_us / t.period_us.unwrap())
-                    .sum();
-                ("schedulable".to_string(), util, Some(1.0))
-            }
-            SchedulabilityResult::Unschedulable { .. } => {
-                let util: f64 = tasks
-                    .iter()
-                    .filter(|t| t.period_us.is_some())
-                    .map(|t| t.wcet_us / t.period_us.unwrap())
-                    .sum();
-                ("unschedulable".to_string(), util, Some(1.0))
-            }
+        let utilization = RMAScheduler::calculate_utilization(tasks);
+        let utilization_bound = match policy {
+            SchedulingPolicy::RMA => Some(RMAScheduler::utilization_bound(tasks)),
+            // EDF schedules any implicit-deadline set up to full utilization
+            SchedulingPolicy::EDF => Some(1.0),
+            // No utilization bound holds for deadlines shorter than periods
+            SchedulingPolicy::DM => None,
         };
 
-        let response_times: AHashMap<String, f64> =
-            tasks.iter().map(|t| (t.name.clone(), t.wcet_us)).collect();
+        let result_str = match schedulability {
+            SchedulabilityResult::Schedulable => "schedulable".to_string(),
+            SchedulabilityResult::Unschedulable { .. } => "unschedulable".to_string(),
+            SchedulabilityResult::UnboundedArrivals { .. } => "unbounded-arrivals".to_string(),
+            SchedulabilityResult::InfeasibleDeadlines { .. } => "infeasible-deadlines".to_string(),
+        };
+
+        let response_times = match policy {
+            SchedulingPolicy::RMA => RMAScheduler::response_time_analysis(tasks),
+            SchedulingPolicy::DM => DMScheduler::response_times(tasks).into_iter().collect(),
+            // Under EDF no fixed-priority recurrence applies
+            SchedulingPolicy::EDF => AHashMap::new(),
+        };
 
         let schedulability_analysis = SchedulabilityAnalysis {
-            method: "RMA".to_string(),
+            method: format!("{:?}", policy),
             result: result_str,
             utilization,
             utilization_bound,
+            harmonic: RMAScheduler::is_harmonic(tasks),
+            worst_case_utilization: utilization,
+            typical_utilization: typical_utilization(tasks),
             response_times,
         };
 
+        // Report the schedule's slack along with it
+        let schedule = schedule.map(|mut timeline| {
+            timeline.idle = Some(StaticScheduleGenerator::idle_report(&timeline));
+            timeline
+        });
+
         AnalysisReport {
             analysis_info,
             wcet_analysis,
             task_model,
             schedulability: schedulability_analysis,
             schedule,
+            module_hotspots: vec![],
+            warnings: vec![],
         }
     }
 
@@ -153,6 +357,7 @@ impl JSONOutput {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analysis::WarningKind;
 
     #[test]
     fn test_json_generation() {
@@ -167,8 +372,7 @@ mod tests {
             period_us: Some(1000.0),
             deadline_us: Some(1000.0),
             priority: Some(0),
-            preemptible: true,
-            dependencies: vec![],
+            ..Default::default()
         }];
 
         let schedulability = SchedulabilityResult::Schedulable;
@@ -177,6 +381,7 @@ mod tests {
             &wcet_results,
             &tasks,
             &schedulability,
+            SchedulingPolicy::RMA,
             None,
             "ARM Cortex-M4",
             168,
@@ -187,4 +392,252 @@ mod tests {
         assert!(json.contains("task1"));
         assert!(json.contains("schedulable"));
     }
+
+    #[test]
+    fn test_report_includes_typical_utilization() {
+        let task = |name: &str, typical_us: Option<f64>| Task {
+            name: name.to_string(),
+            function: name.to_string(),
+            wcet_cycles: 100,
+            wcet_us: 100.0,
+            typical_us,
+            period_us: Some(1000.0),
+            deadline_us: Some(1000.0),
+            ..Default::default()
+        };
+        // Tasks without a typical time count at their WCET
+        let tasks = vec![task("a", Some(20.0)), task("b", None)];
+
+        let report = JSONOutput::generate_report(
+            &AHashMap::new(),
+            &tasks,
+            &SchedulabilityResult::Schedulable,
+            SchedulingPolicy::RMA,
+            None,
+            "ARM Cortex-M4",
+            168,
+        );
+
+        assert!((report.schedulability.worst_case_utilization - 0.2).abs() < 1e-9);
+        assert!((report.schedulability.typical_utilization - 0.12).abs() < 1e-9);
+        assert_eq!(report.task_model.relative_wcet["a"], 0.1);
+
+        // Each policy reports its own response-time analysis
+        let report_under = |policy| {
+            JSONOutput::generate_report(
+                &AHashMap::new(),
+                &tasks,
+                &SchedulabilityResult::Schedulable,
+                policy,
+                None,
+                "ARM Cortex-M4",
+                168,
+            )
+            .schedulability
+        };
+        let edf = report_under(SchedulingPolicy::EDF);
+        assert_eq!(edf.method, "EDF");
+        assert_eq!(edf.utilization_bound, Some(1.0));
+        assert!(edf.response_times.is_empty());
+        let dm = report_under(SchedulingPolicy::DM);
+        assert_eq!(dm.method, "DM");
+        assert_eq!(dm.response_times.len(), 2);
+    }
+
+    #[test]
+    fn test_report_uses_rma_bound() {
+        let task = |name: &str, period_us: f64| Task {
+            name: name.to_string(),
+            function: name.to_string(),
+            wcet_cycles: 100,
+            wcet_us: 10.0,
+            period_us: Some(period_us),
+            deadline_us: Some(period_us),
+            ..Default::default()
+        };
+        let tasks = vec![task("a", 1000.0), task("b", 1500.0), task("c", 3500.0)];
+
+        let report = JSONOutput::generate_report(
+            &AHashMap::new(),
+            &tasks,
+            &SchedulabilityResult::Schedulable,
+            SchedulingPolicy::RMA,
+            None,
+            "ARM Cortex-M4",
+            168,
+        );
+
+        let bound = report.schedulability.utilization_bound.unwrap();
+        assert!((bound - 3.0 * (2.0_f64.powf(1.0 / 3.0) - 1.0)).abs() < 1e-9);
+        assert!(!report.schedulability.harmonic);
+    }
+
+    /// Report with every optional section filled in
+    fn full_report() -> AnalysisReport {
+        use crate::scheduling::static_gen::TimeSlot;
+        use crate::scheduling::Criticality;
+
+        let task = |name: &str, criticality: Option<Criticality>| Task {
+            name: name.to_string(),
+            function: format!("{}_fn", name),
+            wcet_cycles: 1680,
+            wcet_us: 10.0,
+            typical_us: Some(4.5),
+            period_us: Some(100.0),
+            deadline_us: Some(80.0),
+            priority: Some(1),
+            preemptible: false,
+            dependencies: vec!["init".to_string()],
+            criticality,
+            wcet_lo_us: criticality.map(|_| 6.0),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("sensor", Some(Criticality::Hi)),
+            task("logger", Some(Criticality::Lo)),
+            task("control", None),
+        ];
+        tasks[2].period_us = None;
+        tasks[2].min_interarrival_us = Some(250.0);
+
+        let schedule = ScheduleTimeline {
+            hyperperiod_us: 100.0,
+            slots: vec![
+                TimeSlot {
+                    start_us: 0.0,
+                    duration_us: 10.0,
+                    task: "sensor".to_string(),
+                    preemptible: false,
+                },
+                TimeSlot {
+                    start_us: 10.0,
+                    duration_us: 90.0,
+                    task: "IDLE".to_string(),
+                    preemptible: true,
+                },
+            ],
+            idle: None,
+        };
+
+        let wcet_results: AHashMap<String, u64> = [("sensor_fn".to_string(), 1680)].into();
+        let mut report = JSONOutput::generate_report(
+            &wcet_results,
+            &tasks,
+            &SchedulabilityResult::Unschedulable {
+                failing_task: "logger".to_string(),
+                response_time: 90.0,
+                deadline: 80.0,
+            },
+            SchedulingPolicy::RMA,
+            Some(schedule),
+            "ARM Cortex-M4",
+            168,
+        );
+        report.record_margin(20.0, &[("sensor_fn".to_string(), 1400)].into());
+        assert_eq!(
+            report.wcet_analysis.functions[0].raw_wcet_cycles,
+            Some(1400)
+        );
+        report.wcet_analysis.timed_out = vec!["solver_heavy".to_string()];
+        report.schedulability.response_times = [
+            ("sensor".to_string(), 10.0),
+            ("logger".to_string(), 20.5),
+            ("control".to_string(), 30.25),
+        ]
+        .into();
+        report.module_hotspots = vec![BlockHotspot {
+            function: "sensor_fn".to_string(),
+            block: "for.body".to_string(),
+            block_id: 2,
+            executions: 64,
+            cycles: 12,
+            total_cycles: 768,
+            source_file: Some("src/sensor.rs".to_string()),
+            source_line: Some(31),
+        }];
+        report.warnings = vec![AnalysisWarning::in_function(
+            WarningKind::UnknownOpcode,
+            "logger_fn",
+            "no timing for CallBr, using the default cost",
+        )];
+        report
+    }
+
+    #[test]
+    fn test_report_survives_json_round_trip() {
+        let report = full_report();
+
+        let json = JSONOutput::to_json(&report).unwrap();
+        let loaded: AnalysisReport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded, report);
+        assert_eq!(loaded.schedulability.result, "unschedulable");
+        assert_eq!(loaded.schedulability.response_times.len(), 3);
+        assert!(json.contains(r#""criticality": "hi""#));
+    }
+
+    #[test]
+    fn test_report_without_optional_sections_round_trips() {
+        let mut report = full_report();
+        report.schedule = None;
+        report.wcet_analysis.timed_out.clear();
+        report.module_hotspots.clear();
+        report.warnings.clear();
+        report.schedulability.response_times.clear();
+        report.analysis_info.margin_percent = None;
+        report.wcet_analysis.functions[0].raw_wcet_cycles = None;
+
+        let json = JSONOutput::to_json(&report).unwrap();
+        assert!(!json.contains("module_hotspots"));
+        assert!(!json.contains("warnings"));
+        assert!(!json.contains("timed_out"));
+        assert!(!json.contains("margin_percent"));
+        assert!(!json.contains("raw_wcet_cycles"));
+        assert!(!json.contains("source_file"));
+
+        let loaded: AnalysisReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, report);
+    }
+
+    #[test]
+    fn test_dedup_functions_keeps_conflicts() {
+        let function = |name: &str, wcet_cycles: u64| FunctionWCET {
+            name: name.to_string(),
+            llvm_name: format!("@{}", name),
+            wcet_cycles,
+            wcet_us: wcet_cycles as f64 / 168.0,
+            bcet_cycles: 0,
+            bcet_us: -0.0,
+            loop_count: 1,
+            raw_wcet_cycles: None,
+            source_file: None,
+            source_line: None,
+        };
+        let mut zero_bcet = function("filter", 840);
+        zero_bcet.bcet_us = 0.0;
+
+        let mut analysis = WCETAnalysis {
+            functions: vec![
+                function("filter", 840),
+                function("control", 1680),
+                zero_bcet,
+                function("control", 2000),
+            ],
+            timed_out: vec![],
+        };
+        let conflicts = analysis.dedup_functions();
+
+        assert_eq!(conflicts, vec!["control"]);
+        assert_eq!(analysis.functions.len(), 3);
+
+        let reordered = WCETAnalysis {
+            functions: analysis.functions.iter().rev().cloned().collect(),
+            timed_out: vec![],
+        };
+        assert!(analysis.same_functions(&reordered));
+        assert!(!analysis.same_functions(&WCETAnalysis {
+            functions: vec![function("filter", 840)],
+            timed_out: vec![],
+        }));
+    }
 }
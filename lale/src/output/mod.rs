@@ -1,5 +1,16 @@
+pub mod audit;
+pub mod color;
+pub mod csv;
+pub mod format;
 pub mod json;
 pub mod visualization;
 
+pub use audit::{AuditEvent, AuditLog};
+pub use color::{ColorChoice, Palette};
+pub use csv::CsvOutput;
+pub use format::{
+    ChromeTraceFormat, CsvFormat, GanttFormat, GanttSvgFormat, JsonFormat, OutputFormat,
+    OutputFormatRegistry,
+};
 pub use json::{AnalysisReport, JSONOutput};
 pub use visualization::{GanttData, GanttOutput, GraphvizOutput};
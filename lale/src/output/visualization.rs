@@ -1,5 +1,6 @@
-use crate::ir::CFG;
-use crate::scheduling::static_gen::ScheduleTimeline;
+use crate::ir::{demangle, CallGraph, CFG};
+use crate::scheduling::static_gen::{ScheduleTimeline, TIME_EPSILON_US};
+use crate::scheduling::Task;
 use ahash::AHashMap;
 use petgraph::graph::NodeIndex;
 
@@ -39,6 +40,7 @@ impl GraphvizOutput {
                 crate::ir::cfg::EdgeType::ConditionalTrue => "color=green",
                 crate::ir::cfg::EdgeType::ConditionalFalse => "color=red",
                 crate::ir::cfg::EdgeType::LoopBack => "color=blue, style=dashed",
+                crate::ir::cfg::EdgeType::Unwind => "color=orange, style=dotted",
                 _ => "color=black",
             };
 
@@ -63,6 +65,143 @@ impl GraphvizOutput {
         let dot = Self::export_cfg(cfg, timings);
         std::fs::write(path, dot)
     }
+
+    /// Export call graph to Graphviz DOT format
+    ///
+    /// Nodes show the demangled name and WCET from `wcets` (keyed by symbol
+    /// name). Functions in recursive SCCs are filled red, external callees
+    /// are dashed. Multiple call sites between two functions become one edge.
+    pub fn callgraph_to_dot(graph: &CallGraph, wcets: &AHashMap<String, u64>) -> String {
+        let mut dot = String::from("digraph CallGraph {\n");
+        dot.push_str("  node [shape=box];\n");
+        dot.push_str("  rankdir=LR;\n");
+
+        let nodes = graph.nodes();
+        let recursive = Self::recursive_functions(graph);
+
+        for (id, name) in nodes.iter().enumerate() {
+            let label = Self::callgraph_label(name, wcets, "\\n", |text| {
+                text.replace('\\', "\\\\").replace('"', "\\\"")
+            });
+
+            let style = if !graph.is_defined(name) {
+                ", style=dashed"
+            } else if recursive.iter().any(|r| r == name) {
+                ", style=filled, fillcolor=\"#f4a6a6\""
+            } else {
+                ""
+            };
+
+            dot.push_str(&format!("  n{} [label=\"{}\"{}];\n", id, label, style));
+        }
+
+        for (caller, callee, count) in Self::callgraph_edges(graph, &nodes) {
+            let label = if count > 1 {
+                format!(" [label=\"x{}\"]", count)
+            } else {
+                String::new()
+            };
+            dot.push_str(&format!("  n{} -> n{}{};\n", caller, callee, label));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Export call graph to a Mermaid flowchart
+    ///
+    /// Same content as [`Self::callgraph_to_dot`].
+    pub fn callgraph_to_mermaid(graph: &CallGraph, wcets: &AHashMap<String, u64>) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+
+        let nodes = graph.nodes();
+        let recursive = Self::recursive_functions(graph);
+
+        for (id, name) in nodes.iter().enumerate() {
+            let label = Self::callgraph_label(name, wcets, "<br/>", |text| {
+                text.replace('"', "#quot;")
+                    .replace('<', "#lt;")
+                    .replace('>', "#gt;")
+            });
+            mermaid.push_str(&format!("  n{}[\"{}\"]\n", id, label));
+        }
+
+        for (caller, callee, count) in Self::callgraph_edges(graph, &nodes) {
+            if count > 1 {
+                mermaid.push_str(&format!("  n{} -->|x{}| n{}\n", caller, count, callee));
+            } else {
+                mermaid.push_str(&format!("  n{} --> n{}\n", caller, callee));
+            }
+        }
+
+        let class_members = |filter: &dyn Fn(&str) -> bool| -> Vec<String> {
+            nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| filter(name))
+                .map(|(id, _)| format!("n{}", id))
+                .collect()
+        };
+
+        let recursive_nodes = class_members(&|name| recursive.iter().any(|r| r == name));
+        if !recursive_nodes.is_empty() {
+            mermaid.push_str("  classDef recursive fill:#f4a6a6\n");
+            mermaid.push_str(&format!(
+                "  class {} recursive\n",
+                recursive_nodes.join(",")
+            ));
+        }
+
+        let external_nodes = class_members(&|name| !graph.is_defined(name));
+        if !external_nodes.is_empty() {
+            mermaid.push_str("  classDef external stroke-dasharray: 5 5\n");
+            mermaid.push_str(&format!("  class {} external\n", external_nodes.join(",")));
+        }
+
+        mermaid
+    }
+
+    /// Names of functions that belong to a recursive SCC
+    fn recursive_functions(graph: &CallGraph) -> Vec<String> {
+        graph.find_cycles().into_iter().flatten().collect()
+    }
+
+    /// Escaped demangled name plus WCET, joined by a format-specific line break
+    fn callgraph_label(
+        name: &str,
+        wcets: &AHashMap<String, u64>,
+        line_break: &str,
+        escape: fn(&str) -> String,
+    ) -> String {
+        let display_name = escape(&demangle(name));
+        match wcets.get(name) {
+            Some(wcet) => format!("{}{}{} cycles", display_name, line_break, wcet),
+            None => display_name,
+        }
+    }
+
+    /// Distinct caller/callee node index pairs with their call site count
+    fn callgraph_edges(graph: &CallGraph, nodes: &[&str]) -> Vec<(usize, usize, usize)> {
+        let mut edges: Vec<(usize, usize, usize)> = Vec::new();
+
+        for site in &graph.call_sites {
+            let position = |name: &str| nodes.iter().position(|n| *n == name);
+            let (Some(caller), Some(callee)) = (position(&site.caller), position(&site.callee))
+            else {
+                continue;
+            };
+
+            match edges
+                .iter_mut()
+                .find(|(from, to, _)| *from == caller && *to == callee)
+            {
+                Some((_, _, count)) => *count += 1,
+                None => edges.push((caller, callee, 1)),
+            }
+        }
+
+        edges
+    }
 }
 
 /// Gantt chart data generator
@@ -97,6 +236,190 @@ impl GanttOutput {
         }
     }
 
+    /// Export a schedule in the Chrome Trace Event Format
+    ///
+    /// Viewable in chrome://tracing or Perfetto. Every slot, preempted
+    /// slices and idle time included, becomes a complete event on core 0
+    /// with timestamps in microseconds. Each task gets its own thread,
+    /// numbered in order of first appearance and named after the task.
+    pub fn to_chrome_trace(schedule: &ScheduleTimeline) -> String {
+        let mut threads: Vec<&str> = Vec::new();
+        let mut events = Vec::new();
+
+        for slot in &schedule.slots {
+            let tid = match threads.iter().position(|task| *task == slot.task) {
+                Some(tid) => tid,
+                None => {
+                    threads.push(&slot.task);
+                    threads.len() - 1
+                }
+            };
+            events.push(serde_json::json!({
+                "name": slot.task,
+                "cat": if slot.preemptible { "execution" } else { "critical" },
+                "ph": "X",
+                "ts": slot.start_us,
+                "dur": slot.duration_us,
+                "pid": 0,
+                "tid": tid,
+            }));
+        }
+
+        for (tid, task) in threads.iter().enumerate() {
+            events.push(serde_json::json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": 0,
+                "tid": tid,
+                "args": { "name": task },
+            }));
+        }
+
+        serde_json::json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        })
+        .to_string()
+    }
+
+    /// Render a schedule as a standalone SVG Gantt chart
+    ///
+    /// One lane per task in order of first appearance, with a rectangle per
+    /// slot scaled to the hyperperiod. For tasks found in `tasks`, releases
+    /// are ticked at the top of the lane and deadlines drawn as dashed
+    /// lines; a slot running across one of its task's deadlines is red.
+    /// Idle time is left blank.
+    pub fn to_svg(schedule: &ScheduleTimeline, tasks: &[Task], width_px: u32) -> String {
+        const LABEL_WIDTH: f64 = 120.0;
+        const LANE_HEIGHT: f64 = 28.0;
+        const BAR_HEIGHT: f64 = 18.0;
+        const AXIS_HEIGHT: f64 = 24.0;
+        const PALETTE: [&str; 6] = [
+            "#4e79a7", "#59a14f", "#f28e2b", "#76b7b2", "#edc948", "#b07aa1",
+        ];
+        const OVERRUN: &str = "#d62728";
+
+        let mut lanes: Vec<&str> = Vec::new();
+        for slot in schedule.slots.iter().filter(|slot| !slot.is_idle()) {
+            if !lanes.contains(&slot.task.as_str()) {
+                lanes.push(&slot.task);
+            }
+        }
+
+        let width = (width_px as f64).max(LABEL_WIDTH + 1.0);
+        let height = AXIS_HEIGHT + LANE_HEIGHT * lanes.len() as f64;
+        let hyperperiod = if schedule.hyperperiod_us > 0.0 {
+            schedule.hyperperiod_us
+        } else {
+            schedule
+                .slots
+                .iter()
+                .map(|slot| slot.end_us())
+                .fold(1.0, f64::max)
+        };
+        let x = |time_us: f64| LABEL_WIDTH + time_us / hyperperiod * (width - LABEL_WIDTH);
+        let lane_top = |lane: usize| AXIS_HEIGHT + LANE_HEIGHT * lane as f64;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"sans-serif\" font-size=\"12\">\n",
+            width, height
+        );
+        svg.push_str(&format!(
+            "  <line x1=\"{:.2}\" y1=\"{}\" x2=\"{:.2}\" y2=\"{}\" stroke=\"black\"/>\n",
+            x(0.0),
+            AXIS_HEIGHT,
+            x(hyperperiod),
+            AXIS_HEIGHT
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{:.2}\" y=\"16\">0 us</text>\n  \
+             <text x=\"{:.2}\" y=\"16\" text-anchor=\"end\">{} us</text>\n",
+            x(0.0),
+            x(hyperperiod),
+            hyperperiod
+        ));
+
+        for (lane, name) in lanes.iter().enumerate() {
+            let top = lane_top(lane);
+            svg.push_str(&format!(
+                "  <text x=\"4\" y=\"{:.2}\">{}</text>\n",
+                top + LANE_HEIGHT / 2.0 + 4.0,
+                xml_escape(name)
+            ));
+
+            let Some(task) = tasks.iter().find(|task| task.name == *name) else {
+                continue;
+            };
+            for (release, deadline) in Self::task_windows(task, hyperperiod) {
+                svg.push_str(&format!(
+                    "  <line x1=\"{0:.2}\" y1=\"{1:.2}\" x2=\"{0:.2}\" y2=\"{2:.2}\" stroke=\"black\"/>\n",
+                    x(release),
+                    top,
+                    top + 5.0
+                ));
+                if deadline <= hyperperiod {
+                    svg.push_str(&format!(
+                        "  <line x1=\"{0:.2}\" y1=\"{1:.2}\" x2=\"{0:.2}\" y2=\"{2:.2}\" \
+                         stroke=\"{3}\" stroke-dasharray=\"4 3\"/>\n",
+                        x(deadline),
+                        top,
+                        top + LANE_HEIGHT,
+                        OVERRUN
+                    ));
+                }
+            }
+        }
+
+        for slot in schedule.slots.iter().filter(|slot| !slot.is_idle()) {
+            let Some(lane) = lanes.iter().position(|name| *name == slot.task) else {
+                continue;
+            };
+            let overrun = tasks
+                .iter()
+                .find(|task| task.name == slot.task)
+                .is_some_and(|task| {
+                    Self::task_windows(task, hyperperiod).any(|(_, deadline)| {
+                        slot.start_us < deadline - TIME_EPSILON_US
+                            && slot.end_us() > deadline + TIME_EPSILON_US
+                    })
+                });
+            let fill = if overrun {
+                OVERRUN
+            } else {
+                PALETTE[lane % PALETTE.len()]
+            };
+
+            svg.push_str(&format!(
+                "  <rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{}\" fill=\"{}\">\
+                 <title>{} {}-{} us</title></rect>\n",
+                x(slot.start_us),
+                lane_top(lane) + (LANE_HEIGHT - BAR_HEIGHT) / 2.0,
+                x(slot.end_us()) - x(slot.start_us),
+                BAR_HEIGHT,
+                fill,
+                xml_escape(&slot.task),
+                slot.start_us,
+                slot.end_us()
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Release and absolute deadline of each of a task's jobs in the hyperperiod
+    fn task_windows(task: &Task, hyperperiod: f64) -> impl Iterator<Item = (f64, f64)> {
+        let period = task.arrival_period_us().filter(|&period| period > 0.0);
+        let deadline = task.deadline_us.or(period);
+        let jobs = period.map_or(0, |period| (hyperperiod / period).ceil() as usize);
+
+        (0..jobs).filter_map(move |job| {
+            let release = job as f64 * period?;
+            Some((release, release + deadline?))
+        })
+    }
+
     /// Export Gantt data to JSON
     pub fn to_json(data: &GanttData) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(data)
@@ -110,6 +433,14 @@ impl GanttOutput {
     }
 }
 
+/// Escape text for use in XML content and attributes
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Gantt chart data structure
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GanttData {
@@ -129,15 +460,66 @@ pub struct TaskExecution {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::CallSite;
     use crate::ir::CFG;
     use crate::ir::{InkwellCFG, InkwellParser};
 
+    fn recursive_call_graph() -> CallGraph {
+        let site = |caller: &str, callee: &str| CallSite {
+            caller: caller.to_string(),
+            callee: callee.to_string(),
+            block: "entry".to_string(),
+            block_id: 0,
+            constant_args: vec![],
+        };
+
+        CallGraph {
+            functions: vec!["main".to_string(), "even".to_string(), "odd".to_string()],
+            call_sites: vec![
+                site("main", "even"),
+                site("main", "even"),
+                site("even", "odd"),
+                site("odd", "even"),
+                site("main", "memcpy"),
+            ],
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_graphviz_export_exists() {
         // Basic compilation test
         assert!(true);
     }
 
+    #[test]
+    fn test_callgraph_to_dot() {
+        let graph = recursive_call_graph();
+        let mut wcets = AHashMap::new();
+        wcets.insert("main".to_string(), 120);
+
+        let dot = GraphvizOutput::callgraph_to_dot(&graph, &wcets);
+
+        assert!(dot.starts_with("digraph CallGraph {"));
+        assert!(dot.contains("n0 [label=\"main\\n120 cycles\"];"));
+        assert!(dot.contains("n1 [label=\"even\", style=filled"));
+        assert!(dot.contains("n2 [label=\"odd\", style=filled"));
+        assert!(dot.contains("n3 [label=\"memcpy\", style=dashed];"));
+        assert!(dot.contains("n0 -> n1 [label=\"x2\"];"));
+        assert!(dot.contains("n2 -> n1;"));
+    }
+
+    #[test]
+    fn test_callgraph_to_mermaid() {
+        let graph = recursive_call_graph();
+        let mermaid = GraphvizOutput::callgraph_to_mermaid(&graph, &AHashMap::new());
+
+        assert!(mermaid.starts_with("flowchart LR"));
+        assert!(mermaid.contains("n0 -->|x2| n1"));
+        assert!(mermaid.contains("class n1,n2 recursive"));
+        assert!(mermaid.contains("class n3 external"));
+    }
+
     #[test]
     fn test_gantt_generation() {
         use crate::scheduling::static_gen::TimeSlot;
@@ -158,6 +540,7 @@ mod tests {
                     preemptible: false,
                 },
             ],
+            idle: None,
         };
 
         let gantt = GanttOutput::generate_gantt_data(&schedule);
@@ -170,4 +553,95 @@ mod tests {
         assert!(json.contains("task1"));
         assert!(json.contains("task2"));
     }
+
+    #[test]
+    fn test_chrome_trace_has_event_per_slot() {
+        use crate::scheduling::static_gen::TimeSlot;
+
+        let slot = |start_us: f64, duration_us: f64, task: &str| TimeSlot {
+            start_us,
+            duration_us,
+            task: task.to_string(),
+            preemptible: true,
+        };
+        // logger is preempted by sensor and resumes afterwards
+        let schedule = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![
+                slot(0.0, 100.0, "logger"),
+                slot(100.0, 50.0, "sensor"),
+                slot(150.0, 200.0, "logger"),
+                slot(350.0, 650.0, "IDLE"),
+            ],
+            idle: None,
+        };
+
+        let trace: serde_json::Value =
+            serde_json::from_str(&GanttOutput::to_chrome_trace(&schedule)).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let complete: Vec<_> = events.iter().filter(|e| e["ph"] == "X").collect();
+
+        assert_eq!(complete.len(), schedule.slots.len());
+        assert_eq!(complete[2]["name"], "logger");
+        assert_eq!(complete[2]["ts"], 150.0);
+        assert_eq!(complete[2]["dur"], 200.0);
+        assert_eq!(complete[2]["tid"], complete[0]["tid"]);
+        assert_ne!(complete[1]["tid"], complete[0]["tid"]);
+        assert_eq!(events.iter().filter(|e| e["ph"] == "M").count(), 3);
+    }
+
+    #[test]
+    fn test_svg_gantt_is_well_formed() {
+        use crate::scheduling::static_gen::TimeSlot;
+        use quick_xml::events::Event;
+
+        let slot = |start_us: f64, duration_us: f64, task: &str| TimeSlot {
+            start_us,
+            duration_us,
+            task: task.to_string(),
+            preemptible: true,
+        };
+        let task = |name: &str, period_us: f64| Task {
+            name: name.to_string(),
+            function: name.to_string(),
+            wcet_us: 0.0,
+            period_us: Some(period_us),
+            ..Default::default()
+        };
+        // The second "a&b" job runs past its deadline at 100 us
+        let schedule = ScheduleTimeline {
+            hyperperiod_us: 200.0,
+            slots: vec![
+                slot(0.0, 30.0, "a&b"),
+                slot(30.0, 40.0, "logger"),
+                slot(70.0, 20.0, "IDLE"),
+                slot(90.0, 30.0, "a&b"),
+                slot(120.0, 40.0, "logger"),
+            ],
+            idle: None,
+        };
+        let tasks = vec![task("a&b", 100.0), task("logger", 200.0)];
+
+        let svg = GanttOutput::to_svg(&schedule, &tasks, 800);
+
+        let mut reader = quick_xml::Reader::from_str(&svg);
+        let mut fills = Vec::new();
+        loop {
+            match reader.read_event() {
+                Ok(Event::Eof) => break,
+                Ok(Event::Start(tag)) | Ok(Event::Empty(tag)) if tag.name().as_ref() == b"rect" => {
+                    let fill = tag.try_get_attribute("fill").unwrap().unwrap();
+                    fills.push(String::from_utf8(fill.value.to_vec()).unwrap());
+                }
+                Ok(_) => {}
+                Err(e) => panic!("SVG is not well-formed: {}", e),
+            }
+        }
+
+        assert_eq!(fills.len(), 4);
+        assert_eq!(fills.iter().filter(|fill| *fill == "#d62728").count(), 1);
+        assert_eq!(fills[2], "#d62728");
+        assert!(svg.contains("a&amp;b"));
+        assert_eq!(svg.matches("stroke-dasharray").count(), 3);
+    }
 }
@@ -0,0 +1,178 @@
+//! Catalog of built-in platform models
+//!
+//! Single table of the targets `PlatformModel` ships with. Both the
+//! catalog listing and id lookup are derived from it, so the list the CLI
+//! and GUI present can't drift from what `--platform` accepts.
+
+use super::cortex_ar::{CortexA53Model, CortexA7Model, CortexR4Model, CortexR5Model};
+use super::cortex_m::{CortexM0Model, CortexM33Model, CortexM3Model, CortexM4Model, CortexM7Model};
+use super::models::PlatformModel;
+use super::riscv::{RV32GCModel, RV32IMACModel, RV32IModel, RV64GCModel};
+use super::wasm::Wasm32Model;
+use serde::{Deserialize, Serialize};
+
+/// Built-in platform entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    /// Identifier accepted by `--platform`
+    pub id: String,
+    pub name: String,
+    pub frequency_mhz: u32,
+    pub category: String,
+
+    /// Short identifiers also accepted by `--platform`
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// One-line capability summary, see `PlatformModel::summary`
+    #[serde(default)]
+    pub summary: String,
+}
+
+/// One built-in platform: id, aliases, category and model constructor
+struct CatalogEntry {
+    id: &'static str,
+    aliases: &'static [&'static str],
+    category: &'static str,
+    model: fn() -> PlatformModel,
+}
+
+const CATALOG: &[CatalogEntry] = &[
+    // ARM Cortex-M
+    CatalogEntry {
+        id: "cortex-m0",
+        aliases: &["m0"],
+        category: "ARM Cortex-M",
+        model: CortexM0Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-m3",
+        aliases: &["m3"],
+        category: "ARM Cortex-M",
+        model: CortexM3Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-m4",
+        aliases: &["m4"],
+        category: "ARM Cortex-M",
+        model: CortexM4Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-m7",
+        aliases: &["m7"],
+        category: "ARM Cortex-M",
+        model: CortexM7Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-m33",
+        aliases: &["m33"],
+        category: "ARM Cortex-M",
+        model: CortexM33Model::new,
+    },
+    // ARM Cortex-R
+    CatalogEntry {
+        id: "cortex-r4",
+        aliases: &["r4"],
+        category: "ARM Cortex-R",
+        model: CortexR4Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-r5",
+        aliases: &["r5"],
+        category: "ARM Cortex-R",
+        model: CortexR5Model::new,
+    },
+    // ARM Cortex-A
+    CatalogEntry {
+        id: "cortex-a7",
+        aliases: &["a7"],
+        category: "ARM Cortex-A",
+        model: CortexA7Model::new,
+    },
+    CatalogEntry {
+        id: "cortex-a53",
+        aliases: &["a53"],
+        category: "ARM Cortex-A",
+        model: CortexA53Model::new,
+    },
+    // RISC-V
+    CatalogEntry {
+        id: "rv32i",
+        aliases: &[],
+        category: "RISC-V",
+        model: RV32IModel::new,
+    },
+    CatalogEntry {
+        id: "rv32imac",
+        aliases: &[],
+        category: "RISC-V",
+        model: RV32IMACModel::new,
+    },
+    CatalogEntry {
+        id: "rv32gc",
+        aliases: &[],
+        category: "RISC-V",
+        model: RV32GCModel::new,
+    },
+    CatalogEntry {
+        id: "rv64gc",
+        aliases: &[],
+        category: "RISC-V",
+        model: RV64GCModel::new,
+    },
+    // WebAssembly
+    CatalogEntry {
+        id: "wasm32",
+        aliases: &["wasm"],
+        category: "WebAssembly",
+        model: Wasm32Model::new,
+    },
+];
+
+impl PlatformModel {
+    /// All built-in platforms, with name and frequency taken from the models
+    pub fn catalog() -> Vec<PlatformInfo> {
+        CATALOG
+            .iter()
+            .map(|entry| {
+                let model = (entry.model)();
+                PlatformInfo {
+                    id: entry.id.to_string(),
+                    summary: model.summary(),
+                    name: model.name,
+                    frequency_mhz: model.cpu_frequency_mhz,
+                    category: entry.category.to_string(),
+                    aliases: entry.aliases.iter().map(|a| a.to_string()).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Built-in platform by id or alias (case-insensitive)
+    pub fn from_id(id: &str) -> Option<PlatformModel> {
+        let id = id.to_lowercase();
+        CATALOG
+            .iter()
+            .find(|entry| entry.id == id || entry.aliases.contains(&id.as_str()))
+            .map(|entry| (entry.model)())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_ids_resolve() {
+        for info in PlatformModel::catalog() {
+            let model = PlatformModel::from_id(&info.id).expect("catalog id should resolve");
+            assert_eq!(model.name, info.name);
+            for alias in &info.aliases {
+                assert!(PlatformModel::from_id(alias).is_some());
+            }
+        }
+
+        assert_eq!(PlatformModel::from_id("M4").unwrap().name, "ARM Cortex-M4");
+        assert!(PlatformModel::from_id("z80").is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::analysis::timing::{AccessType, AtomicOp, Cycles, InstructionClass};
-use crate::platform::PlatformModel;
+use crate::platform::{PlatformCapabilities, PlatformModel};
 use ahash::AHashMap;
 
 /// ARM Cortex-R4 timing model (ARMv7-R, real-time)
@@ -57,6 +57,14 @@ impl CortexR4Model {
         PlatformModel {
             name: "ARM Cortex-R4".to_string(),
             cpu_frequency_mhz: 600,
+            issue_width: 1,
+            fetch_width: 2,
+            capabilities: PlatformCapabilities {
+                fpu: false,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -120,6 +128,14 @@ impl CortexR5Model {
         PlatformModel {
             name: "ARM Cortex-R5".to_string(),
             cpu_frequency_mhz: 800,
+            issue_width: 1,
+            fetch_width: 2,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -183,6 +199,14 @@ impl CortexA7Model {
         PlatformModel {
             name: "ARM Cortex-A7".to_string(),
             cpu_frequency_mhz: 1200,
+            issue_width: 2,
+            fetch_width: 2,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -246,6 +270,14 @@ impl CortexA53Model {
         PlatformModel {
             name: "ARM Cortex-A53".to_string(),
             cpu_frequency_mhz: 1400,
+            issue_width: 2,
+            fetch_width: 4,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -1,5 +1,5 @@
 use crate::analysis::timing::{AccessType, AtomicOp, Cycles, InstructionClass};
-use crate::platform::PlatformModel;
+use crate::platform::{PlatformCapabilities, PlatformModel};
 use ahash::AHashMap;
 
 /// ARM Cortex-M0/M0+/M1 timing model (ARMv6-M)
@@ -51,6 +51,14 @@ impl CortexM0Model {
         PlatformModel {
             name: "ARM Cortex-M0".to_string(),
             cpu_frequency_mhz: 48,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: false,
+                hw_multiply: false,
+                hw_divide: false,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -114,6 +122,14 @@ impl CortexM3Model {
         PlatformModel {
             name: "ARM Cortex-M3".to_string(),
             cpu_frequency_mhz: 72,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: false,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -178,6 +194,14 @@ impl CortexM4Model {
         PlatformModel {
             name: "ARM Cortex-M4".to_string(),
             cpu_frequency_mhz: 168,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -241,6 +265,14 @@ impl CortexM7Model {
         PlatformModel {
             name: "ARM Cortex-M7".to_string(),
             cpu_frequency_mhz: 400,
+            issue_width: 2,
+            fetch_width: 2,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -304,6 +336,14 @@ impl CortexM33Model {
         PlatformModel {
             name: "ARM Cortex-M33".to_string(),
             cpu_frequency_mhz: 120,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
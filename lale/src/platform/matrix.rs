@@ -0,0 +1,173 @@
+//! Worst-case cycles of every instruction class on every built-in platform
+//!
+//! One row per instruction class any built-in model times, one column per
+//! platform, in catalog order. A class a model has no entry for is timed at
+//! the 1-cycle default of `PlatformModel::get_timing`; the matrix keeps such
+//! cells empty so gaps between the hand-written models stand out.
+
+use super::models::PlatformModel;
+use crate::analysis::InstructionClass;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Worst-case cycles by instruction class and platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingMatrix {
+    /// Platform ids, one per column, as accepted by `--platform`
+    pub platforms: Vec<String>,
+    pub rows: Vec<TimingMatrixRow>,
+}
+
+/// Worst-case cycles of one instruction class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingMatrixRow {
+    /// Instruction class, e.g. `Div` or `Load(Flash)`
+    pub class: String,
+    /// Cycles on each platform, in column order; `None` if the model has no
+    /// entry for the class
+    pub worst_case: Vec<Option<u32>>,
+}
+
+impl TimingMatrix {
+    /// Matrix of `platforms`, each an id and its model
+    pub fn from_models(platforms: &[(String, PlatformModel)]) -> Self {
+        let mut classes: BTreeMap<&InstructionClass, Vec<Option<u32>>> = BTreeMap::new();
+        for (column, (_, model)) in platforms.iter().enumerate() {
+            for (class, cycles) in &model.instruction_timings {
+                classes
+                    .entry(class)
+                    .or_insert_with(|| vec![None; platforms.len()])[column] =
+                    Some(cycles.worst_case);
+            }
+        }
+
+        Self {
+            platforms: platforms.iter().map(|(id, _)| id.clone()).collect(),
+            rows: classes
+                .into_iter()
+                .map(|(class, worst_case)| TimingMatrixRow {
+                    class: class_label(class),
+                    worst_case,
+                })
+                .collect(),
+        }
+    }
+
+    /// Rows whose class some platforms time and others leave to the default
+    pub fn incomplete_rows(&self) -> Vec<&TimingMatrixRow> {
+        self.rows
+            .iter()
+            .filter(|row| row.worst_case.contains(&None))
+            .collect()
+    }
+
+    /// Plain-text table, classes as rows and platforms as columns
+    pub fn to_table(&self) -> String {
+        let class_width = self
+            .rows
+            .iter()
+            .map(|row| row.class.len())
+            .chain(std::iter::once("Class".len()))
+            .max()
+            .unwrap_or(0);
+        let widths: Vec<usize> = self.platforms.iter().map(|id| id.len().max(3)).collect();
+
+        let mut table = format!("{:<class_width$}", "Class");
+        for (id, width) in self.platforms.iter().zip(&widths) {
+            table.push_str(&format!("  {:>width$}", id));
+        }
+        table.push('\n');
+        for row in &self.rows {
+            table.push_str(&format!("{:<class_width$}", row.class));
+            for (cycles, width) in row.worst_case.iter().zip(&widths) {
+                let cell = cycles.map_or_else(|| "-".to_string(), |cycles| cycles.to_string());
+                table.push_str(&format!("  {:>width$}", cell));
+            }
+            table.push('\n');
+        }
+        table
+    }
+}
+
+impl PlatformModel {
+    /// Timing matrix of all built-in platforms
+    pub fn timing_matrix() -> TimingMatrix {
+        let platforms: Vec<(String, PlatformModel)> = Self::catalog()
+            .into_iter()
+            .filter_map(|info| Self::from_id(&info.id).map(|model| (info.id, model)))
+            .collect();
+        TimingMatrix::from_models(&platforms)
+    }
+}
+
+/// Row label of a class, its variant and payload, e.g. `Load(Flash)`
+fn class_label(class: &InstructionClass) -> String {
+    match class {
+        InstructionClass::Intrinsic(name) => format!("Intrinsic({})", name),
+        class => format!("{:?}", class),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::Cycles;
+    use crate::platform::{CortexM0Model, CortexM4Model};
+
+    #[test]
+    fn test_matrix_of_built_in_platforms() {
+        let matrix = PlatformModel::timing_matrix();
+        assert_eq!(matrix.platforms.len(), PlatformModel::catalog().len());
+        assert_eq!(matrix.platforms[0], "cortex-m0");
+
+        // Rows follow the declaration order of the classes
+        let add = matrix.rows.iter().position(|row| row.class == "Add");
+        let div = matrix.rows.iter().position(|row| row.class == "Div");
+        assert!(add.is_some() && add < div);
+
+        let m4 = matrix
+            .platforms
+            .iter()
+            .position(|id| id == "cortex-m4")
+            .unwrap();
+        let div_row = &matrix.rows[div.unwrap()];
+        assert_eq!(
+            div_row.worst_case[m4],
+            Some(
+                CortexM4Model::new()
+                    .get_timing(&InstructionClass::Div)
+                    .worst_case
+            )
+        );
+    }
+
+    #[test]
+    fn test_missing_entries_are_empty_cells() {
+        let mut sparse = CortexM0Model::new();
+        sparse.instruction_timings.clear();
+        sparse
+            .instruction_timings
+            .insert(InstructionClass::Add, Cycles::new(1));
+        let mut full = CortexM0Model::new();
+        full.instruction_timings.clear();
+        full.instruction_timings
+            .insert(InstructionClass::Add, Cycles::new(1));
+        full.instruction_timings
+            .insert(InstructionClass::Div, Cycles::range(2, 12));
+
+        let matrix = TimingMatrix::from_models(&[
+            ("sparse".to_string(), sparse),
+            ("full".to_string(), full),
+        ]);
+
+        assert_eq!(matrix.rows.len(), 2);
+        assert_eq!(matrix.rows[1].worst_case, vec![None, Some(12)]);
+        assert_eq!(matrix.incomplete_rows(), vec![&matrix.rows[1]]);
+        assert_eq!(
+            matrix.to_table(),
+            "Class  sparse  full\n\
+             Add         1     1\n\
+             Div         -    12\n"
+        );
+    }
+}
@@ -1,7 +1,10 @@
+pub mod catalog;
 pub mod cortex_ar;
 pub mod cortex_m;
+pub mod matrix;
 pub mod models;
 pub mod riscv;
+pub mod wasm;
 
 // ARM Cortex-M exports
 pub use cortex_m::{CortexM0Model, CortexM33Model, CortexM3Model, CortexM4Model, CortexM7Model};
@@ -12,5 +15,14 @@ pub use cortex_ar::{CortexA53Model, CortexA7Model, CortexR4Model, CortexR5Model}
 // RISC-V exports
 pub use riscv::{RV32GCModel, RV32IMACModel, RV32IModel, RV64GCModel};
 
+// WebAssembly exports
+pub use wasm::Wasm32Model;
+
 // Platform model
-pub use models::PlatformModel;
+pub use models::{PlatformCapabilities, PlatformModel};
+
+// Built-in platform catalog
+pub use catalog::PlatformInfo;
+
+// Timing comparison across built-in platforms
+pub use matrix::{TimingMatrix, TimingMatrixRow};
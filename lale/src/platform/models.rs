@@ -6,9 +6,25 @@ use ahash::AHashMap;
 pub struct PlatformModel {
     pub name: String,
     pub cpu_frequency_mhz: u32,
+    /// Instructions issued per cycle (1 for scalar pipelines)
+    pub issue_width: u32,
+    /// Instructions fetched per cycle by the front end
+    pub fetch_width: u8,
+    pub capabilities: PlatformCapabilities,
     pub instruction_timings: AHashMap<InstructionClass, Cycles>,
 }
 
+/// Hardware features of a platform, for display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformCapabilities {
+    /// Floating point in hardware (otherwise soft-float library calls)
+    pub fpu: bool,
+    pub hw_multiply: bool,
+    pub hw_divide: bool,
+    /// Instruction and/or data cache in front of memory
+    pub cache: bool,
+}
+
 impl PlatformModel {
     /// Get timing for instruction class
     pub fn get_timing(&self, class: &InstructionClass) -> Cycles {
@@ -17,4 +33,61 @@ impl PlatformModel {
             .copied()
             .unwrap_or(Cycles::new(1))
     }
+
+    /// Same core clocked at `mhz`, e.g. a Cortex-M4 board running at 80 MHz
+    ///
+    /// Cycle costs are unchanged; only their conversion to time follows.
+    pub fn with_frequency(mut self, mhz: u32) -> Self {
+        self.cpu_frequency_mhz = mhz;
+        self
+    }
+
+    /// Time `cycles` take at the platform's clock, in microseconds
+    pub fn cycles_to_us(&self, cycles: u64) -> f64 {
+        cycles as f64 / self.cpu_frequency_mhz as f64
+    }
+
+    /// One-line capability summary
+    ///
+    /// e.g. `ARM Cortex-M4 @ 168 MHz, FPU: yes, HW mul/div: yes/yes, cache: no`
+    pub fn summary(&self) -> String {
+        let yes_no = |present: bool| if present { "yes" } else { "no" };
+        format!(
+            "{} @ {} MHz, FPU: {}, HW mul/div: {}/{}, cache: {}",
+            self.name,
+            self.cpu_frequency_mhz,
+            yes_no(self.capabilities.fpu),
+            yes_no(self.capabilities.hw_multiply),
+            yes_no(self.capabilities.hw_divide),
+            yes_no(self.capabilities.cache)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::{CortexM0Model, CortexM4Model};
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            CortexM4Model::new().summary(),
+            "ARM Cortex-M4 @ 168 MHz, FPU: yes, HW mul/div: yes/yes, cache: no"
+        );
+        assert_eq!(
+            CortexM0Model::new().summary(),
+            "ARM Cortex-M0 @ 48 MHz, FPU: no, HW mul/div: no/no, cache: no"
+        );
+    }
+
+    #[test]
+    fn test_with_frequency_rescales_time_not_cycles() {
+        let stock = CortexM4Model::new();
+        let slow = CortexM4Model::new().with_frequency(80);
+
+        assert_eq!(stock.cycles_to_us(16_800), 100.0);
+        assert_eq!(slow.cycles_to_us(16_800), 210.0);
+        assert_eq!(slow.instruction_timings, stock.instruction_timings);
+        assert!(slow.summary().starts_with("ARM Cortex-M4 @ 80 MHz"));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::analysis::timing::{AccessType, AtomicOp, Cycles, InstructionClass};
-use crate::platform::PlatformModel;
+use crate::platform::{PlatformCapabilities, PlatformModel};
 use ahash::AHashMap;
 
 /// RISC-V RV32I timing model (base integer ISA)
@@ -51,6 +51,14 @@ impl RV32IModel {
         PlatformModel {
             name: "RISC-V RV32I".to_string(),
             cpu_frequency_mhz: 100,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: false,
+                hw_multiply: false,
+                hw_divide: false,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -114,6 +122,14 @@ impl RV32IMACModel {
         PlatformModel {
             name: "RISC-V RV32IMAC".to_string(),
             cpu_frequency_mhz: 320,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: false,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
             instruction_timings: timings,
         }
     }
@@ -177,6 +193,14 @@ impl RV32GCModel {
         PlatformModel {
             name: "RISC-V RV32GC".to_string(),
             cpu_frequency_mhz: 1000,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -240,6 +264,14 @@ impl RV64GCModel {
         PlatformModel {
             name: "RISC-V RV64GC".to_string(),
             cpu_frequency_mhz: 1500,
+            issue_width: 1,
+            fetch_width: 2,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: true,
+            },
             instruction_timings: timings,
         }
     }
@@ -0,0 +1,82 @@
+use crate::analysis::timing::{AccessType, AtomicOp, Cycles, InstructionClass};
+use crate::platform::{PlatformCapabilities, PlatformModel};
+use ahash::AHashMap;
+
+/// WebAssembly wasm32 timing model (abstract engine cycles)
+pub struct Wasm32Model;
+
+impl Wasm32Model {
+    /// Create wasm32 timing model
+    ///
+    /// Costs are abstract per-opcode-class units for a compiling (JIT/AOT)
+    /// engine, not a specific CPU. The nominal 1000 MHz frequency makes one
+    /// unit equal to 1 ns so results stay comparable between runs.
+    pub fn new() -> PlatformModel {
+        let mut timings = AHashMap::new();
+
+        // Integer arithmetic (i32/i64.add, sub, mul)
+        timings.insert(InstructionClass::Add, Cycles::new(1));
+        timings.insert(InstructionClass::Sub, Cycles::new(1));
+        timings.insert(InstructionClass::Mul, Cycles::range(1, 3));
+        // Division includes the engine's divide-by-zero / overflow trap checks
+        timings.insert(InstructionClass::Div, Cycles::range(10, 40));
+        timings.insert(InstructionClass::Rem, Cycles::range(10, 40));
+
+        // Floating point (f32/f64)
+        timings.insert(InstructionClass::FAdd, Cycles::range(2, 4));
+        timings.insert(InstructionClass::FSub, Cycles::range(2, 4));
+        timings.insert(InstructionClass::FMul, Cycles::range(3, 5));
+        timings.insert(InstructionClass::FDiv, Cycles::range(10, 20));
+
+        // Logic
+        timings.insert(InstructionClass::And, Cycles::new(1));
+        timings.insert(InstructionClass::Or, Cycles::new(1));
+        timings.insert(InstructionClass::Xor, Cycles::new(1));
+        timings.insert(InstructionClass::Shl, Cycles::new(1));
+        timings.insert(InstructionClass::Shr, Cycles::new(1));
+
+        // Linear memory access (includes bounds check when not elided)
+        timings.insert(InstructionClass::Load(AccessType::Ram), Cycles::range(2, 4));
+        timings.insert(
+            InstructionClass::Store(AccessType::Ram),
+            Cycles::range(2, 4),
+        );
+        timings.insert(InstructionClass::Load(AccessType::Stack), Cycles::new(1));
+        timings.insert(InstructionClass::Store(AccessType::Stack), Cycles::new(1));
+
+        // Control flow (br, br_if, br_table, call, return)
+        timings.insert(InstructionClass::Branch, Cycles::range(1, 3));
+        timings.insert(InstructionClass::Call, Cycles::range(5, 15)); // call_indirect signature check
+        timings.insert(InstructionClass::Ret, Cycles::range(2, 5));
+
+        // Atomics (threads proposal)
+        timings.insert(
+            InstructionClass::Atomic(AtomicOp::Load),
+            Cycles::range(4, 10),
+        );
+        timings.insert(
+            InstructionClass::Atomic(AtomicOp::Store),
+            Cycles::range(4, 10),
+        );
+        timings.insert(
+            InstructionClass::Atomic(AtomicOp::Add),
+            Cycles::range(10, 30),
+        );
+
+        timings.insert(InstructionClass::Other, Cycles::new(1));
+
+        PlatformModel {
+            name: "WebAssembly wasm32".to_string(),
+            cpu_frequency_mhz: 1000,
+            issue_width: 1,
+            fetch_width: 1,
+            capabilities: PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: false,
+            },
+            instruction_timings: timings,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Deadline Monotonic scheduling
+//!
+//! Fixed priorities by relative deadline, shortest first. Optimal among
+//! fixed-priority assignments when deadlines may be shorter than periods,
+//! where rate-monotonic priorities are not. Without a utilization bound that
+//! holds for such deadlines, schedulability is decided by exact response-time
+//! analysis alone.
+
+use crate::scheduling::{
+    blocking_time, infeasible_deadlines, unbounded_arrivals, RMAScheduler, SchedulabilityResult,
+    Task,
+};
+
+/// Deadline Monotonic scheduler
+pub struct DMScheduler;
+
+impl DMScheduler {
+    /// Perform DM schedulability test
+    pub fn schedulability_test(tasks: &[Task]) -> SchedulabilityResult {
+        let unbounded = unbounded_arrivals(tasks);
+        if !unbounded.is_empty() {
+            return SchedulabilityResult::UnboundedArrivals { tasks: unbounded };
+        }
+
+        let infeasible = infeasible_deadlines(tasks);
+        if !infeasible.is_empty() {
+            return SchedulabilityResult::InfeasibleDeadlines { tasks: infeasible };
+        }
+
+        let ordered = Self::deadline_monotonic_order(tasks);
+        for (i, task) in ordered.iter().enumerate() {
+            let blocking_us = blocking_time(task, &ordered);
+            let response_time =
+                RMAScheduler::calculate_response_time(task, &ordered[..i], blocking_us);
+            let deadline = Self::relative_deadline(task);
+
+            if response_time > deadline {
+                return SchedulabilityResult::Unschedulable {
+                    failing_task: task.name.clone(),
+                    response_time,
+                    deadline,
+                };
+            }
+        }
+
+        SchedulabilityResult::Schedulable
+    }
+
+    /// Worst-case response time of every periodic task, highest priority first
+    ///
+    /// Iteration stops once a response time exceeds the task's deadline, so
+    /// values past the deadline are lower bounds.
+    pub fn response_times(tasks: &[Task]) -> Vec<(String, f64)> {
+        let ordered = Self::deadline_monotonic_order(tasks);
+
+        ordered
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let blocking_us = blocking_time(task, &ordered);
+                let response_time =
+                    RMAScheduler::calculate_response_time(task, &ordered[..i], blocking_us);
+                (task.name.clone(), response_time)
+            })
+            .collect()
+    }
+
+    /// Assign priorities based on DM (shorter deadline = higher priority)
+    pub fn assign_priorities(tasks: &mut [Task]) {
+        // Sort by deadline, use task name as tiebreaker for deterministic ordering
+        tasks.sort_by(|a, b| {
+            Self::relative_deadline(a)
+                .partial_cmp(&Self::relative_deadline(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        // Assign priorities (0 = highest)
+        for (i, task) in tasks.iter_mut().enumerate() {
+            task.priority = Some(i as u8);
+        }
+    }
+
+    /// Periodic and sporadic tasks sorted by relative deadline
+    ///
+    /// Uses task name as tiebreaker for deterministic ordering.
+    fn deadline_monotonic_order(tasks: &[Task]) -> Vec<&Task> {
+        let mut ordered: Vec<_> = tasks
+            .iter()
+            .filter(|t| t.arrival_period_us().is_some())
+            .collect();
+        ordered.sort_by(|a, b| {
+            Self::relative_deadline(a)
+                .partial_cmp(&Self::relative_deadline(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        ordered
+    }
+
+    /// Relative deadline of a task, defaulting to its period
+    fn relative_deadline(task: &Task) -> f64 {
+        task.deadline_us
+            .unwrap_or_else(|| task.arrival_period_us().unwrap_or(f64::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
+
+    fn task(name: &str, wcet_us: f64, period_us: f64, deadline_us: f64) -> Task {
+        Task {
+            deadline_us: Some(deadline_us),
+            ..periodic_task(name, wcet_us, period_us)
+        }
+    }
+
+    #[test]
+    fn test_dm_schedules_constrained_deadlines_rma_cannot() {
+        // The alarm has the longer period but must finish within 3us of release
+        let tasks = vec![task("control", 3.0, 5.0, 5.0), task("alarm", 2.0, 8.0, 3.0)];
+
+        assert_eq!(
+            RMAScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Unschedulable {
+                failing_task: "alarm".to_string(),
+                response_time: 5.0,
+                deadline: 3.0,
+            }
+        );
+        assert_eq!(
+            DMScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Schedulable
+        );
+
+        let response_times = DMScheduler::response_times(&tasks);
+        assert_eq!(response_times[0], ("alarm".to_string(), 2.0));
+        assert_eq!(response_times[1], ("control".to_string(), 5.0));
+    }
+
+    #[test]
+    fn test_dm_reports_failing_task() {
+        let tasks = vec![task("a", 2.0, 4.0, 3.0), task("b", 3.0, 6.0, 4.0)];
+
+        assert_eq!(
+            DMScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Unschedulable {
+                failing_task: "b".to_string(),
+                response_time: 5.0,
+                deadline: 4.0,
+            }
+        );
+
+        let mut tasks = tasks;
+        tasks.reverse();
+        DMScheduler::assign_priorities(&mut tasks);
+        assert_eq!(tasks[0].name, "a");
+        assert_eq!(tasks[0].priority, Some(0));
+        assert_eq!(tasks[1].priority, Some(1));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::scheduling::{rma::SchedulabilityResult, Task};
+use crate::scheduling::{
+    infeasible_deadlines, rma::SchedulabilityResult, unbounded_arrivals, Task,
+};
 
 /// Earliest Deadline First scheduler
 pub struct EDFScheduler;
@@ -6,8 +8,21 @@ pub struct EDFScheduler;
 impl EDFScheduler {
     /// Perform EDF schedulability test
     pub fn schedulability_test(tasks: &[Task]) -> SchedulabilityResult {
-        // Filter tasks with periods
-        let periodic_tasks: Vec<_> = tasks.iter().filter(|t| t.period_us.is_some()).collect();
+        let unbounded = unbounded_arrivals(tasks);
+        if !unbounded.is_empty() {
+            return SchedulabilityResult::UnboundedArrivals { tasks: unbounded };
+        }
+
+        let infeasible = infeasible_deadlines(tasks);
+        if !infeasible.is_empty() {
+            return SchedulabilityResult::InfeasibleDeadlines { tasks: infeasible };
+        }
+
+        // Filter tasks with periods (sporadic tasks at their maximum rate)
+        let periodic_tasks: Vec<_> = tasks
+            .iter()
+            .filter(|t| t.arrival_period_us().is_some())
+            .collect();
 
         if periodic_tasks.is_empty() {
             return SchedulabilityResult::Schedulable;
@@ -16,7 +31,7 @@ impl EDFScheduler {
         // EDF schedulability: U ≤ 1.0
         let total_utilization: f64 = periodic_tasks
             .iter()
-            .map(|t| t.wcet_us / t.period_us.unwrap())
+            .map(|t| t.wcet_us / t.arrival_period_us().unwrap())
             .sum();
 
         if total_utilization <= 1.0 {
@@ -36,8 +51,7 @@ impl EDFScheduler {
     pub fn calculate_utilization(tasks: &[Task]) -> f64 {
         tasks
             .iter()
-            .filter(|t| t.period_us.is_some())
-            .map(|t| t.wcet_us / t.period_us.unwrap())
+            .filter_map(|t| t.arrival_period_us().map(|period| t.wcet_us / period))
             .sum()
     }
 
@@ -90,32 +104,13 @@ pub struct TaskInstance {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
 
     #[test]
     fn test_edf_schedulable() {
         let tasks = vec![
-            Task {
-                name: "task1".to_string(),
-                function: "func1".to_string(),
-                wcet_cycles: 3000,
-                wcet_us: 300.0,
-                period_us: Some(1000.0),
-                deadline_us: Some(1000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
-            Task {
-                name: "task2".to_string(),
-                function: "func2".to_string(),
-                wcet_cycles: 4000,
-                wcet_us: 400.0,
-                period_us: Some(2000.0),
-                deadline_us: Some(2000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
+            periodic_task("task1", 300.0, 1000.0),
+            periodic_task("task2", 400.0, 2000.0),
         ];
 
         let result = EDFScheduler::schedulability_test(&tasks);
@@ -128,28 +123,8 @@ mod tests {
     #[test]
     fn test_edf_unschedulable() {
         let tasks = vec![
-            Task {
-                name: "task1".to_string(),
-                function: "func1".to_string(),
-                wcet_cycles: 9000,
-                wcet_us: 900.0,
-                period_us: Some(1000.0),
-                deadline_us: Some(1000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
-            Task {
-                name: "task2".to_string(),
-                function: "func2".to_string(),
-                wcet_cycles: 3000,
-                wcet_us: 300.0,
-                period_us: Some(2000.0),
-                deadline_us: Some(2000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
+            periodic_task("task1", 900.0, 1000.0),
+            periodic_task("task2", 300.0, 2000.0),
         ];
 
         let result = EDFScheduler::schedulability_test(&tasks);
@@ -158,17 +133,7 @@ mod tests {
 
     #[test]
     fn test_task_instance_generation() {
-        let tasks = vec![Task {
-            name: "task1".to_string(),
-            function: "func1".to_string(),
-            wcet_cycles: 1000,
-            wcet_us: 100.0,
-            period_us: Some(1000.0),
-            deadline_us: Some(1000.0),
-            priority: None,
-            preemptible: true,
-            dependencies: vec![],
-        }];
+        let tasks = vec![periodic_task("task1", 100.0, 1000.0)];
 
         let instances = EDFScheduler::generate_task_instances(&tasks, 3000.0);
         assert_eq!(instances.len(), 3);
@@ -0,0 +1,269 @@
+//! Multi-rate frame scheduling
+//!
+//! Lays out harmonic task sets as a cyclic executive: a major frame split into
+//! fixed-length minor frames, with each task job pinned to a minor frame.
+
+use crate::scheduling::{ScheduleTimeline, Task, TimeSlot};
+use serde::{Deserialize, Serialize};
+
+/// Minor frame within a major frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinorFrame {
+    pub index: usize,
+    pub start_us: f64,
+    pub duration_us: f64,
+    pub tasks: Vec<String>,
+    pub load_us: f64,
+}
+
+impl MinorFrame {
+    /// Unused time remaining in the frame
+    pub fn slack_us(&self) -> f64 {
+        self.duration_us - self.load_us
+    }
+}
+
+/// Major/minor frame schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameSchedule {
+    pub minor_frame_us: f64,
+    pub major_frame_us: f64,
+    pub frames: Vec<MinorFrame>,
+}
+
+impl FrameSchedule {
+    /// Convert the frame layout into a flat timeline with idle padding
+    pub fn to_timeline(&self, tasks: &[Task]) -> ScheduleTimeline {
+        let mut slots = Vec::new();
+
+        for frame in &self.frames {
+            let mut current_time = frame.start_us;
+
+            for name in &frame.tasks {
+                if let Some(task) = tasks.iter().find(|t| &t.name == name) {
+                    slots.push(TimeSlot {
+                        start_us: current_time,
+                        duration_us: task.wcet_us,
+                        task: task.name.clone(),
+                        preemptible: task.preemptible,
+                    });
+                    current_time += task.wcet_us;
+                }
+            }
+
+            let frame_end = frame.start_us + frame.duration_us;
+            if current_time < frame_end {
+                slots.push(TimeSlot {
+                    start_us: current_time,
+                    duration_us: frame_end - current_time,
+                    task: "IDLE".to_string(),
+                    preemptible: true,
+                });
+            }
+        }
+
+        ScheduleTimeline {
+            hyperperiod_us: self.major_frame_us,
+            slots,
+            idle: None,
+        }
+    }
+}
+
+/// Builder for multi-rate frame schedules
+pub struct FrameScheduleBuilder {
+    tasks: Vec<Task>,
+    minor_frame_us: Option<f64>,
+}
+
+impl FrameScheduleBuilder {
+    /// Create builder for a task set
+    pub fn new(tasks: &[Task]) -> Self {
+        Self {
+            tasks: tasks.to_vec(),
+            minor_frame_us: None,
+        }
+    }
+
+    /// Set minor frame length (defaults to the shortest task period)
+    pub fn with_minor_frame(mut self, minor_frame_us: f64) -> Self {
+        self.minor_frame_us = Some(minor_frame_us);
+        self
+    }
+
+    /// Lay out tasks into minor frames and validate that each job fits
+    pub fn build(&self) -> Result<FrameSchedule, String> {
+        let mut periodic: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|t| t.period_us.is_some())
+            .collect();
+
+        if periodic.is_empty() {
+            return Err("No periodic tasks to schedule".to_string());
+        }
+
+        // Shorter period first, name as tiebreaker for deterministic layout
+        periodic.sort_by(|a, b| {
+            a.period_us
+                .partial_cmp(&b.period_us)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        for pair in periodic.windows(2) {
+            let shorter = pair[0].period_us.unwrap();
+            let longer = pair[1].period_us.unwrap();
+            if !Self::divides(shorter, longer) {
+                return Err(format!(
+                    "Periods are not harmonic: {} ({}us) does not divide {} ({}us)",
+                    pair[0].name, shorter, pair[1].name, longer
+                ));
+            }
+        }
+
+        let shortest_period = periodic[0].period_us.unwrap();
+        let major_frame_us = periodic.last().unwrap().period_us.unwrap();
+        let minor_frame_us = self.minor_frame_us.unwrap_or(shortest_period);
+
+        if minor_frame_us <= 0.0 || !Self::divides(minor_frame_us, shortest_period) {
+            return Err(format!(
+                "Minor frame {}us must evenly divide the shortest period {}us",
+                minor_frame_us, shortest_period
+            ));
+        }
+
+        let frame_count = (major_frame_us / minor_frame_us).round() as usize;
+        let mut frames: Vec<MinorFrame> = (0..frame_count)
+            .map(|index| MinorFrame {
+                index,
+                start_us: index as f64 * minor_frame_us,
+                duration_us: minor_frame_us,
+                tasks: Vec::new(),
+                load_us: 0.0,
+            })
+            .collect();
+
+        for task in periodic {
+            let period = task.period_us.unwrap();
+            let deadline = task.deadline_us.unwrap_or(period);
+            let stride = (period / minor_frame_us).round() as usize;
+
+            if task.wcet_us > minor_frame_us {
+                return Err(format!(
+                    "Task {} (WCET {}us) does not fit in a {}us minor frame",
+                    task.name, task.wcet_us, minor_frame_us
+                ));
+            }
+
+            // Pick the frame offset within the period that keeps the busiest
+            // frame lightest, restricted to offsets that meet the deadline
+            let best_offset = (0..stride)
+                .filter(|offset| (*offset + 1) as f64 * minor_frame_us <= deadline + 1e-9)
+                .filter(|offset| {
+                    (*offset..frame_count)
+                        .step_by(stride)
+                        .all(|i| frames[i].load_us + task.wcet_us <= minor_frame_us + 1e-9)
+                })
+                .min_by(|a, b| {
+                    let load = |offset: usize| {
+                        (offset..frame_count)
+                            .step_by(stride)
+                            .map(|i| frames[i].load_us)
+                            .fold(0.0, f64::max)
+                    };
+                    load(*a)
+                        .partial_cmp(&load(*b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "Task {} does not fit in any minor frame before its deadline ({}us)",
+                        task.name, deadline
+                    )
+                })?;
+
+            for i in (best_offset..frame_count).step_by(stride) {
+                frames[i].tasks.push(task.name.clone());
+                frames[i].load_us += task.wcet_us;
+            }
+        }
+
+        Ok(FrameSchedule {
+            minor_frame_us,
+            major_frame_us,
+            frames,
+        })
+    }
+
+    /// Check whether `divisor` evenly divides `value`
+    fn divides(divisor: f64, value: f64) -> bool {
+        let ratio = value / divisor;
+        (ratio - ratio.round()).abs() < 1e-6
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
+
+    fn task(name: &str, wcet_us: f64, period_us: f64) -> Task {
+        Task {
+            wcet_cycles: wcet_us as u64,
+            preemptible: false,
+            ..periodic_task(name, wcet_us, period_us)
+        }
+    }
+
+    #[test]
+    fn test_harmonic_frame_layout() {
+        let tasks = vec![
+            task("fast", 200.0, 1000.0),
+            task("medium", 300.0, 2000.0),
+            task("slow", 300.0, 4000.0),
+        ];
+
+        let schedule = FrameScheduleBuilder::new(&tasks).build().unwrap();
+
+        assert_eq!(schedule.minor_frame_us, 1000.0);
+        assert_eq!(schedule.major_frame_us, 4000.0);
+        assert_eq!(schedule.frames.len(), 4);
+        assert!(schedule
+            .frames
+            .iter()
+            .all(|f| f.tasks.contains(&"fast".to_string())));
+
+        let medium_count = schedule
+            .frames
+            .iter()
+            .filter(|f| f.tasks.contains(&"medium".to_string()))
+            .count();
+        assert_eq!(medium_count, 2);
+
+        // medium and slow are spread over different frames
+        assert!(schedule.frames.iter().all(|f| f.load_us <= 500.0));
+
+        let timeline = schedule.to_timeline(&tasks);
+        let total: f64 = timeline.slots.iter().map(|s| s.duration_us).sum();
+        assert!((total - 4000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_non_harmonic_rejected() {
+        let tasks = vec![task("a", 100.0, 1000.0), task("b", 100.0, 1500.0)];
+        assert!(FrameScheduleBuilder::new(&tasks).build().is_err());
+    }
+
+    #[test]
+    fn test_overloaded_frame_rejected() {
+        let tasks = vec![task("a", 600.0, 1000.0), task("b", 600.0, 1000.0)];
+        assert!(FrameScheduleBuilder::new(&tasks).build().is_err());
+
+        let tasks = vec![task("a", 200.0, 1000.0)];
+        assert!(FrameScheduleBuilder::new(&tasks)
+            .with_minor_frame(300.0)
+            .build()
+            .is_err());
+    }
+}
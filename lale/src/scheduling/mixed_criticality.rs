@@ -0,0 +1,190 @@
+//! Mixed-criticality scheduling with Adaptive Mixed Criticality (AMC)
+//!
+//! Tasks carry two budgets: an optimistic one for low-criticality (LO) mode
+//! and the pessimistic WCET for high-criticality (HI) mode. The system runs
+//! in LO mode until a HI task overruns its LO budget, then drops all LO tasks
+//! and guarantees only HI tasks their full WCET. The response-time test is
+//! AMC-rtb (Baruah, Burns & Davis 2011) over rate-monotonic priorities.
+
+use crate::scheduling::{
+    unbounded_arrivals, Criticality, RMAScheduler, SchedulabilityResult, Task,
+};
+use serde::{Deserialize, Serialize};
+
+/// Fixed-point iterations before giving up on convergence
+const MAX_ITERATIONS: usize = 100;
+
+/// Worst-case response times of one task in each mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModeResponseTimes {
+    pub task: String,
+
+    /// Response time in LO mode, every task at its LO budget
+    pub lo_us: f64,
+
+    /// Response time across the switch to HI mode; HI tasks only
+    pub hi_us: Option<f64>,
+}
+
+/// AMC-rtb schedulability test
+pub struct AMCScheduler;
+
+impl AMCScheduler {
+    /// Check that every task meets its deadline in LO mode and every HI task
+    /// also meets it across the mode switch
+    pub fn schedulability_test(tasks: &[Task]) -> SchedulabilityResult {
+        let unbounded = unbounded_arrivals(tasks);
+        if !unbounded.is_empty() {
+            return SchedulabilityResult::UnboundedArrivals { tasks: unbounded };
+        }
+
+        let ordered = RMAScheduler::rate_monotonic_order(tasks);
+        for (task, response) in ordered.iter().zip(Self::response_times(tasks)) {
+            let deadline = Self::deadline(task);
+            let worst = response.hi_us.unwrap_or(0.0).max(response.lo_us);
+            if worst > deadline {
+                return SchedulabilityResult::Unschedulable {
+                    failing_task: response.task,
+                    response_time: worst,
+                    deadline,
+                };
+            }
+        }
+
+        SchedulabilityResult::Schedulable
+    }
+
+    /// Response times of every periodic task, highest priority first
+    ///
+    /// Iteration stops once a response time exceeds the task's deadline, so
+    /// values past the deadline are lower bounds.
+    pub fn response_times(tasks: &[Task]) -> Vec<ModeResponseTimes> {
+        let ordered = RMAScheduler::rate_monotonic_order(tasks);
+
+        ordered
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let higher = &ordered[..i];
+                let deadline = Self::deadline(task);
+
+                // LO mode: everyone runs at most their LO budget
+                let lo_us = Self::fixed_point(task.lo_budget_us(), deadline, |r| {
+                    higher
+                        .iter()
+                        .map(|hp| Self::releases(r, hp) * hp.lo_budget_us())
+                        .sum()
+                });
+
+                // HI mode: HI tasks at full WCET; LO tasks only interfere
+                // until the switch, which happens before `lo_us`
+                let hi_us = (task.criticality_level() == Criticality::Hi).then(|| {
+                    let lo_interference: f64 = higher
+                        .iter()
+                        .filter(|hp| hp.criticality_level() == Criticality::Lo)
+                        .map(|hp| Self::releases(lo_us, hp) * hp.lo_budget_us())
+                        .sum();
+                    Self::fixed_point(task.wcet_us + lo_interference, deadline, |r| {
+                        higher
+                            .iter()
+                            .filter(|hp| hp.criticality_level() == Criticality::Hi)
+                            .map(|hp| Self::releases(r, hp) * hp.wcet_us)
+                            .sum()
+                    })
+                });
+
+                ModeResponseTimes {
+                    task: task.name.clone(),
+                    lo_us,
+                    hi_us,
+                }
+            })
+            .collect()
+    }
+
+    /// Releases of a higher-priority task within a window
+    fn releases(window_us: f64, task: &Task) -> f64 {
+        (window_us / task.arrival_period_us().unwrap()).ceil()
+    }
+
+    fn deadline(task: &Task) -> f64 {
+        task.deadline_us
+            .unwrap_or(task.arrival_period_us().unwrap())
+    }
+
+    /// Least `r = base + interference(r)`, or the first iterate past `deadline`
+    fn fixed_point(base: f64, deadline: f64, interference: impl Fn(f64) -> f64) -> f64 {
+        let mut r = base;
+        for _ in 0..MAX_ITERATIONS {
+            let next = base + interference(r);
+            if (next - r).abs() < 0.001 || next > deadline {
+                return next;
+            }
+            r = next;
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
+
+    fn task(name: &str, criticality: Criticality, period_us: f64, lo: f64, hi: f64) -> Task {
+        Task {
+            deadline_us: None,
+            criticality: Some(criticality),
+            wcet_lo_us: Some(lo),
+            ..periodic_task(name, hi, period_us)
+        }
+    }
+
+    #[test]
+    fn test_amc_accepts_set_plain_rta_rejects() {
+        let tasks = vec![
+            task("sensor", Criticality::Hi, 10.0, 3.0, 6.0),
+            task("logger", Criticality::Lo, 15.0, 5.0, 5.0),
+            task("control", Criticality::Hi, 30.0, 4.0, 6.0),
+        ];
+
+        // Everything at its HI budget overloads the processor
+        assert!(matches!(
+            RMAScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Unschedulable { .. }
+        ));
+
+        let response = AMCScheduler::response_times(&tasks);
+        assert_eq!(response[1].lo_us, 8.0);
+        assert_eq!(response[1].hi_us, None);
+        // LO: 4 + 2 * 3 + 5; HI: 6 + 3 * 6 + one logger job before the switch
+        assert_eq!(response[2].lo_us, 15.0);
+        assert_eq!(response[2].hi_us, Some(29.0));
+
+        assert_eq!(
+            AMCScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Schedulable
+        );
+    }
+
+    #[test]
+    fn test_amc_rejects_hi_mode_overrun() {
+        let tasks = vec![
+            task("sensor", Criticality::Hi, 10.0, 3.0, 9.0),
+            task("logger", Criticality::Lo, 15.0, 5.0, 5.0),
+            task("control", Criticality::Hi, 30.0, 4.0, 6.0),
+        ];
+
+        match AMCScheduler::schedulability_test(&tasks) {
+            SchedulabilityResult::Unschedulable {
+                failing_task,
+                deadline,
+                ..
+            } => {
+                assert_eq!(failing_task, "control");
+                assert_eq!(deadline, 30.0);
+            }
+            other => panic!("expected unschedulable, got {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,23 @@
+pub mod dm;
 pub mod edf;
+pub mod frame;
+pub mod mixed_criticality;
 pub mod rma;
 pub mod static_gen;
+pub mod system;
 pub mod tasks;
 
+pub use dm::DMScheduler;
 pub use edf::{EDFScheduler, TaskInstance};
+pub use frame::{FrameSchedule, FrameScheduleBuilder, MinorFrame};
+pub use mixed_criticality::{AMCScheduler, ModeResponseTimes};
 pub use rma::{RMAScheduler, SchedulabilityResult};
-pub use static_gen::{ScheduleTimeline, StaticScheduleGenerator, TimeSlot};
-pub use tasks::{Task, TaskExtractor};
+pub use static_gen::{
+    CompactSchedule, IdleReport, PatternSlot, ScheduleTimeline, SlackPoint, SlotRun,
+    StaticScheduleGenerator, TimeSlot,
+};
+pub use system::{SystemSpec, TaskSpec};
+pub use tasks::{
+    blocking_time, infeasible_deadlines, relative_wcets, typical_utilization, unbounded_arrivals,
+    Criticality, ResourceUsage, Task, TaskExtractor,
+};
@@ -1,4 +1,6 @@
-use crate::scheduling::Task;
+use crate::scheduling::static_gen::TIME_EPSILON_US;
+use crate::scheduling::{blocking_time, infeasible_deadlines, unbounded_arrivals, Task};
+use ahash::AHashMap;
 
 /// Rate Monotonic Analysis result
 #[derive(Debug, Clone, PartialEq)]
@@ -9,6 +11,14 @@ pub enum SchedulabilityResult {
         response_time: f64,
         deadline: f64,
     },
+    /// Aperiodic tasks without an arrival bound are mixed into a periodic set
+    UnboundedArrivals {
+        tasks: Vec<String>,
+    },
+    /// Tasks whose WCET alone exceeds their deadline
+    InfeasibleDeadlines {
+        tasks: Vec<String>,
+    },
 }
 
 /// Rate Monotonic Analysis scheduler
@@ -17,40 +27,55 @@ pub struct RMAScheduler;
 impl RMAScheduler {
     /// Perform RMA schedulability test
     pub fn schedulability_test(tasks: &[Task]) -> SchedulabilityResult {
-        // Filter tasks with periods
-        let mut periodic_tasks: Vec<_> = tasks.iter().filter(|t| t.period_us.is_some()).collect();
+        let unbounded = unbounded_arrivals(tasks);
+        if !unbounded.is_empty() {
+            return SchedulabilityResult::UnboundedArrivals { tasks: unbounded };
+        }
+
+        let infeasible = infeasible_deadlines(tasks);
+        if !infeasible.is_empty() {
+            return SchedulabilityResult::InfeasibleDeadlines { tasks: infeasible };
+        }
+
+        let periodic_tasks = Self::rate_monotonic_order(tasks);
 
         if periodic_tasks.is_empty() {
             return SchedulabilityResult::Schedulable;
         }
 
-        // Sort by period (shorter period = higher priority)
-        // Use task name as tiebreaker for deterministic ordering
-        periodic_tasks.sort_by(|a, b| {
-            a.period_us
-                .partial_cmp(&b.period_us)
-                .unwrap_or(std::cmp::Ordering::Equal)
-                .then_with(|| a.name.cmp(&b.name))
-        });
-
-        // Liu & Layland utilization bound test
-        let n = periodic_tasks.len() as f64;
-        let utilization_bound = n * (2.0_f64.powf(1.0 / n) - 1.0);
+        // Harmonic sets are schedulable up to full utilization, otherwise
+        // fall back to the Liu & Layland bound
+        let periods: Vec<f64> = periodic_tasks
+            .iter()
+            .filter_map(|t| t.arrival_period_us())
+            .collect();
+        let utilization_bound = Self::bound_for_periods(&periods);
 
         let total_utilization: f64 = periodic_tasks
             .iter()
-            .map(|t| t.wcet_us / t.period_us.unwrap())
+            .map(|t| t.wcet_us / t.arrival_period_us().unwrap())
             .sum();
 
-        // Quick test: if utilization is below bound, definitely schedulable
-        if total_utilization <= utilization_bound {
+        // Quick test: if utilization is below bound, definitely schedulable.
+        // The bound assumes deadlines at the period and no blocking, so
+        // other sets always go through the exact analysis.
+        let bound_applies = periodic_tasks.iter().all(|t| {
+            t.resources.is_empty()
+                && t.deadline_us
+                    .is_none_or(|deadline| deadline >= t.arrival_period_us().unwrap())
+        });
+        if bound_applies && total_utilization <= utilization_bound {
             return SchedulabilityResult::Schedulable;
         }
 
         // Exact response time analysis
         for (i, task) in periodic_tasks.iter().enumerate() {
-            let response_time = Self::calculate_response_time(task, &periodic_tasks[..i]);
-            let deadline = task.deadline_us.unwrap_or(task.period_us.unwrap());
+            let blocking_us = blocking_time(task, &periodic_tasks);
+            let response_time =
+                Self::calculate_response_time(task, &periodic_tasks[..i], blocking_us);
+            let deadline = task
+                .deadline_us
+                .unwrap_or(task.arrival_period_us().unwrap());
 
             if response_time > deadline {
                 return SchedulabilityResult::Unschedulable {
@@ -64,45 +89,216 @@ impl RMAScheduler {
         SchedulabilityResult::Schedulable
     }
 
+    /// Worst-case response time of every periodic task, highest priority first
+    ///
+    /// Iteration stops once a response time exceeds the task's deadline, so
+    /// values past the deadline are lower bounds.
+    pub fn response_times(tasks: &[Task]) -> Vec<(String, f64)> {
+        let periodic_tasks = Self::rate_monotonic_order(tasks);
+
+        periodic_tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let blocking_us = blocking_time(task, &periodic_tasks);
+                let response_time =
+                    Self::calculate_response_time(task, &periodic_tasks[..i], blocking_us);
+                (task.name.clone(), response_time)
+            })
+            .collect()
+    }
+
+    /// Worst-case response time of every periodic task, by task name
+    ///
+    /// Exact response-time analysis: starting from `R_i = C_i + B_i`, iterate
+    /// `R_i = C_i + B_i + sum over hp(i) of ceil(R_i / T_j) * C_j` to a fixed
+    /// point or until `R_i` exceeds the deadline `D_i`. `B_i` is the task's
+    /// blocking under the Priority Ceiling Protocol.
+    pub fn response_time_analysis(tasks: &[Task]) -> AHashMap<String, f64> {
+        Self::response_times(tasks).into_iter().collect()
+    }
+
+    /// Periodic and sporadic tasks sorted by period (shorter period = higher priority)
+    ///
+    /// Sporadic tasks rank by minimum inter-arrival time. Uses task name as
+    /// tiebreaker for deterministic ordering.
+    pub(crate) fn rate_monotonic_order(tasks: &[Task]) -> Vec<&Task> {
+        let mut periodic_tasks: Vec<_> = tasks
+            .iter()
+            .filter(|t| t.arrival_period_us().is_some())
+            .collect();
+        periodic_tasks.sort_by(|a, b| {
+            a.arrival_period_us()
+                .partial_cmp(&b.arrival_period_us())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        periodic_tasks
+    }
+
+    /// Check if task periods are harmonic (each period divides the next)
+    pub fn is_harmonic(tasks: &[Task]) -> bool {
+        let periods: Vec<f64> = tasks.iter().filter_map(|t| t.arrival_period_us()).collect();
+        Self::periods_harmonic(&periods)
+    }
+
+    /// Utilization bound used by the quick test for a task set
+    ///
+    /// Returns 1.0 for harmonic period sets and the Liu & Layland bound
+    /// `n(2^(1/n) - 1)` otherwise.
+    pub fn utilization_bound(tasks: &[Task]) -> f64 {
+        let periods: Vec<f64> = tasks.iter().filter_map(|t| t.arrival_period_us()).collect();
+        Self::bound_for_periods(&periods)
+    }
+
+    /// Utilization bound for a set of periods
+    fn bound_for_periods(periods: &[f64]) -> f64 {
+        if periods.is_empty() || Self::periods_harmonic(periods) {
+            return 1.0;
+        }
+
+        let n = periods.len() as f64;
+        n * (2.0_f64.powf(1.0 / n) - 1.0)
+    }
+
+    /// Check if a set of periods is harmonic
+    fn periods_harmonic(periods: &[f64]) -> bool {
+        let mut sorted = periods.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        sorted.windows(2).all(|pair| {
+            if pair[0] <= 0.0 {
+                return false;
+            }
+            let ratio = pair[1] / pair[0];
+            (ratio - ratio.round()).abs() < 1e-6
+        })
+    }
+
+    /// Re-run the schedulability test with one task's WCET replaced
+    ///
+    /// Tasks not matching `task_name` are tested unchanged. If no task matches,
+    /// the result is the same as for the original task set.
+    pub fn test_with_modified(
+        tasks: &[Task],
+        task_name: &str,
+        new_wcet_us: f64,
+    ) -> SchedulabilityResult {
+        let modified: Vec<Task> = tasks
+            .iter()
+            .map(|t| {
+                let mut task = t.clone();
+                if task.name == task_name {
+                    task.wcet_us = new_wcet_us;
+                }
+                task
+            })
+            .collect();
+
+        Self::schedulability_test(&modified)
+    }
+
     /// Calculate response time for a task
-    fn calculate_response_time(task: &Task, higher_priority: &[&Task]) -> f64 {
-        let mut r = task.wcet_us;
+    ///
+    /// `blocking_us` is the longest the task can wait on lower-priority
+    /// tasks holding shared resources.
+    ///
+    /// Without release offsets the worst case is the critical instant, all
+    /// tasks released together. With offsets, a busy period can only start
+    /// at the release of the task or of a higher-priority task, so each of
+    /// those is tried as the start, with every other task released at its
+    /// earliest possible phase after it.
+    pub(crate) fn calculate_response_time(
+        task: &Task,
+        higher_priority: &[&Task],
+        blocking_us: f64,
+    ) -> f64 {
+        let phased = std::iter::once(&task)
+            .chain(higher_priority)
+            .any(|t| t.release_offset_us() > 0.0);
+        if !phased {
+            return Self::response_time_from(task, higher_priority, blocking_us, task);
+        }
+
+        std::iter::once(task)
+            .chain(higher_priority.iter().copied())
+            .map(|start| Self::response_time_from(task, higher_priority, blocking_us, start))
+            .fold(0.0, f64::max)
+    }
+
+    /// Response time of a task in a busy period starting at a release of `start`
+    fn response_time_from(
+        task: &Task,
+        higher_priority: &[&Task],
+        blocking_us: f64,
+        start: &Task,
+    ) -> f64 {
+        let own_phase = Self::release_phase(task, start);
+        let deadline = task
+            .deadline_us
+            .unwrap_or(task.arrival_period_us().unwrap());
+        let mut w = task.wcet_us + blocking_us;
         let max_iterations = 100;
 
         for _ in 0..max_iterations {
             let interference: f64 = higher_priority
                 .iter()
                 .map(|hp| {
-                    let period = hp.period_us.unwrap();
-                    (r / period).ceil() * hp.wcet_us
+                    let period = hp.arrival_period_us().unwrap();
+                    let phase = Self::release_phase(hp, start);
+                    ((w - phase).max(0.0) / period).ceil() * hp.wcet_us
                 })
                 .sum();
 
-            let new_r = task.wcet_us + interference;
+            let new_w = task.wcet_us + blocking_us + interference;
+            let response_time = (new_w - own_phase).max(task.wcet_us + blocking_us);
 
             // Check convergence
-            if (new_r - r).abs() < 0.001 {
-                return new_r;
+            if (new_w - w).abs() < 0.001 {
+                return response_time;
             }
 
             // Check if already failed
-            let deadline = task.deadline_us.unwrap_or(task.period_us.unwrap());
-            if new_r > deadline {
-                return new_r;
+            if response_time > deadline {
+                return response_time;
             }
 
-            r = new_r;
+            w = new_w;
+        }
+
+        (w - own_phase).max(task.wcet_us + blocking_us)
+    }
+
+    /// Earliest release of `task` at or after a release of `start`
+    ///
+    /// Releases of two periodic tasks are `gcd(T_a, T_b)`-aligned relative
+    /// to each other, so the closest one is the offset difference modulo
+    /// that. Sporadic tasks can be released at any time, i.e. together.
+    fn release_phase(task: &Task, start: &Task) -> f64 {
+        if task.name == start.name {
+            return 0.0;
         }
+        let (Some(period), Some(start_period)) = (task.period_us, start.period_us) else {
+            return 0.0;
+        };
 
-        r
+        let Some(step) = period_gcd(period, start_period) else {
+            return 0.0;
+        };
+        let phase = (task.release_offset_us() - start.release_offset_us()).rem_euclid(step);
+        if step - phase < TIME_EPSILON_US {
+            0.0
+        } else {
+            phase
+        }
     }
 
     /// Assign priorities based on RMA (shorter period = higher priority)
     pub fn assign_priorities(tasks: &mut [Task]) {
         // Sort by period, use task name as tiebreaker for deterministic ordering
         tasks.sort_by(|a, b| {
-            let period_a = a.period_us.unwrap_or(f64::MAX);
-            let period_b = b.period_us.unwrap_or(f64::MAX);
+            let period_a = a.arrival_period_us().unwrap_or(f64::MAX);
+            let period_b = b.arrival_period_us().unwrap_or(f64::MAX);
             period_a
                 .partial_cmp(&period_b)
                 .unwrap_or(std::cmp::Ordering::Equal)
@@ -119,41 +315,37 @@ impl RMAScheduler {
     pub fn calculate_utilization(tasks: &[Task]) -> f64 {
         tasks
             .iter()
-            .filter(|t| t.period_us.is_some())
-            .map(|t| t.wcet_us / t.period_us.unwrap())
+            .filter_map(|t| t.arrival_period_us().map(|period| t.wcet_us / period))
             .sum()
     }
 }
 
+/// Greatest common divisor of two periods in microseconds
+///
+/// `None` unless both are whole nanoseconds, in which case the caller
+/// should assume the tasks can be released together.
+fn period_gcd(a: f64, b: f64) -> Option<f64> {
+    let to_ns = |us: f64| {
+        let ns = (us * 1000.0).round();
+        (ns >= 1.0 && (us * 1000.0 - ns).abs() < TIME_EPSILON_US).then_some(ns as u64)
+    };
+    let (mut a, mut b) = (to_ns(a)?, to_ns(b)?);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Some(a as f64 / 1000.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
 
     #[test]
     fn test_rma_schedulable() {
         let tasks = vec![
-            Task {
-                name: "task1".to_string(),
-                function: "func1".to_string(),
-                wcet_cycles: 1000,
-                wcet_us: 100.0,
-                period_us: Some(1000.0),
-                deadline_us: Some(1000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
-            Task {
-                name: "task2".to_string(),
-                function: "func2".to_string(),
-                wcet_cycles: 2000,
-                wcet_us: 200.0,
-                period_us: Some(2000.0),
-                deadline_us: Some(2000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
+            periodic_task("task1", 100.0, 1000.0),
+            periodic_task("task2", 200.0, 2000.0),
         ];
 
         let result = RMAScheduler::schedulability_test(&tasks);
@@ -166,31 +358,261 @@ mod tests {
     #[test]
     fn test_rma_unschedulable() {
         let tasks = vec![
-            Task {
-                name: "task1".to_string(),
-                function: "func1".to_string(),
-                wcet_cycles: 9000,
-                wcet_us: 900.0,
-                period_us: Some(1000.0),
-                deadline_us: Some(1000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
-            Task {
-                name: "task2".to_string(),
-                function: "func2".to_string(),
-                wcet_cycles: 1800,
-                wcet_us: 900.0,
-                period_us: Some(2000.0),
-                deadline_us: Some(2000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
+            periodic_task("task1", 900.0, 1000.0),
+            periodic_task("task2", 900.0, 2000.0),
         ];
 
         let result = RMAScheduler::schedulability_test(&tasks);
         assert!(matches!(result, SchedulabilityResult::Unschedulable { .. }));
     }
+
+    #[test]
+    fn test_with_modified_wcet() {
+        let tasks = vec![
+            periodic_task("task1", 100.0, 1000.0),
+            periodic_task("task2", 200.0, 2000.0),
+        ];
+
+        let result = RMAScheduler::test_with_modified(&tasks, "task1", 950.0);
+        assert!(matches!(result, SchedulabilityResult::Unschedulable { .. }));
+
+        // Original task set is untouched
+        assert_eq!(tasks[0].wcet_us, 100.0);
+        assert_eq!(
+            RMAScheduler::test_with_modified(&tasks, "missing", 900.0),
+            SchedulabilityResult::Schedulable
+        );
+    }
+
+    #[test]
+    fn test_response_time_analysis_above_utilization_bound() {
+        // U = 0.814, above the Liu & Layland bound of 0.780 for three tasks
+        let tasks = vec![
+            periodic_task("sensor", 1.0, 4.0),
+            periodic_task("filter", 2.0, 6.0),
+            periodic_task("logger", 3.0, 13.0),
+        ];
+        assert!(
+            RMAScheduler::calculate_utilization(&tasks) > RMAScheduler::utilization_bound(&tasks)
+        );
+
+        // R_filter: 2 -> 2 + 1 = 3
+        // R_logger: 3 -> 3 + 1 + 2 = 6 -> 3 + 2 + 2 = 7 -> 3 + 2 + 4 = 9
+        //           -> 3 + 3 + 4 = 10
+        let response_times = RMAScheduler::response_time_analysis(&tasks);
+        assert_eq!(response_times.len(), 3);
+        assert_eq!(response_times["sensor"], 1.0);
+        assert_eq!(response_times["filter"], 3.0);
+        assert_eq!(response_times["logger"], 10.0);
+
+        assert_eq!(
+            RMAScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Schedulable
+        );
+    }
+
+    #[test]
+    fn test_constrained_deadline_skips_utilization_bound() {
+        let task = |name: &str, wcet_us: f64, period_us: f64, deadline_us: f64| Task {
+            deadline_us: Some(deadline_us),
+            ..periodic_task(name, wcet_us, period_us)
+        };
+
+        // U = 0.6 is within the harmonic bound, but the alarm misses its
+        // deadline behind the control task
+        let tasks = vec![
+            task("control", 2.0, 5.0, 5.0),
+            task("alarm", 2.0, 10.0, 3.0),
+        ];
+        assert_eq!(
+            RMAScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Unschedulable {
+                failing_task: "alarm".to_string(),
+                response_time: 4.0,
+                deadline: 3.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_low_priority_critical_section_blocks_high_priority_task() {
+        use crate::scheduling::ResourceUsage;
+
+        let task = |name: &str, wcet_us: f64, period_us: f64, section_us: f64| Task {
+            resources: vec![ResourceUsage {
+                resource_id: "spi_bus".to_string(),
+                critical_section_us: section_us,
+            }],
+            ..periodic_task(name, wcet_us, period_us)
+        };
+
+        let short = vec![
+            task("control", 2.0, 5.0, 1.0),
+            task("logger", 3.0, 20.0, 1.0),
+        ];
+        // Control waits at most 1us for the logger to release the bus
+        assert_eq!(blocking_time(&short[0], &short), 1.0);
+        assert_eq!(
+            RMAScheduler::schedulability_test(&short),
+            SchedulabilityResult::Schedulable
+        );
+
+        // The logger now holds the bus for 4us; control can wait that long
+        let long = vec![
+            task("control", 2.0, 5.0, 1.0),
+            task("logger", 3.0, 20.0, 4.0),
+        ];
+        let by_priority = RMAScheduler::rate_monotonic_order(&long);
+        assert_eq!(blocking_time(&long[0], &by_priority), 4.0);
+        assert_eq!(blocking_time(&long[1], &by_priority), 0.0);
+        assert_eq!(
+            RMAScheduler::schedulability_test(&long),
+            SchedulabilityResult::Unschedulable {
+                failing_task: "control".to_string(),
+                response_time: 6.0,
+                deadline: 5.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_harmonic_detection() {
+        let harmonic = vec![
+            periodic_task("a", 500.0, 1000.0),
+            periodic_task("b", 500.0, 2000.0),
+            periodic_task("c", 1000.0, 4000.0),
+        ];
+        assert!(RMAScheduler::is_harmonic(&harmonic));
+        assert_eq!(RMAScheduler::utilization_bound(&harmonic), 1.0);
+
+        // U = 1.0 exceeds the Liu & Layland bound but is fine for harmonic sets
+        assert_eq!(
+            RMAScheduler::schedulability_test(&harmonic),
+            SchedulabilityResult::Schedulable
+        );
+
+        let non_harmonic = vec![
+            periodic_task("a", 100.0, 1000.0),
+            periodic_task("b", 100.0, 1500.0),
+        ];
+        assert!(!RMAScheduler::is_harmonic(&non_harmonic));
+        let bound = RMAScheduler::utilization_bound(&non_harmonic);
+        assert!((bound - 2.0 * (2.0_f64.sqrt() - 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aperiodic_tasks_mixed_into_periodic_set() {
+        let task = |name: &str, period_us: Option<f64>| Task {
+            name: name.to_string(),
+            function: name.to_string(),
+            wcet_us: 100.0,
+            period_us,
+            deadline_us: period_us,
+            ..Default::default()
+        };
+
+        let mixed = vec![task("control", Some(1000.0)), task("irq", None)];
+        let expected = SchedulabilityResult::UnboundedArrivals {
+            tasks: vec!["irq".to_string()],
+        };
+        assert_eq!(RMAScheduler::schedulability_test(&mixed), expected);
+        assert_eq!(
+            crate::scheduling::EDFScheduler::schedulability_test(&mixed),
+            expected
+        );
+
+        // Purely aperiodic sets have no hard real-time guarantee to violate
+        let aperiodic = vec![task("irq", None)];
+        assert_eq!(
+            RMAScheduler::schedulability_test(&aperiodic),
+            SchedulabilityResult::Schedulable
+        );
+    }
+
+    #[test]
+    fn test_sporadic_task_uses_min_interarrival() {
+        let periodic = Task {
+            deadline_us: None,
+            ..periodic_task("control", 300.0, 1000.0)
+        };
+        let sporadic = Task {
+            name: "button".to_string(),
+            function: "button".to_string(),
+            wcet_us: 100.0,
+            typical_us: None,
+            period_us: None,
+            min_interarrival_us: Some(500.0),
+            offset_us: None,
+            ..periodic.clone()
+        };
+        let tasks = vec![periodic, sporadic];
+
+        assert!((RMAScheduler::calculate_utilization(&tasks) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            RMAScheduler::schedulability_test(&tasks),
+            SchedulabilityResult::Schedulable
+        );
+
+        // Shorter inter-arrival time ranks first and preempts the periodic task
+        let response_times = RMAScheduler::response_times(&tasks);
+        assert_eq!(response_times[0], ("button".to_string(), 100.0));
+        assert_eq!(response_times[1], ("control".to_string(), 400.0));
+    }
+
+    #[test]
+    fn test_wcet_beyond_deadline_is_infeasible() {
+        let task = |name: &str, wcet_us: f64, deadline_us: Option<f64>| Task {
+            deadline_us,
+            ..periodic_task(name, wcet_us, 1000.0)
+        };
+
+        // Deadline defaults to the period
+        let tasks = vec![
+            task("fast", 10.0, Some(50.0)),
+            task("tight", 80.0, Some(50.0)),
+            task("long", 1200.0, None),
+        ];
+        let expected = SchedulabilityResult::InfeasibleDeadlines {
+            tasks: vec!["tight".to_string(), "long".to_string()],
+        };
+        assert_eq!(RMAScheduler::schedulability_test(&tasks), expected);
+        assert_eq!(
+            crate::scheduling::EDFScheduler::schedulability_test(&tasks),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_release_offsets_limit_interference() {
+        let task = |name: &str, offset_us: f64, deadline_us: f64| Task {
+            offset_us: Some(offset_us),
+            deadline_us: Some(deadline_us),
+            ..periodic_task(name, 5.0, 10.0)
+        };
+
+        // Released together, transmit waits for all of sample
+        let synchronous = vec![task("sample", 0.0, 10.0), task("transmit", 0.0, 7.0)];
+        assert_eq!(
+            RMAScheduler::response_time_analysis(&synchronous)["transmit"],
+            10.0
+        );
+
+        // Released after sample has finished, it never waits
+        let staggered = vec![task("sample", 0.0, 10.0), task("transmit", 5.0, 7.0)];
+        assert_eq!(
+            RMAScheduler::schedulability_test(&staggered),
+            SchedulabilityResult::Schedulable
+        );
+
+        // Released while sample still runs, it waits for the rest of it
+        let overlapping = vec![task("sample", 0.0, 10.0), task("transmit", 2.0, 7.0)];
+        assert_eq!(
+            RMAScheduler::schedulability_test(&overlapping),
+            SchedulabilityResult::Unschedulable {
+                failing_task: "transmit".to_string(),
+                response_time: 8.0,
+                deadline: 7.0,
+            }
+        );
+    }
 }
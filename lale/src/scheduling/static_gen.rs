@@ -2,7 +2,7 @@ use crate::scheduling::Task;
 use serde::{Deserialize, Serialize};
 
 /// Time slot in static schedule
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeSlot {
     pub start_us: f64,
     pub duration_us: f64,
@@ -10,11 +10,344 @@ pub struct TimeSlot {
     pub preemptible: bool,
 }
 
+impl TimeSlot {
+    /// End time of the slot
+    pub fn end_us(&self) -> f64 {
+        self.start_us + self.duration_us
+    }
+
+    /// Check if the slot is idle time
+    pub fn is_idle(&self) -> bool {
+        self.task == "IDLE"
+    }
+}
+
+/// Tolerance when comparing slot times
+pub(crate) const TIME_EPSILON_US: f64 = 1e-6;
+
+/// Longest slot sequence considered when searching for repeated patterns
+const MAX_PATTERN_SLOTS: usize = 64;
+
 /// Static schedule timeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScheduleTimeline {
     pub hyperperiod_us: f64,
     pub slots: Vec<TimeSlot>,
+
+    /// Slack of the schedule, filled in for reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<IdleReport>,
+}
+
+/// Time no task runs within a schedule's hyperperiod
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdleReport {
+    /// `[start, end)` gaps, in time order
+    pub intervals: Vec<(f64, f64)>,
+    pub total_idle_us: f64,
+
+    /// Fraction of the hyperperiod spent running tasks
+    pub cpu_utilization: f64,
+}
+
+impl ScheduleTimeline {
+    /// Fraction of the window `[start_us, end_us)` occupied by task execution
+    pub fn utilization_in_window(&self, start_us: f64, end_us: f64) -> f64 {
+        if end_us <= start_us {
+            return 0.0;
+        }
+
+        let busy: f64 = self
+            .slots
+            .iter()
+            .filter(|slot| !slot.is_idle())
+            .map(|slot| {
+                let overlap_start = slot.start_us.max(start_us);
+                let overlap_end = slot.end_us().min(end_us);
+                (overlap_end - overlap_start).max(0.0)
+            })
+            .sum();
+
+        busy / (end_us - start_us)
+    }
+
+    /// Get all idle slots in the timeline
+    pub fn idle_slots(&self) -> Vec<&TimeSlot> {
+        self.slots.iter().filter(|slot| slot.is_idle()).collect()
+    }
+
+    /// Find the window of length `window_us` with the highest utilization
+    ///
+    /// Returns the window start time and its utilization.
+    pub fn peak_load_window(&self, window_us: f64) -> Option<(f64, f64)> {
+        if window_us <= 0.0 || self.slots.is_empty() {
+            return None;
+        }
+
+        let latest_start = (self.hyperperiod_us - window_us).max(0.0);
+
+        // Busy time is piecewise linear in the window start, so the maximum
+        // is reached when a window edge aligns with a slot edge.
+        let mut candidates = vec![0.0, latest_start];
+        for slot in self.slots.iter().filter(|slot| !slot.is_idle()) {
+            candidates.push(slot.start_us);
+            candidates.push(slot.end_us() - window_us);
+        }
+
+        candidates
+            .into_iter()
+            .map(|start| start.clamp(0.0, latest_start))
+            .map(|start| (start, self.utilization_in_window(start, start + window_us)))
+            .fold(None, |best: Option<(f64, f64)>, candidate| match best {
+                Some(b) if b.1 >= candidate.1 => Some(b),
+                _ => Some(candidate),
+            })
+    }
+
+    /// Merge adjacent idle slots into one
+    pub fn merge_idle_slots(&mut self) {
+        let mut merged: Vec<TimeSlot> = Vec::with_capacity(self.slots.len());
+
+        for slot in self.slots.drain(..) {
+            match merged.last_mut() {
+                Some(last)
+                    if last.is_idle()
+                        && slot.is_idle()
+                        && (last.end_us() - slot.start_us).abs() < TIME_EPSILON_US =>
+                {
+                    last.duration_us += slot.duration_us;
+                }
+                _ => merged.push(slot),
+            }
+        }
+
+        self.slots = merged;
+    }
+
+    /// Cumulative slack after each task slot, given actual execution times
+    ///
+    /// `actual_us` holds one duration per non-idle slot, in timeline order.
+    /// Busy slots run back to back from the previous actual end; an idle
+    /// slot is a wait for a release, so the slot after it starts no earlier
+    /// than scheduled. Slack is how far each slot ends ahead of its
+    /// scheduled (worst-case) end, and goes negative on an overrun. A runtime
+    /// monitor compares its own progress against these points.
+    pub fn slack_profile(&self, actual_us: &[f64]) -> Result<Vec<SlackPoint>, String> {
+        let busy = self.slots.iter().filter(|slot| !slot.is_idle()).count();
+        if actual_us.len() != busy {
+            return Err(format!(
+                "Expected {} actual durations, one per task slot, got {}",
+                busy,
+                actual_us.len()
+            ));
+        }
+        if let Some(negative) = actual_us.iter().find(|&&duration| duration < 0.0) {
+            return Err(format!("Actual duration {} us is negative", negative));
+        }
+
+        let mut points = Vec::with_capacity(busy);
+        let mut actual = actual_us.iter();
+        let mut actual_end_us = 0.0_f64;
+        let mut after_idle = true;
+
+        for slot in &self.slots {
+            if slot.is_idle() {
+                after_idle = true;
+                continue;
+            }
+
+            let start_us = if after_idle {
+                actual_end_us.max(slot.start_us)
+            } else {
+                actual_end_us
+            };
+            after_idle = false;
+
+            actual_end_us = start_us + actual.next().copied().unwrap_or(0.0);
+            points.push(SlackPoint {
+                task: slot.task.clone(),
+                scheduled_end_us: slot.end_us(),
+                actual_end_us,
+                slack_us: slot.end_us() - actual_end_us,
+            });
+        }
+
+        Ok(points)
+    }
+
+    /// Run-length encode the timeline
+    ///
+    /// Adjacent idle slots are merged, then each repeated slot sequence
+    /// (same tasks and durations at a fixed stride) is stored once with a
+    /// repeat count. `CompactSchedule::expand` restores the full timeline.
+    pub fn compact(&self) -> CompactSchedule {
+        let mut timeline = self.clone();
+        timeline.merge_idle_slots();
+        let slots = &timeline.slots;
+
+        let mut runs: Vec<SlotRun> = Vec::new();
+        let mut i = 0;
+        while i < slots.len() {
+            match Self::longest_repetition(slots, i) {
+                Some((pattern_len, repeat)) => {
+                    let start_us = slots[i].start_us;
+                    runs.push(SlotRun {
+                        start_us,
+                        stride_us: slots[i + pattern_len].start_us - start_us,
+                        repeat,
+                        pattern: slots[i..i + pattern_len]
+                            .iter()
+                            .map(|slot| PatternSlot::relative_to(slot, start_us))
+                            .collect(),
+                    });
+                    i += pattern_len * repeat;
+                }
+                None => {
+                    // Fold non-repeating slots into the previous one-off run
+                    match runs.last_mut() {
+                        Some(run) if run.repeat == 1 => {
+                            let start_us = run.start_us;
+                            run.pattern
+                                .push(PatternSlot::relative_to(&slots[i], start_us));
+                        }
+                        _ => runs.push(SlotRun {
+                            start_us: slots[i].start_us,
+                            stride_us: 0.0,
+                            repeat: 1,
+                            pattern: vec![PatternSlot::relative_to(&slots[i], slots[i].start_us)],
+                        }),
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        CompactSchedule {
+            hyperperiod_us: self.hyperperiod_us,
+            runs,
+        }
+    }
+
+    /// Pattern length and repeat count covering the most slots from `start`
+    ///
+    /// Only patterns repeated at least twice are returned.
+    fn longest_repetition(slots: &[TimeSlot], start: usize) -> Option<(usize, usize)> {
+        let remaining = slots.len() - start;
+        let mut best: Option<(usize, usize)> = None;
+
+        for pattern_len in 1..=MAX_PATTERN_SLOTS.min(remaining / 2) {
+            let stride = slots[start + pattern_len].start_us - slots[start].start_us;
+            if stride <= 0.0 {
+                continue;
+            }
+
+            let mut repeat = 1;
+            while (repeat + 1) * pattern_len <= remaining
+                && (0..pattern_len).all(|j| {
+                    let first = &slots[start + j];
+                    let other = &slots[start + repeat * pattern_len + j];
+                    first.task == other.task
+                        && first.preemptible == other.preemptible
+                        && (first.duration_us - other.duration_us).abs() < TIME_EPSILON_US
+                        && (first.start_us + repeat as f64 * stride - other.start_us).abs()
+                            < TIME_EPSILON_US
+                })
+            {
+                repeat += 1;
+            }
+
+            let covered = pattern_len * repeat;
+            if repeat >= 2 && best.is_none_or(|(len, count)| covered > len * count) {
+                best = Some((pattern_len, repeat));
+            }
+        }
+
+        best
+    }
+}
+
+/// Slot within a repeated pattern, relative to the pattern start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternSlot {
+    pub offset_us: f64,
+    pub duration_us: f64,
+    pub task: String,
+    pub preemptible: bool,
+}
+
+impl PatternSlot {
+    fn relative_to(slot: &TimeSlot, start_us: f64) -> Self {
+        Self {
+            offset_us: slot.start_us - start_us,
+            duration_us: slot.duration_us,
+            task: slot.task.clone(),
+            preemptible: slot.preemptible,
+        }
+    }
+}
+
+/// Slot pattern repeated `repeat` times, `stride_us` apart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotRun {
+    pub start_us: f64,
+    pub stride_us: f64,
+    pub repeat: usize,
+    pub pattern: Vec<PatternSlot>,
+}
+
+/// Run-length encoded schedule timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactSchedule {
+    pub hyperperiod_us: f64,
+    pub runs: Vec<SlotRun>,
+}
+
+impl CompactSchedule {
+    /// Number of slots in the expanded timeline
+    pub fn slot_count(&self) -> usize {
+        self.runs
+            .iter()
+            .map(|run| run.pattern.len() * run.repeat)
+            .sum()
+    }
+
+    /// Restore the full slot-by-slot timeline
+    pub fn expand(&self) -> ScheduleTimeline {
+        let mut slots = Vec::with_capacity(self.slot_count());
+
+        for run in &self.runs {
+            for k in 0..run.repeat {
+                let base_us = run.start_us + k as f64 * run.stride_us;
+                slots.extend(run.pattern.iter().map(|slot| TimeSlot {
+                    start_us: base_us + slot.offset_us,
+                    duration_us: slot.duration_us,
+                    task: slot.task.clone(),
+                    preemptible: slot.preemptible,
+                }));
+            }
+        }
+
+        ScheduleTimeline {
+            hyperperiod_us: self.hyperperiod_us,
+            slots,
+            idle: None,
+        }
+    }
+}
+
+/// Progress against the static schedule at the end of one task slot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlackPoint {
+    pub task: String,
+
+    /// End of the slot in the schedule, i.e. at worst-case execution times
+    pub scheduled_end_us: f64,
+
+    /// End of the slot at the actual execution times
+    pub actual_end_us: f64,
+
+    /// Time the execution is ahead of the schedule; negative when behind
+    pub slack_us: f64,
 }
 
 /// Static schedule generator
@@ -22,6 +355,10 @@ pub struct StaticScheduleGenerator;
 
 impl StaticScheduleGenerator {
     /// Generate static schedule for time-triggered architecture
+    ///
+    /// Each job is released at its task's offset plus a whole number of
+    /// periods, so with offsets the last jobs of a hyperperiod can run past
+    /// its end, into the start of the next one.
     pub fn generate_schedule(tasks: &[Task]) -> ScheduleTimeline {
         // Calculate hyperperiod (LCM of all periods)
         let periods: Vec<u64> = tasks
@@ -41,10 +378,11 @@ impl StaticScheduleGenerator {
             if let Some(period) = task.period_us {
                 let num_instances = (hyperperiod / period) as usize;
                 for i in 0..num_instances {
+                    let release_time = task.release_offset_us() + i as f64 * period;
                     instances.push(TaskInstance {
                         task: task.clone(),
-                        release_time: i as f64 * period,
-                        absolute_deadline: i as f64 * period + task.deadline_us.unwrap_or(period),
+                        release_time,
+                        absolute_deadline: release_time + task.deadline_us.unwrap_or(period),
                     });
                 }
             }
@@ -97,6 +435,63 @@ impl StaticScheduleGenerator {
         ScheduleTimeline {
             hyperperiod_us: hyperperiod,
             slots,
+            idle: None,
+        }
+    }
+
+    /// Gaps `[start, end)` within the hyperperiod where no task runs
+    ///
+    /// Worked out from the task slots, whether or not idle slots fill the
+    /// gaps. A job running past the end of the hyperperiod runs into the
+    /// start of the next one, so its overrun is busy time at the start. A
+    /// fully packed schedule has no gaps.
+    pub fn idle_intervals(timeline: &ScheduleTimeline) -> Vec<(f64, f64)> {
+        let hyperperiod = timeline.hyperperiod_us;
+
+        let mut busy = Vec::new();
+        for slot in timeline.slots.iter().filter(|slot| !slot.is_idle()) {
+            let (start, end) = (slot.start_us, slot.end_us());
+            busy.push((start.max(0.0), end.min(hyperperiod)));
+            if end > hyperperiod {
+                busy.push((
+                    (start - hyperperiod).max(0.0),
+                    (end - hyperperiod).min(hyperperiod),
+                ));
+            }
+        }
+        busy.retain(|(start, end)| end > start);
+        busy.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut gaps = Vec::new();
+        let mut cursor = 0.0;
+        for (start, end) in busy {
+            if start - cursor > TIME_EPSILON_US {
+                gaps.push((cursor, start));
+            }
+            cursor = f64::max(cursor, end);
+        }
+        if hyperperiod - cursor > TIME_EPSILON_US {
+            gaps.push((cursor, hyperperiod));
+        }
+        gaps
+    }
+
+    /// Idle intervals, their total and the share of the hyperperiod left
+    /// to tasks
+    pub fn idle_report(timeline: &ScheduleTimeline) -> IdleReport {
+        let intervals = Self::idle_intervals(timeline);
+        let total_idle_us: f64 = intervals.iter().map(|(start, end)| end - start).sum();
+        let hyperperiod = timeline.hyperperiod_us;
+        let cpu_utilization = if hyperperiod > 0.0 {
+            (hyperperiod - total_idle_us) / hyperperiod
+        } else {
+            0.0
+        };
+
+        IdleReport {
+            intervals,
+            total_idle_us,
+            cpu_utilization,
         }
     }
 
@@ -136,6 +531,7 @@ struct TaskInstance {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduling::tasks::fixtures::periodic_task;
 
     #[test]
     fn test_gcd_lcm() {
@@ -149,28 +545,8 @@ mod tests {
     #[test]
     fn test_static_schedule_generation() {
         let tasks = vec![
-            Task {
-                name: "task1".to_string(),
-                function: "func1".to_string(),
-                wcet_cycles: 1000,
-                wcet_us: 100.0,
-                period_us: Some(1000.0),
-                deadline_us: Some(1000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
-            Task {
-                name: "task2".to_string(),
-                function: "func2".to_string(),
-                wcet_cycles: 2000,
-                wcet_us: 200.0,
-                period_us: Some(2000.0),
-                deadline_us: Some(2000.0),
-                priority: None,
-                preemptible: true,
-                dependencies: vec![],
-            },
+            periodic_task("task1", 100.0, 1000.0),
+            periodic_task("task2", 200.0, 2000.0),
         ];
 
         let schedule = StaticScheduleGenerator::generate_schedule(&tasks);
@@ -182,4 +558,202 @@ mod tests {
         let total_time: f64 = schedule.slots.iter().map(|s| s.duration_us).sum();
         assert!((total_time - schedule.hyperperiod_us).abs() < 0.001);
     }
+
+    #[test]
+    fn test_offsets_delay_releases() {
+        let task = |name: &str, wcet_us: f64, offset_us: Option<f64>| Task {
+            offset_us,
+            deadline_us: None,
+            ..periodic_task(name, wcet_us, 1000.0)
+        };
+
+        let tasks = vec![
+            task("sample", 100.0, None),
+            task("transmit", 200.0, Some(300.0)),
+        ];
+        assert!(crate::scheduling::TaskExtractor::validate_offsets(&tasks).is_ok());
+
+        let schedule = StaticScheduleGenerator::generate_schedule(&tasks);
+        assert_eq!(
+            schedule.slots,
+            vec![
+                slot(0.0, 100.0, "sample"),
+                slot(100.0, 200.0, "IDLE"),
+                slot(300.0, 200.0, "transmit"),
+                slot(500.0, 500.0, "IDLE"),
+            ]
+        );
+
+        // An offset must fall within the first period
+        for offset_us in [-1.0, 1000.0] {
+            let late = vec![task("transmit", 200.0, Some(offset_us))];
+            assert!(crate::scheduling::TaskExtractor::validate_offsets(&late).is_err());
+        }
+    }
+
+    fn slot(start_us: f64, duration_us: f64, task: &str) -> TimeSlot {
+        TimeSlot {
+            start_us,
+            duration_us,
+            task: task.to_string(),
+            preemptible: true,
+        }
+    }
+
+    #[test]
+    fn test_timeline_queries() {
+        let timeline = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![
+                slot(0.0, 100.0, "task1"),
+                slot(100.0, 400.0, "IDLE"),
+                slot(500.0, 300.0, "task2"),
+                slot(800.0, 200.0, "IDLE"),
+            ],
+            idle: None,
+        };
+
+        assert!((timeline.utilization_in_window(0.0, 1000.0) - 0.4).abs() < 1e-9);
+        assert!((timeline.utilization_in_window(0.0, 200.0) - 0.5).abs() < 1e-9);
+        assert_eq!(timeline.utilization_in_window(100.0, 100.0), 0.0);
+
+        let idle = timeline.idle_slots();
+        assert_eq!(idle.len(), 2);
+        assert_eq!(idle[0].start_us, 100.0);
+
+        let (start, utilization) = timeline.peak_load_window(300.0).unwrap();
+        assert_eq!(start, 500.0);
+        assert!((utilization - 1.0).abs() < 1e-9);
+
+        assert!(timeline.peak_load_window(0.0).is_none());
+    }
+
+    #[test]
+    fn test_slack_profile() {
+        let timeline = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![
+                slot(0.0, 100.0, "task1"),
+                slot(100.0, 200.0, "task2"),
+                slot(300.0, 200.0, "IDLE"),
+                slot(500.0, 100.0, "task1"),
+                slot(600.0, 400.0, "IDLE"),
+            ],
+            idle: None,
+        };
+
+        let profile = timeline.slack_profile(&[60.0, 150.0, 130.0]).unwrap();
+        assert_eq!(profile.len(), 3);
+        // Slack accumulates over back-to-back slots...
+        assert_eq!(profile[0].slack_us, 40.0);
+        assert_eq!(profile[1].actual_end_us, 210.0);
+        assert_eq!(profile[1].slack_us, 90.0);
+        // ...but a release after idle time cannot be run early
+        assert_eq!(profile[2].task, "task1");
+        assert_eq!(profile[2].actual_end_us, 630.0);
+        assert_eq!(profile[2].slack_us, -30.0);
+
+        assert!(timeline.slack_profile(&[60.0, 150.0]).is_err());
+        assert!(timeline.slack_profile(&[60.0, -1.0, 100.0]).is_err());
+    }
+
+    #[test]
+    fn test_idle_intervals_of_two_task_set() {
+        let task = |name: &str, wcet_us: f64, period_us: f64| Task {
+            deadline_us: None,
+            ..periodic_task(name, wcet_us, period_us)
+        };
+        let tasks = vec![task("fast", 100.0, 1000.0), task("slow", 200.0, 2000.0)];
+
+        // fast at 0 and 1000, slow right after the second fast job
+        let schedule = StaticScheduleGenerator::generate_schedule(&tasks);
+        assert_eq!(
+            StaticScheduleGenerator::idle_intervals(&schedule),
+            vec![(100.0, 1000.0), (1300.0, 2000.0)]
+        );
+
+        let report = StaticScheduleGenerator::idle_report(&schedule);
+        assert_eq!(report.total_idle_us, 1600.0);
+        assert_eq!(report.cpu_utilization, 0.2);
+    }
+
+    #[test]
+    fn test_idle_intervals_of_packed_and_wrapping_schedules() {
+        let packed = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![slot(0.0, 400.0, "task1"), slot(400.0, 600.0, "task2")],
+            idle: None,
+        };
+        let report = StaticScheduleGenerator::idle_report(&packed);
+        assert!(report.intervals.is_empty());
+        assert_eq!(report.cpu_utilization, 1.0);
+
+        // A job running past the end keeps the start of the next one busy
+        let wrapping = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![slot(0.0, 900.0, "IDLE"), slot(900.0, 200.0, "task1")],
+            idle: None,
+        };
+        assert_eq!(
+            StaticScheduleGenerator::idle_intervals(&wrapping),
+            vec![(100.0, 900.0)]
+        );
+    }
+
+    #[test]
+    fn test_merge_idle_slots() {
+        let mut timeline = ScheduleTimeline {
+            hyperperiod_us: 1000.0,
+            slots: vec![
+                slot(0.0, 100.0, "task1"),
+                slot(100.0, 150.0, "IDLE"),
+                slot(250.0, 250.0, "IDLE"),
+                slot(500.0, 500.0, "task2"),
+            ],
+            idle: None,
+        };
+
+        timeline.merge_idle_slots();
+
+        assert_eq!(timeline.slots.len(), 3);
+        assert_eq!(timeline.slots[1].start_us, 100.0);
+        assert_eq!(timeline.slots[1].duration_us, 400.0);
+    }
+
+    #[test]
+    fn test_compact_repeated_pattern() {
+        // 1000 periods of task1 + idle, then a one-off tail
+        let mut slots = Vec::new();
+        for k in 0..1000 {
+            let base = k as f64 * 10.0;
+            slots.push(slot(base, 2.0, "task1"));
+            slots.push(slot(base + 2.0, 8.0, "IDLE"));
+        }
+        slots.push(slot(10000.0, 50.0, "task2"));
+        slots.push(slot(10050.0, 50.0, "IDLE"));
+
+        let timeline = ScheduleTimeline {
+            hyperperiod_us: 10100.0,
+            slots,
+            idle: None,
+        };
+
+        let compact = timeline.compact();
+
+        assert_eq!(compact.runs.len(), 2);
+        assert_eq!(compact.runs[0].repeat, 1000);
+        assert_eq!(compact.runs[0].pattern.len(), 2);
+        assert_eq!(compact.runs[0].stride_us, 10.0);
+        assert_eq!(compact.runs[1].repeat, 1);
+        assert_eq!(compact.runs[1].pattern.len(), 2);
+        assert_eq!(compact.slot_count(), timeline.slots.len());
+
+        let expanded = compact.expand();
+        assert_eq!(expanded.slots.len(), timeline.slots.len());
+        for (original, restored) in timeline.slots.iter().zip(&expanded.slots) {
+            assert_eq!(original.task, restored.task);
+            assert!((original.start_us - restored.start_us).abs() < 1e-9);
+            assert!((original.duration_us - restored.duration_us).abs() < 1e-9);
+        }
+    }
 }
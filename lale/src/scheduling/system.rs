@@ -0,0 +1,273 @@
+//! Task set of a whole system, by entry point
+//!
+//! A tasks file names the function each task runs and its timing; the WCET
+//! comes from the analysis. In TOML:
+//!
+//! ```toml
+//! [[task]]
+//! function = "control_loop"
+//! period_us = 1000
+//!
+//! [[task]]
+//! name = "telemetry"
+//! function = "send_telemetry"
+//! period_us = 10000
+//! deadline_us = 5000
+//! priority = 2
+//! ```
+//!
+//! or the same as a JSON object. A task is named after its function unless
+//! it sets a name, and its deadline is its arrival period unless set.
+
+use super::tasks::{Criticality, ResourceUsage, Task, TaskExtractor};
+use crate::error::LaleError;
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Tasks of a system, each released at one entry point
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemSpec {
+    #[serde(rename = "task", default)]
+    pub tasks: Vec<TaskSpec>,
+}
+
+/// One task of a tasks file, everything but its WCET
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    /// Task name; the function name if unset
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Entry point the task runs, by LLVM name
+    pub function: String,
+    #[serde(default)]
+    pub period_us: Option<f64>,
+    #[serde(default)]
+    pub min_interarrival_us: Option<f64>,
+    #[serde(default)]
+    pub offset_us: Option<f64>,
+    #[serde(default)]
+    pub deadline_us: Option<f64>,
+    #[serde(default)]
+    pub priority: Option<u8>,
+    #[serde(default = "default_preemptible")]
+    pub preemptible: bool,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub criticality: Option<Criticality>,
+    #[serde(default)]
+    pub wcet_lo_us: Option<f64>,
+    #[serde(default)]
+    pub resources: Vec<ResourceUsage>,
+}
+
+fn default_preemptible() -> bool {
+    true
+}
+
+impl TaskSpec {
+    /// Task name, the function name unless set
+    pub fn task_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.function)
+    }
+}
+
+impl SystemSpec {
+    /// Load a tasks file, JSON if its extension is `.json` and TOML
+    /// otherwise
+    pub fn load(path: &Path) -> Result<Self, LaleError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| LaleError::io(format!("Failed to read tasks {}", path.display()), e))?;
+        let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&content)
+        } else {
+            Self::from_toml(&content)
+        };
+        parsed.map_err(|e| {
+            LaleError::Config(format!("Failed to parse tasks {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, LaleError> {
+        toml::from_str(content).map_err(|e| LaleError::Config(e.to_string()))
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, LaleError> {
+        serde_json::from_str(content).map_err(|e| LaleError::Config(e.to_string()))
+    }
+
+    /// Functions the tasks run, each once, in file order
+    pub fn entry_points(&self) -> Vec<&str> {
+        let mut seen = AHashSet::new();
+        self.tasks
+            .iter()
+            .map(|spec| spec.function.as_str())
+            .filter(|function| seen.insert(*function))
+            .collect()
+    }
+
+    /// Tasks with the WCET of their entry points
+    ///
+    /// `wcets` maps each entry point to its WCET in cycles, callees
+    /// included. Fails if a task's entry point has no WCET, two tasks share
+    /// a name, a dependency names no task, a timing parameter isn't
+    /// positive or an offset is outside its period.
+    pub fn tasks(
+        &self,
+        wcets: &AHashMap<String, u64>,
+        cpu_freq_mhz: u32,
+    ) -> Result<Vec<Task>, LaleError> {
+        if self.tasks.is_empty() {
+            return Err(LaleError::Config("No tasks defined".to_string()));
+        }
+
+        let mut names = AHashSet::new();
+        for spec in &self.tasks {
+            if !names.insert(spec.task_name()) {
+                return Err(LaleError::Config(format!(
+                    "Task '{}' is defined more than once",
+                    spec.task_name()
+                )));
+            }
+        }
+
+        let mut tasks = Vec::with_capacity(self.tasks.len());
+        for spec in &self.tasks {
+            let name = spec.task_name();
+            let &wcet_cycles = wcets.get(&spec.function).ok_or_else(|| {
+                LaleError::Config(format!(
+                    "Entry point '{}' of task '{}' has no WCET",
+                    spec.function, name
+                ))
+            })?;
+            let timings = [
+                ("period_us", spec.period_us),
+                ("min_interarrival_us", spec.min_interarrival_us),
+                ("deadline_us", spec.deadline_us),
+            ];
+            for (field, value) in timings {
+                if value.is_some_and(|value| value.is_nan() || value <= 0.0) {
+                    return Err(LaleError::Config(format!(
+                        "Task '{}' has a {} that isn't positive",
+                        name, field
+                    )));
+                }
+            }
+            if let Some(dependency) = spec
+                .dependencies
+                .iter()
+                .find(|dependency| !names.contains(dependency.as_str()))
+            {
+                return Err(LaleError::Config(format!(
+                    "Task '{}' depends on unknown task '{}'",
+                    name, dependency
+                )));
+            }
+
+            tasks.push(Task {
+                name: name.to_string(),
+                function: spec.function.clone(),
+                wcet_cycles,
+                wcet_us: TaskExtractor::cycles_to_us(wcet_cycles, cpu_freq_mhz),
+                typical_us: None,
+                period_us: spec.period_us,
+                min_interarrival_us: spec.min_interarrival_us,
+                offset_us: spec.offset_us,
+                deadline_us: spec
+                    .deadline_us
+                    .or(spec.period_us)
+                    .or(spec.min_interarrival_us),
+                priority: spec.priority,
+                preemptible: spec.preemptible,
+                dependencies: spec.dependencies.clone(),
+                criticality: spec.criticality,
+                wcet_lo_us: spec.wcet_lo_us,
+                resources: spec.resources.clone(),
+            });
+        }
+
+        TaskExtractor::validate_offsets(&tasks).map_err(LaleError::Config)?;
+        Ok(tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASKS: &str = r#"
+[[task]]
+function = "control_loop"
+period_us = 1000
+
+[[task]]
+name = "telemetry"
+function = "send_telemetry"
+period_us = 10000
+deadline_us = 5000
+priority = 2
+dependencies = ["control_loop"]
+"#;
+
+    fn wcets() -> AHashMap<String, u64> {
+        AHashMap::from_iter([
+            ("control_loop".to_string(), 16_800),
+            ("send_telemetry".to_string(), 1_680),
+        ])
+    }
+
+    #[test]
+    fn test_tasks_from_toml() {
+        let spec = SystemSpec::from_toml(TASKS).unwrap();
+        assert_eq!(spec.entry_points(), vec!["control_loop", "send_telemetry"]);
+
+        let tasks = spec.tasks(&wcets(), 168).unwrap();
+        assert_eq!(tasks.len(), 2);
+
+        // Named after its function, deadline at its period
+        assert_eq!(tasks[0].name, "control_loop");
+        assert_eq!(tasks[0].wcet_us, 100.0);
+        assert_eq!(tasks[0].deadline_us, Some(1000.0));
+        assert!(tasks[0].preemptible);
+
+        assert_eq!(tasks[1].name, "telemetry");
+        assert_eq!(tasks[1].function, "send_telemetry");
+        assert_eq!(tasks[1].wcet_us, 10.0);
+        assert_eq!(tasks[1].deadline_us, Some(5000.0));
+        assert_eq!(tasks[1].priority, Some(2));
+    }
+
+    #[test]
+    fn test_invalid_tasks_are_rejected() {
+        assert!(SystemSpec::from_toml("[[task]]\nfunction = \"f\"\nperiod = 10\n").is_err());
+        assert!(SystemSpec::default().tasks(&wcets(), 168).is_err());
+
+        let missing = SystemSpec::from_toml("[[task]]\nfunction = \"idle_hook\"\n").unwrap();
+        assert!(missing
+            .tasks(&wcets(), 168)
+            .unwrap_err()
+            .to_string()
+            .contains("idle_hook"));
+
+        let duplicate = SystemSpec::from_toml(
+            "[[task]]\nfunction = \"control_loop\"\n\
+             [[task]]\nname = \"control_loop\"\nfunction = \"send_telemetry\"\n",
+        )
+        .unwrap();
+        assert!(duplicate.tasks(&wcets(), 168).is_err());
+
+        let zero_period =
+            SystemSpec::from_toml("[[task]]\nfunction = \"control_loop\"\nperiod_us = 0\n")
+                .unwrap();
+        assert!(zero_period.tasks(&wcets(), 168).is_err());
+
+        let late_offset = SystemSpec::from_toml(
+            "[[task]]\nfunction = \"control_loop\"\nperiod_us = 100\noffset_us = 100\n",
+        )
+        .unwrap();
+        assert!(late_offset.tasks(&wcets(), 168).is_err());
+    }
+}
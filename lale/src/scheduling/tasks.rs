@@ -1,18 +1,211 @@
 use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
 
 /// Real-time task model
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub name: String,
     pub function: String,
     pub wcet_cycles: u64,
     pub wcet_us: f64,
+    /// Typical execution time, e.g. from the BCET; the WCET if unknown
+    #[serde(default)]
+    pub typical_us: Option<f64>,
     pub period_us: Option<f64>,
+    /// Minimum gap between releases of a sporadic task (no fixed period)
+    #[serde(default)]
+    pub min_interarrival_us: Option<f64>,
+    /// Release time of the first job of a periodic task; zero if unset
+    #[serde(default)]
+    pub offset_us: Option<f64>,
     pub deadline_us: Option<f64>,
     pub priority: Option<u8>,
     pub preemptible: bool,
     pub dependencies: Vec<String>,
+    /// Mixed-criticality level; high if unset
+    #[serde(default)]
+    pub criticality: Option<Criticality>,
+    /// Optimistic WCET budget in low-criticality mode; the WCET if unset
+    #[serde(default)]
+    pub wcet_lo_us: Option<f64>,
+    /// Shared resources the task locks, e.g. mutexes
+    #[serde(default)]
+    pub resources: Vec<ResourceUsage>,
+}
+
+impl Default for Task {
+    /// Preemptible aperiodic task with no WCET or deadline, to fill in with
+    /// struct update syntax
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            function: String::new(),
+            wcet_cycles: 0,
+            wcet_us: 0.0,
+            typical_us: None,
+            period_us: None,
+            min_interarrival_us: None,
+            offset_us: None,
+            deadline_us: None,
+            priority: None,
+            preemptible: true,
+            dependencies: vec![],
+            criticality: None,
+            wcet_lo_us: None,
+            resources: vec![],
+        }
+    }
+}
+
+/// Shared resource locked by a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Identifier shared by every task locking the same resource
+    pub resource_id: String,
+    /// Longest critical section holding the resource
+    pub critical_section_us: f64,
+}
+
+/// Criticality level of a task in a mixed-criticality system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Criticality {
+    /// Dropped when the system switches to high-criticality mode
+    Lo,
+    /// Guaranteed its pessimistic WCET in both modes
+    Hi,
+}
+
+impl Task {
+    /// Task without any bound on how often it can be released
+    pub fn is_aperiodic(&self) -> bool {
+        self.arrival_period_us().is_none()
+    }
+
+    /// Shortest time between two releases
+    ///
+    /// The period of a periodic task, or the minimum inter-arrival time of
+    /// a sporadic task, which is its worst-case arrival rate.
+    pub fn arrival_period_us(&self) -> Option<f64> {
+        self.period_us.or(self.min_interarrival_us)
+    }
+
+    /// Release time of the first job, zero unless a periodic task sets one
+    ///
+    /// A sporadic task has no fixed release pattern, so any offset it
+    /// carries is ignored.
+    pub fn release_offset_us(&self) -> f64 {
+        match self.period_us {
+            Some(_) => self.offset_us.unwrap_or(0.0),
+            None => 0.0,
+        }
+    }
+
+    /// Criticality level, high unless stated otherwise
+    pub fn criticality_level(&self) -> Criticality {
+        self.criticality.unwrap_or(Criticality::Hi)
+    }
+
+    /// Execution budget in low-criticality mode, never above the WCET
+    pub fn lo_budget_us(&self) -> f64 {
+        self.wcet_lo_us.unwrap_or(self.wcet_us).min(self.wcet_us)
+    }
+
+    /// Execution time expected in the common case
+    pub fn typical_execution_us(&self) -> f64 {
+        self.typical_us.unwrap_or(self.wcet_us)
+    }
+
+    /// WCET as a fraction of the arrival period, the task's own utilization
+    pub fn relative_wcet(&self) -> Option<f64> {
+        self.arrival_period_us().map(|period| self.wcet_us / period)
+    }
+}
+
+/// Relative WCET of every task with a period, by task name
+pub fn relative_wcets(tasks: &[Task]) -> AHashMap<String, f64> {
+    tasks
+        .iter()
+        .filter_map(|t| t.relative_wcet().map(|share| (t.name.clone(), share)))
+        .collect()
+}
+
+/// Processor load at typical execution times
+///
+/// The expected load, as opposed to the guaranteed-safe worst-case
+/// utilization the schedulability tests use.
+pub fn typical_utilization(tasks: &[Task]) -> f64 {
+    tasks
+        .iter()
+        .filter_map(|t| {
+            t.arrival_period_us()
+                .map(|period| t.typical_execution_us() / period)
+        })
+        .sum()
+}
+
+/// Aperiodic tasks mixed into a set that also has periodic or sporadic tasks
+///
+/// Their arrivals are unbounded, so the CPU time they consume can't be
+/// accounted for in a hard real-time schedulability test. Purely aperiodic
+/// sets aren't flagged.
+pub fn unbounded_arrivals(tasks: &[Task]) -> Vec<String> {
+    if tasks.iter().all(Task::is_aperiodic) {
+        return vec![];
+    }
+    tasks
+        .iter()
+        .filter(|t| t.is_aperiodic())
+        .map(|t| t.name.clone())
+        .collect()
+}
+
+/// Tasks whose WCET exceeds their own deadline
+///
+/// Such a task misses its deadline even running alone on the processor, so
+/// the set is infeasible under any scheduling policy. The deadline defaults
+/// to the arrival period.
+pub fn infeasible_deadlines(tasks: &[Task]) -> Vec<String> {
+    tasks
+        .iter()
+        .filter(|t| {
+            t.deadline_us
+                .or(t.arrival_period_us())
+                .is_some_and(|deadline| t.wcet_us > deadline)
+        })
+        .map(|t| t.name.clone())
+        .collect()
+}
+
+/// Worst-case blocking of a task under the Priority Ceiling Protocol
+///
+/// `by_priority` is the task set, highest priority first. A task can be
+/// blocked by at most one critical section of a lower-priority task, on a
+/// resource whose ceiling (the priority of its highest-priority user) is at
+/// least the task's own priority. Zero if `task` is not in the set.
+pub fn blocking_time<T: Borrow<Task>>(task: &Task, by_priority: &[T]) -> f64 {
+    let Some(position) = by_priority
+        .iter()
+        .position(|t| t.borrow().name == task.name)
+    else {
+        return 0.0;
+    };
+
+    // Ceiling of each resource, as the position of its highest-priority user
+    let mut ceilings: AHashMap<&str, usize> = AHashMap::new();
+    for (i, t) in by_priority.iter().enumerate() {
+        for usage in &t.borrow().resources {
+            ceilings.entry(usage.resource_id.as_str()).or_insert(i);
+        }
+    }
+
+    by_priority[position + 1..]
+        .iter()
+        .flat_map(|t| &t.borrow().resources)
+        .filter(|usage| ceilings[usage.resource_id.as_str()] <= position)
+        .map(|usage| usage.critical_section_us)
+        .fold(0.0, f64::max)
 }
 
 /// Task attributes from annotations
@@ -33,4 +226,47 @@ impl TaskExtractor {
     pub fn cycles_to_us(cycles: u64, cpu_freq_mhz: u32) -> f64 {
         cycles as f64 / cpu_freq_mhz as f64
     }
+
+    /// Check that every release offset is within `[0, period)`
+    ///
+    /// An offset of a whole period or more is the same release pattern as
+    /// its remainder, so it is rejected rather than silently wrapped. Only
+    /// periodic tasks can have an offset.
+    pub fn validate_offsets(tasks: &[Task]) -> Result<(), String> {
+        for task in tasks {
+            let Some(offset) = task.offset_us else {
+                continue;
+            };
+            let Some(period) = task.period_us else {
+                return Err(format!(
+                    "Task '{}' has an offset of {} us but no period",
+                    task.name, offset
+                ));
+            };
+            if !(0.0..period).contains(&offset) {
+                return Err(format!(
+                    "Offset {} us of task '{}' is outside [0, {}) us",
+                    offset, task.name, period
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::Task;
+
+    /// Preemptible periodic task whose deadline is its period
+    pub(crate) fn periodic_task(name: &str, wcet_us: f64, period_us: f64) -> Task {
+        Task {
+            name: name.to_string(),
+            function: name.to_string(),
+            wcet_us,
+            period_us: Some(period_us),
+            deadline_us: Some(period_us),
+            ..Default::default()
+        }
+    }
 }
@@ -0,0 +1,140 @@
+//! Test that a load depends on an earlier store only when they may alias
+
+use lale::ir::SsaRegisters;
+use lale::microarch::{HazardDetector, HazardType, Register};
+use lale::InkwellParser;
+
+const STORE_LOAD_IR: &str = r#"; ModuleID = 'alias_test'
+source_filename = "alias_test"
+
+@a = global i32 0
+@b = global i32 0
+
+define i32 @unknown(ptr %p, ptr %q) {
+entry:
+  store i32 1, ptr %p
+  %v = load i32, ptr %q
+  ret i32 %v
+}
+
+define i32 @locals() {
+entry:
+  %x = alloca [4 x i32]
+  %y = alloca i32
+  %slot = getelementptr [4 x i32], ptr %x, i32 0, i32 2
+  store i32 1, ptr %slot
+  %v = load i32, ptr %y
+  %w = load i32, ptr %x
+  ret i32 %v
+}
+
+define i32 @globals(ptr %p) {
+entry:
+  store i32 1, ptr @a
+  %v = load i32, ptr @b
+  %w = load i32, ptr %p
+  ret i32 %v
+}
+
+define i32 @scoped(ptr %p, ptr %q) {
+entry:
+  store i32 1, ptr %p, !alias.scope !3, !noalias !4
+  %v = load i32, ptr %q, !alias.scope !4, !noalias !3
+  %w = load i32, ptr %q
+  ret i32 %v
+}
+
+define float @typed(ptr %p, ptr %q) {
+entry:
+  store i32 1, ptr %p, !tbaa !9
+  %f = load float, ptr %q, !tbaa !10
+  %c = load i8, ptr %q, !tbaa !11
+  ret float %f
+}
+
+!0 = distinct !{!0, !"domain"}
+!1 = distinct !{!1, !0, !"store scope"}
+!2 = distinct !{!2, !0, !"load scope"}
+!3 = !{!1}
+!4 = !{!2}
+
+!5 = !{!"Simple C/C++ TBAA"}
+!6 = !{!"omnipotent char", !5, i64 0}
+!7 = !{!"int", !6, i64 0}
+!8 = !{!"float", !6, i64 0}
+!9 = !{!7, !7, i64 0}
+!10 = !{!8, !8, i64 0}
+!11 = !{!6, !6, i64 0}
+"#;
+
+/// Ids of the instructions that wait for the store in a function's entry
+fn store_dependents(function_name: &str) -> Vec<usize> {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(STORE_LOAD_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function(function_name)
+        .expect("function should exist");
+    let entry = function.get_first_basic_block().expect("entry block");
+    let registers = SsaRegisters::from_function(&function);
+    let deps = registers.block_dependencies(entry);
+
+    // Memory registers are numbered after the SSA registers
+    let memory: Vec<Register> = deps
+        .iter()
+        .flat_map(|dep| dep.writes.iter().copied())
+        .filter(|register| register.0 as usize >= registers.len())
+        .collect();
+    deps.iter()
+        .filter(|dep| dep.reads.iter().any(|register| memory.contains(register)))
+        .map(|dep| dep.id)
+        .collect()
+}
+
+#[test]
+fn test_unknown_pointers_are_assumed_to_alias() {
+    assert_eq!(store_dependents("unknown"), vec![1]);
+}
+
+#[test]
+fn test_provenance_separates_distinct_objects() {
+    // The store to %x[2] and the load of %y are apart; %x itself is not
+    assert_eq!(store_dependents("locals"), vec![5]);
+
+    // @a and @b are apart, but %p may point at @a
+    assert_eq!(store_dependents("globals"), vec![2]);
+}
+
+#[test]
+fn test_noalias_scopes_separate_accesses() {
+    // Only the load without scope metadata waits for the store
+    assert_eq!(store_dependents("scoped"), vec![2]);
+}
+
+#[test]
+fn test_tbaa_separates_unrelated_types() {
+    // int and float are apart; char aliases everything
+    assert_eq!(store_dependents("typed"), vec![2]);
+}
+
+#[test]
+fn test_no_stall_without_dependency() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(STORE_LOAD_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let stalls = |name: &str| {
+        let function = module.get_function(name).expect("function should exist");
+        let entry = function.get_first_basic_block().expect("entry block");
+        let deps = SsaRegisters::from_function(&function).block_dependencies(entry);
+        HazardDetector::new(5, true)
+            .detect_hazards(&deps)
+            .iter()
+            .filter(|h| h.hazard_type == HazardType::RAW && h.producer == 0)
+            .count()
+    };
+
+    assert_eq!(stalls("unknown"), 1);
+    assert_eq!(stalls("scoped"), 0);
+}
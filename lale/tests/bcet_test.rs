@@ -0,0 +1,60 @@
+//! Test that BCET is a best-case path of its own rather than a copy of WCET
+
+use lale::{CortexM3Model, FunctionAnalyzer, InkwellCFG, InkwellParser, InkwellTimingCalculator};
+
+const DIVIDE_LOOP_IR: &str = r#"; ModuleID = 'bcet_test'
+source_filename = "bcet_test"
+
+define i32 @scale(i32 %x, i32 %d) {
+entry:
+  br label %bb.loop_2_8
+
+bb.loop_2_8:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %bb.loop_2_8 ]
+  %acc = phi i32 [ %x, %entry ], [ %q, %bb.loop_2_8 ]
+  %q = udiv i32 %acc, %d
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, 8
+  br i1 %done, label %exit, label %bb.loop_2_8
+
+exit:
+  ret i32 %q
+}
+"#;
+
+#[test]
+fn test_bcet_below_wcet_with_variable_latency_divide() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(DIVIDE_LOOP_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("scale")
+        .expect("scale function should exist");
+
+    // The hardware divide takes 2 to 12 cycles
+    let platform = CortexM3Model::new();
+    let cfg = InkwellCFG::from_function(&function);
+    let worst = InkwellTimingCalculator::calculate_block_timings(&function, &cfg, &platform);
+    let best = InkwellTimingCalculator::calculate_best_case_block_timings(&cfg, &platform);
+    let loop_block = cfg
+        .blocks
+        .iter()
+        .find(|block| block.name == "bb.loop_2_8")
+        .expect("loop block should exist");
+    assert_eq!(worst[&loop_block.id] - best[&loop_block.id], 10);
+
+    let result = FunctionAnalyzer::new(platform)
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    // At most 8 iterations at the slowest divide, at least 2 at the fastest;
+    // the self-loop block runs once more than its back edge is taken
+    let every_block_once: u64 = best.values().sum();
+    assert!(result.bcet_cycles < result.wcet_cycles);
+    assert!(
+        result.bcet_cycles <= every_block_once + 2 * best[&loop_block.id],
+        "BCET {} should take the minimum iterations",
+        result.bcet_cycles
+    );
+}
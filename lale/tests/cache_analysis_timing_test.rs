@@ -0,0 +1,188 @@
+//! Test that block timings follow the Must/May cache classification
+
+use lale::config::types::{CacheLevelConfig, ReplacementPolicy, WritePolicy};
+use lale::microarch::cache::must::CacheAbstractState;
+use lale::microarch::cache::{CacheAnalysisResult, GlobalLayout, MayCacheState, MustCacheState};
+use lale::{CortexM7Model, FunctionAnalyzer, InkwellCFG, InkwellParser, InkwellTimingCalculator};
+
+const CACHE_IR: &str = r#"; ModuleID = 'cache_analysis_timing_test'
+source_filename = "cache_analysis_timing_test"
+
+@hot_value = global i32 0
+@cold_value = global i32 0
+
+define i32 @read(i1 %which) {
+entry:
+  br i1 %which, label %hot, label %cold
+
+hot:
+  %h = load i32, ptr @hot_value
+  ret i32 %h
+
+cold:
+  %c = load i32, ptr @cold_value
+  ret i32 %c
+}
+"#;
+
+#[test]
+fn test_always_hit_block_cheaper_than_always_miss_block() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(CACHE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("read").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let graph = cfg.to_petgraph();
+    let platform = CortexM7Model::new();
+
+    let layout = GlobalLayout::of(&cfg);
+
+    let block_id = |label: &str| cfg.block_map[label];
+    let node = |label: &str| {
+        graph
+            .graph
+            .node_indices()
+            .find(|&node| graph.graph[node].execution_count_var == block_id(label))
+            .expect("block should have a node")
+    };
+    let load_address = |label: &str| {
+        let load = cfg.blocks[block_id(label)]
+            .block
+            .get_first_instruction()
+            .expect("block should have a load");
+        layout.address(&load).expect("load should have an address")
+    };
+    assert_ne!(load_address("hot"), load_address("cold"));
+
+    // Only the hot load is guaranteed to be cached where it executes
+    let mut hot_state = CacheAbstractState::empty();
+    hot_state.access(load_address("hot"), 4);
+    let mut must = MustCacheState::new();
+    must.states.insert(node("hot"), hot_state);
+    let cache_result = CacheAnalysisResult::new(must, MayCacheState::new());
+
+    let timings = InkwellTimingCalculator::calculate_with_cache_analysis(
+        &function,
+        &cfg,
+        &platform,
+        &cache_result,
+    );
+    assert!(timings[&block_id("hot")] < timings[&block_id("cold")]);
+}
+
+const POLL_IR: &str = r#"; ModuleID = 'cache_analysis_timing_test'
+source_filename = "cache_analysis_timing_test"
+
+@counter = global i32 0
+
+define i32 @poll(i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %v = load i32, ptr @counter
+  %i.next = add i32 %i, %v
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %i.next
+}
+"#;
+
+fn dcache() -> CacheLevelConfig {
+    CacheLevelConfig {
+        size_kb: 16,
+        line_size_bytes: 32,
+        associativity: 4,
+        replacement_policy: ReplacementPolicy::LRU,
+        hit_latency: 1,
+        miss_latency: 10,
+        write_policy: WritePolicy::WriteBack,
+    }
+}
+
+#[test]
+fn test_function_analyzer_prices_loads_from_data_cache_analysis() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(POLL_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("poll").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let entry = cfg.block_map["entry"];
+    let body = cfg.block_map["loop"];
+
+    let plain = FunctionAnalyzer::new(CortexM7Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+    let cached = FunctionAnalyzer::new(CortexM7Model::new())
+        .with_data_cache(dcache())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    // The load is persistent in the loop: it hits in the body and its miss
+    // is charged once, on entry to the loop
+    assert!(cached.block_timings[&body] < plain.block_timings[&body]);
+    assert_eq!(
+        cached.block_timings[&entry] - plain.block_timings[&entry],
+        plain.block_timings[&body] - cached.block_timings[&body]
+    );
+}
+
+const RECORD_IR: &str = r#"; ModuleID = 'cache_analysis_timing_test'
+source_filename = "cache_analysis_timing_test"
+
+@sample = global i32 0
+
+define void @record(i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  store i32 %i, ptr @sample
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret void
+}
+"#;
+
+#[test]
+fn test_write_through_stores_pay_memory_latency() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(RECORD_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("record")
+        .expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let body = cfg.block_map["loop"];
+
+    let analyze = |write_policy| {
+        FunctionAnalyzer::new(CortexM7Model::new())
+            .with_data_cache(CacheLevelConfig {
+                write_policy,
+                ..dcache()
+            })
+            .analyze(&function)
+            .expect("analysis should succeed")
+    };
+    let write_back = analyze(WritePolicy::WriteBack);
+    let write_through = analyze(WritePolicy::WriteThrough);
+    let uncached = FunctionAnalyzer::new(CortexM7Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    assert!(write_back.block_timings[&body] < write_through.block_timings[&body]);
+    assert_eq!(
+        write_through.block_timings[&body],
+        uncached.block_timings[&body]
+    );
+}
@@ -0,0 +1,101 @@
+//! Test that a caller's WCET includes the WCET of the functions it calls
+
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser, ModuleAnalyzer};
+
+const CALLS_IR: &str = r#"; ModuleID = 'callee_wcet_test'
+source_filename = "callee_wcet_test"
+
+declare void @log(i32)
+
+define i32 @leaf(i32 %x) {
+entry:
+  %a = mul i32 %x, %x
+  %b = udiv i32 %a, 3
+  %c = add i32 %b, %x
+  ret i32 %c
+}
+
+define i32 @caller(i32 %x) {
+entry:
+  %y = call i32 @leaf(i32 %x)
+  %z = call i32 @leaf(i32 %y)
+  call void @log(i32 %z)
+  ret i32 %z
+}
+"#;
+
+#[test]
+fn test_caller_wcet_includes_callee_wcet() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(CALLS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = ModuleAnalyzer::new(CortexM4Model::new());
+
+    // Each function on its own, every call at its flat cost
+    let leaf_own = analyzer.analyze_function(&module, "leaf").unwrap();
+    let caller_own = analyzer.analyze_function(&module, "caller").unwrap();
+
+    let result = analyzer.analyze_module(&module).unwrap();
+    assert_eq!(result.function_wcets["leaf"], leaf_own);
+
+    // Two calls to the leaf; the declared @log keeps its flat cost
+    assert_eq!(result.function_wcets["caller"], caller_own + 2 * leaf_own);
+    assert!(result.warnings.is_empty());
+}
+
+const LOOP_CALLS_IR: &str = r#"; ModuleID = 'callee_loop_test'
+source_filename = "callee_loop_test"
+
+define i32 @leaf(i32 %x) {
+entry:
+  %a = mul i32 %x, %x
+  %b = udiv i32 %a, 3
+  ret i32 %b
+}
+
+define i32 @caller(i32 %x) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %acc = phi i32 [ %x, %entry ], [ %y, %loop ]
+  %y = call i32 @leaf(i32 %acc)
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, 4
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %y
+}
+"#;
+
+#[test]
+fn test_call_in_loop_is_charged_every_iteration() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_CALLS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = ModuleAnalyzer::new(CortexM4Model::new());
+    let leaf_own = analyzer.analyze_function(&module, "leaf").unwrap();
+    let caller_own = analyzer.analyze_function(&module, "caller").unwrap();
+
+    let caller = module
+        .get_function("caller")
+        .expect("function should exist");
+    let loop_executions = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&caller)
+        .unwrap()
+        .block_costs
+        .iter()
+        .find(|cost| cost.block == "loop")
+        .map(|cost| cost.executions)
+        .expect("loop block should be costed");
+    // The exit compare bounds the back edge at 4, so like IPET the block
+    // costs run the self-loop header one final time, 5 in all
+    assert_eq!(loop_executions, 5);
+
+    let result = analyzer.analyze_module(&module).unwrap();
+    assert_eq!(result.function_wcets["caller"], caller_own + 5 * leaf_own);
+}
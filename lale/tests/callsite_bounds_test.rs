@@ -0,0 +1,67 @@
+//! Test that only induction-variable exit tests bound loops by a parameter
+
+use lale::analyzers::ParameterBound;
+use lale::{CallSiteAnalyzer, InkwellParser};
+
+const BOUNDS_IR: &str = r#"; ModuleID = 'callsite_bounds_test'
+source_filename = "callsite_bounds_test"
+
+define void @inclusive(i32 %n) {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %header ]
+  %next = add i32 %i, 1
+  %more = icmp ule i32 %next, %n
+  br i1 %more, label %header, label %exit
+
+exit:
+  ret void
+}
+
+define void @guarded(i32 %limit, i1 %stop) {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %latch ]
+  %small = icmp ult i32 %i, %limit
+  br i1 %small, label %latch, label %latch
+
+latch:
+  %next = add i32 %i, 1
+  br i1 %stop, label %exit, label %header
+
+exit:
+  ret void
+}
+"#;
+
+#[test]
+fn test_parameter_bounds_follow_exit_tests() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(BOUNDS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+
+    // `<=` keeps looping when the counter reaches the parameter
+    let inclusive = module
+        .get_function("inclusive")
+        .expect("inclusive should exist");
+    let bounded = CallSiteAnalyzer::parameter_bounded_loops(&inclusive);
+    assert_eq!(
+        bounded.get(&1),
+        Some(&ParameterBound {
+            param: 0,
+            inclusive: true,
+        })
+    );
+
+    // Comparing with a parameter doesn't bound a loop that exits on
+    // something else
+    let guarded = module
+        .get_function("guarded")
+        .expect("guarded should exist");
+    assert!(CallSiteAnalyzer::parameter_bounded_loops(&guarded).is_empty());
+}
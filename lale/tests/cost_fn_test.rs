@@ -0,0 +1,65 @@
+//! Test that a custom cost function overrides the built-in instruction costs
+
+use inkwell::values::InstructionOpcode;
+use lale::{FunctionAnalyzer, InkwellParser, InkwellTimingCalculator, RV32IModel};
+
+const STRAIGHT_LINE_IR: &str = r#"; ModuleID = 'cost_fn_test'
+source_filename = "cost_fn_test"
+
+define i32 @mac(i32 %a, i32 %b, i32 %c) {
+entry:
+  %prod = mul i32 %a, %b
+  %sum = add i32 %prod, %c
+  ret i32 %sum
+}
+"#;
+
+#[test]
+fn test_custom_cost_fn_overrides_builtin() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(STRAIGHT_LINE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("mac")
+        .expect("mac function should exist");
+
+    // Accelerator with a single-cycle multiplier, everything else 7 cycles
+    let analyzer =
+        FunctionAnalyzer::new(RV32IModel::new()).with_cost_fn(Box::new(|opcode| match opcode {
+            InstructionOpcode::Mul => 1,
+            _ => 7,
+        }));
+
+    let result = analyzer
+        .analyze_simple(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(result.wcet_cycles, 1 + 7 + 7);
+}
+
+#[test]
+fn test_cost_fn_can_fall_back_to_builtin() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(STRAIGHT_LINE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("mac")
+        .expect("mac function should exist");
+
+    let platform = RV32IModel::new();
+    let builtin = FunctionAnalyzer::new(platform.clone())
+        .analyze_simple(&function)
+        .expect("analysis should succeed");
+
+    let fallback_platform = platform.clone();
+    let custom = FunctionAnalyzer::new(platform)
+        .with_cost_fn(Box::new(move |opcode| {
+            InkwellTimingCalculator::instruction_cost(opcode, &fallback_platform)
+        }))
+        .analyze_simple(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(custom.wcet_cycles, builtin.wcet_cycles);
+}
@@ -0,0 +1,104 @@
+//! Test that blocks off every normal path are reported as dead
+
+use lale::analysis::{DeadBlock, DeadBlockKind};
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser};
+
+const DEAD_IR: &str = r#"; ModuleID = 'dead_blocks_test'
+source_filename = "dead_blocks_test"
+
+declare void @panic_bounds_check() noreturn
+
+define i32 @get(ptr %buf, i32 %i) {
+entry:
+  %in_bounds = icmp ult i32 %i, 8
+  br i1 %in_bounds, label %ok, label %panic
+
+ok:
+  %value = load i32, ptr %buf
+  ret i32 %value
+
+panic:
+  call void @panic_bounds_check()
+  unreachable
+
+leftover:
+  %twice = mul i32 %i, 2
+  br label %ok
+}
+"#;
+
+#[test]
+fn test_dead_and_panic_only_blocks_reported() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(DEAD_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("get").expect("get should exist");
+    let result = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(
+        result.dead_blocks,
+        vec![
+            DeadBlock {
+                block: "panic".to_string(),
+                block_id: 2,
+                kind: DeadBlockKind::PanicPathOnly,
+            },
+            DeadBlock {
+                block: "leftover".to_string(),
+                block_id: 3,
+                kind: DeadBlockKind::NeverExecutes,
+            },
+        ]
+    );
+
+    // Dead code adds nothing to the loop-aware bound
+    let leftover = result
+        .block_costs
+        .iter()
+        .find(|cost| cost.block == "leftover")
+        .expect("leftover should be costed");
+    assert_eq!(leftover.total_cycles(), 0);
+}
+
+// Numbered blocks have no name, so every label is empty
+const UNNAMED_IR: &str = r#"; ModuleID = 'dead_unnamed_test'
+source_filename = "dead_unnamed_test"
+
+define i32 @pick(i32 %x) {
+  %1 = icmp eq i32 %x, 0
+  br i1 %1, label %2, label %4
+
+2:
+  %3 = mul i32 %x, 3
+  ret i32 %3
+
+4:
+  %5 = add i32 %x, 1
+  ret i32 %5
+
+6:
+  %7 = sub i32 %x, 1
+  ret i32 %7
+}
+"#;
+
+#[test]
+fn test_dead_unnamed_block_leaves_other_unnamed_blocks_costed() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(UNNAMED_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("pick").expect("pick should exist");
+    let result = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(result.dead_blocks.len(), 1);
+    assert_eq!(result.dead_blocks[0].block_id, 3);
+    for cost in &result.block_costs {
+        assert_eq!(cost.total_cycles() == 0, cost.block_id == 3);
+    }
+}
@@ -0,0 +1,48 @@
+//! Test that SSA def-use chains give the hazard detector register sets
+
+use lale::ir::SsaRegisters;
+use lale::microarch::{HazardDetector, HazardType, Register};
+use lale::InkwellParser;
+
+const MADD_IR: &str = r#"; ModuleID = 'def_use_test'
+source_filename = "def_use_test"
+
+define i32 @madd(i32 %a, i32 %b, ptr %out) {
+entry:
+  %prod = mul i32 %a, %b
+  %sum = add i32 %prod, %a
+  store i32 %sum, ptr %out
+  ret i32 %sum
+}
+"#;
+
+#[test]
+fn test_def_use_chains_drive_hazard_detection() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(MADD_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("madd").expect("madd should exist");
+    let entry = function.get_first_basic_block().expect("entry block");
+
+    // %a, %b, %out, %prod, %sum; the store and ret define nothing
+    let registers = SsaRegisters::from_function(&function);
+    assert_eq!(registers.len(), 5);
+
+    let deps = registers.block_dependencies(entry);
+    assert_eq!(deps.len(), 4);
+    assert_eq!(deps[0].reads, vec![Register(0), Register(1)]);
+    assert_eq!(deps[0].writes, vec![Register(3)]);
+    assert_eq!(deps[1].reads, vec![Register(3), Register(0)]);
+    assert_eq!(deps[2].reads, vec![Register(4), Register(2)]);
+    assert!(deps[2].writes.is_empty());
+
+    // mul -> add and add -> store are back to back; ret is far enough away
+    let hazards = HazardDetector::new(5, true).detect_hazards(&deps);
+    let raw: Vec<(usize, usize)> = hazards
+        .iter()
+        .filter(|h| h.hazard_type == HazardType::RAW)
+        .map(|h| (h.producer, h.consumer))
+        .collect();
+    assert_eq!(raw, vec![(0, 1), (1, 2)]);
+}
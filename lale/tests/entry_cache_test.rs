@@ -0,0 +1,128 @@
+//! Test that the instruction-cache state is carried from callers into callees
+
+use lale::microarch::cache::{is_code_cached, EntryCacheStates};
+use lale::microarch::state::{CacheConfig, CacheLevelConfig, ReplacementPolicy, WritePolicy};
+use lale::{CortexM7Model, InkwellParser, ModuleAnalyzer};
+
+const CALLS_IR: &str = r#"; ModuleID = 'entry_cache_test'
+source_filename = "entry_cache_test"
+
+declare void @log()
+
+define void @sample() {
+entry:
+  ret void
+}
+
+define void @filter() {
+entry:
+  call void @sample()
+  ret void
+}
+
+define void @control() {
+entry:
+  call void @sample()
+  call void @sample()
+  call void @filter()
+  call void @log()
+  call void @sample()
+  ret void
+}
+"#;
+
+fn cache_config() -> CacheConfig {
+    CacheConfig {
+        instruction_cache: Some(CacheLevelConfig {
+            size_kb: 4,
+            line_size_bytes: 32,
+            associativity: 4,
+            replacement_policy: ReplacementPolicy::LRU,
+            write_policy: WritePolicy::WriteBack,
+        }),
+        data_cache: None,
+    }
+}
+
+#[test]
+fn test_callee_warm_after_earlier_call() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(CALLS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let states = EntryCacheStates::analyze(&module, &cache_config());
+
+    let warm: Vec<(&str, &str, bool)> = states
+        .call_sites()
+        .iter()
+        .map(|s| {
+            let cached = is_code_cached(&s.state, &s.site.callee);
+            (s.site.caller.as_str(), s.site.callee.as_str(), cached)
+        })
+        .collect();
+
+    // The first call fetches sample, the external log may evict it, and
+    // filter only runs once sample has been fetched
+    assert_eq!(
+        warm,
+        vec![
+            ("control", "sample", false),
+            ("control", "sample", true),
+            ("control", "filter", false),
+            ("control", "sample", false),
+            ("filter", "sample", true),
+        ]
+    );
+    assert!(is_code_cached(
+        states.entry_state("filter").expect("filter is called"),
+        "sample"
+    ));
+
+    // Warm calls skip the cold miss at the callee's entry
+    let timings = ModuleAnalyzer::new(CortexM7Model::new())
+        .analyze_calls_with_cache(&module, &cache_config())
+        .expect("analysis should succeed");
+    assert_eq!(timings.len(), 5);
+    assert!(!timings[0].warm);
+    assert_eq!(timings[0].wcet_cycles, timings[0].cold_wcet_cycles);
+    assert!(timings[1].warm);
+    assert!(timings[1].wcet_cycles < timings[1].cold_wcet_cycles);
+}
+
+// Numbered blocks have no name, so every label is empty
+const UNNAMED_IR: &str = r#"; ModuleID = 'entry_cache_unnamed_test'
+source_filename = "entry_cache_unnamed_test"
+
+define void @sample() {
+  ret void
+}
+
+define void @pick(i1 %fast) {
+  br i1 %fast, label %1, label %2
+
+1:
+  call void @sample()
+  ret void
+
+2:
+  call void @sample()
+  ret void
+}
+"#;
+
+#[test]
+fn test_unnamed_blocks_only_see_their_own_calls() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(UNNAMED_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let states = EntryCacheStates::analyze(&module, &cache_config());
+
+    // Neither branch runs the other's call first
+    let warm: Vec<(usize, bool)> = states
+        .call_sites()
+        .iter()
+        .map(|s| (s.site.block_id, is_code_cached(&s.state, &s.site.callee)))
+        .collect();
+    assert_eq!(warm, vec![(1, false), (2, false)]);
+}
@@ -0,0 +1,70 @@
+//! Test that a flow-facts file replaces a loop's bound in the WCET
+
+use lale::analysis::FlowFactStore;
+use lale::analyzers::LoopBoundSource;
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser};
+
+const FLOW_FACTS_IR: &str = r#"; ModuleID = 'flow_facts_test'
+source_filename = "flow_facts_test"
+
+define void @fill(ptr %buf, i32 %n) {
+entry:
+  br label %bb.loop_1_16
+
+bb.loop_1_16:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %bb.loop_1_16 ]
+  %slot = getelementptr i32, ptr %buf, i32 %i
+  store i32 %i, ptr %slot
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %bb.loop_1_16
+
+exit:
+  ret void
+}
+"#;
+
+#[test]
+fn test_flow_fact_halving_a_bound_halves_the_loop_cost() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(FLOW_FACTS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("fill").expect("function should exist");
+
+    let path = std::env::temp_dir().join(format!("lale-flow-facts-{}.toml", std::process::id()));
+    std::fs::write(&path, "[\"fill\"]\n\"bb.loop_1_16\" = 8\n\n[\"renamed\"]\n").unwrap();
+    let flow_facts = FlowFactStore::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    let flow_facts = flow_facts.expect("flow facts should load");
+
+    let annotated = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+    let halved = FunctionAnalyzer::new(CortexM4Model::new())
+        .with_flow_facts(flow_facts.clone())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(annotated.loop_bound_decisions[0].max_iterations, Some(16));
+    assert_eq!(halved.loop_bound_decisions[0].max_iterations, Some(8));
+    assert_eq!(
+        halved.loop_bound_decisions[0].source,
+        LoopBoundSource::FlowFact
+    );
+
+    // Eight fewer iterations of the single-block loop
+    let loop_cycles = halved
+        .block_costs
+        .iter()
+        .find(|cost| cost.block == "bb.loop_1_16")
+        .expect("loop block should be costed")
+        .cycles;
+    assert_eq!(annotated.wcet_cycles - halved.wcet_cycles, 8 * loop_cycles);
+
+    // Unknown functions are reported, not fatal
+    let loop_headers = [("fill".to_string(), vec!["bb.loop_1_16".to_string()])].into();
+    let unmatched = flow_facts.unmatched(&loop_headers);
+    assert_eq!(unmatched.len(), 1);
+    assert_eq!(unmatched[0].function.as_deref(), Some("renamed"));
+}
@@ -0,0 +1,41 @@
+//! Test that data hazards stall the pipeline in block timings
+
+use lale::{CortexM4Model, InkwellCFG, InkwellParser, InkwellTimingCalculator};
+
+/// A load whose value the next instruction uses, and a return of the sum
+const LOAD_USE_IR: &str = r#"; ModuleID = 'hazard_test'
+source_filename = "hazard_test"
+
+define i32 @load_use(ptr %p) {
+entry:
+  %v = load i32, ptr %p
+  %w = add i32 %v, 1
+  ret i32 %w
+}
+"#;
+
+#[test]
+fn test_forwarding_shortens_load_use_stall() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOAD_USE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("load_use")
+        .expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let entry = cfg.block_map["entry"];
+    let platform = CortexM4Model::new();
+
+    let base = InkwellTimingCalculator::calculate_block_timings(&function, &cfg, &platform);
+    let stalled =
+        InkwellTimingCalculator::calculate_with_hazards(&function, &cfg, &platform, 5, false);
+    let forwarded =
+        InkwellTimingCalculator::calculate_with_hazards(&function, &cfg, &platform, 5, true);
+
+    // Two back-to-back uses: 3 cycles each until write-back, 1 with the
+    // result forwarded out of execute
+    assert_eq!(stalled[&entry], base[&entry] + 6);
+    assert_eq!(forwarded[&entry], base[&entry] + 2);
+    assert!(forwarded[&entry] < stalled[&entry]);
+}
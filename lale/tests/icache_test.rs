@@ -0,0 +1,192 @@
+//! Test the instruction-cache analysis of a loop's fetch stream
+
+use lale::config::types::{CacheLevelConfig, ReplacementPolicy, WritePolicy};
+use lale::microarch::cache::{CacheAccessClass, InstructionCacheAnalysis};
+use lale::{CortexM7Model, FunctionAnalyzer, InkwellCFG, InkwellParser, InkwellTimingCalculator};
+
+/// 1 + 17 + 1 instructions: at 4 bytes each the loop spans the 32-byte
+/// lines at 0x00, 0x20 and 0x40, and the exit sits in the last of them
+const LOOP_IR: &str = r#"; ModuleID = 'icache_test'
+source_filename = "icache_test"
+
+define i32 @sum(i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %acc = phi i32 [ 0, %entry ], [ %acc.next, %loop ]
+  %a = add i32 %acc, %i
+  %b = xor i32 %a, 7
+  %c = shl i32 %b, 1
+  %d = add i32 %c, %i
+  %e = and i32 %d, 255
+  %f = or i32 %e, 1
+  %g = sub i32 %f, %i
+  %h = add i32 %g, 3
+  %j = xor i32 %h, %a
+  %k = shl i32 %j, 2
+  %l = add i32 %k, %b
+  %acc.next = add i32 %l, %c
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %acc.next
+}
+"#;
+
+fn icache() -> CacheLevelConfig {
+    CacheLevelConfig {
+        size_kb: 16,
+        line_size_bytes: 32,
+        associativity: 4,
+        replacement_policy: ReplacementPolicy::LRU,
+        hit_latency: 1,
+        miss_latency: 10,
+        write_policy: WritePolicy::WriteBack,
+    }
+}
+
+#[test]
+fn test_loop_fetches_miss_once_per_entry() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("sum").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let block_id = |label: &str| cfg.block_map[label];
+
+    let result = InstructionCacheAnalysis::new(&icache()).analyze(&cfg);
+
+    // Blocks are laid out back to back
+    assert_eq!(result.layout.address(block_id("entry")), Some(0));
+    assert_eq!(result.layout.address(block_id("loop")), Some(4));
+    assert_eq!(result.layout.size(block_id("loop")), Some(68));
+    assert_eq!(result.layout.address(block_id("exit")), Some(72));
+
+    let classes = |label: &str| -> Vec<(u64, CacheAccessClass)> {
+        result.fetches[&block_id(label)]
+            .iter()
+            .map(|fetch| (fetch.line, fetch.class))
+            .collect()
+    };
+
+    // The cache starts empty
+    assert_eq!(classes("entry"), vec![(0x00, CacheAccessClass::AlwaysMiss)]);
+
+    // The entry already fetched the first line; the other two stay cached
+    // across iterations
+    assert_eq!(
+        classes("loop"),
+        vec![
+            (0x00, CacheAccessClass::AlwaysHit),
+            (0x20, CacheAccessClass::FirstMiss),
+            (0x40, CacheAccessClass::FirstMiss),
+        ]
+    );
+    assert_eq!(classes("exit"), vec![(0x40, CacheAccessClass::AlwaysHit)]);
+    assert_eq!(result.misses(block_id("loop")), 0);
+}
+
+#[test]
+fn test_instruction_cache_misses_are_charged_on_loop_entry() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("sum").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let block_id = |label: &str| cfg.block_map[label];
+    let platform = CortexM7Model::new();
+
+    let base = InkwellTimingCalculator::calculate_block_timings(&function, &cfg, &platform);
+    let timings = InkwellTimingCalculator::calculate_with_instruction_cache(
+        &function,
+        &cfg,
+        &platform,
+        &icache(),
+    );
+
+    // The entry's own miss and the loop's two first misses, 9 cycles each
+    assert_eq!(timings[&block_id("entry")], base[&block_id("entry")] + 27);
+    assert_eq!(timings[&block_id("loop")], base[&block_id("loop")]);
+    assert_eq!(timings[&block_id("exit")], base[&block_id("exit")]);
+}
+
+/// Entry, loop and exit all fit in the 32-byte line at 0x00
+const CALL_IR: &str = r#"; ModuleID = 'icache_call_test'
+source_filename = "icache_call_test"
+
+declare void @tick()
+
+define void @spin(i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  call void @tick()
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret void
+}
+"#;
+
+#[test]
+fn test_call_may_evict_the_callers_lines() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(CALL_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("spin").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let block_id = |label: &str| cfg.block_map[label];
+
+    let result = InstructionCacheAnalysis::new(&icache()).analyze(&cfg);
+    let classes: Vec<(u64, CacheAccessClass)> = result.fetches[&block_id("loop")]
+        .iter()
+        .map(|fetch| (fetch.line, fetch.class))
+        .collect();
+
+    // The line is fetched again on return from the callee, which may have
+    // evicted it, so it is neither a hit nor persistent in the loop
+    assert_eq!(
+        classes,
+        vec![
+            (0x00, CacheAccessClass::AlwaysHit),
+            (0x00, CacheAccessClass::Unknown),
+        ]
+    );
+    assert_eq!(result.misses(block_id("loop")), 1);
+}
+
+#[test]
+fn test_function_analyzer_charges_instruction_cache_misses() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("sum").expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function);
+    let entry = cfg.block_map["entry"];
+
+    let plain = FunctionAnalyzer::new(CortexM7Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+    let cached = FunctionAnalyzer::new(CortexM7Model::new())
+        .with_instruction_cache(icache())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    assert_eq!(
+        cached.block_timings[&entry],
+        plain.block_timings[&entry] + 27
+    );
+    assert_eq!(cached.wcet_cycles, plain.wcet_cycles + 27);
+}
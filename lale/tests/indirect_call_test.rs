@@ -0,0 +1,135 @@
+//! Test that indirect calls are charged their worst candidate target
+
+use lale::analyzers::TargetSource;
+use lale::{CortexM4Model, FunctionAnalyzer, IndirectCallAnalyzer, InkwellParser};
+
+const INDIRECT_IR: &str = r#"; ModuleID = 'indirect_call_test'
+source_filename = "indirect_call_test"
+
+define void @on_tick(i32 %n) {
+entry:
+  ret void
+}
+
+define void @on_error(i32 %n) {
+entry:
+  %a = mul i32 %n, %n
+  %b = mul i32 %a, %n
+  %c = sdiv i32 %b, 7
+  %d = sdiv i32 %c, 3
+  ret void
+}
+
+define void @dispatch(ptr %handler, i32 %n) {
+entry:
+  call void %handler(i32 %n)
+  ret void
+}
+
+define i64 @apply(ptr %fn, i64 %x) {
+entry:
+  %y = call i64 %fn(i64 %x)
+  ret i64 %y
+}
+"#;
+
+#[test]
+fn test_indirect_calls_charged_worst_target() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(INDIRECT_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = FunctionAnalyzer::new(CortexM4Model::new());
+    let wcet = |name: &str| {
+        let function = module.get_function(name).expect("function should exist");
+        analyzer
+            .analyze(&function)
+            .expect("analysis should succeed")
+            .wcet_cycles
+    };
+
+    let bounds = IndirectCallAnalyzer::new().analyze_module(&analyzer, &module);
+    assert_eq!(bounds.len(), 2);
+
+    // Both handlers match the signature; the slower one is charged
+    let dispatch = &bounds[0];
+    assert_eq!(dispatch.function, "dispatch");
+    assert_eq!(dispatch.sites[0].targets, vec!["on_tick", "on_error"]);
+    assert_eq!(dispatch.sites[0].source, TargetSource::Signature);
+    assert_eq!(dispatch.sites[0].wcet_cycles, Some(wcet("on_error")));
+    assert_eq!(
+        dispatch.wcet_cycles,
+        dispatch.context_free_wcet_cycles + wcet("on_error")
+    );
+    assert!(dispatch.is_sound());
+
+    // Nothing in the module matches i64 (i64)
+    let apply = &bounds[1];
+    assert_eq!(apply.function, "apply");
+    assert!(apply.sites[0].targets.is_empty());
+    assert!(!apply.is_sound());
+    assert_eq!(apply.warnings().len(), 1);
+
+    // User-supplied targets replace the inferred ones
+    let bounds = IndirectCallAnalyzer::new()
+        .with_targets("dispatch", vec!["on_tick".to_string()])
+        .analyze_module(&analyzer, &module);
+    assert_eq!(bounds[0].sites[0].source, TargetSource::Annotation);
+    assert_eq!(bounds[0].sites[0].wcet_cycles, Some(wcet("on_tick")));
+}
+
+const VTABLE_IR: &str = r#"; ModuleID = 'vtable_call_test'
+source_filename = "vtable_call_test"
+
+@vtable.adc = private unnamed_addr constant <{ ptr, [16 x i8], ptr }> <{ ptr null, [16 x i8] c"\04\00\00\00\00\00\00\00\04\00\00\00\00\00\00\00", ptr @adc_read }>, align 8
+@vtable.spi = private unnamed_addr constant <{ ptr, [16 x i8], ptr }> <{ ptr null, [16 x i8] c"\04\00\00\00\00\00\00\00\04\00\00\00\00\00\00\00", ptr @spi_read }>, align 8
+
+define i32 @adc_read(ptr %self) {
+entry:
+  %value = load i32, ptr %self
+  ret i32 %value
+}
+
+define i32 @spi_read(ptr %self) {
+entry:
+  %raw = load i32, ptr %self
+  %scaled = sdiv i32 %raw, 3
+  ret i32 %scaled
+}
+
+define i32 @checksum(ptr %buf) {
+entry:
+  %a = load i32, ptr %buf
+  %b = mul i32 %a, %a
+  %c = sdiv i32 %b, 7
+  %d = sdiv i32 %c, 5
+  ret i32 %d
+}
+
+define i32 @poll(ptr %data, ptr %vtable) {
+entry:
+  %slot = getelementptr inbounds i8, ptr %vtable, i64 24
+  %read = load ptr, ptr %slot, align 8
+  %value = call i32 %read(ptr %data)
+  ret i32 %value
+}
+"#;
+
+#[test]
+fn test_trait_object_calls_resolved_through_vtables() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(VTABLE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = FunctionAnalyzer::new(CortexM4Model::new());
+
+    let bounds = IndirectCallAnalyzer::new().analyze_module(&analyzer, &module);
+    assert_eq!(bounds.len(), 1);
+
+    // checksum has the same signature but is in no vtable
+    let site = &bounds[0].sites[0];
+    assert_eq!(site.source, TargetSource::Vtable);
+    assert_eq!(site.targets, vec!["adc_read", "spi_read"]);
+    assert_eq!(site.vtable_slot.as_ref().map(|slot| slot.offset), Some(24));
+    assert!(bounds[0].is_sound());
+}
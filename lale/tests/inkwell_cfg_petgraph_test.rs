@@ -0,0 +1,101 @@
+//! Test that the inkwell CFG exports to petgraph for loop analysis and IPET
+
+use lale::analysis::LoopBounds;
+use lale::{FunctionAnalyzer, InkwellCFG, InkwellParser, LoopAnalyzer, RV32IModel};
+
+const LOOP_IR: &str = r#"; ModuleID = 'loop_test'
+source_filename = "loop_test"
+
+define i32 @sum(i32 %n) {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %acc = phi i32 [ 0, %entry ], [ %acc.next, %body ]
+  %cond = icmp slt i32 %i, %n
+  br i1 %cond, label %body, label %exit
+
+body:
+  %acc.next = add i32 %acc, %i
+  %next = add i32 %i, 1
+  br label %header
+
+exit:
+  ret i32 %acc
+}
+"#;
+
+#[test]
+fn test_inkwell_cfg_to_petgraph() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("loop IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("sum")
+        .expect("sum function should exist");
+
+    let inkwell_cfg = InkwellCFG::from_function(&function);
+    let cfg = inkwell_cfg.to_petgraph();
+
+    assert_eq!(cfg.graph.node_count(), 4);
+    assert_eq!(cfg.graph.edge_count(), 4);
+    assert_eq!(cfg.exits.len(), 1);
+
+    let loops = LoopAnalyzer::analyze_loops(&cfg);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(cfg.graph[loops[0].header].label, "header");
+}
+
+#[test]
+fn test_ipet_wcet_exceeds_block_sum_for_loops() {
+    let (_context, module) = InkwellParser::parse_ir_from_buffer(LOOP_IR).unwrap();
+    let function = module.get_function("sum").unwrap();
+
+    let analyzer = FunctionAnalyzer::new(RV32IModel::new());
+    let ipet = analyzer.analyze(&function).unwrap();
+    let simple = analyzer.analyze_simple(&function).unwrap();
+
+    assert_eq!(ipet.loops.len(), 1);
+    // Whether solved by IPET or bounded approximately, the loop body runs
+    // more than once
+    assert!(ipet.wcet_cycles > simple.wcet_cycles);
+}
+
+const CONSTANT_TRIP_IR: &str = r#"; ModuleID = 'constant_trip_test'
+source_filename = "constant_trip_test"
+
+define i32 @sum8() {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %acc = phi i32 [ 0, %entry ], [ %acc.next, %body ]
+  %cond = icmp slt i32 %i, 8
+  br i1 %cond, label %body, label %exit
+
+body:
+  %acc.next = add i32 %acc, %i
+  %next = add i32 %i, 1
+  br label %header
+
+exit:
+  ret i32 %acc
+}
+"#;
+
+#[test]
+fn test_constant_trip_loop_gets_constant_bound() {
+    let (_context, module) = InkwellParser::parse_ir_from_buffer(CONSTANT_TRIP_IR).unwrap();
+    let function = module.get_function("sum8").unwrap();
+
+    let cfg = InkwellCFG::from_function(&function).to_petgraph();
+    let loops = LoopAnalyzer::analyze_loops(&cfg);
+
+    assert_eq!(loops.len(), 1);
+    // The header's exit compare is exported with it, so the bound comes
+    // from its constant rather than a default
+    assert_eq!(loops[0].bounds, LoopBounds::Constant { min: 0, max: 8 });
+}
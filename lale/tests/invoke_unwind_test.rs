@@ -0,0 +1,68 @@
+//! Test that the unwind edge of an `invoke` is part of the analyzed CFG
+
+use lale::analysis::{DeadBlock, DeadBlockKind};
+use lale::ir::cfg::EdgeType;
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellCFG, InkwellParser};
+
+const INVOKE_IR: &str = r#"; ModuleID = 'invoke_unwind_test'
+source_filename = "invoke_unwind_test"
+
+declare void @may_panic()
+declare void @drop_guard(ptr)
+declare i32 @rust_eh_personality(...)
+
+define void @guarded(ptr %guard) personality ptr @rust_eh_personality {
+entry:
+  invoke void @may_panic()
+          to label %done unwind label %cleanup
+
+done:
+  call void @drop_guard(ptr %guard)
+  ret void
+
+cleanup:
+  %lp = landingpad { ptr, i32 }
+          cleanup
+  call void @drop_guard(ptr %guard)
+  resume { ptr, i32 } %lp
+}
+"#;
+
+#[test]
+fn test_invoke_unwind_edge_is_analyzed() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(INVOKE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("guarded")
+        .expect("guarded should exist");
+
+    // entry -> done normally, entry -> cleanup when may_panic unwinds
+    let cfg = InkwellCFG::from_function(&function);
+    assert_eq!(cfg.edges, vec![(0, 1), (0, 2)]);
+    assert_eq!(cfg.unwind_edges, vec![(0, 2)]);
+
+    let graph = cfg.to_petgraph();
+    let unwind: Vec<_> = graph
+        .graph
+        .edge_weights()
+        .filter(|&&edge| edge == EdgeType::Unwind)
+        .collect();
+    assert_eq!(unwind.len(), 1);
+
+    // The unwinding path is a branch of its own that IPET can pick
+    let result = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+    let cleanup_path = result.block_timings[&0] + result.block_timings[&2];
+    assert!(result.wcet_cycles >= cleanup_path);
+    assert_eq!(
+        result.dead_blocks,
+        vec![DeadBlock {
+            block: "cleanup".to_string(),
+            block_id: 2,
+            kind: DeadBlockKind::PanicPathOnly,
+        }]
+    );
+}
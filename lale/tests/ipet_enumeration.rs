@@ -0,0 +1,96 @@
+//! IPET against exhaustive path enumeration
+//!
+//! On a loop-free CFG the WCET is the cost of the most expensive path from
+//! the entry to an exit, which for a handful of blocks can be found by
+//! walking every path. The ILP formulation has to agree with it exactly.
+
+use ahash::AHashMap;
+use lale::ir::{BasicBlock, EdgeType, CFG};
+use lale::{Cycles, IPETSolver};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::Direction;
+use proptest::prelude::*;
+
+/// Most blocks in a generated CFG
+const MAX_BLOCKS: usize = 7;
+
+/// DAG over `cycles.len()` blocks in topological order, entry first
+///
+/// Block `i` has an edge to each later block `j` whose pair bit is set.
+/// Blocks left without successors get one to `i + 1`, except the last two,
+/// so there may be one or two exits besides unreachable blocks.
+fn dag(cycles: &[u32], pair_bits: &[u8]) -> (CFG, AHashMap<NodeIndex, Cycles>) {
+    let mut graph = DiGraph::new();
+    let mut label_to_node = AHashMap::new();
+    let nodes: Vec<NodeIndex> = (0..cycles.len())
+        .map(|id| {
+            let label = format!("bb{}", id);
+            let node = graph.add_node(BasicBlock {
+                label: label.clone(),
+                instructions: vec![],
+                execution_count_var: id,
+                loop_bound: None,
+            });
+            label_to_node.insert(label, node);
+            node
+        })
+        .collect();
+
+    let mut bits = pair_bits.iter().cycle();
+    for from in 0..nodes.len() {
+        let mut successors = 0;
+        for to in from + 1..nodes.len() {
+            if bits.next() == Some(&1) {
+                graph.add_edge(nodes[from], nodes[to], EdgeType::Direct);
+                successors += 1;
+            }
+        }
+        if successors == 0 && from + 2 < nodes.len() {
+            graph.add_edge(nodes[from], nodes[from + 1], EdgeType::Direct);
+        }
+    }
+
+    let exits = graph
+        .node_indices()
+        .filter(|&node| graph.neighbors(node).next().is_none())
+        .collect();
+    let timings = nodes
+        .iter()
+        .zip(cycles)
+        .map(|(&node, &cycles)| (node, Cycles::new(cycles)))
+        .collect();
+
+    let cfg = CFG {
+        graph,
+        entry: nodes[0],
+        exits,
+        label_to_node,
+    };
+    (cfg, timings)
+}
+
+/// Cost of the most expensive path from `node` to an exit, by walking every path
+fn longest_path(cfg: &CFG, timings: &AHashMap<NodeIndex, Cycles>, node: NodeIndex) -> u64 {
+    let own = timings[&node].worst_case as u64;
+    let rest = cfg
+        .graph
+        .neighbors_directed(node, Direction::Outgoing)
+        .map(|succ| longest_path(cfg, timings, succ))
+        .max()
+        .unwrap_or(0);
+    own + rest
+}
+
+proptest! {
+    #[test]
+    fn ipet_matches_longest_path_on_dags(
+        cycles in prop::collection::vec(1u32..50, 2..MAX_BLOCKS + 1),
+        pair_bits in prop::collection::vec(0u8..2, 1..MAX_BLOCKS * MAX_BLOCKS),
+    ) {
+        let (cfg, timings) = dag(&cycles, &pair_bits);
+
+        let expected = longest_path(&cfg, &timings, cfg.entry);
+        let wcet = IPETSolver::solve_wcet(&cfg, &timings, &[]).expect("IPET should solve");
+        prop_assert_eq!(wcet, expected);
+    }
+}
@@ -0,0 +1,78 @@
+//! Test that loops are classified by what their exit condition depends on
+
+use lale::analysis::LoopKind;
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser};
+
+const LOOPS_IR: &str = r#"; ModuleID = 'loop_kind_test'
+source_filename = "loop_kind_test"
+
+define void @counted(ptr %buf) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  store i32 %i, ptr %buf
+  %i.next = add i32 %i, 1
+  %done = icmp eq i32 %i.next, 16
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret void
+}
+
+define void @parametric(ptr %buf, i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  store i32 %i, ptr %buf
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret void
+}
+
+define i32 @data_dependent(ptr %head) {
+entry:
+  br label %loop
+
+loop:
+  %node = phi ptr [ %head, %entry ], [ %next, %loop ]
+  %count = phi i32 [ 0, %entry ], [ %count.next, %loop ]
+  %count.next = add i32 %count, 1
+  %next = load ptr, ptr %node
+  %done = icmp eq ptr %next, null
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %count.next
+}
+"#;
+
+#[test]
+fn test_loops_classified_by_exit_condition() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOPS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = FunctionAnalyzer::new(CortexM4Model::new());
+    let kinds = |name: &str| -> Vec<LoopKind> {
+        let function = module.get_function(name).expect("function should exist");
+        analyzer
+            .analyze(&function)
+            .expect("analysis should succeed")
+            .loop_bound_decisions
+            .iter()
+            .map(|decision| decision.kind)
+            .collect()
+    };
+
+    assert_eq!(kinds("counted"), vec![LoopKind::Counted]);
+    assert_eq!(kinds("parametric"), vec![LoopKind::Parametric]);
+    assert_eq!(kinds("data_dependent"), vec![LoopKind::DataDependent]);
+    assert!(LoopKind::DataDependent.needs_annotation());
+}
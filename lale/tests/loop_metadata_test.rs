@@ -0,0 +1,79 @@
+//! Test that `!llvm.loop` bound metadata takes priority over heuristics
+
+use lale::analysis::LoopBounds;
+use lale::{InkwellCFG, InkwellParser, LoopAnalyzer};
+
+const LOOP_METADATA_IR: &str = r#"; ModuleID = 'loop_metadata_test'
+source_filename = "loop_metadata_test"
+
+define void @annotated(ptr %buf, i32 %n) {
+entry:
+  br label %for.body
+
+for.body:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %for.body ]
+  store i32 %i, ptr %buf
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %for.body, !llvm.loop !0
+
+exit:
+  ret void
+}
+
+define void @malformed(ptr %buf, i32 %n) {
+entry:
+  br label %bb.loop_2_6
+
+bb.loop_2_6:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %bb.loop_2_6 ]
+  store i32 %i, ptr %buf
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %bb.loop_2_6, !llvm.loop !3
+
+exit:
+  ret void
+}
+
+!0 = distinct !{!0, !1, !2}
+!1 = !{!"llvm.loop.mustprogress"}
+!2 = !{!"llvm.loop.unroll.count", i32 8}
+!3 = distinct !{!3, !4}
+!4 = !{!"llvm.loop.bound", !"many"}
+"#;
+
+fn loop_bounds(function_name: &str) -> Vec<(LoopBounds, bool)> {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(LOOP_METADATA_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function(function_name)
+        .expect("function should exist");
+    let cfg = InkwellCFG::from_function(&function).to_petgraph();
+
+    LoopAnalyzer::analyze_loops(&cfg)
+        .iter()
+        .map(|loop_info| {
+            let annotated = LoopAnalyzer::has_annotated_bound(&cfg, loop_info);
+            (loop_info.bounds.clone(), annotated)
+        })
+        .collect()
+}
+
+#[test]
+fn test_loop_metadata_bound_overrides_label_heuristic() {
+    assert_eq!(
+        loop_bounds("annotated"),
+        vec![(LoopBounds::Constant { min: 0, max: 8 }, true)]
+    );
+}
+
+#[test]
+fn test_malformed_loop_metadata_falls_back_to_label() {
+    assert_eq!(
+        loop_bounds("malformed"),
+        vec![(LoopBounds::Constant { min: 2, max: 6 }, true)]
+    );
+}
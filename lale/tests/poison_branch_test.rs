@@ -0,0 +1,63 @@
+//! Test that branches on undef and poison are reported with both sides costed
+
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser};
+
+const POISON_IR: &str = r#"; ModuleID = 'poison_branch_test'
+source_filename = "poison_branch_test"
+
+define i32 @pick(i32 %x) {
+entry:
+  %flag = freeze i1 undef
+  br i1 %flag, label %cheap, label %expensive
+
+cheap:
+  br label %check
+
+expensive:
+  %a = sdiv i32 %x, 3
+  %b = sdiv i32 %a, 5
+  %c = sdiv i32 %b, 7
+  br label %check
+
+check:
+  %v = phi i32 [ poison, %cheap ], [ %c, %expensive ]
+  %positive = icmp sgt i32 %v, 0
+  br i1 %positive, label %done, label %negate
+
+negate:
+  %n = sub i32 0, %x
+  br label %done
+
+done:
+  %r = phi i32 [ %x, %check ], [ %n, %negate ]
+  %small = icmp slt i32 %r, 10
+  br i1 %small, label %exit, label %exit
+
+exit:
+  ret i32 %r
+}
+"#;
+
+#[test]
+fn test_poison_branches_reported_and_costed_on_worst_side() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(POISON_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module.get_function("pick").expect("pick should exist");
+    let result = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .expect("analysis should succeed");
+
+    // The branch on %r depends only on the argument
+    assert_eq!(result.poison_branches, vec!["entry", "check"]);
+
+    // The expensive side of the undef branch is not pruned
+    let expensive = result
+        .block_costs
+        .iter()
+        .find(|cost| cost.block == "expensive")
+        .expect("expensive should be costed");
+    assert!(result.wcet_cycles >= expensive.cycles);
+    assert_eq!(expensive.executions, 1);
+}
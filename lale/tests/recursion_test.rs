@@ -0,0 +1,120 @@
+//! Test that recursive calls are found and rejected by module analysis
+
+use lale::{CallGraph, CortexM4Model, InkwellParser, LaleError, ModuleAnalyzer};
+
+const DIRECT_IR: &str = r#"; ModuleID = 'direct_recursion'
+source_filename = "direct_recursion"
+
+define i32 @a(i32 %n) {
+entry:
+  %done = icmp eq i32 %n, 0
+  br i1 %done, label %exit, label %recurse
+
+recurse:
+  %m = sub i32 %n, 1
+  %r = call i32 @a(i32 %m)
+  br label %exit
+
+exit:
+  %v = phi i32 [ 0, %entry ], [ %r, %recurse ]
+  ret i32 %v
+}
+
+define i32 @main() {
+entry:
+  %v = call i32 @a(i32 3)
+  ret i32 %v
+}
+"#;
+
+const MUTUAL_IR: &str = r#"; ModuleID = 'mutual_recursion'
+source_filename = "mutual_recursion"
+
+define i32 @a(i32 %n) {
+entry:
+  %v = call i32 @b(i32 %n)
+  ret i32 %v
+}
+
+define i32 @b(i32 %n) {
+entry:
+  %done = icmp eq i32 %n, 0
+  br i1 %done, label %exit, label %recurse
+
+recurse:
+  %m = sub i32 %n, 1
+  %r = call i32 @a(i32 %m)
+  br label %exit
+
+exit:
+  %v = phi i32 [ 0, %entry ], [ %r, %recurse ]
+  ret i32 %v
+}
+"#;
+
+const ACYCLIC_IR: &str = r#"; ModuleID = 'acyclic'
+source_filename = "acyclic"
+
+define i32 @leaf(i32 %n) {
+entry:
+  %v = add i32 %n, 1
+  ret i32 %v
+}
+
+define i32 @a(i32 %n) {
+entry:
+  %x = call i32 @leaf(i32 %n)
+  %y = call i32 @leaf(i32 %x)
+  ret i32 %y
+}
+"#;
+
+fn cycles_and_error(ir: &str) -> (Vec<Vec<String>>, Option<LaleError>) {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(ir) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let mut cycles = CallGraph::from_module(&module).find_cycles();
+    for cycle in &mut cycles {
+        cycle.sort();
+    }
+    let error = ModuleAnalyzer::new(CortexM4Model::new())
+        .analyze_module(&module)
+        .err();
+    (cycles, error)
+}
+
+#[test]
+fn test_direct_recursion_is_rejected() {
+    let (cycles, error) = cycles_and_error(DIRECT_IR);
+
+    assert_eq!(cycles, vec![vec!["a".to_string()]]);
+    match error {
+        Some(error @ LaleError::Recursion(_)) => {
+            assert_eq!(
+                error.to_string(),
+                "Recursive functions have no bounded WCET: [a]"
+            );
+        }
+        other => panic!("expected a recursion error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_mutual_recursion_is_rejected() {
+    let (cycles, error) = cycles_and_error(MUTUAL_IR);
+
+    assert_eq!(cycles, vec![vec!["a".to_string(), "b".to_string()]]);
+    match error {
+        Some(LaleError::Recursion(error_cycles)) => assert_eq!(error_cycles, cycles),
+        other => panic!("expected a recursion error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_calls_without_recursion_are_analyzed() {
+    let (cycles, error) = cycles_and_error(ACYCLIC_IR);
+
+    assert!(cycles.is_empty());
+    assert!(error.is_none());
+}
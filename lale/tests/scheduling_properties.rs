@@ -0,0 +1,104 @@
+//! Property tests for the schedulability math
+//!
+//! Document the intended semantics of the RMA and EDF tests as invariants
+//! over random periodic task sets with implicit deadlines.
+
+use lale::scheduling::{EDFScheduler, RMAScheduler, SchedulabilityResult, Task};
+use proptest::prelude::*;
+
+fn task(index: usize, wcet_us: f64, period_us: f64) -> Task {
+    Task {
+        name: format!("task{}", index),
+        function: format!("func{}", index),
+        wcet_us,
+        period_us: Some(period_us),
+        deadline_us: Some(period_us),
+        ..Default::default()
+    }
+}
+
+/// Periodic task sets; periods are multiples of 100us so harmonic sets occur
+fn task_set() -> impl Strategy<Value = Vec<Task>> {
+    prop::collection::vec((1u32..50, 0.01f64..1.0), 1..8).prop_map(|params| {
+        params
+            .into_iter()
+            .enumerate()
+            .map(|(i, (period_steps, load))| {
+                let period_us = period_steps as f64 * 100.0;
+                task(i, period_us * load, period_us)
+            })
+            .collect()
+    })
+}
+
+/// Copy of a task set with one task's WCET increased
+fn with_longer_wcet(tasks: &[Task], index: usize, extra_us: f64) -> Vec<Task> {
+    let mut longer = tasks.to_vec();
+    longer[index % tasks.len()].wcet_us += extra_us;
+    longer
+}
+
+fn schedulable(result: &SchedulabilityResult) -> bool {
+    matches!(result, SchedulabilityResult::Schedulable)
+}
+
+proptest! {
+    #[test]
+    fn longer_wcet_never_makes_rma_schedulable(
+        tasks in task_set(),
+        index in 0usize..8,
+        extra_us in 0.0f64..1000.0,
+    ) {
+        let longer = with_longer_wcet(&tasks, index, extra_us);
+        if !schedulable(&RMAScheduler::schedulability_test(&tasks)) {
+            prop_assert!(!schedulable(&RMAScheduler::schedulability_test(&longer)));
+        }
+    }
+
+    #[test]
+    fn longer_wcet_never_makes_edf_schedulable(
+        tasks in task_set(),
+        index in 0usize..8,
+        extra_us in 0.0f64..1000.0,
+    ) {
+        let longer = with_longer_wcet(&tasks, index, extra_us);
+        if !schedulable(&EDFScheduler::schedulability_test(&tasks)) {
+            prop_assert!(!schedulable(&EDFScheduler::schedulability_test(&longer)));
+        }
+    }
+
+    #[test]
+    fn utilization_is_monotone_in_wcet(
+        tasks in task_set(),
+        index in 0usize..8,
+        extra_us in 0.0f64..1000.0,
+    ) {
+        let longer = with_longer_wcet(&tasks, index, extra_us);
+        prop_assert!(
+            RMAScheduler::calculate_utilization(&longer)
+                >= RMAScheduler::calculate_utilization(&tasks)
+        );
+        prop_assert!(
+            EDFScheduler::calculate_utilization(&longer)
+                >= EDFScheduler::calculate_utilization(&tasks)
+        );
+    }
+
+    #[test]
+    fn response_time_is_at_least_wcet(tasks in task_set()) {
+        let response_times = RMAScheduler::response_times(&tasks);
+        prop_assert_eq!(response_times.len(), tasks.len());
+
+        for (name, response_time) in response_times {
+            let task = tasks.iter().find(|t| t.name == name).unwrap();
+            prop_assert!(response_time >= task.wcet_us);
+        }
+    }
+
+    #[test]
+    fn edf_accepts_every_set_rma_accepts(tasks in task_set()) {
+        if schedulable(&RMAScheduler::schedulability_test(&tasks)) {
+            prop_assert!(schedulable(&EDFScheduler::schedulability_test(&tasks)));
+        }
+    }
+}
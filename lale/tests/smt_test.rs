@@ -0,0 +1,93 @@
+//! Test that SMT-proven infeasible paths tighten the WCET
+
+use lale::analysis::WarningKind;
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser, SmtFeasibility};
+
+/// `if (x > 10) a; if (x < 5) b;`: no `x` takes both branches
+const EXCLUSIVE_IR: &str = r#"; ModuleID = 'smt_test'
+source_filename = "smt_test"
+
+define i32 @exclusive(i32 %x) {
+entry:
+  %c1 = icmp sgt i32 %x, 10
+  br i1 %c1, label %a, label %join
+
+a:
+  %a1 = udiv i32 %x, 3
+  %a2 = udiv i32 %a1, 7
+  br label %join
+
+join:
+  %v = phi i32 [ %a2, %a ], [ %x, %entry ]
+  %c2 = icmp slt i32 %x, 5
+  br i1 %c2, label %b, label %exit
+
+b:
+  %b1 = udiv i32 %v, 5
+  %b2 = udiv i32 %b1, 9
+  br label %exit
+
+exit:
+  %r = phi i32 [ %b2, %b ], [ %v, %join ]
+  ret i32 %r
+}
+"#;
+
+#[test]
+fn test_smt_pruned_pair_lowers_wcet() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(EXCLUSIVE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("exclusive")
+        .expect("function should exist");
+
+    let unpruned = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .unwrap();
+    assert!(unpruned.infeasible_paths.is_empty());
+
+    // Stands in for z3: the one query, whether both `a` and `b` run, is unsat
+    let solver = SmtFeasibility::new().with_solver(
+        "sh",
+        vec!["-c".to_string(), "cat > /dev/null; echo unsat".to_string()],
+    );
+    let pruned = FunctionAnalyzer::new(CortexM4Model::new())
+        .with_smt_feasibility(solver)
+        .analyze(&function)
+        .unwrap();
+
+    assert_eq!(
+        pruned.infeasible_paths,
+        vec![("a".to_string(), "b".to_string())]
+    );
+    assert!(pruned.wcet_cycles < unpruned.wcet_cycles);
+    assert_eq!(pruned.warnings, unpruned.warnings);
+}
+
+#[test]
+fn test_missing_solver_falls_back_with_warning() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(EXCLUSIVE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let function = module
+        .get_function("exclusive")
+        .expect("function should exist");
+
+    let unpruned = FunctionAnalyzer::new(CortexM4Model::new())
+        .analyze(&function)
+        .unwrap();
+    let result = FunctionAnalyzer::new(CortexM4Model::new())
+        .with_smt_feasibility(SmtFeasibility::new().with_solver("lale-no-such-smt-solver", vec![]))
+        .analyze(&function)
+        .unwrap();
+
+    assert_eq!(result.wcet_cycles, unpruned.wcet_cycles);
+    assert_eq!(result.warnings.len(), unpruned.warnings.len() + 1);
+    assert_eq!(
+        result.warnings.last().map(|w| w.kind),
+        Some(WarningKind::SmtFailure)
+    );
+}
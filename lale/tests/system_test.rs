@@ -0,0 +1,175 @@
+//! Test that an entry point's WCET includes callees defined in other modules
+
+use inkwell::module::Module;
+use lale::{CortexM4Model, FunctionAnalyzer, InkwellParser, LaleError, SystemAnalyzer};
+
+const FILTER_IR: &str = r#"; ModuleID = 'filter'
+source_filename = "filter"
+
+define i32 @filter(i32 %x) {
+entry:
+  %a = mul i32 %x, %x
+  %b = udiv i32 %a, 3
+  ret i32 %b
+}
+"#;
+
+const TASKS_IR: &str = r#"; ModuleID = 'tasks'
+source_filename = "tasks"
+
+declare i32 @filter(i32)
+declare void @log(i32)
+
+define i32 @control_loop(i32 %x) {
+entry:
+  %y = call i32 @filter(i32 %x)
+  %z = call i32 @filter(i32 %y)
+  call void @log(i32 %z)
+  ret i32 %z
+}
+
+define i32 @unused(i32 %x) {
+entry:
+  %y = add i32 %x, 1
+  ret i32 %y
+}
+"#;
+
+// Each module has its own internal @scale, of different cost
+const SENSOR_IR: &str = r#"; ModuleID = 'sensor'
+source_filename = "sensor"
+
+define internal i32 @scale(i32 %x) {
+entry:
+  %a = udiv i32 %x, 3
+  %b = udiv i32 %a, 5
+  %c = udiv i32 %b, 7
+  ret i32 %c
+}
+
+define i32 @read_sensor(i32 %x) {
+entry:
+  %y = call i32 @scale(i32 %x)
+  ret i32 %y
+}
+"#;
+
+const ACTUATOR_IR: &str = r#"; ModuleID = 'actuator'
+source_filename = "actuator"
+
+define internal i32 @scale(i32 %x) {
+entry:
+  %a = add i32 %x, 1
+  ret i32 %a
+}
+
+define i32 @drive(i32 %x) {
+entry:
+  %y = call i32 @scale(i32 %x)
+  ret i32 %y
+}
+"#;
+
+const RECURSIVE_IR: &str = r#"; ModuleID = 'recursive'
+source_filename = "recursive"
+
+define i32 @ping(i32 %x) {
+entry:
+  %y = call i32 @pong(i32 %x)
+  ret i32 %y
+}
+
+define i32 @pong(i32 %x) {
+entry:
+  %y = call i32 @ping(i32 %x)
+  ret i32 %y
+}
+"#;
+
+#[test]
+fn test_entry_point_wcet_includes_callees_of_other_modules() {
+    let (_filter_context, filter) = match InkwellParser::parse_ir_from_buffer(FILTER_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let (_tasks_context, tasks) = match InkwellParser::parse_ir_from_buffer(TASKS_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = FunctionAnalyzer::new(CortexM4Model::new());
+    let filter_own = analyzer
+        .analyze(&filter.get_function("filter").unwrap())
+        .unwrap()
+        .wcet_cycles;
+    let control_own = analyzer
+        .analyze(&tasks.get_function("control_loop").unwrap())
+        .unwrap()
+        .wcet_cycles;
+
+    let result = SystemAnalyzer::new(FunctionAnalyzer::new(CortexM4Model::new()))
+        .analyze(&[&tasks, &filter], &["control_loop"])
+        .unwrap();
+
+    // Two calls to the filter; the declared @log keeps its flat cost
+    assert_eq!(result.function_wcets["filter"], filter_own);
+    assert_eq!(
+        result.function_wcets["control_loop"],
+        control_own + 2 * filter_own
+    );
+
+    // Functions no entry point reaches aren't analyzed
+    assert!(!result.function_wcets.contains_key("unused"));
+}
+
+#[test]
+fn test_internal_callees_resolve_to_the_callers_module() {
+    let (_sensor_context, sensor) = match InkwellParser::parse_ir_from_buffer(SENSOR_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let (_actuator_context, actuator) = match InkwellParser::parse_ir_from_buffer(ACTUATOR_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = FunctionAnalyzer::new(CortexM4Model::new());
+    let own = |module: &Module, name: &str| {
+        analyzer
+            .analyze(&module.get_function(name).unwrap())
+            .unwrap()
+            .wcet_cycles
+    };
+
+    let result = SystemAnalyzer::new(FunctionAnalyzer::new(CortexM4Model::new()))
+        .analyze(&[&sensor, &actuator], &["read_sensor", "drive"])
+        .unwrap();
+
+    // drive calls the cheap @scale next to it, not the sensor's
+    assert_eq!(
+        result.function_wcets["drive"],
+        own(&actuator, "drive") + own(&actuator, "scale")
+    );
+    assert_eq!(
+        result.function_wcets["read_sensor"],
+        own(&sensor, "read_sensor") + own(&sensor, "scale")
+    );
+}
+
+#[test]
+fn test_undefined_entry_point_and_recursion_fail() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(RECURSIVE_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let analyzer = SystemAnalyzer::new(FunctionAnalyzer::new(CortexM4Model::new()));
+
+    assert!(matches!(
+        analyzer.analyze(&[&module], &["idle_hook"]),
+        Err(LaleError::Analysis(_))
+    ));
+    match analyzer.analyze(&[&module], &["ping"]) {
+        Err(LaleError::Recursion(cycles)) => {
+            assert_eq!(cycles, vec![vec!["ping".to_string(), "pong".to_string()]]);
+        }
+        other => panic!("expected a recursion error, got {:?}", other.map(|_| ())),
+    }
+}
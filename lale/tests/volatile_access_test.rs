@@ -0,0 +1,51 @@
+//! Test that volatile (MMIO) accesses are never costed as cache hits
+
+use lale::{CacheAssumption, CortexA53Model, FunctionAnalyzer, InkwellParser};
+
+const MMIO_IR: &str = r#"; ModuleID = 'volatile_access_test'
+source_filename = "volatile_access_test"
+
+define i32 @read_status(ptr %reg) {
+entry:
+  %status = load volatile i32, ptr %reg
+  ret i32 %status
+}
+
+define i32 @read_buffer(ptr %buf) {
+entry:
+  %value = load i32, ptr %buf
+  ret i32 %value
+}
+"#;
+
+#[test]
+fn test_volatile_load_stays_uncached_when_warm() {
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(MMIO_IR) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("IR should parse: {}", e),
+    };
+    let wcet = |name: &str, cache: CacheAssumption| {
+        let function = module.get_function(name).expect("function should exist");
+        FunctionAnalyzer::new(CortexA53Model::new())
+            .with_cache_assumption(cache)
+            .analyze_simple(&function)
+            .expect("analysis should succeed")
+            .wcet_cycles
+    };
+
+    // Cold: both loads pay the 6-cycle RAM latency
+    assert_eq!(
+        wcet("read_status", CacheAssumption::Cold),
+        wcet("read_buffer", CacheAssumption::Cold)
+    );
+
+    // Warm: the plain load hits in 1 cycle, the volatile one still pays 6
+    assert_eq!(
+        wcet("read_status", CacheAssumption::Warm),
+        wcet("read_buffer", CacheAssumption::Warm) + 5
+    );
+    assert_eq!(
+        wcet("read_status", CacheAssumption::Warm),
+        wcet("read_status", CacheAssumption::Cold)
+    );
+}
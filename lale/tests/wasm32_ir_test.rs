@@ -0,0 +1,35 @@
+//! Test that wasm32-targeted IR parses and analyzes with the wasm32 model
+
+use lale::{FunctionAnalyzer, InkwellParser, Wasm32Model};
+
+#[test]
+fn test_wasm32_ir_analysis() {
+    let wasm_ir = r#"; ModuleID = 'wasm_test'
+source_filename = "wasm_test"
+target datalayout = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20"
+target triple = "wasm32-unknown-unknown"
+
+define i32 @scale(i32 %x, i32 %y) {
+entry:
+  %sum = add i32 %x, %y
+  %prod = mul i32 %sum, 3
+  %q = sdiv i32 %prod, %y
+  ret i32 %q
+}
+"#;
+
+    let (_context, module) = match InkwellParser::parse_ir_from_buffer(wasm_ir) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("wasm32 IR should parse: {}", e),
+    };
+
+    let function = module
+        .get_function("scale")
+        .expect("scale function should exist");
+
+    let analyzer = FunctionAnalyzer::new(Wasm32Model::new());
+    let result = analyzer.analyze_simple(&function).unwrap();
+
+    assert_eq!(result.block_count, 1);
+    assert!(result.wcet_cycles > 0);
+}
@@ -18,6 +18,7 @@ interface Actor {
   core_affinity: number | null;
   actor_wcet_cycles: number;
   actor_wcet_us: number;
+  platform_wcet_cycles?: Record<string, number>;
   segments: ActorSegment[];
 }
 
@@ -26,9 +27,12 @@ interface VeecleProjectResult {
   schedulability: {
     per_core: Array<{
       core_id: number;
+      core_platform: string;
+      cpu_frequency_mhz: number;
       schedulable: boolean;
       utilization: number;
       actors: string[];
+      actor_wcets_us?: number[];
       violations: Array<{
         actor_name: string;
         response_time_us: number;
@@ -46,7 +50,7 @@ export default function ActorProjectAnalysis() {
   const [irDirectory, setIrDirectory] = useState('');
   const [selectedBoard, setSelectedBoard] = useState<string>('');
   const [numCores, setNumCores] = useState(2);
-  const [policy, setPolicy] = useState<'RMA' | 'EDF'>('RMA');
+  const [policy, setPolicy] = useState<'RMA' | 'EDF' | 'DM'>('RMA');
   const [loading, setLoading] = useState(false);
   const [result, setResult] = useState<VeecleProjectResult | null>(null);
   const [error, setError] = useState<string | null>(null);
@@ -262,7 +266,7 @@ export default function ActorProjectAnalysis() {
               </label>
               <select
                 value={policy}
-                onChange={(e) => setPolicy(e.target.value as 'RMA' | 'EDF')}
+                onChange={(e) => setPolicy(e.target.value as 'RMA' | 'EDF' | 'DM')}
                 className="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg 
                          bg-gray-50 dark:bg-gray-700 text-gray-900 dark:text-white
                          focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
@@ -270,6 +274,7 @@ export default function ActorProjectAnalysis() {
               >
                 <option value="RMA" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Rate Monotonic Analysis (RMA)</option>
                 <option value="EDF" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Earliest Deadline First (EDF)</option>
+                <option value="DM" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Deadline Monotonic (DM)</option>
               </select>
             </div>
           </div>
@@ -22,6 +22,7 @@ interface BoardConfig {
     memory_regions: number;
   };
   board?: string;
+  warnings: string[];
 }
 
 export default function Configuration() {
@@ -218,6 +219,22 @@ export default function Configuration() {
                     </span>
                   </div>
 
+                  {/* Precision Warnings */}
+                  {boardDetails.warnings.length > 0 && (
+                    <div className="bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200 dark:border-yellow-800 rounded-lg p-4">
+                      <p className="text-sm font-medium text-yellow-900 dark:text-yellow-200">
+                        Cache analysis precision is limited by this configuration
+                      </p>
+                      <ul className="mt-2 space-y-1 list-disc list-inside">
+                        {boardDetails.warnings.map((warning) => (
+                          <li key={warning} className="text-sm text-yellow-700 dark:text-yellow-300">
+                            {warning}
+                          </li>
+                        ))}
+                      </ul>
+                    </div>
+                  )}
+
                   {/* Core Information */}
                   <div>
                     <h3 className="text-sm font-semibold text-gray-700 dark:text-gray-300 mb-3">
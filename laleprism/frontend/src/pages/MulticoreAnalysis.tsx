@@ -3,10 +3,14 @@ import { invoke } from '@tauri-apps/api/core';
 
 interface CoreResult {
   core_id: number;
+  core_platform: string;
+  cpu_frequency_mhz: number;
   schedulable: boolean;
   utilization: number;
   actors: string[];
+  actor_wcets_us?: number[];
   violations: DeadlineViolation[];
+  unbounded_arrivals?: string[];
 }
 
 interface DeadlineViolation {
@@ -26,7 +30,7 @@ interface MulticoreResult {
 export default function MulticoreAnalysis() {
   const [irDirectory, setIrDirectory] = useState('');
   const [numCores, setNumCores] = useState(2);
-  const [policy, setPolicy] = useState<'RMA' | 'EDF'>('RMA');
+  const [policy, setPolicy] = useState<'RMA' | 'EDF' | 'DM'>('RMA');
   const [selectedBoard, setSelectedBoard] = useState<string>('');
   const [loading, setLoading] = useState(false);
   const [result, setResult] = useState<MulticoreResult | null>(null);
@@ -193,7 +197,7 @@ export default function MulticoreAnalysis() {
               </label>
               <select
                 value={policy}
-                onChange={(e) => setPolicy(e.target.value as 'RMA' | 'EDF')}
+                onChange={(e) => setPolicy(e.target.value as 'RMA' | 'EDF' | 'DM')}
                 className="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg 
                          bg-gray-50 dark:bg-gray-700 text-gray-900 dark:text-white
                          focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
@@ -201,6 +205,7 @@ export default function MulticoreAnalysis() {
               >
                 <option value="RMA" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Rate Monotonic Analysis (RMA)</option>
                 <option value="EDF" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Earliest Deadline First (EDF)</option>
+                <option value="DM" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Deadline Monotonic (DM)</option>
               </select>
             </div>
 
@@ -275,9 +280,16 @@ export default function MulticoreAnalysis() {
                   className="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6"
                 >
                   <div className="flex items-center justify-between mb-4">
-                    <h3 className="text-lg font-semibold text-gray-900 dark:text-white">
-                      Core {core.core_id}
-                    </h3>
+                    <div>
+                      <h3 className="text-lg font-semibold text-gray-900 dark:text-white">
+                        Core {core.core_id}
+                      </h3>
+                      {core.core_platform && (
+                        <p className="text-xs text-gray-500 dark:text-gray-400">
+                          {core.core_platform} @ {core.cpu_frequency_mhz} MHz
+                        </p>
+                      )}
+                    </div>
                     <span className={`px-3 py-1 rounded-full text-sm font-medium ${
                       core.schedulable
                         ? 'bg-green-100 dark:bg-green-900/30 text-green-800 dark:text-green-300'
@@ -325,6 +337,15 @@ export default function MulticoreAnalysis() {
                     </div>
                   </div>
 
+                  {/* Aperiodic actors without an arrival bound */}
+                  {core.unbounded_arrivals && core.unbounded_arrivals.length > 0 && (
+                    <div className="mb-4 text-xs bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200
+                                  dark:border-yellow-800 rounded p-2 text-yellow-800 dark:text-yellow-300">
+                      Aperiodic actors without a period can't be bounded:{' '}
+                      {core.unbounded_arrivals.join(', ')}
+                    </div>
+                  )}
+
                   {/* Violations */}
                   {core.violations.length > 0 && (
                     <div>
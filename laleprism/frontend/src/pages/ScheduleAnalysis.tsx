@@ -107,6 +107,7 @@ const ScheduleAnalysis: React.FC = () => {
             >
               <option value="rma" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Rate Monotonic (RMA)</option>
               <option value="edf" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Earliest Deadline First (EDF)</option>
+              <option value="dm" style={{ color: '#111827', backgroundColor: '#f9fafb' }}>Deadline Monotonic (DM)</option>
             </select>
           </div>
 
@@ -241,6 +241,16 @@ const ScheduleView: React.FC = () => {
                     : '100%'}
                 </span>
               </div>
+              <div className="flex justify-between">
+                <span className="text-gray-600 dark:text-gray-400">Harmonic Periods:</span>
+                <span className={`font-semibold ${
+                  schedulability.harmonic
+                    ? 'text-gray-900 dark:text-white'
+                    : 'text-yellow-600 dark:text-yellow-400'
+                }`}>
+                  {schedulability.harmonic ? 'Yes' : 'No (consider harmonic periods)'}
+                </span>
+              </div>
               {schedule && firstTask && (
                 <div className="flex justify-between">
                   <span className="text-gray-600 dark:text-gray-400">Slack Time:</span>
@@ -93,6 +93,9 @@ const ScheduleViewer: React.FC = () => {
             <div className="text-lg font-medium">
               {(report.schedulability.utilization * 100).toFixed(2)}%
             </div>
+            <div className="text-xs text-gray-500 dark:text-gray-400">
+              typical {(report.schedulability.typical_utilization * 100).toFixed(2)}%
+            </div>
           </div>
           <div>
             <div className="text-sm text-gray-600 dark:text-gray-400">Bound</div>
@@ -15,6 +15,7 @@ export interface TaskConfig {
   name: string;
   function: string;
   period_us: number;
+  offset_us?: number;
   deadline_us?: number;
   priority?: number;
 }
@@ -24,6 +25,8 @@ export interface PlatformInfo {
   name: string;
   frequency_mhz: number;
   category: string;
+  aliases: string[];
+  summary: string;
 }
 
 export interface DemangledName {
@@ -54,6 +57,31 @@ export interface AnalysisReport {
   task_model: TaskModel;
   schedulability: SchedulabilityAnalysis;
   schedule: ScheduleTimeline | null;
+  module_hotspots?: BlockHotspot[];
+  warnings?: AnalysisWarning[];
+}
+
+export type WarningKind =
+  | 'parse-failure'
+  | 'analysis-failure'
+  | 'solve-timeout'
+  | 'unknown-opcode'
+  | 'unmatched-flow-fact'
+  | 'smt-failure'
+  | 'unbounded-indirect-call';
+
+export interface AnalysisWarning {
+  kind: WarningKind;
+  function?: string;
+  message: string;
+}
+
+export interface BlockHotspot {
+  function: string;
+  block: string;
+  executions: number;
+  cycles: number;
+  total_cycles: number;
 }
 
 export interface AnalysisInfo {
@@ -61,10 +89,12 @@ export interface AnalysisInfo {
   version: string;
   timestamp: string;
   platform: string;
+  margin_percent?: number;
 }
 
 export interface WCETAnalysis {
   functions: FunctionWCET[];
+  timed_out?: string[];
 }
 
 export interface FunctionWCET {
@@ -75,10 +105,12 @@ export interface FunctionWCET {
   bcet_cycles: number;
   bcet_us: number;
   loop_count: number;
+  raw_wcet_cycles?: number;
 }
 
 export interface TaskModel {
   tasks: Task[];
+  relative_wcet?: Record<string, number>;
 }
 
 export interface Task {
@@ -86,11 +118,22 @@ export interface Task {
   function: string;
   wcet_cycles: number;
   wcet_us: number;
+  typical_us: number | null;
   period_us: number | null;
+  min_interarrival_us: number | null;
+  offset_us: number | null;
   deadline_us: number | null;
   priority: number | null;
   preemptible: boolean;
   dependencies: string[];
+  criticality: "lo" | "hi" | null;
+  wcet_lo_us: number | null;
+  resources?: ResourceUsage[];
+}
+
+export interface ResourceUsage {
+  resource_id: string;
+  critical_section_us: number;
 }
 
 export interface SchedulabilityAnalysis {
@@ -98,12 +141,22 @@ export interface SchedulabilityAnalysis {
   result: string;
   utilization: number;
   utilization_bound: number | null;
+  harmonic: boolean;
+  worst_case_utilization: number;
+  typical_utilization: number;
   response_times: Record<string, number>;
 }
 
 export interface ScheduleTimeline {
   hyperperiod_us: number;
   slots: TimeSlot[];
+  idle?: IdleReport;
+}
+
+export interface IdleReport {
+  intervals: [number, number][];
+  total_idle_us: number;
+  cpu_utilization: number;
 }
 
 export interface TimeSlot {
@@ -1,9 +1,6 @@
 use anyhow::{Context, Result};
-use lale::{
-    AnalysisReport, CortexA53Model, CortexA7Model, CortexM0Model, CortexM33Model, CortexM3Model,
-    CortexM4Model, CortexM7Model, CortexR4Model, CortexR5Model, InkwellParser, PlatformModel,
-    RV32GCModel, RV32IMACModel, RV32IModel, RV64GCModel, SchedulingPolicy, Task,
-};
+pub use lale::PlatformInfo;
+use lale::{AnalysisReport, InkwellParser, PlatformModel, SchedulingPolicy, Task, TaskExtractor};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -22,106 +19,13 @@ pub struct TaskConfig {
     pub name: String,
     pub function: String,
     pub period_us: f64,
+    /// Release of the first job, within `[0, period_us)`
+    #[serde(default)]
+    pub offset_us: Option<f64>,
     pub deadline_us: Option<f64>,
     pub priority: Option<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlatformInfo {
-    pub id: String,
-    pub name: String,
-    pub frequency_mhz: u32,
-    pub category: String,
-}
-
-/// Get list of all available platforms
-pub fn list_platforms() -> Vec<PlatformInfo> {
-    vec![
-        // ARM Cortex-M
-        PlatformInfo {
-            id: "cortex-m0".to_string(),
-            name: "ARM Cortex-M0".to_string(),
-            frequency_mhz: 48,
-            category: "ARM Cortex-M".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-m3".to_string(),
-            name: "ARM Cortex-M3".to_string(),
-            frequency_mhz: 72,
-            category: "ARM Cortex-M".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-m4".to_string(),
-            name: "ARM Cortex-M4".to_string(),
-            frequency_mhz: 168,
-            category: "ARM Cortex-M".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-m7".to_string(),
-            name: "ARM Cortex-M7".to_string(),
-            frequency_mhz: 400,
-            category: "ARM Cortex-M".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-m33".to_string(),
-            name: "ARM Cortex-M33".to_string(),
-            frequency_mhz: 120,
-            category: "ARM Cortex-M".to_string(),
-        },
-        // ARM Cortex-R
-        PlatformInfo {
-            id: "cortex-r4".to_string(),
-            name: "ARM Cortex-R4".to_string(),
-            frequency_mhz: 600,
-            category: "ARM Cortex-R".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-r5".to_string(),
-            name: "ARM Cortex-R5".to_string(),
-            frequency_mhz: 800,
-            category: "ARM Cortex-R".to_string(),
-        },
-        // ARM Cortex-A
-        PlatformInfo {
-            id: "cortex-a7".to_string(),
-            name: "ARM Cortex-A7".to_string(),
-            frequency_mhz: 1200,
-            category: "ARM Cortex-A".to_string(),
-        },
-        PlatformInfo {
-            id: "cortex-a53".to_string(),
-            name: "ARM Cortex-A53".to_string(),
-            frequency_mhz: 1400,
-            category: "ARM Cortex-A".to_string(),
-        },
-        // RISC-V
-        PlatformInfo {
-            id: "rv32i".to_string(),
-            name: "RISC-V RV32I".to_string(),
-            frequency_mhz: 100,
-            category: "RISC-V".to_string(),
-        },
-        PlatformInfo {
-            id: "rv32imac".to_string(),
-            name: "RISC-V RV32IMAC".to_string(),
-            frequency_mhz: 320,
-            category: "RISC-V".to_string(),
-        },
-        PlatformInfo {
-            id: "rv32gc".to_string(),
-            name: "RISC-V RV32GC".to_string(),
-            frequency_mhz: 1000,
-            category: "RISC-V".to_string(),
-        },
-        PlatformInfo {
-            id: "rv64gc".to_string(),
-            name: "RISC-V RV64GC".to_string(),
-            frequency_mhz: 1500,
-            category: "RISC-V".to_string(),
-        },
-    ]
-}
-
 /// Select platform model by ID or configuration path
 fn select_platform(platform_id: &str) -> Result<PlatformModel> {
     // Check if it's a TOML configuration path (e.g., "platforms/nucleo-h743zi")
@@ -129,18 +33,20 @@ fn select_platform(platform_id: &str) -> Result<PlatformModel> {
         use lale::analysis::timing::AccessType;
         use lale::analysis::{Cycles, InstructionClass};
         use lale::config::ConfigManager;
-        use std::path::PathBuf;
 
-        let config_dir = PathBuf::from("config");
+        let config_dir = crate::get_config_dir();
         let mut manager = ConfigManager::new(config_dir);
 
-        let config = manager.load_platform(platform_id).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to load platform configuration '{}': {}",
-                platform_id,
-                e
-            )
-        })?;
+        let config = manager
+            .load_platform(platform_id)
+            .and_then(|config| manager.validate_analyzable(&config).map(|_| config))
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to load platform configuration '{}': {}",
+                    platform_id,
+                    e
+                )
+            })?;
 
         // Build instruction timings from ISA config
         let mut instruction_timings = ahash::AHashMap::new();
@@ -213,38 +119,32 @@ fn select_platform(platform_id: &str) -> Result<PlatformModel> {
             Cycles::new(config.isa.instruction_timings.divide),
         );
 
-        // Get CPU frequency from SoC or use default
+        // Validated above: the SoC is present and clocked
         let cpu_frequency_mhz = config
             .soc
             .as_ref()
             .map(|s| s.cpu_frequency_mhz)
-            .unwrap_or(100); // Default 100 MHz if no SoC specified
+            .context("Platform configuration has no SoC")?;
 
         return Ok(PlatformModel {
             name: platform_id.to_string(),
             cpu_frequency_mhz,
+            issue_width: config.core.pipeline.issue_width as u32,
+            fetch_width: config.core.pipeline.fetch_width as u8,
+            capabilities: lale::PlatformCapabilities {
+                fpu: true,
+                hw_multiply: true,
+                hw_divide: true,
+                cache: config.core.cache.instruction_cache.is_some()
+                    || config.core.cache.data_cache.is_some(),
+            },
             instruction_timings,
         });
     }
 
-    // Fallback to hardcoded platform models for backward compatibility
-    let model = match platform_id.to_lowercase().as_str() {
-        "cortex-m0" | "m0" => CortexM0Model::new(),
-        "cortex-m3" | "m3" => CortexM3Model::new(),
-        "cortex-m4" | "m4" => CortexM4Model::new(),
-        "cortex-m7" | "m7" => CortexM7Model::new(),
-        "cortex-m33" | "m33" => CortexM33Model::new(),
-        "cortex-r4" | "r4" => CortexR4Model::new(),
-        "cortex-r5" | "r5" => CortexR5Model::new(),
-        "cortex-a7" | "a7" => CortexA7Model::new(),
-        "cortex-a53" | "a53" => CortexA53Model::new(),
-        "rv32i" => RV32IModel::new(),
-        "rv32imac" => RV32IMACModel::new(),
-        "rv32gc" => RV32GCModel::new(),
-        "rv64gc" => RV64GCModel::new(),
-        _ => anyhow::bail!("Unknown platform: {}", platform_id),
-    };
-    Ok(model)
+    // Fall back to the built-in platform models
+    PlatformModel::from_id(platform_id)
+        .ok_or_else(|| anyhow::anyhow!("Unknown platform: {}", platform_id))
 }
 
 /// Find all .ll files in directory recursively
@@ -311,7 +211,7 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
             .iter()
             .filter_map(|tc| {
                 let wcet_cycles = result.function_wcets.get(&tc.function).copied()?;
-                let wcet_us = wcet_cycles as f64 / platform.cpu_frequency_mhz as f64;
+                let wcet_us = platform.cycles_to_us(wcet_cycles);
 
                 Some(Task {
                     name: tc.name.clone(),
@@ -319,10 +219,10 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
                     wcet_cycles,
                     wcet_us,
                     period_us: Some(tc.period_us),
+                    offset_us: tc.offset_us,
                     deadline_us: tc.deadline_us.or(Some(tc.period_us)),
                     priority: tc.priority,
-                    preemptible: true,
-                    dependencies: vec![],
+                    ..Default::default()
                 })
             })
             .collect()
@@ -331,11 +231,13 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
     if tasks.is_empty() {
         anyhow::bail!("No valid tasks configured or found");
     }
+    TaskExtractor::validate_offsets(&tasks).map_err(anyhow::Error::msg)?;
 
     // Parse scheduling policy
     let policy = match config.policy.to_lowercase().as_str() {
         "rma" => SchedulingPolicy::RMA,
         "edf" => SchedulingPolicy::EDF,
+        "dm" => SchedulingPolicy::DM,
         _ => SchedulingPolicy::RMA,
     };
 
@@ -343,6 +245,7 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
     let schedulability = match policy {
         SchedulingPolicy::RMA => lale::scheduling::RMAScheduler::schedulability_test(&tasks),
         SchedulingPolicy::EDF => lale::scheduling::EDFScheduler::schedulability_test(&tasks),
+        SchedulingPolicy::DM => lale::scheduling::DMScheduler::schedulability_test(&tasks),
     };
 
     // Create analysis report with proper structure
@@ -356,26 +259,35 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
         version: lale::VERSION.to_string(),
         timestamp: Utc::now().to_rfc3339(),
         platform: platform.name.clone(),
+        margin_percent: None,
     };
 
     let wcet_analysis = WCETAnalysis {
         functions: result
             .function_wcets
             .iter()
-            .map(|(name, &wcet)| FunctionWCET {
-                name: name.clone(),
-                llvm_name: name.clone(),
-                wcet_cycles: wcet,
-                wcet_us: wcet as f64 / platform.cpu_frequency_mhz as f64,
-                bcet_cycles: wcet, // Conservative estimate
-                bcet_us: wcet as f64 / platform.cpu_frequency_mhz as f64,
-                loop_count: 0,
+            .map(|(name, &wcet)| {
+                let bcet = result.function_bcets.get(name).copied().unwrap_or(wcet);
+                FunctionWCET {
+                    name: name.clone(),
+                    llvm_name: name.clone(),
+                    wcet_cycles: wcet,
+                    wcet_us: platform.cycles_to_us(wcet),
+                    bcet_cycles: bcet,
+                    bcet_us: platform.cycles_to_us(bcet),
+                    loop_count: 0,
+                    raw_wcet_cycles: None,
+                    source_file: None,
+                    source_line: None,
+                }
             })
             .collect(),
+        timed_out: result.timed_out_functions.clone(),
     };
 
     let task_model = TaskModel {
         tasks: tasks.clone(),
+        relative_wcet: lale::scheduling::relative_wcets(&tasks),
     };
 
     // Calculate utilization
@@ -392,10 +304,33 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
             lale::scheduling::SchedulabilityResult::Unschedulable { .. } => {
                 "Not Schedulable".to_string()
             }
+            lale::scheduling::SchedulabilityResult::UnboundedArrivals { .. } => {
+                "Not Schedulable (unbounded aperiodic tasks)".to_string()
+            }
+            lale::scheduling::SchedulabilityResult::InfeasibleDeadlines { .. } => {
+                "Not Schedulable (WCET exceeds deadline)".to_string()
+            }
         },
         utilization,
-        utilization_bound: Some(1.0),
-        response_times: ahash::AHashMap::new(),
+        utilization_bound: match policy {
+            SchedulingPolicy::RMA => {
+                Some(lale::scheduling::RMAScheduler::utilization_bound(&tasks))
+            }
+            SchedulingPolicy::EDF => Some(1.0),
+            // No utilization bound holds for deadlines shorter than periods
+            SchedulingPolicy::DM => None,
+        },
+        harmonic: lale::scheduling::RMAScheduler::is_harmonic(&tasks),
+        worst_case_utilization: utilization,
+        typical_utilization: lale::scheduling::typical_utilization(&tasks),
+        response_times: match policy {
+            SchedulingPolicy::RMA => lale::scheduling::RMAScheduler::response_time_analysis(&tasks),
+            SchedulingPolicy::DM => lale::scheduling::DMScheduler::response_times(&tasks)
+                .into_iter()
+                .collect(),
+            // Under EDF no fixed-priority recurrence applies
+            SchedulingPolicy::EDF => ahash::AHashMap::new(),
+        },
     };
 
     let report = AnalysisReport {
@@ -404,6 +339,8 @@ pub fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport> {
         task_model,
         schedulability: schedulability_analysis,
         schedule: None,
+        module_hotspots: vec![],
+        warnings: result.warnings.clone(),
     };
 
     Ok(report)
@@ -28,7 +28,7 @@ pub async fn analyze_directory(config: AnalysisConfig) -> Result<AnalysisReport,
 /// List all available platforms
 #[tauri::command]
 pub fn list_platforms() -> Vec<PlatformInfo> {
-    analysis::list_platforms()
+    lale::PlatformModel::catalog()
 }
 
 /// Demangle a symbol name
@@ -109,8 +109,7 @@ pub fn health_check() -> String {
 pub fn list_board_configs() -> Result<Vec<String>, String> {
     use lale::config::ConfigManager;
 
-    let initial_cwd = crate::get_initial_cwd();
-    let config_dir = initial_cwd.join("config");
+    let config_dir = crate::get_config_dir();
 
     if !config_dir.exists() {
         return Err(format!(
@@ -128,8 +127,7 @@ pub fn list_board_configs() -> Result<Vec<String>, String> {
 pub fn validate_board_config(board_name: String) -> Result<BoardConfigDetails, String> {
     use lale::config::ConfigManager;
 
-    let initial_cwd = crate::get_initial_cwd();
-    let config_dir = initial_cwd.join("config");
+    let config_dir = crate::get_config_dir();
 
     if !config_dir.exists() {
         return Err(format!(
@@ -143,6 +141,7 @@ pub fn validate_board_config(board_name: String) -> Result<BoardConfigDetails, S
     let config = manager
         .load_platform(&board_name)
         .map_err(|e| e.to_string())?;
+    let warnings = manager.precision_warnings(&config);
 
     Ok(BoardConfigDetails {
         name: board_name,
@@ -170,6 +169,7 @@ pub fn validate_board_config(board_name: String) -> Result<BoardConfigDetails, S
             memory_regions: s.memory_regions.len(),
         }),
         board: config.board.as_ref().map(|b| b.name.clone()),
+        warnings,
     })
 }
 
@@ -178,8 +178,7 @@ pub fn validate_board_config(board_name: String) -> Result<BoardConfigDetails, S
 pub fn export_board_config(board_name: String) -> Result<String, String> {
     use lale::config::ConfigManager;
 
-    let initial_cwd = crate::get_initial_cwd();
-    let config_dir = initial_cwd.join("config");
+    let config_dir = crate::get_config_dir();
 
     if !config_dir.exists() {
         return Err(format!(
@@ -206,6 +205,8 @@ pub struct BoardConfigDetails {
     pub data_cache: Option<CacheInfo>,
     pub soc: Option<SoCInfo>,
     pub board: Option<String>,
+    /// Settings that limit cache analysis precision
+    pub warnings: Vec<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -240,16 +241,16 @@ pub async fn analyze_multicore(
     let scheduling_policy = match policy.as_str() {
         "RMA" => SchedulingPolicy::RMA,
         "EDF" => SchedulingPolicy::EDF,
+        "DM" => SchedulingPolicy::DM,
         _ => return Err(format!("Invalid scheduling policy: {}", policy)),
     };
 
-    // Use the initial CWD captured at startup (before Tauri changed it)
-    let initial_cwd = crate::get_initial_cwd();
-    let config_dir = initial_cwd.join("config");
+    // Resolved config dir, or `config` under the initial CWD captured at startup
+    let config_dir = crate::get_config_dir();
 
     if !config_dir.exists() {
         return Err(format!(
-            "Config directory not found at: {}. Please run from a directory containing a 'config' subdirectory.",
+            "Config directory not found at: {}. Set LALE_CONFIG_DIR to your board definitions directory.",
             config_dir.display()
         ));
     }
@@ -325,6 +326,7 @@ pub async fn analyze_multicore(
                     }
                     Err(e) => {
                         // Only log non-debug-intrinsic errors
+                        let e = e.to_string();
                         if !e.contains("dbg_value") && !e.contains("dbg_declare") {
                             parse_errors.push(ParseError {
                                 file: file_name,
@@ -338,7 +340,8 @@ pub async fn analyze_multicore(
     }
 
     // Perform multicore analysis
-    let scheduler = MultiCoreScheduler::new(num_cores, scheduling_policy);
+    let scheduler = MultiCoreScheduler::new(num_cores, scheduling_policy)
+        .with_core_platforms(vec![platform_model.clone(); num_cores]);
     let result = scheduler.analyze(&actors);
 
     // Log parse errors if any
@@ -376,20 +379,18 @@ pub async fn analyze_veecle_project(
     let scheduling_policy = match policy.as_str() {
         "RMA" => SchedulingPolicy::RMA,
         "EDF" => SchedulingPolicy::EDF,
+        "DM" => SchedulingPolicy::DM,
         _ => return Err(format!("Invalid scheduling policy: {}", policy)),
     };
 
-    // Use the initial CWD captured at startup (before Tauri changed it)
-    let initial_cwd = crate::get_initial_cwd();
-    eprintln!("Using initial CWD: {}", initial_cwd.display());
-
-    let config_dir = initial_cwd.join("config");
+    // Resolved config dir, or `config` under the initial CWD captured at startup
+    let config_dir = crate::get_config_dir();
     eprintln!("Config directory: {}", config_dir.display());
     eprintln!("Config directory exists: {}", config_dir.exists());
 
     if !config_dir.exists() {
         return Err(format!(
-            "Config directory not found at: {}. Please run from a directory containing a 'config' subdirectory.",
+            "Config directory not found at: {}. Set LALE_CONFIG_DIR to your board definitions directory.",
             config_dir.display()
         ));
     }
@@ -16,6 +16,16 @@ pub fn get_initial_cwd() -> &'static std::path::PathBuf {
     INITIAL_CWD.get().expect("Initial CWD not set")
 }
 
+/// Board definitions directory, resolving a relative `config` against the initial CWD
+pub fn get_config_dir() -> std::path::PathBuf {
+    let config_dir = lale::config::ConfigManager::resolve_config_dir(None);
+    if config_dir.is_relative() {
+        get_initial_cwd().join(config_dir)
+    } else {
+        config_dir
+    }
+}
+
 fn main() {
     // Use LALE_ROOT env var if set, otherwise use current directory
     let initial_cwd = if let Ok(lale_root) = std::env::var("LALE_ROOT") {
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -8,12 +9,40 @@ pub struct BenchmarkCompiler {
     optimization_level: OptLevel,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum OptLevel {
     O0,
     O1,
     O2,
     O3,
+    Os,
+    Oz,
+}
+
+impl OptLevel {
+    /// All levels, in sweep order
+    pub fn all() -> [OptLevel; 6] {
+        [
+            OptLevel::O0,
+            OptLevel::O1,
+            OptLevel::O2,
+            OptLevel::O3,
+            OptLevel::Os,
+            OptLevel::Oz,
+        ]
+    }
+
+    /// Level name as passed to clang (without the dash)
+    pub fn flag(&self) -> &'static str {
+        match self {
+            OptLevel::O0 => "O0",
+            OptLevel::O1 => "O1",
+            OptLevel::O2 => "O2",
+            OptLevel::O3 => "O3",
+            OptLevel::Os => "Os",
+            OptLevel::Oz => "Oz",
+        }
+    }
 }
 
 impl BenchmarkCompiler {
@@ -99,12 +128,7 @@ impl BenchmarkCompiler {
     }
 
     fn opt_flag(&self) -> &str {
-        match self.optimization_level {
-            OptLevel::O0 => "O0",
-            OptLevel::O1 => "O1",
-            OptLevel::O2 => "O2",
-            OptLevel::O3 => "O3",
-        }
+        self.optimization_level.flag()
     }
 
     /// Get target triple string
@@ -153,6 +177,12 @@ mod tests {
         assert_eq!(compiler.opt_flag(), "O2");
     }
 
+    #[test]
+    fn test_opt_level_flags() {
+        let flags: Vec<&str> = OptLevel::all().iter().map(|l| l.flag()).collect();
+        assert_eq!(flags, vec!["O0", "O1", "O2", "O3", "Os", "Oz"]);
+    }
+
     #[test]
     fn test_target_info() {
         assert_eq!(BenchmarkCompiler::target_triple(), "armv7em-none-eabi");
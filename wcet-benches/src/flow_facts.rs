@@ -2,10 +2,11 @@ use anyhow::{Context, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlowFacts {
+    #[serde(default)]
     pub loop_bounds: HashMap<String, LoopBound>,
     pub entry_point: Option<String>,
 }
@@ -25,6 +26,44 @@ impl FlowFacts {
         }
     }
 
+    /// Flow-facts file for an IR file: `<name>.facts.toml` next to `<name>.ll`
+    pub fn facts_path_for(ir_path: &Path) -> PathBuf {
+        let stem = ir_path
+            .file_stem()
+            .map(|s| s.to_string_lossy())
+            .unwrap_or_default();
+        ir_path.with_file_name(format!("{}.facts.toml", stem))
+    }
+
+    /// Load the flow-facts file of an IR file, if there is one
+    ///
+    /// A file that exists but doesn't parse is an error rather than being
+    /// skipped, so a typo can't silently bring back guessed bounds.
+    pub fn discover(ir_path: &Path) -> Result<Option<Self>> {
+        let facts_path = Self::facts_path_for(ir_path);
+        if !facts_path.exists() {
+            return Ok(None);
+        }
+        Self::load_toml(&facts_path).map(Some)
+    }
+
+    /// Parse flow facts from a TOML file
+    ///
+    /// ```toml
+    /// entry_point = "binarysearch_main"
+    ///
+    /// [loop_bounds.search]
+    /// location = "binarysearch.c:94"
+    /// min_iterations = 1
+    /// max_iterations = 4
+    /// ```
+    pub fn load_toml(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read flow facts {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse flow facts {}", path.display()))
+    }
+
     /// Parse flow facts from C source file
     pub fn parse_from_source(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path).context("Failed to read source file")?;
@@ -190,4 +229,41 @@ void _Pragma( "entrypoint" ) main_function( void )
         let facts = FlowFacts::parse_from_source(file.path()).unwrap();
         assert_eq!(facts.entry_point, Some("main_function".to_string()));
     }
+
+    #[test]
+    fn test_discover_facts_file_next_to_ir() {
+        let dir = tempfile::tempdir().unwrap();
+        let ir_path = dir.path().join("bsort.ll");
+        assert_eq!(
+            FlowFacts::facts_path_for(&ir_path),
+            dir.path().join("bsort.facts.toml")
+        );
+        assert!(FlowFacts::discover(&ir_path).unwrap().is_none());
+
+        std::fs::write(
+            dir.path().join("bsort.facts.toml"),
+            r#"
+entry_point = "bsort_main"
+
+[loop_bounds.outer]
+location = "bsort.c:12"
+min_iterations = 1
+max_iterations = 99
+
+[loop_bounds.inner]
+location = "bsort.c:14"
+min_iterations = 1
+max_iterations = 99
+"#,
+        )
+        .unwrap();
+
+        let facts = FlowFacts::discover(&ir_path).unwrap().unwrap();
+        assert_eq!(facts.entry_point, Some("bsort_main".to_string()));
+        assert_eq!(facts.loop_count(), 2);
+        assert_eq!(facts.max_total_iterations(), 198);
+
+        std::fs::write(dir.path().join("bsort.facts.toml"), "loop_bounds = 3").unwrap();
+        assert!(FlowFacts::discover(&ir_path).is_err());
+    }
 }
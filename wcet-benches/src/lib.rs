@@ -1,9 +1,11 @@
 pub mod compiler;
 pub mod flow_facts;
+pub mod opt_compare;
 pub mod runner;
 pub mod suite;
 
 pub use compiler::{BenchmarkCompiler, CompilationResult, OptLevel};
 pub use flow_facts::{FlowFacts, LoopBound};
+pub use opt_compare::{compare_opt_levels, format_table, FunctionOptComparison, OptLevelMetrics};
 pub use runner::{BenchmarkResult, BenchmarkRunner, ResultDetails};
 pub use suite::{BenchmarkCategory, BenchmarkInfo, BenchmarkSuite};
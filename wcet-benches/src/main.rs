@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
-use wcet_benches::{BenchmarkResult, BenchmarkRunner};
+use wcet_benches::{
+    compare_opt_levels, format_table, BenchmarkCategory, BenchmarkResult, BenchmarkRunner, OptLevel,
+};
 
 #[derive(Parser)]
 #[command(name = "bench-runner")]
@@ -29,6 +31,20 @@ enum Commands {
         /// Specific benchmark name (optional)
         #[arg(short, long)]
         benchmark: Option<String>,
+
+        /// Only run benchmarks of this category (e.g. kernel, sequential)
+        #[arg(short, long)]
+        category: Option<BenchmarkCategory>,
+    },
+
+    /// Compare WCET and code size of each function across optimization levels
+    CompareOptLevels {
+        /// C source file to compile at each level
+        source: PathBuf,
+
+        /// Directory for the generated LLVM IR
+        #[arg(short, long, default_value = "results/opt-levels")]
+        work_dir: PathBuf,
     },
 
     /// List available benchmarks
@@ -47,8 +63,12 @@ fn main() {
             platform,
             ir_dir,
             benchmark,
+            category,
         } => {
-            run_benchmarks(platform, ir_dir, benchmark);
+            run_benchmarks(platform, ir_dir, benchmark, category);
+        }
+        Commands::CompareOptLevels { source, work_dir } => {
+            compare_levels(source, work_dir);
         }
         Commands::List { ir_dir } => {
             list_benchmarks(ir_dir);
@@ -56,14 +76,21 @@ fn main() {
     }
 }
 
-fn run_benchmarks(platform: String, ir_dir: PathBuf, specific_benchmark: Option<String>) {
+fn run_benchmarks(
+    platform: String,
+    ir_dir: PathBuf,
+    specific_benchmark: Option<String>,
+    category: Option<BenchmarkCategory>,
+) {
     println!("=== WCET Benchmark Analysis ===");
     println!("Platform: {}", platform);
     println!("IR Directory: {}", ir_dir.display());
+    if let Some(category) = category {
+        println!("Category: {}", category.name());
+    }
     println!();
 
     let runner = BenchmarkRunner::new(platform);
-    let mut results = Vec::new();
 
     // Find all .ll files
     let mut ir_files = Vec::new();
@@ -105,20 +132,23 @@ fn run_benchmarks(platform: String, ir_dir: PathBuf, specific_benchmark: Option<
         });
     }
 
-    println!("Found {} benchmarks\n", ir_files.len());
+    // Filter by category if provided
+    if let Some(category) = category {
+        ir_files.retain(|p| {
+            p.file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|name| runner.category_of(name))
+                == Some(category)
+        });
+    }
 
-    // Run analysis on each benchmark
-    for ir_file in &ir_files {
-        let name = ir_file
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+    println!("Found {} benchmarks\n", ir_files.len());
 
-        print!("Analyzing: {} ... ", name);
-        std::io::Write::flush(&mut std::io::stdout()).ok();
+    // Benchmarks are independent; analyze them in parallel
+    let results = runner.run_benchmarks(&ir_files);
 
-        let result = runner.run_benchmark(&name, ir_file);
+    for result in &results {
+        print!("Analyzing: {} ... ", result.name);
 
         if result.success {
             print!("✓ WCET: {} cycles", result.wcet_cycles);
@@ -145,6 +175,9 @@ fn run_benchmarks(platform: String, ir_dir: PathBuf, specific_benchmark: Option<
             if let Some(ref entry) = result.details.entry_point {
                 print!(", Entry: {}", entry);
             }
+            if let Some(ref facts) = result.details.flow_facts_file {
+                print!(", Facts: {}", facts.display());
+            }
             println!();
         } else {
             println!("✗ Failed");
@@ -152,8 +185,6 @@ fn run_benchmarks(platform: String, ir_dir: PathBuf, specific_benchmark: Option<
                 println!("  Error: {}", error);
             }
         }
-
-        results.push(result);
     }
 
     // Print summary
@@ -192,6 +223,34 @@ fn run_benchmarks(platform: String, ir_dir: PathBuf, specific_benchmark: Option<
     }
 }
 
+fn compare_levels(source: PathBuf, work_dir: PathBuf) {
+    println!("=== WCET vs Code Size by Optimization Level ===");
+    println!("Source: {}", source.display());
+    println!();
+
+    let comparisons = match compare_opt_levels(&source, &work_dir, &OptLevel::all()) {
+        Ok(comparisons) => comparisons,
+        Err(e) => {
+            eprintln!("✗ Comparison failed: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    print!("{}", format_table(&comparisons));
+
+    // Save results
+    let output_path = PathBuf::from("results/opt_level_comparison.json");
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+
+    if let Ok(json) = serde_json::to_string_pretty(&comparisons) {
+        if std::fs::write(&output_path, json).is_ok() {
+            println!("\nResults saved to: {}", output_path.display());
+        }
+    }
+}
+
 fn list_benchmarks(ir_dir: PathBuf) {
     println!("=== Available Benchmarks ===\n");
 
@@ -0,0 +1,149 @@
+use crate::compiler::{BenchmarkCompiler, OptLevel};
+use anyhow::Result;
+use lale::{CortexM7Model, FunctionAnalyzer};
+use serde::Serialize;
+use std::path::Path;
+
+/// Rough Thumb-2 encoding size; IR instructions don't map 1:1 to machine code
+pub const ESTIMATED_BYTES_PER_INSTRUCTION: u64 = 4;
+
+/// WCET and code size of one function at one optimization level
+#[derive(Debug, Clone, Serialize)]
+pub struct OptLevelMetrics {
+    pub opt_level: OptLevel,
+    pub wcet_cycles: u64,
+    pub instruction_count: u64,
+    pub estimated_bytes: u64,
+}
+
+/// Per-function results across optimization levels
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionOptComparison {
+    pub function: String,
+    /// One entry per level where the function still exists (not inlined away)
+    pub levels: Vec<OptLevelMetrics>,
+}
+
+/// Compile a source at every optimization level and analyze each function
+pub fn compare_opt_levels(
+    source: &Path,
+    work_dir: &Path,
+    levels: &[OptLevel],
+) -> Result<Vec<FunctionOptComparison>> {
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("source");
+    let analyzer = FunctionAnalyzer::new(CortexM7Model::new());
+    let mut comparisons: Vec<FunctionOptComparison> = Vec::new();
+
+    for &level in levels {
+        let output = work_dir.join(format!("{}.{}.ll", stem, level.flag()));
+        BenchmarkCompiler::new()?
+            .with_optimization(level)
+            .compile(source, &output)?;
+
+        let (_context, module) = lale::InkwellParser::parse_file(&output)
+            .map_err(|e| anyhow::anyhow!("Failed to parse LLVM IR: {}", e))?;
+
+        for function in module.get_functions() {
+            let name = function.get_name().to_str().unwrap_or("").to_string();
+
+            // Skip intrinsics and declarations
+            if name.starts_with("llvm.") || function.count_basic_blocks() == 0 {
+                continue;
+            }
+
+            let result = analyzer
+                .analyze(&function)
+                .map_err(|e| anyhow::anyhow!("Failed to analyze {}: {}", name, e))?;
+
+            let instruction_count: u64 = lale::InkwellCFG::from_function(&function)
+                .blocks
+                .iter()
+                .map(|b| b.instruction_count as u64)
+                .sum();
+
+            let metrics = OptLevelMetrics {
+                opt_level: level,
+                wcet_cycles: result.wcet_cycles,
+                instruction_count,
+                estimated_bytes: instruction_count * ESTIMATED_BYTES_PER_INSTRUCTION,
+            };
+
+            match comparisons.iter_mut().find(|c| c.function == name) {
+                Some(comparison) => comparison.levels.push(metrics),
+                None => comparisons.push(FunctionOptComparison {
+                    function: name,
+                    levels: vec![metrics],
+                }),
+            }
+        }
+    }
+
+    Ok(comparisons)
+}
+
+/// Render comparisons as a plain-text table
+pub fn format_table(comparisons: &[FunctionOptComparison]) -> String {
+    let mut table = format!(
+        "{:<32} {:<6} {:>12} {:>8} {:>10}\n",
+        "Function", "Level", "WCET cycles", "Instrs", "Est. bytes"
+    );
+    table.push_str(&format!("{}\n", "-".repeat(72)));
+
+    for comparison in comparisons {
+        for (i, metrics) in comparison.levels.iter().enumerate() {
+            let name = if i == 0 {
+                comparison.function.as_str()
+            } else {
+                ""
+            };
+            table.push_str(&format!(
+                "{:<32} {:<6} {:>12} {:>8} {:>10}\n",
+                name,
+                metrics.opt_level.flag(),
+                metrics.wcet_cycles,
+                metrics.instruction_count,
+                metrics.estimated_bytes
+            ));
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_table() {
+        let comparisons = vec![FunctionOptComparison {
+            function: "filter".to_string(),
+            levels: vec![
+                OptLevelMetrics {
+                    opt_level: OptLevel::O2,
+                    wcet_cycles: 120,
+                    instruction_count: 40,
+                    estimated_bytes: 160,
+                },
+                OptLevelMetrics {
+                    opt_level: OptLevel::Os,
+                    wcet_cycles: 150,
+                    instruction_count: 25,
+                    estimated_bytes: 100,
+                },
+            ],
+        }];
+
+        let table = format_table(&comparisons);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("filter"));
+        assert!(lines[2].contains("O2"));
+        assert!(lines[3].trim_start().starts_with("Os"));
+        assert!(lines[3].ends_with("100"));
+    }
+}
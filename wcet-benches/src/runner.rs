@@ -1,6 +1,7 @@
 use crate::flow_facts::FlowFacts;
-use crate::suite::BenchmarkSuite;
+use crate::suite::{BenchmarkCategory, BenchmarkSuite};
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
@@ -23,8 +24,19 @@ pub struct ResultDetails {
     pub accuracy: Option<f64>,
     pub loop_bounds_found: usize,
     pub entry_point: Option<String>,
+    /// Flow-facts file the loop bounds came from, if not the source pragmas
+    #[serde(default)]
+    pub flow_facts_file: Option<PathBuf>,
 }
 
+/// Categories TACLeBench sorts its sources into, one directory each
+const TACLEBENCH_CATEGORIES: [BenchmarkCategory; 4] = [
+    BenchmarkCategory::Kernel,
+    BenchmarkCategory::Sequential,
+    BenchmarkCategory::Parallel,
+    BenchmarkCategory::Test,
+];
+
 pub struct BenchmarkRunner {
     platform_config_path: String,
     metadata: Option<BenchmarkSuite>,
@@ -58,6 +70,45 @@ impl BenchmarkRunner {
         })
     }
 
+    /// Category of a benchmark
+    ///
+    /// Taken from the suite metadata, or else from the TACLeBench directory
+    /// its source is in.
+    pub fn category_of(&self, benchmark_name: &str) -> Option<BenchmarkCategory> {
+        let from_metadata = self.metadata.as_ref().and_then(|meta| {
+            meta.benchmarks
+                .iter()
+                .find(|b| b.name == benchmark_name)
+                .map(|b| b.category)
+        });
+
+        from_metadata.or_else(|| {
+            TACLEBENCH_CATEGORIES.into_iter().find(|category| {
+                PathBuf::from("data/sources/taclebench")
+                    .join(category.name())
+                    .join(benchmark_name)
+                    .is_dir()
+            })
+        })
+    }
+
+    /// Analyze benchmarks in parallel
+    ///
+    /// Each benchmark is named after its IR file. Results are in the order
+    /// of `ir_paths`.
+    pub fn run_benchmarks(&self, ir_paths: &[PathBuf]) -> Vec<BenchmarkResult> {
+        ir_paths
+            .par_iter()
+            .map(|ir_path| {
+                let name = ir_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown");
+                self.run_benchmark(name, ir_path)
+            })
+            .collect()
+    }
+
     pub fn run_benchmark(&self, name: &str, ir_path: &Path) -> BenchmarkResult {
         let start = Instant::now();
         let reference_wcet = self.get_reference_wcet(name);
@@ -97,9 +148,15 @@ impl BenchmarkRunner {
     }
 
     fn analyze_internal(&self, ir_path: &Path) -> Result<(u64, ResultDetails)> {
-        // Try to find corresponding source file
-        let source_path = self.find_source_file(ir_path);
-        let flow_facts = source_path.and_then(|p| FlowFacts::parse_from_source(&p).ok());
+        // A `<name>.facts.toml` next to the IR wins over the source pragmas
+        let facts_file = FlowFacts::discover(ir_path)?;
+        let flow_facts_file = facts_file
+            .is_some()
+            .then(|| FlowFacts::facts_path_for(ir_path));
+        let flow_facts = facts_file.or_else(|| {
+            self.find_source_file(ir_path)
+                .and_then(|p| FlowFacts::parse_from_source(&p).ok())
+        });
 
         // Parse LLVM IR
         let (_context, module) = lale::InkwellParser::parse_file(ir_path)
@@ -136,6 +193,7 @@ impl BenchmarkRunner {
             accuracy: None,
             loop_bounds_found: flow_facts.as_ref().map(|f| f.loop_count()).unwrap_or(0),
             entry_point: flow_facts.as_ref().and_then(|f| f.entry_point.clone()),
+            flow_facts_file,
         };
 
         // Calculate WCET estimate
@@ -160,8 +218,8 @@ impl BenchmarkRunner {
         // Check in taclebench directories
         let base = PathBuf::from("data/sources/taclebench");
 
-        for category in &["kernel", "sequential", "parallel", "test"] {
-            let source_dir = base.join(category).join(file_stem);
+        for category in TACLEBENCH_CATEGORIES {
+            let source_dir = base.join(category.name()).join(file_stem);
             let source_file = source_dir.join(format!("{}.c", file_stem));
 
             if source_file.exists() {
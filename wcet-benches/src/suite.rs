@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BenchmarkSuite {
@@ -54,6 +55,52 @@ pub enum BenchmarkCategory {
     Mixed,
 }
 
+impl BenchmarkCategory {
+    /// Every category, in declaration order
+    pub fn all() -> [BenchmarkCategory; 7] {
+        [
+            BenchmarkCategory::Kernel,
+            BenchmarkCategory::Sequential,
+            BenchmarkCategory::Parallel,
+            BenchmarkCategory::Test,
+            BenchmarkCategory::Numeric,
+            BenchmarkCategory::Control,
+            BenchmarkCategory::Mixed,
+        ]
+    }
+
+    /// Lowercase name, as used on the command line and in source directories
+    pub fn name(&self) -> &'static str {
+        match self {
+            BenchmarkCategory::Kernel => "kernel",
+            BenchmarkCategory::Sequential => "sequential",
+            BenchmarkCategory::Parallel => "parallel",
+            BenchmarkCategory::Test => "test",
+            BenchmarkCategory::Numeric => "numeric",
+            BenchmarkCategory::Control => "control",
+            BenchmarkCategory::Mixed => "mixed",
+        }
+    }
+}
+
+impl FromStr for BenchmarkCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|category| category.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::all().iter().map(|c| c.name()).collect();
+                format!(
+                    "unknown category '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkProperties {
     pub loc: usize,
@@ -106,6 +153,23 @@ mod tests {
         assert_eq!(cat, parsed);
     }
 
+    #[test]
+    fn test_benchmark_category_from_str() {
+        assert_eq!(
+            "sequential".parse::<BenchmarkCategory>(),
+            Ok(BenchmarkCategory::Sequential)
+        );
+        assert_eq!(
+            "Kernel".parse::<BenchmarkCategory>(),
+            Ok(BenchmarkCategory::Kernel)
+        );
+        assert!("loops".parse::<BenchmarkCategory>().is_err());
+
+        for category in BenchmarkCategory::all() {
+            assert_eq!(category.name().parse::<BenchmarkCategory>(), Ok(category));
+        }
+    }
+
     #[test]
     fn test_benchmark_info_serialization() {
         let info = BenchmarkInfo {